@@ -76,6 +76,17 @@ impl AfkDetector {
         self.state
     }
 
+    /// 当前的 AFK 判定阈值（秒）
+    pub fn timeout_secs(&self) -> u64 {
+        self.timeout.as_secs()
+    }
+
+    /// 修改 AFK 判定阈值，保留上次活动时间和当前状态，下次 `check_state` 时按新阈值判断
+    pub fn set_timeout(&mut self, timeout_secs: u64) {
+        debug!(timeout_secs = timeout_secs, "修改 AFK 判定阈值");
+        self.timeout = Duration::from_secs(timeout_secs);
+    }
+
     /// 获取当前状态 (不更新)
     pub fn current_state(&self) -> AfkState {
         self.state
@@ -156,6 +167,18 @@ mod tests {
         assert!(detector.is_afk());
     }
 
+    #[test]
+    fn test_set_timeout_keeps_last_activity() {
+        let mut detector = AfkDetector::new(300);
+        thread::sleep(Duration::from_secs(2));
+        assert_eq!(detector.check_state(), AfkState::Active);
+
+        // 缩短阈值后，已经空闲的时间立即按新阈值判断
+        detector.set_timeout(1);
+        assert_eq!(detector.timeout_secs(), 1);
+        assert!(matches!(detector.check_state(), AfkState::Afk { .. }));
+    }
+
     #[test]
     fn test_default_timeout() {
         let detector = AfkDetector::default();
@@ -5,6 +5,7 @@
 pub mod pool;
 pub mod queries;
 pub mod repositories;
+pub mod snapshot;
 
 use pool::{create_pool, init_schema};
 use std::sync::Arc;
@@ -13,7 +14,9 @@ use crate::services::{CategoryServiceImpl, GoalServiceImpl, UsageServiceImpl};
 
 // 重新导出 pool 模块的内容
 pub use pool::DbConfig as Config;
+pub use pool::DbHealth;
 pub use pool::DbPool;
+pub use snapshot::ReadRepo;
 
 // ============================================================================
 // Repository - 模块化数据库入口
@@ -21,7 +24,7 @@ pub use pool::DbPool;
 
 /// 模块化数据库入口
 ///
-/// 提供访问各个仓储和服务的方法。
+/// 提供访问各个仓储和服务的方法。时间范围参数都是半开区间 `[start, end)`。
 pub struct Repository {
     pool: Arc<DbPool>,
 }
@@ -75,6 +78,756 @@ impl Repository {
         repositories::AliasRepositoryImpl::new(Arc::clone(&self.pool))
     }
 
+    /// 获取设置仓储
+    pub fn settings(&self) -> repositories::SettingsRepositoryImpl {
+        repositories::SettingsRepositoryImpl::new(Arc::clone(&self.pool))
+    }
+
+    /// 获取保存的视图仓储
+    pub fn saved_views(&self) -> repositories::SavedViewRepositoryImpl {
+        repositories::SavedViewRepositoryImpl::new(Arc::clone(&self.pool))
+    }
+
+    /// 获取应用颜色仓储
+    pub fn app_colors(&self) -> repositories::AppColorRepositoryImpl {
+        repositories::AppColorRepositoryImpl::new(Arc::clone(&self.pool))
+    }
+
+    /// 获取每日应用使用汇总仓储
+    pub fn rollups(&self) -> repositories::DailyRollupRepositoryImpl {
+        repositories::DailyRollupRepositoryImpl::new(Arc::clone(&self.pool))
+    }
+
+    // ========================================================================
+    // 复合操作
+    // ========================================================================
+
+    /// 检查数据库是否可用
+    pub async fn health_check(&self) -> pool::DbHealth {
+        let pool = self.pool.clone();
+        tokio::task::spawn_blocking(move || pool::check_health(&pool))
+            .await
+            .unwrap_or_else(|e| pool::DbHealth::unhealthy(format!("Task join error: {}", e)))
+    }
+
+    /// 保存视图（筛选预设），同名视图已存在时覆盖
+    pub async fn save_view(&self, view: &crate::models::SavedView) -> crate::errors::DbResult<()> {
+        self.saved_views().save_view(view).await
+    }
+
+    /// 获取所有保存的视图，按名称排序
+    pub async fn list_views(&self) -> crate::errors::DbResult<Vec<crate::models::SavedView>> {
+        self.saved_views().list_views().await
+    }
+
+    /// 删除保存的视图，返回是否存在该视图
+    pub async fn delete_view(&self, name: &str) -> crate::errors::DbResult<bool> {
+        self.saved_views().delete_view(name).await
+    }
+
+    /// 设置应用在图表中的颜色（`#RRGGBB`），`None` 表示清除
+    pub async fn set_app_color(
+        &self,
+        app_name: &str,
+        color: Option<&str>,
+    ) -> crate::errors::DbResult<()> {
+        self.app_colors().set_app_color(app_name, color).await
+    }
+
+    /// 获取所有应用的颜色设置（应用名称 -> `#RRGGBB`）
+    pub async fn get_app_colors(
+        &self,
+    ) -> crate::errors::DbResult<std::collections::HashMap<String, String>> {
+        self.app_colors().get_app_colors().await
+    }
+
+    /// 记录追踪服务心跳，由守护进程定期调用
+    pub async fn touch_heartbeat(&self) -> crate::errors::DbResult<()> {
+        self.settings().touch_heartbeat().await
+    }
+
+    /// 获取追踪服务最近一次心跳时间，从未记录时返回 `None`
+    pub async fn get_last_heartbeat(
+        &self,
+    ) -> crate::errors::DbResult<Option<chrono::DateTime<chrono::Utc>>> {
+        self.settings().get_last_heartbeat().await
+    }
+
+    /// 在线备份数据库到 `path`，应用运行期间也可安全执行
+    pub async fn backup_to(&self, path: &std::path::Path) -> crate::errors::DbResult<()> {
+        let pool = self.pool.clone();
+        let path = path.to_path_buf();
+        tokio::task::spawn_blocking(move || pool::backup_to(&pool, &path))
+            .await
+            .map_err(|e| crate::errors::DbError::Validation(format!("Task join error: {}", e)))?
+    }
+
+    /// 记录一次窗口切换：在单个事务中结束上一个事件并插入新事件
+    pub async fn record_focus_change(
+        &self,
+        finalize: Option<(i64, i64)>,
+        new_event: &crate::models::WindowEvent,
+    ) -> crate::errors::DbResult<i64> {
+        self.window_events()
+            .record_focus_change(finalize, new_event)
+            .await
+    }
+
+    /// 获取时间范围内的窗口事件，按 `order` 指定的时间方向排序
+    pub async fn get_window_events(
+        &self,
+        start: chrono::DateTime<chrono::Utc>,
+        end: chrono::DateTime<chrono::Utc>,
+        order: crate::models::SortOrder,
+    ) -> crate::errors::DbResult<Vec<crate::models::WindowEvent>> {
+        self.window_events()
+            .get_by_time_range_ordered(start, end, order)
+            .await
+    }
+
+    /// 获取时间范围内最多 `max_events` 个窗口事件，返回 `(事件, 是否被截断)`
+    pub async fn get_window_events_capped(
+        &self,
+        start: chrono::DateTime<chrono::Utc>,
+        end: chrono::DateTime<chrono::Utc>,
+        order: crate::models::SortOrder,
+        max_events: usize,
+    ) -> crate::errors::DbResult<(Vec<crate::models::WindowEvent>, bool)> {
+        self.window_events()
+            .get_by_time_range_capped(start, end, order, max_events)
+            .await
+    }
+
+    /// 按时间倒序分页获取早于游标的窗口事件，游标为 `None` 时从最新事件开始
+    pub async fn get_window_events_before(
+        &self,
+        cursor: Option<chrono::DateTime<chrono::Utc>>,
+        limit: usize,
+    ) -> crate::errors::DbResult<Vec<crate::models::WindowEvent>> {
+        self.window_events().get_before(cursor, limit).await
+    }
+
+    /// 查找时间范围内重复的相邻事件，返回 `(保留的事件 id, 重复的事件 id)`
+    pub async fn find_duplicate_events(
+        &self,
+        start: chrono::DateTime<chrono::Utc>,
+        end: chrono::DateTime<chrono::Utc>,
+    ) -> crate::errors::DbResult<Vec<(i64, i64)>> {
+        self.window_events().find_duplicate_events(start, end).await
+    }
+
+    /// 删除时间范围内重复的相邻事件（每组保留第一个），返回删除的数量
+    pub async fn remove_duplicate_events(
+        &self,
+        start: chrono::DateTime<chrono::Utc>,
+        end: chrono::DateTime<chrono::Utc>,
+    ) -> crate::errors::DbResult<usize> {
+        self.window_events()
+            .remove_duplicate_events(start, end)
+            .await
+    }
+
+    /// 删除某个应用的全部窗口事件，返回删除的事件数量
+    ///
+    /// `include_metadata` 为 true 时一并删除该应用的分类关联和每日目标。
+    pub async fn delete_app_events(
+        &self,
+        app_name: &str,
+        include_metadata: bool,
+    ) -> crate::errors::DbResult<usize> {
+        self.window_events()
+            .delete_app_events(app_name, include_metadata)
+            .await
+    }
+
+    /// 删除时间范围内 `duration_secs = 0` 的占位事件，返回删除的事件数量
+    ///
+    /// `include_afk` 为 false 时保留 AFK 事件；最新的一条事件（追踪器正在记录的事件）总是保留。
+    pub async fn delete_zero_duration_events(
+        &self,
+        start: chrono::DateTime<chrono::Utc>,
+        end: chrono::DateTime<chrono::Utc>,
+        include_afk: bool,
+    ) -> crate::errors::DbResult<usize> {
+        self.window_events()
+            .delete_zero_duration_events(start, end, include_afk)
+            .await
+    }
+
+    /// 获取应用名称不是有效 UTF-8 的窗口事件 id
+    pub async fn find_invalid_utf8_apps(&self) -> crate::errors::DbResult<Vec<i64>> {
+        self.window_events().find_invalid_utf8_apps().await
+    }
+
+    /// 在同一事务中修复应用名称中无效的 UTF-8 序列，返回修复的事件数量
+    pub async fn sanitize_app_names(&self) -> crate::errors::DbResult<usize> {
+        self.window_events().sanitize_app_names().await
+    }
+
+    /// 数据库中是否有任何窗口事件，用于首次运行时跳过必然为空的查询
+    pub async fn has_any_events(&self) -> crate::errors::DbResult<bool> {
+        self.window_events().has_any_events().await
+    }
+
+    /// 获取每个应用最近一次事件的时间，最久未使用的应用排在前面
+    pub async fn get_last_seen(
+        &self,
+    ) -> crate::errors::DbResult<Vec<(String, chrono::DateTime<chrono::Utc>)>> {
+        self.window_events().get_last_seen().await
+    }
+
+    /// 获取若干应用在时间范围内合计的非 AFK 时长（秒），空列表返回 0
+    pub async fn get_combined_usage(
+        &self,
+        app_names: &[String],
+        start: chrono::DateTime<chrono::Utc>,
+        end: chrono::DateTime<chrono::Utc>,
+    ) -> crate::errors::DbResult<i64> {
+        self.window_events()
+            .get_combined_usage(app_names, start, end)
+            .await
+    }
+
+    /// 按查询条件（应用、标题关键词、时间范围、AFK、分页）获取窗口事件
+    pub async fn query_events(
+        &self,
+        filter: crate::models::EventFilter,
+    ) -> crate::errors::DbResult<Vec<crate::models::WindowEvent>> {
+        self.window_events().query_events(filter).await
+    }
+
+    /// 获取窗口标题包含关键词（忽略 ASCII 大小写）的非 AFK 总时长（秒）
+    pub async fn get_usage_by_title_keyword(
+        &self,
+        keyword: &str,
+        start: chrono::DateTime<chrono::Utc>,
+        end: chrono::DateTime<chrono::Utc>,
+    ) -> crate::errors::DbResult<i64> {
+        self.window_events()
+            .get_usage_by_title_keyword(keyword, start, end)
+            .await
+    }
+
+    /// 获取时间范围内的窗口事件（按时间升序）及其应用所属的未归档分类
+    pub async fn get_window_events_with_categories(
+        &self,
+        start: chrono::DateTime<chrono::Utc>,
+        end: chrono::DateTime<chrono::Utc>,
+    ) -> crate::errors::DbResult<Vec<(crate::models::WindowEvent, Vec<crate::models::Category>)>>
+    {
+        self.window_events()
+            .get_events_with_categories(start, end)
+            .await
+    }
+
+    /// 获取时间范围内各工作区的非 AFK 时长（秒），按时长降序
+    pub async fn get_workspace_usage(
+        &self,
+        start: chrono::DateTime<chrono::Utc>,
+        end: chrono::DateTime<chrono::Utc>,
+    ) -> crate::errors::DbResult<Vec<(String, i64)>> {
+        self.window_events().get_workspace_usage(start, end).await
+    }
+
+    /// 获取时间范围内每个 `(工作区, 应用)` 组合的非 AFK 时长（秒）
+    pub async fn get_workspace_app_usage(
+        &self,
+        start: chrono::DateTime<chrono::Utc>,
+        end: chrono::DateTime<chrono::Utc>,
+    ) -> crate::errors::DbResult<Vec<(String, String, i64)>> {
+        self.window_events()
+            .get_workspace_app_usage(start, end)
+            .await
+    }
+
+    /// 查找时间范围内超过 `min_gap_secs` 的记录空白，通常意味着守护进程没有运行
+    pub async fn find_tracking_gaps(
+        &self,
+        start: chrono::DateTime<chrono::Utc>,
+        end: chrono::DateTime<chrono::Utc>,
+        min_gap_secs: i64,
+    ) -> crate::errors::DbResult<Vec<crate::time::range::TimeRange>> {
+        self.window_events()
+            .find_tracking_gaps(start, end, min_gap_secs)
+            .await
+    }
+
+    /// 将工作区标签 `from` 的全部事件改为 `to`，返回修改的事件数量
+    pub async fn rename_workspace(&self, from: &str, to: &str) -> crate::errors::DbResult<usize> {
+        self.window_events().rename_workspace(from, to).await
+    }
+
+    /// 获取使用时长最多的 N 个应用，其余合并为"其他"
+    pub async fn get_app_usage_topn_with_other(
+        &self,
+        start: chrono::DateTime<chrono::Utc>,
+        end: chrono::DateTime<chrono::Utc>,
+        n: usize,
+    ) -> crate::errors::DbResult<Vec<crate::models::AppUsage>> {
+        use crate::traits::AppUsageQuery;
+
+        let usages = self.usage_service().get_app_usage(start, end).await?;
+        Ok(crate::utils::filter::top_n_with_other(usages, n))
+    }
+
+    /// 获取截至当前时刻、长度为 `duration` 的滚动窗口内的应用使用统计
+    pub async fn get_app_usage_last(
+        &self,
+        duration: chrono::Duration,
+    ) -> crate::errors::DbResult<Vec<crate::models::AppUsage>> {
+        use crate::traits::AppUsageQuery;
+
+        let end = chrono::Utc::now();
+        self.usage_service()
+            .get_app_usage(end - duration, end)
+            .await
+    }
+
+    /// 在同一事务中批量导入 `(原始名称, 别名)`，已有别名会被覆盖，返回导入的条数
+    pub async fn import_aliases(
+        &self,
+        pairs: &[(String, String)],
+    ) -> crate::errors::DbResult<usize> {
+        self.aliases().import_aliases(pairs).await
+    }
+
+    /// 导出所有别名 `(原始名称, 别名)`，按原始名称排序
+    pub async fn export_aliases(&self) -> crate::errors::DbResult<Vec<(String, String)>> {
+        self.aliases().export_aliases().await
+    }
+
+    /// 清空并重新计算所有日期的每日应用使用汇总，返回汇总的日期数量
+    pub async fn rebuild_rollup(&self) -> crate::errors::DbResult<usize> {
+        self.rollups().rebuild_rollup().await
+    }
+
+    /// 重新计算单个本地日期的每日应用使用汇总
+    pub async fn update_rollup_for_day(
+        &self,
+        date: chrono::NaiveDate,
+    ) -> crate::errors::DbResult<()> {
+        self.rollups().update_rollup_for_day(date).await
+    }
+
+    /// 获取本地日期 `[first, last]` 内各应用的总时长，已汇总的日期直接读取汇总表
+    pub async fn get_app_usage_summary(
+        &self,
+        first: chrono::NaiveDate,
+        last: chrono::NaiveDate,
+    ) -> crate::errors::DbResult<Vec<(String, i64)>> {
+        self.rollups().get_app_usage_summary(first, last).await
+    }
+
+    /// 获取应用使用统计，最多读取 `max_events` 个窗口事件，返回 `(统计, 是否被截断)`
+    pub async fn get_app_usage_capped(
+        &self,
+        start: chrono::DateTime<chrono::Utc>,
+        end: chrono::DateTime<chrono::Utc>,
+        max_events: usize,
+    ) -> crate::errors::DbResult<(Vec<crate::models::AppUsage>, bool)> {
+        crate::db::queries::AppUsageQueryImpl::new(self.pool.clone())
+            .get_app_usage_capped(start, end, max_events)
+            .await
+    }
+
+    /// 获取应用使用统计，分组前先把应用名称规范化
+    pub async fn get_app_usage_normalized(
+        &self,
+        start: chrono::DateTime<chrono::Utc>,
+        end: chrono::DateTime<chrono::Utc>,
+        normalizer: &crate::utils::normalize::NameNormalizer,
+    ) -> crate::errors::DbResult<Vec<crate::models::AppUsage>> {
+        crate::db::queries::AppUsageQueryImpl::new(self.pool.clone())
+            .get_app_usage_normalized(start, end, normalizer)
+            .await
+    }
+
+    /// 获取若干个（可不连续的）本地日期内的应用使用统计
+    pub async fn get_app_usage_for_dates(
+        &self,
+        dates: &[chrono::NaiveDate],
+    ) -> crate::errors::DbResult<Vec<crate::models::AppUsage>> {
+        crate::db::queries::AppUsageQueryImpl::new(self.pool.clone())
+            .get_app_usage_for_dates(dates)
+            .await
+    }
+
+    /// 获取时间范围内每个本地日期使用时长最多的应用 `(日期, 应用名称, 秒数)`
+    pub async fn get_daily_top_app(
+        &self,
+        start: chrono::DateTime<chrono::Utc>,
+        end: chrono::DateTime<chrono::Utc>,
+    ) -> crate::errors::DbResult<Vec<(chrono::NaiveDate, String, i64)>> {
+        crate::db::queries::AppUsageQueryImpl::new(self.pool.clone())
+            .get_daily_top_app(start, end)
+            .await
+    }
+
+    /// 获取应用使用统计，并把每个应用的时长拆分为活跃时长和与 AFK 会话重叠的时长
+    pub async fn get_app_usage_with_afk(
+        &self,
+        start: chrono::DateTime<chrono::Utc>,
+        end: chrono::DateTime<chrono::Utc>,
+    ) -> crate::errors::DbResult<Vec<crate::models::AppUsageWithAfk>> {
+        crate::db::queries::AppUsageQueryImpl::new(self.pool.clone())
+            .get_app_usage_with_afk(start, end)
+            .await
+    }
+
+    /// 从 ActivityWatch 的分类规则 JSON 导入分类，返回新建的分类数量
+    pub async fn import_aw_category_rules(&self, json: &str) -> crate::errors::DbResult<usize> {
+        self.category_service().import_aw_category_rules(json).await
+    }
+
+    /// 获取分类列表，`include_archived` 为 `false` 时不包含已归档的分类
+    pub async fn get_categories(
+        &self,
+        include_archived: bool,
+    ) -> crate::errors::DbResult<Vec<crate::models::Category>> {
+        self.categories().get_categories(include_archived).await
+    }
+
+    /// 按名称排序分页获取未归档的分类
+    pub async fn get_categories_page(
+        &self,
+        limit: usize,
+        offset: usize,
+    ) -> crate::errors::DbResult<Vec<crate::models::Category>> {
+        self.categories().get_categories_page(limit, offset).await
+    }
+
+    /// 归档分类（保留应用关联，可恢复）
+    pub async fn archive_category(&self, id: i64) -> crate::errors::DbResult<()> {
+        self.categories().archive_category(id).await
+    }
+
+    /// 取消归档分类
+    pub async fn unarchive_category(&self, id: i64) -> crate::errors::DbResult<()> {
+        self.categories().unarchive_category(id).await
+    }
+
+    /// 获取单个分类在时间范围内的使用统计，分类不存在时返回 `None`
+    pub async fn get_single_category_usage(
+        &self,
+        category_id: i64,
+        start: chrono::DateTime<chrono::Utc>,
+        end: chrono::DateTime<chrono::Utc>,
+    ) -> crate::errors::DbResult<Option<crate::models::CategoryUsage>> {
+        self.categories()
+            .get_single_category_usage(category_id, start, end)
+            .await
+    }
+
+    /// 获取分类中使用时长最多的前 `limit` 个应用
+    pub async fn get_top_apps_in_category(
+        &self,
+        category_id: i64,
+        start: chrono::DateTime<chrono::Utc>,
+        end: chrono::DateTime<chrono::Utc>,
+        limit: usize,
+    ) -> crate::errors::DbResult<Vec<crate::models::AppUsageInCategory>> {
+        self.categories()
+            .get_top_apps_in_category(category_id, start, end, limit)
+            .await
+    }
+
+    /// 批量删除分类及其应用关联，返回实际删除的分类数量
+    pub async fn delete_categories(&self, ids: &[i64]) -> crate::errors::DbResult<usize> {
+        self.categories().delete_categories(ids).await
+    }
+
+    /// 在同一事务中把源分类的所有应用移到目标分类（可选删除源分类），返回移动的应用数量
+    pub async fn move_category_apps(
+        &self,
+        from_id: i64,
+        to_id: i64,
+        delete_source: bool,
+    ) -> crate::errors::DbResult<usize> {
+        self.categories()
+            .move_category_apps(from_id, to_id, delete_source)
+            .await
+    }
+
+    /// 按名称确保分类存在（已存在的跳过），返回与输入一一对应的分类 id
+    pub async fn ensure_categories(
+        &self,
+        categories: &[crate::models::Category],
+    ) -> crate::errors::DbResult<Vec<i64>> {
+        self.categories().ensure_categories(categories).await
+    }
+
+    /// 获取 `since` 之后有记录的应用名称
+    pub async fn get_all_app_names_since(
+        &self,
+        since: chrono::DateTime<chrono::Utc>,
+    ) -> crate::errors::DbResult<Vec<String>> {
+        self.categories().get_all_app_names_since(since).await
+    }
+
+    /// 获取时间范围内每个本地日期的活跃时长（秒），没有数据的日期为 0
+    pub async fn get_daily_activity(
+        &self,
+        start: chrono::DateTime<chrono::Utc>,
+        end: chrono::DateTime<chrono::Utc>,
+    ) -> crate::errors::DbResult<Vec<(chrono::NaiveDate, i64)>> {
+        crate::db::queries::TimeStatsQueryImpl::new(self.pool.clone())
+            .get_daily_activity(start, end)
+            .await
+    }
+
+    /// 获取时间范围内每个本地日期的活跃时长，区分真实的 0 与没有数据
+    ///
+    /// 没有活跃时长且整天都处于 [`Self::find_tracking_gaps`] 找到的记录空白中的日期为 `None`
+    /// （守护进程没有运行）；其余日期与 [`Self::get_daily_activity`] 相同。
+    /// 范围内第一条记录之前和最后一条记录之后无法判断守护进程是否运行，按真实的 0 处理。
+    /// 结果可以交给 [`crate::time::analysis::TimeSeriesAnalyzer::mark_missing`] 绘制。
+    pub async fn get_daily_activity_with_missing(
+        &self,
+        start: chrono::DateTime<chrono::Utc>,
+        end: chrono::DateTime<chrono::Utc>,
+    ) -> crate::errors::DbResult<Vec<(chrono::NaiveDate, Option<i64>)>> {
+        let daily = self.get_daily_activity(start, end).await?;
+        let gaps = self.find_tracking_gaps(start, end, 0).await?;
+
+        Ok(daily
+            .into_iter()
+            .map(|(date, seconds)| {
+                let day = crate::time::range::TimeRangeCalculator::day(date);
+                let untracked = seconds == 0
+                    && gaps
+                        .iter()
+                        .any(|gap| gap.start <= day.start && gap.end >= day.end);
+                (date, (!untracked).then_some(seconds))
+            })
+            .collect())
+    }
+
+    /// 获取时间范围内每个本地日期使用过的不同应用数量，没有数据的日期为 0
+    pub async fn get_daily_app_diversity(
+        &self,
+        start: chrono::DateTime<chrono::Utc>,
+        end: chrono::DateTime<chrono::Utc>,
+    ) -> crate::errors::DbResult<Vec<(chrono::NaiveDate, usize)>> {
+        crate::db::queries::TimeStatsQueryImpl::new(self.pool.clone())
+            .get_daily_app_diversity(start, end)
+            .await
+    }
+
+    /// 统计时间范围内有非 AFK 活动的本地日期数量
+    pub async fn count_active_days(
+        &self,
+        start: chrono::DateTime<chrono::Utc>,
+        end: chrono::DateTime<chrono::Utc>,
+    ) -> crate::errors::DbResult<usize> {
+        crate::db::queries::TimeStatsQueryImpl::new(self.pool.clone())
+            .count_active_days(start, end)
+            .await
+    }
+
+    /// 统计应用在时间范围内不超过每日上限的天数，返回 `(达成天数, 有使用记录的天数)`
+    pub async fn get_goal_adherence(
+        &self,
+        app_name: &str,
+        start: chrono::DateTime<chrono::Utc>,
+        end: chrono::DateTime<chrono::Utc>,
+        cap_secs: i64,
+    ) -> crate::errors::DbResult<(u32, u32)> {
+        crate::db::queries::TimeStatsQueryImpl::new(self.pool.clone())
+            .get_goal_adherence(app_name, start, end, cap_secs)
+            .await
+    }
+
+    /// 获取时间范围内每个本地日期每小时的使用时长（秒，不含 AFK），按日期升序
+    pub async fn get_daily_hourly_profiles(
+        &self,
+        start: chrono::DateTime<chrono::Utc>,
+        end: chrono::DateTime<chrono::Utc>,
+    ) -> crate::errors::DbResult<Vec<[i64; 24]>> {
+        crate::db::queries::TimeStatsQueryImpl::new(self.pool.clone())
+            .get_daily_hourly_profiles(start, end)
+            .await
+    }
+
+    /// 获取单个应用在指定日期每小时的使用时长（秒，不含 AFK）
+    pub async fn get_app_hourly_usage(
+        &self,
+        app_name: &str,
+        date: chrono::NaiveDate,
+    ) -> crate::errors::DbResult<[i64; 24]> {
+        crate::db::queries::TimeStatsQueryImpl::new(self.pool.clone())
+            .get_app_hourly_usage(app_name, date)
+            .await
+    }
+
+    /// 按查询规格获取年/月/周/天/小时分段使用统计（不含 AFK）
+    pub async fn get_period_usage(
+        &self,
+        spec: crate::models::PeriodSpec,
+    ) -> crate::errors::DbResult<Vec<crate::models::PeriodUsage>> {
+        crate::db::queries::TimeStatsQueryImpl::new(self.pool.clone())
+            .get_period_usage(spec)
+            .await
+    }
+
+    /// 获取分类使用统计及所有分类时长之和
+    pub async fn get_category_usage_with_total(
+        &self,
+        start: chrono::DateTime<chrono::Utc>,
+        end: chrono::DateTime<chrono::Utc>,
+    ) -> crate::errors::DbResult<(Vec<crate::models::CategoryUsage>, i64)> {
+        crate::db::queries::CategoryUsageQueryImpl::new(self.pool.clone())
+            .get_category_usage_with_total(start, end)
+            .await
+    }
+
+    /// 获取分类使用统计，并追加汇总了不属于任何分类的应用的"未分类"分组
+    pub async fn get_category_usage_with_uncategorized(
+        &self,
+        start: chrono::DateTime<chrono::Utc>,
+        end: chrono::DateTime<chrono::Utc>,
+    ) -> crate::errors::DbResult<Vec<crate::models::CategoryUsage>> {
+        crate::db::queries::CategoryUsageQueryImpl::new(self.pool.clone())
+            .get_category_usage_with_uncategorized(start, end)
+            .await
+    }
+
+    /// 导出指定日期（本地时间）的时间线为 JSON 数组 `[{start, end, app, is_afk}]`
+    pub async fn export_day_timeline_json(
+        &self,
+        date: chrono::NaiveDate,
+    ) -> crate::errors::DbResult<String> {
+        let entries = self
+            .with_read_snapshot(move |snapshot| snapshot.day_timeline(date))
+            .await?;
+        serde_json::to_string(&entries)
+            .map_err(|e| crate::errors::DbError::Validation(format!("序列化时间线失败: {}", e)))
+    }
+
+    /// 在单个只读事务中执行 `f`，其中的所有查询看到同一份数据快照
+    ///
+    /// 用于由多次查询组成的报告（如导出），避免追踪器在查询之间的写入造成各部分不一致。
+    pub async fn with_read_snapshot<T, F>(&self, f: F) -> crate::errors::DbResult<T>
+    where
+        F: FnOnce(&snapshot::ReadRepo) -> crate::errors::DbResult<T> + Send + 'static,
+        T: Send + 'static,
+    {
+        let pool = Arc::clone(&self.pool);
+        tokio::task::spawn_blocking(move || snapshot::with_read_snapshot_sync(&pool, f))
+            .await
+            .map_err(|e| crate::errors::DbError::Validation(format!("Task join error: {}", e)))?
+    }
+
+    /// 获取指定日期开始、跨过次日零点的事件
+    pub async fn get_midnight_crossing_events(
+        &self,
+        date: chrono::NaiveDate,
+    ) -> crate::errors::DbResult<Vec<crate::models::WindowEvent>> {
+        self.window_events()
+            .get_midnight_crossing_events(date)
+            .await
+    }
+
+    /// 在零点处拆分指定日期跨过零点的事件，返回拆分数量
+    pub async fn split_midnight_crossings(
+        &self,
+        date: chrono::NaiveDate,
+    ) -> crate::errors::DbResult<usize> {
+        self.window_events().split_midnight_crossings(date).await
+    }
+
+    /// 获取指定日期生效的每日目标（分钟）
+    pub async fn get_goal_at(
+        &self,
+        app_name: &str,
+        date: chrono::NaiveDate,
+    ) -> crate::errors::DbResult<Option<i64>> {
+        self.daily_goals().get_goal_at(app_name, date).await
+    }
+
+    /// 获取所有每日目标及其今天已使用的秒数（单次查询）
+    pub async fn get_goals_with_progress(
+        &self,
+    ) -> crate::errors::DbResult<Vec<(crate::models::DailyGoal, i64)>> {
+        self.daily_goals().get_goals_with_progress().await
+    }
+
+    /// 将应用的每日目标转移到另一个应用名下（冲突时保留更严格的上限）
+    pub async fn rename_goal_app(&self, from: &str, to: &str) -> crate::errors::DbResult<()> {
+        self.daily_goals().rename_goal_app(from, to).await
+    }
+
+    /// 获取单个分类每天的使用时长（没有数据的日期为 0）
+    pub async fn get_category_daily_series(
+        &self,
+        category_id: i64,
+        start: chrono::DateTime<chrono::Utc>,
+        end: chrono::DateTime<chrono::Utc>,
+    ) -> crate::errors::DbResult<Vec<crate::models::PeriodUsage>> {
+        self.categories()
+            .get_category_daily_series(category_id, start, end)
+            .await
+    }
+
+    /// 按时间段获取分类使用统计（`unit` 为每段的时间单位）
+    pub async fn get_category_usage_series(
+        &self,
+        start: chrono::DateTime<chrono::Utc>,
+        end: chrono::DateTime<chrono::Utc>,
+        unit: crate::time::TimeGranularity,
+    ) -> crate::errors::DbResult<
+        Vec<(
+            crate::time::range::TimeRange,
+            Vec<crate::models::CategoryUsage>,
+        )>,
+    > {
+        self.categories()
+            .get_category_usage_series(start, end, unit)
+            .await
+    }
+
+    /// 执行任意 SQL 并以通用行的形式返回结果（需启用 `raw-sql` 特性）
+    ///
+    /// 面向只读的临时分析查询：SQL 不经过任何校验，调用方需自行保证不修改数据。
+    /// 该方法同步执行，会阻塞当前线程。
+    #[cfg(feature = "raw-sql")]
+    pub fn query_raw(
+        &self,
+        sql: &str,
+        params: &[&dyn rusqlite::ToSql],
+    ) -> crate::errors::DbResult<Vec<Vec<rusqlite::types::Value>>> {
+        let conn = self.pool.get()?;
+        let mut stmt = conn.prepare(sql)?;
+        let column_count = stmt.column_count();
+
+        let rows = stmt
+            .query_map(params, |row| {
+                (0..column_count)
+                    .map(|i| row.get::<_, rusqlite::types::Value>(i))
+                    .collect::<Result<Vec<_>, _>>()
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(rows)
+    }
+
+    /// 执行 `EXPLAIN QUERY PLAN` 并返回查询计划的描述行（需启用 `raw-sql` 特性）
+    ///
+    /// 用于性能调试，确认查询是否命中索引。参数占位符无需绑定。
+    #[cfg(feature = "raw-sql")]
+    pub fn explain(&self, sql: &str) -> crate::errors::DbResult<Vec<String>> {
+        let conn = self.pool.get()?;
+        let mut stmt = conn.prepare(&format!("EXPLAIN QUERY PLAN {}", sql))?;
+
+        // 结果列：id, parent, notused, detail；未绑定的参数按 NULL 处理
+        let mut rows = stmt.raw_query();
+        let mut details = Vec::new();
+        while let Some(row) = rows.next()? {
+            details.push(row.get::<_, String>(3)?);
+        }
+
+        Ok(details)
+    }
+
     // ========================================================================
     // 服务层访问
     // ========================================================================
@@ -251,3 +1004,488 @@ impl crate::traits::AliasRepository for Repository {
         self.aliases().delete(app_name).await
     }
 }
+
+#[async_trait::async_trait]
+impl crate::traits::SettingsRepository for Repository {
+    async fn get_setting(&self, key: &str) -> crate::errors::DbResult<Option<String>> {
+        self.settings().get_setting(key).await
+    }
+
+    async fn set_setting(&self, key: &str, value: &str) -> crate::errors::DbResult<()> {
+        self.settings().set_setting(key, value).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::traits::*;
+    use chrono::{Datelike, Local, Utc};
+
+    #[tokio::test]
+    async fn test_queries_on_empty_database() {
+        let repo = Repository::with_pool(pool::create_memory_pool());
+        let end = Utc::now();
+        let start = end - chrono::Duration::days(7);
+        let today = Local::now().date_naive();
+        let year = today.year();
+
+        assert!(repo
+            .get_window_events(start, end, crate::models::SortOrder::Desc)
+            .await
+            .unwrap()
+            .is_empty());
+        assert!(repo
+            .get_window_events_before(None, 10)
+            .await
+            .unwrap()
+            .is_empty());
+        assert!(repo
+            .get_app_usage_topn_with_other(start, end, 5)
+            .await
+            .unwrap()
+            .is_empty());
+        assert!(repo
+            .get_app_usage_for_dates(&[today])
+            .await
+            .unwrap()
+            .is_empty());
+        assert!(repo.get_daily_top_app(start, end).await.unwrap().is_empty());
+        assert!(repo.get_categories(true).await.unwrap().is_empty());
+        assert!(repo
+            .get_midnight_crossing_events(today)
+            .await
+            .unwrap()
+            .is_empty());
+        assert_eq!(repo.split_midnight_crossings(today).await.unwrap(), 0);
+        assert_eq!(repo.get_goal_at("firefox", today).await.unwrap(), None);
+        assert!(repo
+            .get_category_usage_series(start, end, crate::time::TimeGranularity::Day)
+            .await
+            .unwrap()
+            .iter()
+            .all(|(_, usage)| usage.is_empty()));
+        assert!(repo.get_all_app_names().await.unwrap().is_empty());
+        assert!(DailyGoalRepository::get_all(&repo)
+            .await
+            .unwrap()
+            .is_empty());
+        assert_eq!(repo.get_today_usage("firefox").await.unwrap(), 0);
+
+        let usage = repo.usage_service();
+        assert!(usage.get_app_usage(start, end).await.unwrap().is_empty());
+        assert!(usage
+            .get_category_usage(start, end)
+            .await
+            .unwrap()
+            .is_empty());
+        let zero =
+            |periods: Vec<crate::models::PeriodUsage>| periods.iter().all(|p| p.total_seconds == 0);
+        assert!(zero(usage.get_yearly_usage(3).await.unwrap()));
+        assert!(zero(usage.get_monthly_usage(year).await.unwrap()));
+        assert!(zero(
+            usage.get_weekly_usage(year, today.month()).await.unwrap()
+        ));
+        assert!(zero(
+            usage
+                .get_daily_usage_for_week(year, today.month(), 1)
+                .await
+                .unwrap()
+        ));
+        assert!(zero(
+            usage
+                .get_hourly_usage(year, today.month(), today.day())
+                .await
+                .unwrap()
+        ));
+
+        let dashboard = usage.get_dashboard_data().await.unwrap();
+        assert!(dashboard.app_usage.is_empty());
+        assert!(dashboard.daily_goals.is_empty());
+        let stats = usage
+            .get_stats_data(&crate::models::TimeNavigationState::new(year))
+            .await
+            .unwrap();
+        assert!(stats.app_usage.is_empty());
+        assert!(zero(stats.period_usage));
+
+        let categories = repo
+            .category_service()
+            .get_category_management_data(start, end)
+            .await
+            .unwrap();
+        assert!(categories.categories.is_empty());
+        assert!(categories.unclassified_apps.is_empty());
+        assert_eq!(categories.classified_app_count, 0);
+
+        assert!(repo
+            .goal_service()
+            .get_all_goal_progress()
+            .await
+            .unwrap()
+            .is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_get_app_usage_last() {
+        let repo = Repository::with_pool(pool::create_memory_pool());
+        let now = Utc::now();
+        for (app_name, hours_ago) in [("firefox", 1), ("code", 5), ("kitty", 7), ("mpv", 30)] {
+            repo.window_events()
+                .insert(&crate::models::WindowEvent {
+                    id: None,
+                    timestamp: now - chrono::Duration::hours(hours_ago),
+                    app_name: app_name.to_string(),
+                    window_title: String::new(),
+                    workspace: String::new(),
+                    duration_secs: 60,
+                    is_afk: false,
+                })
+                .await
+                .unwrap();
+        }
+
+        let mut apps: Vec<String> = repo
+            .get_app_usage_last(chrono::Duration::hours(6))
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|u| u.app_name)
+            .collect();
+        apps.sort();
+        assert_eq!(apps, vec!["code", "firefox"]);
+    }
+
+    #[tokio::test]
+    async fn test_get_daily_activity_with_missing() {
+        let repo = Repository::with_pool(pool::create_memory_pool());
+        let monday = chrono::NaiveDate::from_ymd_opt(2024, 3, 4).unwrap();
+        let day = |offset| {
+            crate::time::range::TimeRangeCalculator::day(monday + chrono::Duration::days(offset))
+        };
+        // 周二整天 AFK（守护进程在运行，真实的 0）；周三、周四没有任何记录（守护进程未运行）
+        for (offset, duration_secs, is_afk) in [(0, 3600, false), (1, 600, true), (4, 1800, false)]
+        {
+            repo.window_events()
+                .insert(&crate::models::WindowEvent {
+                    id: None,
+                    timestamp: day(offset).start + chrono::Duration::hours(10),
+                    app_name: "code".to_string(),
+                    window_title: String::new(),
+                    workspace: String::new(),
+                    duration_secs,
+                    is_afk,
+                })
+                .await
+                .unwrap();
+        }
+
+        let daily = repo
+            .get_daily_activity_with_missing(day(0).start, day(4).end)
+            .await
+            .unwrap();
+        let values: Vec<Option<i64>> = daily.into_iter().map(|(_, seconds)| seconds).collect();
+        assert_eq!(values, vec![Some(3600), Some(0), None, None, Some(1800)]);
+    }
+
+    #[tokio::test]
+    async fn test_day_boundary_matches_between_query_and_aggregator() {
+        use crate::time::aggregate::TimeAggregator;
+        use crate::time::range::TimeRangeCalculator;
+
+        let repo = Repository::with_pool(pool::create_memory_pool());
+        let date = chrono::NaiveDate::from_ymd_opt(2024, 3, 4).unwrap();
+        let day = TimeRangeCalculator::day(date);
+        let next_day = TimeRangeCalculator::day(date.succ_opt().unwrap());
+        assert_eq!(day.end, next_day.start);
+
+        // 当天开始、结束前最后 1ms、以及恰好位于结束时间（即次日零点）的事件
+        for (app_name, timestamp) in [
+            ("code", day.start),
+            ("kitty", day.end - chrono::Duration::milliseconds(1)),
+            ("firefox", day.end),
+        ] {
+            repo.window_events()
+                .insert(&crate::models::WindowEvent {
+                    id: None,
+                    timestamp,
+                    app_name: app_name.to_string(),
+                    window_title: String::new(),
+                    workspace: String::new(),
+                    duration_secs: 60,
+                    is_afk: false,
+                })
+                .await
+                .unwrap();
+        }
+
+        let usage = repo
+            .usage_service()
+            .get_app_usage(day.start, day.end)
+            .await
+            .unwrap();
+        let mut apps: Vec<_> = usage.iter().map(|u| u.app_name.as_str()).collect();
+        apps.sort();
+        assert_eq!(apps, vec!["code", "kitty"]);
+        // 聚合器按同一范围过滤，结果与数据库查询一致
+        let slots = TimeAggregator::new(&usage)
+            .with_time_range(day)
+            .aggregate_by_day();
+        assert_eq!(slots.total_duration().as_seconds(), 120);
+
+        // 边界上的事件只属于次日
+        let next_usage = repo
+            .usage_service()
+            .get_app_usage(next_day.start, next_day.end)
+            .await
+            .unwrap();
+        assert_eq!(next_usage.len(), 1);
+        assert_eq!(next_usage[0].app_name, "firefox");
+        let daily = repo
+            .get_daily_activity(day.start, next_day.end)
+            .await
+            .unwrap();
+        assert_eq!(daily, vec![(date, 120), (date.succ_opt().unwrap(), 60)]);
+    }
+
+    #[tokio::test]
+    async fn test_export_day_timeline_json() {
+        let repo = Repository::with_pool(pool::create_memory_pool());
+        let event = |app_name: &str, timestamp| crate::models::WindowEvent {
+            id: None,
+            timestamp,
+            app_name: app_name.to_string(),
+            window_title: String::new(),
+            workspace: String::new(),
+            duration_secs: 0,
+            is_afk: false,
+        };
+        let date = chrono::NaiveDate::from_ymd_opt(2024, 3, 4).unwrap();
+        let nine =
+            crate::time::range::TimeRangeCalculator::day(date).start + chrono::Duration::hours(9);
+
+        // 两段会话，中间隔着一段 AFK；插入顺序打乱
+        let mut afk = event("code", nine + chrono::Duration::minutes(30));
+        afk.duration_secs = 1800;
+        afk.is_afk = true;
+        let mut second = event("firefox", nine + chrono::Duration::hours(1));
+        second.duration_secs = 600;
+        let mut first = event("code", nine);
+        first.duration_secs = 1800;
+        for e in [&second, &afk, &first] {
+            repo.window_events().insert(e).await.unwrap();
+        }
+        // 其他日期的事件不导出
+        repo.window_events()
+            .insert(&event("kitty", nine + chrono::Duration::days(1)))
+            .await
+            .unwrap();
+
+        let json = repo.export_day_timeline_json(date).await.unwrap();
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        let entries = value.as_array().unwrap();
+        assert_eq!(entries.len(), 3);
+        for entry in entries {
+            let mut keys: Vec<_> = entry.as_object().unwrap().keys().cloned().collect();
+            keys.sort();
+            assert_eq!(keys, ["app", "end", "is_afk", "start"]);
+        }
+
+        let parsed: Vec<crate::models::TimelineEntry> = serde_json::from_str(&json).unwrap();
+        assert_eq!(
+            parsed,
+            vec![
+                crate::models::TimelineEntry {
+                    start: nine,
+                    end: nine + chrono::Duration::minutes(30),
+                    app: "code".to_string(),
+                    is_afk: false,
+                },
+                crate::models::TimelineEntry {
+                    start: nine + chrono::Duration::minutes(30),
+                    end: nine + chrono::Duration::hours(1),
+                    app: "code".to_string(),
+                    is_afk: true,
+                },
+                crate::models::TimelineEntry {
+                    start: nine + chrono::Duration::hours(1),
+                    end: nine + chrono::Duration::minutes(70),
+                    app: "firefox".to_string(),
+                    is_afk: false,
+                },
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_backup_to_file() {
+        use crate::traits::WindowEventRepository;
+
+        let repo = Repository::with_pool(pool::create_memory_pool());
+        let start = chrono::Utc::now() - chrono::Duration::hours(1);
+        for (i, app_name) in ["firefox", "code", "kitty"].iter().enumerate() {
+            repo.window_events()
+                .insert(&crate::models::WindowEvent {
+                    id: None,
+                    timestamp: start + chrono::Duration::minutes(i as i64),
+                    app_name: app_name.to_string(),
+                    window_title: String::new(),
+                    workspace: String::new(),
+                    duration_secs: 60,
+                    is_afk: false,
+                })
+                .await
+                .unwrap();
+        }
+
+        let path = std::env::temp_dir().join(format!("tail-core-backup-{}.db", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        repo.backup_to(&path).await.unwrap();
+
+        let restored = Repository::new(&Config {
+            path: path.to_string_lossy().into_owned(),
+        })
+        .unwrap();
+        let end = chrono::Utc::now();
+        let original = repo
+            .window_events()
+            .get_by_time_range(start, end)
+            .await
+            .unwrap();
+        let copied = restored
+            .window_events()
+            .get_by_time_range(start, end)
+            .await
+            .unwrap();
+        assert_eq!(copied.len(), 3);
+        assert_eq!(
+            copied.iter().map(|e| &e.app_name).collect::<Vec<_>>(),
+            original.iter().map(|e| &e.app_name).collect::<Vec<_>>()
+        );
+
+        drop(restored);
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[tokio::test]
+    async fn test_read_snapshot_hides_concurrent_writes() {
+        use crate::traits::WindowEventRepository;
+
+        let path =
+            std::env::temp_dir().join(format!("tail-core-snapshot-{}.db", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let repo = Repository::new(&Config {
+            path: path.to_string_lossy().into_owned(),
+        })
+        .unwrap();
+        // WAL 模式下写入方不会被读事务阻塞，可以验证快照隔离
+        repo.pool
+            .get()
+            .unwrap()
+            .query_row("PRAGMA journal_mode=WAL", [], |row| row.get::<_, String>(0))
+            .unwrap();
+
+        let start = chrono::Utc::now() - chrono::Duration::hours(1);
+        let event = move |app_name: &str, minutes: i64| crate::models::WindowEvent {
+            id: None,
+            timestamp: start + chrono::Duration::minutes(minutes),
+            app_name: app_name.to_string(),
+            window_title: String::new(),
+            workspace: String::new(),
+            duration_secs: 60,
+            is_afk: false,
+        };
+        repo.window_events()
+            .insert(&event("code", 0))
+            .await
+            .unwrap();
+
+        let end = start + chrono::Duration::hours(1);
+        let writer = repo.window_events();
+        let (before, after) = repo
+            .with_read_snapshot(move |snapshot| {
+                let before = snapshot.window_events(start, end, crate::models::SortOrder::Asc)?;
+                tokio::runtime::Handle::current().block_on(writer.insert(&event("firefox", 1)))?;
+                let after = snapshot.window_events(start, end, crate::models::SortOrder::Asc)?;
+                Ok((before.len(), after.len()))
+            })
+            .await
+            .unwrap();
+        assert_eq!((before, after), (1, 1));
+
+        // 快照结束后可以看到新写入的事件
+        assert_eq!(
+            repo.window_events()
+                .get_by_time_range(start, end)
+                .await
+                .unwrap()
+                .len(),
+            2
+        );
+
+        drop(repo);
+        for suffix in ["", "-wal", "-shm"] {
+            let _ = std::fs::remove_file(format!("{}{}", path.display(), suffix));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_period_queries_reject_invalid_dates() {
+        let usage = Repository::with_pool(pool::create_memory_pool()).usage_service();
+
+        assert!(matches!(
+            usage.get_weekly_usage(2024, 13).await,
+            Err(crate::errors::DbError::Validation(_))
+        ));
+        assert!(matches!(
+            usage.get_hourly_usage(2024, 2, 30).await,
+            Err(crate::errors::DbError::Validation(_))
+        ));
+    }
+
+    #[cfg(feature = "raw-sql")]
+    #[tokio::test]
+    async fn test_query_raw_count() {
+        let repo = Repository::with_pool(pool::create_memory_pool());
+        for app_name in ["firefox", "code", "kitty"] {
+            repo.window_events()
+                .insert(&crate::models::WindowEvent {
+                    id: None,
+                    timestamp: chrono::Utc::now(),
+                    app_name: app_name.to_string(),
+                    window_title: String::new(),
+                    workspace: String::new(),
+                    duration_secs: 10,
+                    is_afk: false,
+                })
+                .await
+                .unwrap();
+        }
+
+        let rows = repo
+            .query_raw(
+                "SELECT COUNT(*) FROM window_events WHERE duration_secs >= ?1",
+                &[&10],
+            )
+            .unwrap();
+        assert_eq!(rows, vec![vec![rusqlite::types::Value::Integer(3)]]);
+    }
+
+    #[cfg(feature = "raw-sql")]
+    #[test]
+    fn test_explain_uses_app_timestamp_index() {
+        let repo = Repository::with_pool(pool::create_memory_pool());
+        let plan = repo
+            .explain(
+                "SELECT COALESCE(SUM(duration_secs), 0) FROM window_events
+                 WHERE app_name = ?1 AND timestamp >= ?2",
+            )
+            .unwrap();
+        assert!(
+            plan.iter()
+                .any(|line| line.contains("idx_window_events_app_timestamp")),
+            "{:?}",
+            plan
+        );
+    }
+}
@@ -2,6 +2,8 @@
 
 use r2d2::Pool;
 use r2d2_sqlite::SqliteConnectionManager;
+use std::path::Path;
+use std::time::Duration;
 use tracing::info;
 
 use crate::errors::DbResult;
@@ -34,6 +36,9 @@ impl Default for DbConfig {
 pub fn create_pool(config: &DbConfig) -> DbResult<DbPool> {
     info!("正在初始化数据库连接池，路径: {}", config.path);
 
+    // 先直接打开一次：路径无法打开时 r2d2 会一直重试到 connection_timeout（默认 30 秒）才报错
+    rusqlite::Connection::open(&config.path)?;
+
     let manager = SqliteConnectionManager::file(&config.path);
     let pool = Pool::builder().max_size(10).build(manager)?;
 
@@ -41,6 +46,74 @@ pub fn create_pool(config: &DbConfig) -> DbResult<DbPool> {
     Ok(pool)
 }
 
+/// 数据库健康状态
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DbHealth {
+    /// 数据库是否可用
+    pub ok: bool,
+    /// 不可用时的错误详情
+    pub detail: Option<String>,
+}
+
+impl DbHealth {
+    /// 健康状态
+    pub fn healthy() -> Self {
+        Self {
+            ok: true,
+            detail: None,
+        }
+    }
+
+    /// 不健康状态，附带错误详情
+    pub fn unhealthy(detail: impl Into<String>) -> Self {
+        Self {
+            ok: false,
+            detail: Some(detail.into()),
+        }
+    }
+}
+
+/// 检查数据库是否可用（从连接池获取连接并执行 `SELECT 1`）
+pub fn check_health(pool: &DbPool) -> DbHealth {
+    let ping = || -> DbResult<()> {
+        let conn = pool.get()?;
+        conn.query_row("SELECT 1", [], |row| row.get::<_, i64>(0))?;
+        Ok(())
+    };
+
+    match ping() {
+        Ok(()) => DbHealth::healthy(),
+        Err(e) => DbHealth::unhealthy(e.to_string()),
+    }
+}
+
+/// 使用 SQLite 在线备份 API 把数据库复制到 `path`（目标文件已存在时会被覆盖）
+///
+/// 按批复制数据页并在批次之间短暂休眠，备份期间写入方只会被短暂阻塞；
+/// 备份过程中发生的写入会让备份重新开始，保证结果是一致的快照。
+pub fn backup_to(pool: &DbPool, path: &Path) -> DbResult<()> {
+    let conn = pool.get()?;
+    let mut dest = rusqlite::Connection::open(path)?;
+    let backup = rusqlite::backup::Backup::new(&conn, &mut dest)?;
+    backup.run_to_completion(256, Duration::from_millis(5), None)?;
+
+    info!("数据库已备份到: {}", path.display());
+    Ok(())
+}
+
+/// 创建已初始化 schema 的内存数据库连接池（测试用）
+///
+/// 只保留一个连接，保证所有操作共享同一个内存库。
+#[cfg(test)]
+pub(crate) fn create_memory_pool() -> DbPool {
+    let pool = Pool::builder()
+        .max_size(1)
+        .build(SqliteConnectionManager::memory())
+        .expect("Failed to create in-memory pool");
+    init_schema(&pool).expect("Failed to init schema");
+    pool
+}
+
 /// 初始化数据库 schema
 pub fn init_schema(pool: &DbPool) -> DbResult<()> {
     let conn = pool.get()?;
@@ -70,6 +143,12 @@ pub fn init_schema(pool: &DbPool) -> DbResult<()> {
          ON window_events(app_name)",
         [],
     )?;
+    // 按应用 + 时间范围查询的复合索引
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_window_events_app_timestamp
+         ON window_events(app_name, timestamp)",
+        [],
+    )?;
 
     // AFK 事件表
     conn.execute(
@@ -93,6 +172,18 @@ pub fn init_schema(pool: &DbPool) -> DbResult<()> {
         [],
     )?;
 
+    // 每日目标历史表（max_minutes 为 NULL 表示自该日起目标被删除）
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS daily_goal_history (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            app_name TEXT NOT NULL,
+            max_minutes INTEGER,
+            effective_from DATE NOT NULL,
+            UNIQUE(app_name, effective_from)
+        )",
+        [],
+    )?;
+
     // 分类表
     conn.execute(
         "CREATE TABLE IF NOT EXISTS categories (
@@ -100,10 +191,18 @@ pub fn init_schema(pool: &DbPool) -> DbResult<()> {
             name TEXT NOT NULL UNIQUE,
             icon TEXT NOT NULL,
             color TEXT,
+            archived BOOLEAN NOT NULL DEFAULT 0,
             created_at DATETIME DEFAULT CURRENT_TIMESTAMP
         )",
         [],
     )?;
+    // 旧版本数据库的分类表没有 archived 列
+    add_column_if_missing(
+        &conn,
+        "categories",
+        "archived",
+        "BOOLEAN NOT NULL DEFAULT 0",
+    )?;
 
     // 应用-分类关联表
     conn.execute(
@@ -129,6 +228,79 @@ pub fn init_schema(pool: &DbPool) -> DbResult<()> {
         [],
     )?;
 
+    // 保存的视图（筛选预设）表，config_json 为序列化的 ViewSpec
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS saved_views (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            name TEXT NOT NULL UNIQUE,
+            config_json TEXT NOT NULL,
+            created_at DATETIME DEFAULT CURRENT_TIMESTAMP
+        )",
+        [],
+    )?;
+
+    // 应用颜色表（图表中为应用指定的颜色，#RRGGBB）
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS app_colors (
+            app_name TEXT PRIMARY KEY,
+            color TEXT NOT NULL
+        )",
+        [],
+    )?;
+
+    // 每日应用使用汇总表（可选的预计算数据，随时可由原始事件重建）
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS daily_app_rollup (
+            date TEXT NOT NULL,
+            app_name TEXT NOT NULL,
+            total_seconds INTEGER NOT NULL,
+            PRIMARY KEY (date, app_name)
+        )",
+        [],
+    )?;
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS daily_app_rollup_days (
+            date TEXT PRIMARY KEY
+        )",
+        [],
+    )?;
+    // 窗口事件的任何写入（迟到的插入、时长更新、拆分、去重、删除等）都让所在本地日期的汇总失效，
+    // 失效的日期回退为从原始事件统计，直到重新汇总
+    conn.execute(
+        "CREATE TRIGGER IF NOT EXISTS window_events_rollup_insert
+         AFTER INSERT ON window_events
+         BEGIN
+            DELETE FROM daily_app_rollup_days WHERE date = date(NEW.timestamp, 'localtime');
+         END",
+        [],
+    )?;
+    conn.execute(
+        "CREATE TRIGGER IF NOT EXISTS window_events_rollup_update
+         AFTER UPDATE ON window_events
+         BEGIN
+            DELETE FROM daily_app_rollup_days
+            WHERE date IN (date(OLD.timestamp, 'localtime'), date(NEW.timestamp, 'localtime'));
+         END",
+        [],
+    )?;
+    conn.execute(
+        "CREATE TRIGGER IF NOT EXISTS window_events_rollup_delete
+         AFTER DELETE ON window_events
+         BEGIN
+            DELETE FROM daily_app_rollup_days WHERE date = date(OLD.timestamp, 'localtime');
+         END",
+        [],
+    )?;
+
+    // 设置表（键值对）
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS settings (
+            key TEXT PRIMARY KEY,
+            value TEXT NOT NULL
+        )",
+        [],
+    )?;
+
     // 索引
     conn.execute(
         "CREATE INDEX IF NOT EXISTS idx_app_categories_app ON app_categories(app_name)",
@@ -142,3 +314,128 @@ pub fn init_schema(pool: &DbPool) -> DbResult<()> {
     info!("数据库 schema 初始化完成");
     Ok(())
 }
+
+/// 列不存在时为表添加该列
+fn add_column_if_missing(
+    conn: &rusqlite::Connection,
+    table: &str,
+    column: &str,
+    definition: &str,
+) -> DbResult<()> {
+    let exists: bool = conn.query_row(
+        &format!(
+            "SELECT EXISTS(SELECT 1 FROM pragma_table_info('{}') WHERE name = ?1)",
+            table
+        ),
+        [column],
+        |row| row.get(0),
+    )?;
+
+    if !exists {
+        conn.execute(
+            &format!("ALTER TABLE {} ADD COLUMN {} {}", table, column, definition),
+            [],
+        )?;
+        info!(table, column, "已添加缺失的列");
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_health_ok() {
+        let pool = create_memory_pool();
+        assert_eq!(check_health(&pool), DbHealth::healthy());
+    }
+
+    #[test]
+    fn test_app_timestamp_index_exists() {
+        let pool = create_memory_pool();
+        // 重复初始化不应失败
+        init_schema(&pool).unwrap();
+
+        let conn = pool.get().unwrap();
+        let columns: Vec<String> = conn
+            .prepare("SELECT name FROM pragma_index_info('idx_window_events_app_timestamp')")
+            .unwrap()
+            .query_map([], |row| row.get(0))
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(columns, vec!["app_name", "timestamp"]);
+
+        let exists: bool = conn
+            .query_row(
+                "SELECT EXISTS(SELECT 1 FROM sqlite_master
+                 WHERE type = 'index' AND name = 'idx_window_events_app_timestamp')",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert!(exists);
+    }
+
+    #[test]
+    fn test_create_pool_fails_fast_on_unopenable_path() {
+        let started = std::time::Instant::now();
+        let result = create_pool(&DbConfig {
+            path: "/nonexistent/tail/tail.db".to_string(),
+        });
+        assert!(result.is_err());
+        assert!(started.elapsed() < std::time::Duration::from_secs(5));
+    }
+
+    #[test]
+    fn test_check_health_unavailable_pool() {
+        let pool = Pool::builder()
+            .max_size(1)
+            .connection_timeout(std::time::Duration::from_millis(50))
+            .build(SqliteConnectionManager::memory())
+            .unwrap();
+
+        // 占用唯一的连接，模拟数据库不可用
+        let _held = pool.get().unwrap();
+        let health = check_health(&pool);
+        assert!(!health.ok);
+        assert!(health.detail.is_some());
+    }
+
+    #[test]
+    fn test_init_schema_adds_archived_to_legacy_categories() {
+        let pool = Pool::builder()
+            .max_size(1)
+            .build(SqliteConnectionManager::memory())
+            .unwrap();
+        pool.get()
+            .unwrap()
+            .execute(
+                "CREATE TABLE categories (
+                    id INTEGER PRIMARY KEY AUTOINCREMENT,
+                    name TEXT NOT NULL UNIQUE,
+                    icon TEXT NOT NULL,
+                    color TEXT,
+                    created_at DATETIME DEFAULT CURRENT_TIMESTAMP
+                )",
+                [],
+            )
+            .unwrap();
+
+        init_schema(&pool).unwrap();
+        init_schema(&pool).unwrap();
+
+        let archived: i64 = pool
+            .get()
+            .unwrap()
+            .query_row(
+                "SELECT COUNT(*) FROM pragma_table_info('categories') WHERE name = 'archived'",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(archived, 1);
+    }
+}
@@ -1,23 +1,28 @@
 //! 应用使用查询实现
 
 use crate::db::pool::DbPool;
-use crate::db::repositories::WindowEventRepositoryImpl;
+use crate::db::repositories::{AfkEventRepositoryImpl, WindowEventRepositoryImpl};
 use crate::errors::{DbError, DbResult};
-use crate::models::AppUsage;
+use crate::models::{AfkEvent, AppUsage, AppUsageWithAfk, SortOrder, WindowEvent};
+use crate::time::range::TimeRangeCalculator;
 use crate::traits::AppUsageQuery;
+use crate::utils::normalize::NameNormalizer;
 use async_trait::async_trait;
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, Local, NaiveDate, Utc};
+use std::collections::{BTreeMap, HashMap};
 use std::sync::Arc;
 
 /// 应用使用查询实现
 pub struct AppUsageQueryImpl {
     window_event_repo: WindowEventRepositoryImpl,
+    afk_event_repo: AfkEventRepositoryImpl,
 }
 
 impl AppUsageQueryImpl {
     pub fn new(pool: Arc<DbPool>) -> Self {
         Self {
             window_event_repo: WindowEventRepositoryImpl::new((*pool).clone()),
+            afk_event_repo: AfkEventRepositoryImpl::new((*pool).clone()),
         }
     }
 
@@ -26,12 +31,174 @@ impl AppUsageQueryImpl {
         start: DateTime<Utc>,
         end: DateTime<Utc>,
     ) -> DbResult<Vec<AppUsage>> {
-        use crate::models::WindowEvent;
-
         // 获取所有窗口事件
         let events = self.window_event_repo.get_by_time_range_sync(start, end)?;
 
-        // 按应用名称分组并计算总时长
+        Ok(Self::aggregate_events(events))
+    }
+
+    fn get_app_usage_capped_sync(
+        &self,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+        max_events: usize,
+    ) -> DbResult<(Vec<AppUsage>, bool)> {
+        let (events, truncated) = self.window_event_repo.get_by_time_range_capped_sync(
+            start,
+            end,
+            SortOrder::Asc,
+            max_events,
+        )?;
+
+        Ok((Self::aggregate_events(events), truncated))
+    }
+
+    fn get_app_usage_normalized_sync(
+        &self,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+        normalizer: &NameNormalizer,
+    ) -> DbResult<Vec<AppUsage>> {
+        let events = self
+            .window_event_repo
+            .get_by_time_range_sync(start, end)?
+            .into_iter()
+            .map(|mut event| {
+                event.app_name = normalizer.canonical(&event.app_name);
+                event
+            })
+            .collect();
+
+        Ok(Self::aggregate_events(events))
+    }
+
+    fn get_app_usage_for_dates_sync(&self, dates: &[NaiveDate]) -> DbResult<Vec<AppUsage>> {
+        if dates.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        // 去重后逐日查询，避免重复日期导致时长被重复累加
+        let mut days = dates.to_vec();
+        days.sort();
+        days.dedup();
+
+        let mut events = Vec::new();
+        for date in days {
+            let range = TimeRangeCalculator::day(date);
+            events.extend(
+                self.window_event_repo
+                    .get_by_time_range_sync(range.start, range.end)?,
+            );
+        }
+
+        Ok(Self::aggregate_events(events))
+    }
+
+    fn get_daily_top_app_sync(
+        &self,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> DbResult<Vec<(NaiveDate, String, i64)>> {
+        let events = self.window_event_repo.get_by_time_range_sync(start, end)?;
+
+        // 按本地日期分组，统计每天各应用的时长（不计 AFK）
+        let mut days: BTreeMap<NaiveDate, HashMap<String, i64>> = BTreeMap::new();
+        for event in events.into_iter().filter(|e| !e.is_afk) {
+            let date = event.timestamp.with_timezone(&Local).date_naive();
+            *days
+                .entry(date)
+                .or_default()
+                .entry(event.app_name)
+                .or_insert(0) += event.duration_secs;
+        }
+
+        Ok(days
+            .into_iter()
+            .filter_map(|(date, apps)| {
+                // 时长相同时按应用名称字母序取第一个
+                apps.into_iter()
+                    .min_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)))
+                    .map(|(app_name, seconds)| (date, app_name, seconds))
+            })
+            .collect())
+    }
+
+    fn get_app_usage_with_afk_sync(
+        &self,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> DbResult<Vec<AppUsageWithAfk>> {
+        let events = self.window_event_repo.get_by_time_range_sync(start, end)?;
+        let sessions = self.afk_event_repo.get_overlapping_sync(start, end)?;
+
+        Ok(Self::split_afk(&events, &sessions, end))
+    }
+
+    /// 把每个应用的时长拆分为活跃时长和与 AFK 会话重叠的时长
+    ///
+    /// 尚未结束的 AFK 会话视为持续到 `open_end`；相互重叠的会话先合并，避免重复计算。
+    /// 结果按活跃时长降序、应用名称升序排列。
+    fn split_afk(
+        events: &[WindowEvent],
+        sessions: &[AfkEvent],
+        open_end: DateTime<Utc>,
+    ) -> Vec<AppUsageWithAfk> {
+        let mut intervals: Vec<(DateTime<Utc>, DateTime<Utc>)> = sessions
+            .iter()
+            .map(|s| (s.start_time, s.end_time.unwrap_or(open_end)))
+            .filter(|(start, end)| start < end)
+            .collect();
+        intervals.sort();
+        let mut merged: Vec<(DateTime<Utc>, DateTime<Utc>)> = Vec::new();
+        for (start, end) in intervals {
+            match merged.last_mut() {
+                Some(last) if start <= last.1 => last.1 = last.1.max(end),
+                _ => merged.push((start, end)),
+            }
+        }
+
+        let mut apps: HashMap<String, (i64, i64)> = HashMap::new();
+        for event in events {
+            let entry = apps.entry(event.app_name.clone()).or_insert((0, 0));
+            if event.is_afk {
+                entry.1 += event.duration_secs;
+                continue;
+            }
+
+            let event_start = event.timestamp;
+            let event_end = event_start + chrono::Duration::seconds(event.duration_secs);
+            let overlap: i64 = merged
+                .iter()
+                .map(|&(afk_start, afk_end)| {
+                    let from = afk_start.max(event_start);
+                    let to = afk_end.min(event_end);
+                    (to - from).num_seconds().max(0)
+                })
+                .sum();
+            entry.0 += event.duration_secs - overlap;
+            entry.1 += overlap;
+        }
+
+        let mut usages: Vec<AppUsageWithAfk> = apps
+            .into_iter()
+            .map(
+                |(app_name, (active_seconds, afk_seconds))| AppUsageWithAfk {
+                    app_name,
+                    active_seconds,
+                    afk_seconds,
+                },
+            )
+            .collect();
+        usages.sort_by(|a, b| {
+            b.active_seconds
+                .cmp(&a.active_seconds)
+                .then_with(|| a.app_name.cmp(&b.app_name))
+        });
+        usages
+    }
+
+    /// 按应用名称分组并计算总时长，结果按总时长降序、应用名称升序排列
+    fn aggregate_events(events: Vec<WindowEvent>) -> Vec<AppUsage> {
         let mut app_map: std::collections::HashMap<String, (i64, Vec<WindowEvent>)> =
             std::collections::HashMap::new();
 
@@ -53,9 +220,84 @@ impl AppUsageQueryImpl {
             })
             .collect();
 
-        usages.sort_by(|a, b| b.total_seconds.cmp(&a.total_seconds));
+        usages.sort_by(|a, b| {
+            b.total_seconds
+                .cmp(&a.total_seconds)
+                .then_with(|| a.app_name.cmp(&b.app_name))
+        });
+
+        usages
+    }
+
+    /// 获取应用使用统计，最多读取 `max_events` 个窗口事件，返回 `(统计, 是否被截断)`
+    ///
+    /// 截断时只统计时间范围内最早的 `max_events` 个事件。
+    pub async fn get_app_usage_capped(
+        &self,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+        max_events: usize,
+    ) -> DbResult<(Vec<AppUsage>, bool)> {
+        let query = self.clone();
+        tokio::task::spawn_blocking(move || query.get_app_usage_capped_sync(start, end, max_events))
+            .await
+            .map_err(|e| DbError::Validation(format!("Task join error: {}", e)))?
+    }
+
+    /// 获取应用使用统计，分组前先用 `normalizer` 把应用名称转换为规范名称
+    pub async fn get_app_usage_normalized(
+        &self,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+        normalizer: &NameNormalizer,
+    ) -> DbResult<Vec<AppUsage>> {
+        let query = self.clone();
+        let normalizer = normalizer.clone();
+        tokio::task::spawn_blocking(move || {
+            query.get_app_usage_normalized_sync(start, end, &normalizer)
+        })
+        .await
+        .map_err(|e| DbError::Validation(format!("Task join error: {}", e)))?
+    }
+
+    /// 获取若干个（可不连续的）本地日期内的应用使用统计
+    ///
+    /// 空日期列表直接返回空结果，不执行查询。
+    pub async fn get_app_usage_for_dates(&self, dates: &[NaiveDate]) -> DbResult<Vec<AppUsage>> {
+        let query = self.clone();
+        let dates = dates.to_vec();
+        tokio::task::spawn_blocking(move || query.get_app_usage_for_dates_sync(&dates))
+            .await
+            .map_err(|e| DbError::Validation(format!("Task join error: {}", e)))?
+    }
+
+    /// 获取应用使用统计，并把每个应用的时长拆分为活跃时长和 AFK 时长
+    ///
+    /// 与 AFK 会话重叠的事件时长计入 `afk_seconds`，规则见 `AppUsageWithAfk`。
+    pub async fn get_app_usage_with_afk(
+        &self,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> DbResult<Vec<AppUsageWithAfk>> {
+        let query = self.clone();
+        tokio::task::spawn_blocking(move || query.get_app_usage_with_afk_sync(start, end))
+            .await
+            .map_err(|e| DbError::Validation(format!("Task join error: {}", e)))?
+    }
 
-        Ok(usages)
+    /// 获取时间范围内每个本地日期使用时长最多的应用
+    ///
+    /// 返回 `(日期, 应用名称, 秒数)`，按日期升序，只包含有数据的日期；
+    /// 时长相同时按应用名称字母序选取。
+    pub async fn get_daily_top_app(
+        &self,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> DbResult<Vec<(NaiveDate, String, i64)>> {
+        let query = self.clone();
+        tokio::task::spawn_blocking(move || query.get_daily_top_app_sync(start, end))
+            .await
+            .map_err(|e| DbError::Validation(format!("Task join error: {}", e)))?
     }
 }
 
@@ -77,6 +319,176 @@ impl Clone for AppUsageQueryImpl {
     fn clone(&self) -> Self {
         Self {
             window_event_repo: self.window_event_repo.clone(),
+            afk_event_repo: self.afk_event_repo.clone(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::pool::create_memory_pool;
+    use crate::traits::WindowEventRepository;
+
+    fn event(app_name: &str, date: NaiveDate, duration_secs: i64) -> WindowEvent {
+        WindowEvent {
+            id: None,
+            timestamp: TimeRangeCalculator::day(date).start + chrono::Duration::hours(10),
+            app_name: app_name.to_string(),
+            window_title: String::new(),
+            workspace: String::new(),
+            duration_secs,
+            is_afk: false,
         }
     }
+
+    #[tokio::test]
+    async fn test_get_app_usage_for_dates_only_selected_days() {
+        let pool = create_memory_pool();
+        let repo = WindowEventRepositoryImpl::new(pool.clone());
+        let query = AppUsageQueryImpl::new(Arc::new(pool));
+
+        let day1 = NaiveDate::from_ymd_opt(2024, 3, 1).unwrap();
+        let day2 = NaiveDate::from_ymd_opt(2024, 3, 2).unwrap();
+        let day3 = NaiveDate::from_ymd_opt(2024, 3, 3).unwrap();
+
+        repo.insert(&event("firefox", day1, 100)).await.unwrap();
+        repo.insert(&event("firefox", day2, 1000)).await.unwrap();
+        repo.insert(&event("code", day2, 500)).await.unwrap();
+        repo.insert(&event("firefox", day3, 50)).await.unwrap();
+
+        let usages = query.get_app_usage_for_dates(&[day3, day1]).await.unwrap();
+        assert_eq!(usages.len(), 1);
+        assert_eq!(usages[0].app_name, "firefox");
+        assert_eq!(usages[0].total_seconds, 150);
+        assert_eq!(usages[0].window_events.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_get_app_usage_for_dates_empty_input() {
+        let query = AppUsageQueryImpl::new(Arc::new(create_memory_pool()));
+        assert!(query.get_app_usage_for_dates(&[]).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_get_daily_top_app() {
+        let pool = create_memory_pool();
+        let repo = WindowEventRepositoryImpl::new(pool.clone());
+        let query = AppUsageQueryImpl::new(Arc::new(pool));
+
+        let day1 = NaiveDate::from_ymd_opt(2024, 3, 1).unwrap();
+        let day2 = NaiveDate::from_ymd_opt(2024, 3, 2).unwrap();
+        let day3 = NaiveDate::from_ymd_opt(2024, 3, 3).unwrap();
+
+        repo.insert(&event("firefox", day1, 900)).await.unwrap();
+        repo.insert(&event("code", day1, 300)).await.unwrap();
+        repo.insert(&event("code", day1, 300)).await.unwrap();
+        repo.insert(&event("firefox", day2, 200)).await.unwrap();
+        repo.insert(&event("code", day2, 1200)).await.unwrap();
+        // 时长相同按名称排序
+        repo.insert(&event("zed", day3, 400)).await.unwrap();
+        repo.insert(&event("alacritty", day3, 400)).await.unwrap();
+
+        let range_start = TimeRangeCalculator::day(day1).start;
+        let range_end = TimeRangeCalculator::day(day3).end;
+        let winners = query
+            .get_daily_top_app(range_start, range_end)
+            .await
+            .unwrap();
+
+        assert_eq!(
+            winners,
+            vec![
+                (day1, "firefox".to_string(), 900),
+                (day2, "code".to_string(), 1200),
+                (day3, "alacritty".to_string(), 400),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_get_app_usage_normalized_merges_variants() {
+        let pool = create_memory_pool();
+        let repo = WindowEventRepositoryImpl::new(pool.clone());
+        let query = AppUsageQueryImpl::new(Arc::new(pool));
+
+        let day = NaiveDate::from_ymd_opt(2024, 3, 1).unwrap();
+        repo.insert(&event("Firefox", day, 100)).await.unwrap();
+        repo.insert(&event("firefox.desktop", day, 200))
+            .await
+            .unwrap();
+        repo.insert(&event("code", day, 50)).await.unwrap();
+
+        let range = TimeRangeCalculator::day(day);
+        let usages = query
+            .get_app_usage_normalized(range.start, range.end, &NameNormalizer::default())
+            .await
+            .unwrap();
+        assert_eq!(usages.len(), 2);
+        assert_eq!(usages[0].app_name, "firefox");
+        assert_eq!(usages[0].total_seconds, 300);
+    }
+
+    #[tokio::test]
+    async fn test_get_app_usage_with_afk() {
+        use crate::traits::AfkEventRepository;
+
+        let pool = create_memory_pool();
+        let repo = WindowEventRepositoryImpl::new(pool.clone());
+        let afk = AfkEventRepositoryImpl::new(pool.clone());
+        let query = AppUsageQueryImpl::new(Arc::new(pool));
+
+        let day = NaiveDate::from_ymd_opt(2024, 3, 1).unwrap();
+        // firefox: 10:00 - 10:30，AFK 会话 10:20 - 10:40 与其重叠 10 分钟
+        repo.insert(&event("firefox", day, 1800)).await.unwrap();
+        let ten = TimeRangeCalculator::day(day).start + chrono::Duration::hours(10);
+        afk.insert(&AfkEvent {
+            id: None,
+            start_time: ten + chrono::Duration::minutes(20),
+            end_time: Some(ten + chrono::Duration::minutes(40)),
+            duration_secs: 1200,
+        })
+        .await
+        .unwrap();
+        // code: 10:40 - 10:50，不与 AFK 会话重叠
+        let mut code = event("code", day, 600);
+        code.timestamp = ten + chrono::Duration::minutes(40);
+        repo.insert(&code).await.unwrap();
+
+        let range = TimeRangeCalculator::day(day);
+        let usages = query
+            .get_app_usage_with_afk(range.start, range.end)
+            .await
+            .unwrap();
+        assert_eq!(
+            usages,
+            vec![
+                AppUsageWithAfk {
+                    app_name: "firefox".to_string(),
+                    active_seconds: 1200,
+                    afk_seconds: 600,
+                },
+                AppUsageWithAfk {
+                    app_name: "code".to_string(),
+                    active_seconds: 600,
+                    afk_seconds: 0,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_aggregate_events_ties_sorted_by_name() {
+        let day = NaiveDate::from_ymd_opt(2024, 3, 1).unwrap();
+        let events = ["zed", "kitty", "alacritty", "kitty"]
+            .iter()
+            .map(|app_name| event(app_name, day, 300))
+            .collect();
+
+        let names: Vec<String> = AppUsageQueryImpl::aggregate_events(events)
+            .into_iter()
+            .map(|u| u.app_name)
+            .collect();
+        assert_eq!(names, vec!["kitty", "alacritty", "zed"]);
+    }
 }
@@ -28,6 +28,43 @@ impl CategoryUsageQueryImpl {
     ) -> DbResult<Vec<CategoryUsage>> {
         self.category_repo.get_category_usage_sync(start, end)
     }
+
+    /// 获取分类使用统计及所有分类时长之和
+    ///
+    /// 总时长可直接传给 `CategoryUsage::percentage_of` 计算各分类占比。
+    pub async fn get_category_usage_with_total(
+        &self,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> DbResult<(Vec<CategoryUsage>, i64)> {
+        let usage = self.get_category_usage(start, end).await?;
+        let total = usage.iter().map(|c| c.total_seconds).sum();
+        Ok((usage, total))
+    }
+
+    /// 获取分类使用统计，并在末尾追加一个"未分类"分组，汇总不属于任何分类的应用
+    ///
+    /// 没有未分类的使用记录时不追加。各分组时长之和等于全部应用的非 AFK 总时长
+    /// （同时属于多个分类的应用会在每个分类中各计一次）。
+    pub async fn get_category_usage_with_uncategorized(
+        &self,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> DbResult<Vec<CategoryUsage>> {
+        let query = self.clone();
+        tokio::task::spawn_blocking(move || {
+            let mut usage = query.get_category_usage_sync(start, end)?;
+            let uncategorized = query
+                .category_repo
+                .get_uncategorized_usage_sync(start, end)?;
+            if uncategorized.app_count > 0 {
+                usage.push(uncategorized);
+            }
+            Ok(usage)
+        })
+        .await
+        .map_err(|e| DbError::Validation(format!("Task join error: {}", e)))?
+    }
 }
 
 #[async_trait]
@@ -51,3 +88,72 @@ impl Clone for CategoryUsageQueryImpl {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::pool::create_memory_pool;
+    use crate::db::queries::AppUsageQueryImpl;
+    use crate::db::repositories::WindowEventRepositoryImpl;
+    use crate::models::{Category, WindowEvent};
+    use crate::traits::{AppUsageQuery, CategoryRepository, WindowEventRepository};
+
+    #[tokio::test]
+    async fn test_category_usage_with_uncategorized() {
+        let pool = Arc::new(create_memory_pool());
+        let query = CategoryUsageQueryImpl::new(Arc::clone(&pool));
+        let categories = CategoryRepositoryImpl::new(Arc::clone(&pool));
+        let events = WindowEventRepositoryImpl::new((*pool).clone());
+
+        let id = categories
+            .insert(&Category {
+                id: None,
+                name: "开发".to_string(),
+                icon: "🗀".to_string(),
+                color: None,
+            })
+            .await
+            .unwrap();
+        categories.add_app_to_category("code", id).await.unwrap();
+
+        let start = Utc::now() - chrono::Duration::hours(1);
+        for (minutes, app_name, duration_secs) in [(0, "code", 1200), (20, "mpv", 600)] {
+            events
+                .insert(&WindowEvent {
+                    id: None,
+                    timestamp: start + chrono::Duration::minutes(minutes),
+                    app_name: app_name.to_string(),
+                    window_title: String::new(),
+                    workspace: String::new(),
+                    duration_secs,
+                    is_afk: false,
+                })
+                .await
+                .unwrap();
+        }
+
+        let end = Utc::now();
+        let usage = query
+            .get_category_usage_with_uncategorized(start, end)
+            .await
+            .unwrap();
+        assert_eq!(usage.len(), 2);
+        assert_eq!(usage[0].category.name, "开发");
+        assert_eq!(usage[0].total_seconds, 1200);
+        let uncategorized = &usage[1];
+        assert_eq!(uncategorized.category.id, None);
+        assert_eq!(uncategorized.category.name, Category::UNCATEGORIZED_NAME);
+        assert_eq!(uncategorized.total_seconds, 600);
+        assert_eq!(uncategorized.apps[0].app_name, "mpv");
+
+        let app_total: i64 = AppUsageQueryImpl::new(Arc::clone(&pool))
+            .get_app_usage(start, end)
+            .await
+            .unwrap()
+            .iter()
+            .map(|a| a.total_seconds)
+            .sum();
+        let category_total: i64 = usage.iter().map(|c| c.total_seconds).sum();
+        assert_eq!(category_total, app_total);
+    }
+}
@@ -2,13 +2,24 @@
 
 use crate::db::pool::DbPool;
 use crate::errors::{DbError, DbResult};
-use crate::models::PeriodUsage;
+use crate::models::{PeriodSpec, PeriodUsage};
+use crate::time::range::{TimeRange, TimeRangeCalculator};
+use crate::time::{TimeGranularity, WeekNumbering};
 use crate::traits::TimeStatsQuery;
 use async_trait::async_trait;
-use chrono::{Datelike, Local, NaiveDate, Timelike, Utc};
-use rusqlite::params;
+use chrono::{DateTime, Datelike, Local, NaiveDate, NaiveTime, Utc};
+use rusqlite::{params, Connection};
+use std::collections::HashSet;
 use std::sync::Arc;
 
+/// 分段统计中的一个时间段
+struct PeriodBucket {
+    label: String,
+    index: i32,
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+}
+
 /// 时间统计查询实现
 pub struct TimeStatsQueryImpl {
     pool: Arc<DbPool>,
@@ -19,343 +30,455 @@ impl TimeStatsQueryImpl {
         Self { pool }
     }
 
-    fn get_yearly_usage_sync(&self, years: i32) -> DbResult<Vec<PeriodUsage>> {
-        let conn = self.pool.get()?;
-        let current_year = Local::now().year();
-        let mut result = Vec::new();
-
-        for i in 0..years {
-            let year = current_year - i;
-            let year_start = NaiveDate::from_ymd_opt(year, 1, 1)
-                .unwrap()
-                .and_hms_opt(0, 0, 0)
-                .unwrap()
-                .and_local_timezone(Local)
-                .unwrap()
-                .with_timezone(&Utc);
-            let year_end = NaiveDate::from_ymd_opt(year, 12, 31)
-                .unwrap()
-                .and_hms_opt(23, 59, 59)
-                .unwrap()
-                .and_local_timezone(Local)
-                .unwrap()
-                .with_timezone(&Utc);
-
-            let mut stmt = conn.prepare(
-                "SELECT COALESCE(SUM(duration_secs), 0)
-                 FROM window_events
-                 WHERE timestamp >= ?1 AND timestamp <= ?2 AND is_afk = 0",
-            )?;
-
-            let total: i64 = stmt.query_row(params![year_start, year_end], |row| row.get(0))?;
-
-            result.push(PeriodUsage {
-                label: format!("{}年", year),
-                index: year,
-                total_seconds: total,
-            });
+    /// 指定月份的第一天和最后一天，年月无效时返回校验错误
+    fn month_bounds(year: i32, month: u32) -> DbResult<(NaiveDate, NaiveDate)> {
+        let first_day = NaiveDate::from_ymd_opt(year, month, 1)
+            .ok_or_else(|| DbError::Validation(format!("无效的月份: {}-{}", year, month)))?;
+        let next_month = if month == 12 {
+            NaiveDate::from_ymd_opt(year + 1, 1, 1)
+        } else {
+            NaiveDate::from_ymd_opt(year, month + 1, 1)
         }
-
-        result.reverse();
-        Ok(result)
+        .ok_or_else(|| DbError::Validation(format!("无效的月份: {}-{}", year, month)))?;
+        let last_day = next_month.pred_opt().unwrap_or(first_day);
+        Ok((first_day, last_day))
     }
 
-    fn get_monthly_usage_sync(&self, year: i32) -> DbResult<Vec<PeriodUsage>> {
+    /// 按本地日期统计非 AFK 时长，`app_name` 为 `None` 时统计所有应用
+    fn get_daily_activity_sync(
+        &self,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+        app_name: Option<&str>,
+    ) -> DbResult<Vec<(NaiveDate, i64)>> {
+        let days = TimeRangeCalculator::split(&TimeRange::new(start, end), TimeGranularity::Day);
+        let mut day_seconds = vec![0i64; days.len()];
+
         let conn = self.pool.get()?;
-        let mut result = Vec::new();
-
-        for month in 1..=12 {
-            let month_start = NaiveDate::from_ymd_opt(year, month, 1)
-                .unwrap()
-                .and_hms_opt(0, 0, 0)
-                .unwrap()
-                .and_local_timezone(Local)
-                .unwrap()
-                .with_timezone(&Utc);
-
-            let next_month = if month == 12 {
-                NaiveDate::from_ymd_opt(year + 1, 1, 1)
-            } else {
-                NaiveDate::from_ymd_opt(year, month + 1, 1)
+        let mut stmt = conn.prepare(
+            "SELECT timestamp, duration_secs
+             FROM window_events
+             WHERE timestamp >= ?1 AND timestamp < ?2 AND is_afk = 0
+               AND (?3 IS NULL OR app_name = ?3)",
+        )?;
+        let rows = stmt.query_map(params![start, end, app_name], |row| {
+            Ok((row.get::<_, DateTime<Utc>>(0)?, row.get::<_, i64>(1)?))
+        })?;
+
+        for row in rows {
+            let (timestamp, duration_secs) = row?;
+            if let Some(idx) = days.iter().rposition(|d| d.start <= timestamp) {
+                day_seconds[idx] += duration_secs;
             }
-            .unwrap();
-            let last_day = next_month.pred_opt().unwrap();
-
-            let month_end = last_day
-                .and_hms_opt(23, 59, 59)
-                .unwrap()
-                .and_local_timezone(Local)
-                .unwrap()
-                .with_timezone(&Utc);
-
-            let mut stmt = conn.prepare(
-                "SELECT COALESCE(SUM(duration_secs), 0)
-                 FROM window_events
-                 WHERE timestamp >= ?1 AND timestamp <= ?2 AND is_afk = 0",
-            )?;
-
-            let total: i64 = stmt.query_row(params![month_start, month_end], |row| row.get(0))?;
-
-            result.push(PeriodUsage {
-                label: format!("{}月", month),
-                index: month as i32,
-                total_seconds: total,
-            });
         }
 
-        Ok(result)
+        Ok(days
+            .iter()
+            .map(|day| day.start.with_timezone(&Local).date_naive())
+            .zip(day_seconds)
+            .collect())
     }
 
-    fn get_weekly_usage_sync(&self, year: i32, month: u32) -> DbResult<Vec<PeriodUsage>> {
-        let conn = self.pool.get()?;
-        let mut result = Vec::new();
+    /// 获取时间范围内每个本地日期的活跃时长（秒，不含 AFK）
+    ///
+    /// 范围内的每一天都有一项，没有数据的日期为 0，按日期升序排列。
+    pub async fn get_daily_activity(
+        &self,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> DbResult<Vec<(NaiveDate, i64)>> {
+        let query = self.clone();
+        tokio::task::spawn_blocking(move || query.get_daily_activity_sync(start, end, None))
+            .await
+            .map_err(|e| DbError::Validation(format!("Task join error: {}", e)))?
+    }
 
-        let first_day = NaiveDate::from_ymd_opt(year, month, 1).unwrap();
-        let next_month = if month == 12 {
-            NaiveDate::from_ymd_opt(year + 1, 1, 1)
-        } else {
-            NaiveDate::from_ymd_opt(year, month + 1, 1)
-        }
-        .unwrap();
-        let last_day = next_month.pred_opt().unwrap();
+    /// 获取某个应用在时间范围内每个本地日期的使用时长（秒，不含 AFK）
+    ///
+    /// 与 [`Self::get_daily_activity`] 相同，范围内的每一天都有一项，按日期升序排列。
+    pub async fn get_app_daily_series(
+        &self,
+        app_name: &str,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> DbResult<Vec<(NaiveDate, i64)>> {
+        let query = self.clone();
+        let app_name = app_name.to_string();
+        tokio::task::spawn_blocking(move || {
+            query.get_daily_activity_sync(start, end, Some(&app_name))
+        })
+        .await
+        .map_err(|e| DbError::Validation(format!("Task join error: {}", e)))?
+    }
 
-        let mut week_num = 1;
-        let mut current_day = first_day;
+    /// 统计应用在时间范围内达成每日上限的天数，返回 `(达成天数, 有使用记录的天数)`
+    ///
+    /// 只统计该应用有使用记录的本地日期；当天总时长不超过 `cap_secs` 即视为达成。
+    pub async fn get_goal_adherence(
+        &self,
+        app_name: &str,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+        cap_secs: i64,
+    ) -> DbResult<(u32, u32)> {
+        let daily = self.get_app_daily_series(app_name, start, end).await?;
+        let (mut met, mut total) = (0, 0);
+        for (_, seconds) in daily.into_iter().filter(|&(_, seconds)| seconds > 0) {
+            total += 1;
+            if seconds <= cap_secs {
+                met += 1;
+            }
+        }
+        Ok((met, total))
+    }
 
-        while current_day <= last_day {
-            let week_start = current_day;
-            let mut week_end = current_day;
+    fn get_daily_app_diversity_sync(
+        &self,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> DbResult<Vec<(NaiveDate, usize)>> {
+        let days = TimeRangeCalculator::split(&TimeRange::new(start, end), TimeGranularity::Day);
+        let mut day_apps: Vec<HashSet<String>> = vec![HashSet::new(); days.len()];
 
-            while week_end.weekday() != chrono::Weekday::Sun && week_end < last_day {
-                week_end = week_end.succ_opt().unwrap();
+        let conn = self.pool.get()?;
+        let mut stmt = conn.prepare(
+            "SELECT DISTINCT timestamp, app_name
+             FROM window_events
+             WHERE timestamp >= ?1 AND timestamp < ?2 AND is_afk = 0 AND app_name != ''",
+        )?;
+        let rows = stmt.query_map(params![start, end], |row| {
+            Ok((row.get::<_, DateTime<Utc>>(0)?, row.get::<_, String>(1)?))
+        })?;
+
+        for row in rows {
+            let (timestamp, app_name) = row?;
+            if let Some(idx) = days.iter().rposition(|d| d.start <= timestamp) {
+                day_apps[idx].insert(app_name);
             }
-
-            let start_dt = week_start
-                .and_hms_opt(0, 0, 0)
-                .unwrap()
-                .and_local_timezone(Local)
-                .unwrap()
-                .with_timezone(&Utc);
-            let end_dt = week_end
-                .and_hms_opt(23, 59, 59)
-                .unwrap()
-                .and_local_timezone(Local)
-                .unwrap()
-                .with_timezone(&Utc);
-
-            let mut stmt = conn.prepare(
-                "SELECT COALESCE(SUM(duration_secs), 0)
-                 FROM window_events
-                 WHERE timestamp >= ?1 AND timestamp <= ?2 AND is_afk = 0",
-            )?;
-
-            let total: i64 = stmt.query_row(params![start_dt, end_dt], |row| row.get(0))?;
-
-            result.push(PeriodUsage {
-                label: format!("第{}周", week_num),
-                index: week_num,
-                total_seconds: total,
-            });
-
-            current_day = week_end.succ_opt().unwrap();
-            week_num += 1;
         }
 
-        Ok(result)
+        Ok(days
+            .iter()
+            .map(|day| day.start.with_timezone(&Local).date_naive())
+            .zip(day_apps.iter().map(HashSet::len))
+            .collect())
     }
 
-    fn get_daily_usage_for_week_sync(
+    /// 获取时间范围内每个本地日期使用过的不同应用数量（不含 AFK）
+    ///
+    /// 范围内的每一天都有一项，没有数据的日期为 0，按日期升序排列。
+    pub async fn get_daily_app_diversity(
         &self,
-        year: i32,
-        month: u32,
-        week: u32,
-    ) -> DbResult<Vec<PeriodUsage>> {
-        let conn = self.pool.get()?;
-        let mut result = Vec::new();
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> DbResult<Vec<(NaiveDate, usize)>> {
+        let query = self.clone();
+        tokio::task::spawn_blocking(move || query.get_daily_app_diversity_sync(start, end))
+            .await
+            .map_err(|e| DbError::Validation(format!("Task join error: {}", e)))?
+    }
 
-        let first_day = NaiveDate::from_ymd_opt(year, month, 1).unwrap();
-        let next_month = if month == 12 {
-            NaiveDate::from_ymd_opt(year + 1, 1, 1)
-        } else {
-            NaiveDate::from_ymd_opt(year, month + 1, 1)
-        }
-        .unwrap();
-        let last_day = next_month.pred_opt().unwrap();
+    fn count_active_days_sync(&self, start: DateTime<Utc>, end: DateTime<Utc>) -> DbResult<usize> {
+        let conn = self.pool.get()?;
+        let mut stmt = conn.prepare(
+            "SELECT DISTINCT timestamp
+             FROM window_events
+             WHERE timestamp >= ?1 AND timestamp < ?2 AND is_afk = 0",
+        )?;
+        let days = stmt
+            .query_map(params![start, end], |row| row.get::<_, DateTime<Utc>>(0))?
+            .map(|timestamp| timestamp.map(|t| t.with_timezone(&Local).date_naive()))
+            .collect::<Result<HashSet<_>, _>>()?;
+        Ok(days.len())
+    }
 
-        // 找到指定周的开始日期
-        let mut current_day = first_day;
-        let mut current_week = 1u32;
+    /// 统计时间范围内有非 AFK 活动的本地日期数量，用于计算"每个活跃日"的平均值
+    pub async fn count_active_days(
+        &self,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> DbResult<usize> {
+        let query = self.clone();
+        tokio::task::spawn_blocking(move || query.count_active_days_sync(start, end))
+            .await
+            .map_err(|e| DbError::Validation(format!("Task join error: {}", e)))?
+    }
 
-        while current_week < week && current_day <= last_day {
-            while current_day.weekday() != chrono::Weekday::Sun && current_day < last_day {
-                current_day = current_day.succ_opt().unwrap();
-            }
-            current_day = current_day.succ_opt().unwrap_or(current_day);
-            current_week += 1;
+    /// 本地日期 `[first, last]` 对应的时间段，结束边界为 `last` 次日零点（不含）
+    fn day_span(label: String, index: i32, first: NaiveDate, last: NaiveDate) -> PeriodBucket {
+        let midnight = NaiveTime::MIN;
+        PeriodBucket {
+            label,
+            index,
+            start: TimeRangeCalculator::local_to_utc(first.and_time(midnight)),
+            end: TimeRangeCalculator::local_to_utc(
+                (last + chrono::Duration::days(1)).and_time(midnight),
+            ),
         }
+    }
 
-        let weekday_names = ["周一", "周二", "周三", "周四", "周五", "周六", "周日"];
-
-        loop {
-            if current_day > last_day {
-                break;
+    /// 计算查询规格对应的时间段，按时间升序排列
+    ///
+    /// 每段为本地时间对齐的半开区间 [开始, 结束)，相邻时间段首尾相接，不会遗漏最后一秒内的事件。
+    fn period_buckets(spec: PeriodSpec) -> DbResult<Vec<PeriodBucket>> {
+        let buckets = match spec {
+            PeriodSpec::Years(years) => {
+                let current_year = Local::now().year();
+                (0..years.max(0))
+                    .rev()
+                    .map(|offset| {
+                        let year = current_year - offset;
+                        let (first_day, _) = Self::month_bounds(year, 1)?;
+                        let (_, last_day) = Self::month_bounds(year, 12)?;
+                        Ok(Self::day_span(
+                            format!("{}年", year),
+                            year,
+                            first_day,
+                            last_day,
+                        ))
+                    })
+                    .collect::<DbResult<Vec<_>>>()?
             }
-
-            let start_dt = current_day
-                .and_hms_opt(0, 0, 0)
-                .unwrap()
-                .and_local_timezone(Local)
-                .unwrap()
-                .with_timezone(&Utc);
-            let end_dt = current_day
-                .and_hms_opt(23, 59, 59)
-                .unwrap()
-                .and_local_timezone(Local)
-                .unwrap()
-                .with_timezone(&Utc);
-
-            let mut stmt = conn.prepare(
-                "SELECT COALESCE(SUM(duration_secs), 0)
-                 FROM window_events
-                 WHERE timestamp >= ?1 AND timestamp <= ?2 AND is_afk = 0",
-            )?;
-
-            let total: i64 = stmt.query_row(params![start_dt, end_dt], |row| row.get(0))?;
-
-            let weekday_idx = current_day.weekday().num_days_from_monday() as usize;
-            result.push(PeriodUsage {
-                label: weekday_names[weekday_idx].to_string(),
-                index: current_day.day() as i32,
-                total_seconds: total,
-            });
-
-            if current_day.weekday() == chrono::Weekday::Sun || current_day >= last_day {
-                break;
+            PeriodSpec::MonthsOf(year) => (1..=12)
+                .map(|month| {
+                    let (first_day, last_day) = Self::month_bounds(year, month)?;
+                    Ok(Self::day_span(
+                        format!("{}月", month),
+                        month as i32,
+                        first_day,
+                        last_day,
+                    ))
+                })
+                .collect::<DbResult<Vec<_>>>()?,
+            PeriodSpec::WeeksOf {
+                year,
+                month,
+                numbering,
+            } => {
+                Self::month_bounds(year, month)?;
+                numbering
+                    .weeks_in_month(year, month)
+                    .into_iter()
+                    .map(|(week, first, last)| {
+                        Self::day_span(numbering.week_label(week), week as i32, first, last)
+                    })
+                    .collect()
             }
-
-            current_day = current_day.succ_opt().unwrap();
-        }
-
-        Ok(result)
+            PeriodSpec::DaysOf {
+                year,
+                month,
+                week,
+                numbering,
+            } => {
+                if week == 0 {
+                    return Err(DbError::Validation(format!("无效的周: {}", week)));
+                }
+                Self::month_bounds(year, month)?;
+                let weekday_names = ["周一", "周二", "周三", "周四", "周五", "周六", "周日"];
+                match numbering.week_dates(year, month, week) {
+                    Some((first, last)) => first
+                        .iter_days()
+                        .take_while(|d| *d <= last)
+                        .filter(|d| d.month() == month)
+                        .map(|date| {
+                            let weekday =
+                                weekday_names[date.weekday().num_days_from_monday() as usize];
+                            Self::day_span(weekday.to_string(), date.day() as i32, date, date)
+                        })
+                        .collect(),
+                    None => Vec::new(),
+                }
+            }
+            PeriodSpec::HoursOf { year, month, day } => {
+                let date = NaiveDate::from_ymd_opt(year, month, day).ok_or_else(|| {
+                    DbError::Validation(format!("无效的日期: {}-{}-{}", year, month, day))
+                })?;
+                let day_start = date.and_time(NaiveTime::MIN);
+                (0..24)
+                    .map(|hour| PeriodBucket {
+                        label: format!("{}时", hour),
+                        index: hour,
+                        start: TimeRangeCalculator::local_to_utc(
+                            day_start + chrono::Duration::hours(hour as i64),
+                        ),
+                        end: TimeRangeCalculator::local_to_utc(
+                            day_start + chrono::Duration::hours(hour as i64 + 1),
+                        ),
+                    })
+                    .collect()
+            }
+        };
+        Ok(buckets)
     }
 
-    fn get_hourly_usage_sync(&self, year: i32, month: u32, day: u32) -> DbResult<Vec<PeriodUsage>> {
-        let conn = self.pool.get()?;
-        let mut result = Vec::new();
-
-        let date = NaiveDate::from_ymd_opt(year, month, day).unwrap();
+    /// 按事件开始时间把时长计入所在的时间段
+    fn sum_by_start(conn: &Connection, buckets: &[PeriodBucket]) -> DbResult<Vec<i64>> {
+        let mut stmt = conn.prepare(
+            "SELECT COALESCE(SUM(duration_secs), 0)
+             FROM window_events
+             WHERE timestamp >= ?1 AND timestamp < ?2 AND is_afk = 0",
+        )?;
+        buckets
+            .iter()
+            .map(|bucket| Ok(stmt.query_row(params![bucket.start, bucket.end], |row| row.get(0))?))
+            .collect()
+    }
 
-        // 获取该天的所有事件（一次性获取，然后按小时分配）
-        let day_start = date
-            .and_hms_opt(0, 0, 0)
-            .unwrap()
-            .and_local_timezone(Local)
-            .unwrap()
-            .with_timezone(&Utc);
-        let day_end = date
-            .and_hms_opt(23, 59, 59)
-            .unwrap()
-            .and_local_timezone(Local)
-            .unwrap()
-            .with_timezone(&Utc);
+    /// 把事件区间 [开始, 开始 + 时长) 按重叠部分拆分到各时间段
+    ///
+    /// 只统计在第一个时间段开始到最后一个时间段结束之间开始的事件，超出范围的部分不计入。
+    /// `app_name` 不为 `None` 时只统计该应用的事件。
+    fn split_by_overlap(
+        conn: &Connection,
+        buckets: &[PeriodBucket],
+        app_name: Option<&str>,
+    ) -> DbResult<Vec<i64>> {
+        let (Some(first), Some(last)) = (buckets.first(), buckets.last()) else {
+            return Ok(Vec::new());
+        };
 
         let mut stmt = conn.prepare(
             "SELECT timestamp, duration_secs
              FROM window_events
-             WHERE timestamp >= ?1 AND timestamp <= ?2 AND is_afk = 0
-             ORDER BY timestamp",
+             WHERE timestamp >= ?1 AND timestamp < ?2 AND is_afk = 0
+               AND (?3 IS NULL OR app_name = ?3)",
         )?;
-
-        let events: Vec<(chrono::DateTime<Utc>, i64)> = stmt
-            .query_map(params![day_start, day_end], |row| {
-                Ok((
-                    row.get::<_, chrono::DateTime<Utc>>(0)?,
-                    row.get::<_, i64>(1)?,
-                ))
+        let events = stmt
+            .query_map(params![first.start, last.end, app_name], |row| {
+                Ok((row.get::<_, DateTime<Utc>>(0)?, row.get::<_, i64>(1)?))
             })?
             .collect::<Result<Vec<_>, _>>()?;
 
-        // 初始化24小时的槽
-        let mut hour_totals = [0i64; 24];
-
-        // 将每个事件的时长分配到对应的小时槽
-        for (event_timestamp, duration_secs) in events {
-            let start_time = event_timestamp.with_timezone(&Local);
-            let end_time = start_time + chrono::Duration::seconds(duration_secs);
+        let mut totals = vec![0i64; buckets.len()];
+        for (timestamp, duration_secs) in events {
+            let event_end = timestamp + chrono::Duration::seconds(duration_secs);
+            for (total, bucket) in totals.iter_mut().zip(buckets) {
+                let from = timestamp.max(bucket.start);
+                let to = event_end.min(bucket.end);
+                if to > from {
+                    *total += (to - from).num_seconds();
+                }
+            }
+        }
+        Ok(totals)
+    }
 
-            let mut current = start_time;
-            let mut remaining_seconds = duration_secs;
+    fn get_period_usage_sync(&self, spec: PeriodSpec) -> DbResult<Vec<PeriodUsage>> {
+        let buckets = Self::period_buckets(spec)?;
+        let conn = self.pool.get()?;
 
-            // 只处理当天的部分
-            while remaining_seconds > 0 && current.date_naive() == date {
-                let hour = current.hour() as usize;
+        // 小时视图中长事件常跨越多个小时，按重叠拆分；更粗的粒度按开始时间归属即可
+        let totals = match spec {
+            PeriodSpec::HoursOf { .. } => Self::split_by_overlap(&conn, &buckets, None)?,
+            _ => Self::sum_by_start(&conn, &buckets)?,
+        };
+
+        Ok(buckets
+            .into_iter()
+            .zip(totals)
+            .map(|(bucket, total_seconds)| PeriodUsage {
+                label: bucket.label,
+                index: bucket.index,
+                total_seconds,
+            })
+            .collect())
+    }
 
-                if hour >= 24 {
-                    break;
-                }
+    fn get_app_hourly_usage_sync(&self, app_name: &str, date: NaiveDate) -> DbResult<[i64; 24]> {
+        let buckets = Self::period_buckets(PeriodSpec::HoursOf {
+            year: date.year(),
+            month: date.month(),
+            day: date.day(),
+        })?;
+        let conn = self.pool.get()?;
+        let totals = Self::split_by_overlap(&conn, &buckets, Some(app_name))?;
 
-                // 计算当前小时的结束时间
-                let next_hour = current
-                    .with_minute(0)
-                    .and_then(|t| t.with_second(0))
-                    .and_then(|t| t.with_nanosecond(0))
-                    .unwrap()
-                    .checked_add_signed(chrono::Duration::hours(1))
-                    .unwrap();
-
-                // 计算当前小时内的时间
-                let seconds_in_this_hour = if next_hour > end_time {
-                    remaining_seconds
-                } else {
-                    let duration_in_hour = (next_hour - current).num_seconds().max(0);
-                    remaining_seconds.min(duration_in_hour)
-                };
-
-                if hour < 24 && seconds_in_this_hour > 0 {
-                    hour_totals[hour] += seconds_in_this_hour;
-                }
+        let mut hours = [0i64; 24];
+        hours.copy_from_slice(&totals);
+        Ok(hours)
+    }
 
-                remaining_seconds -= seconds_in_this_hour;
-                current = next_hour;
-            }
-        }
+    fn get_daily_hourly_profiles_sync(
+        &self,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> DbResult<Vec<[i64; 24]>> {
+        let days = TimeRangeCalculator::split(&TimeRange::new(start, end), TimeGranularity::Day);
+        let conn = self.pool.get()?;
 
-        // 转换为 PeriodUsage
-        #[allow(clippy::needless_range_loop)]
-        for hour in 0..24 {
-            result.push(PeriodUsage {
-                label: format!("{}时", hour),
-                index: hour as i32,
-                total_seconds: hour_totals[hour],
-            });
+        let mut profiles = Vec::with_capacity(days.len());
+        for day in days {
+            let date = day.start.with_timezone(&Local).date_naive();
+            let buckets = Self::period_buckets(PeriodSpec::HoursOf {
+                year: date.year(),
+                month: date.month(),
+                day: date.day(),
+            })?;
+            let mut hours = [0i64; 24];
+            hours.copy_from_slice(&Self::split_by_overlap(&conn, &buckets, None)?);
+            profiles.push(hours);
         }
-
-        Ok(result)
+        Ok(profiles)
     }
-}
 
-#[async_trait]
-impl TimeStatsQuery for TimeStatsQueryImpl {
-    async fn get_yearly_usage(&self, years: i32) -> DbResult<Vec<PeriodUsage>> {
+    /// 获取时间范围内每个本地日期每小时的使用时长（秒，不含 AFK），按日期升序
+    ///
+    /// 范围内的每一天都有一项，没有数据的日期全为 0；拆分规则同 `get_hourly_usage`。
+    pub async fn get_daily_hourly_profiles(
+        &self,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> DbResult<Vec<[i64; 24]>> {
         let query = self.clone();
-        tokio::task::spawn_blocking(move || query.get_yearly_usage_sync(years))
+        tokio::task::spawn_blocking(move || query.get_daily_hourly_profiles_sync(start, end))
             .await
             .map_err(|e| DbError::Validation(format!("Task join error: {}", e)))?
     }
 
-    async fn get_monthly_usage(&self, year: i32) -> DbResult<Vec<PeriodUsage>> {
+    /// 获取单个应用在指定日期（本地时间）每小时的使用时长（秒，不含 AFK）
+    ///
+    /// 拆分规则同 `get_hourly_usage`：跨小时的事件按重叠部分计入各小时。
+    pub async fn get_app_hourly_usage(
+        &self,
+        app_name: &str,
+        date: NaiveDate,
+    ) -> DbResult<[i64; 24]> {
         let query = self.clone();
-        tokio::task::spawn_blocking(move || query.get_monthly_usage_sync(year))
+        let app_name = app_name.to_string();
+        tokio::task::spawn_blocking(move || query.get_app_hourly_usage_sync(&app_name, date))
             .await
             .map_err(|e| DbError::Validation(format!("Task join error: {}", e)))?
     }
 
-    async fn get_weekly_usage(&self, year: i32, month: u32) -> DbResult<Vec<PeriodUsage>> {
+    /// 按查询规格获取分段使用统计（不含 AFK）
+    ///
+    /// 年、月、周、天四种视图共用同一套边界计算，每段都是本地时间对齐的半开区间。
+    pub async fn get_period_usage(&self, spec: PeriodSpec) -> DbResult<Vec<PeriodUsage>> {
         let query = self.clone();
-        tokio::task::spawn_blocking(move || query.get_weekly_usage_sync(year, month))
+        tokio::task::spawn_blocking(move || query.get_period_usage_sync(spec))
             .await
             .map_err(|e| DbError::Validation(format!("Task join error: {}", e)))?
     }
+}
+
+#[async_trait]
+impl TimeStatsQuery for TimeStatsQueryImpl {
+    async fn get_yearly_usage(&self, years: i32) -> DbResult<Vec<PeriodUsage>> {
+        self.get_period_usage(PeriodSpec::Years(years)).await
+    }
+
+    async fn get_monthly_usage(&self, year: i32) -> DbResult<Vec<PeriodUsage>> {
+        self.get_period_usage(PeriodSpec::MonthsOf(year)).await
+    }
+
+    async fn get_weekly_usage(&self, year: i32, month: u32) -> DbResult<Vec<PeriodUsage>> {
+        self.get_period_usage(PeriodSpec::WeeksOf {
+            year,
+            month,
+            numbering: WeekNumbering::MonthRelative,
+        })
+        .await
+    }
 
     async fn get_daily_usage_for_week(
         &self,
@@ -363,10 +486,13 @@ impl TimeStatsQuery for TimeStatsQueryImpl {
         month: u32,
         week: u32,
     ) -> DbResult<Vec<PeriodUsage>> {
-        let query = self.clone();
-        tokio::task::spawn_blocking(move || query.get_daily_usage_for_week_sync(year, month, week))
-            .await
-            .map_err(|e| DbError::Validation(format!("Task join error: {}", e)))?
+        self.get_period_usage(PeriodSpec::DaysOf {
+            year,
+            month,
+            week,
+            numbering: WeekNumbering::MonthRelative,
+        })
+        .await
     }
 
     async fn get_hourly_usage(
@@ -375,10 +501,8 @@ impl TimeStatsQuery for TimeStatsQueryImpl {
         month: u32,
         day: u32,
     ) -> DbResult<Vec<PeriodUsage>> {
-        let query = self.clone();
-        tokio::task::spawn_blocking(move || query.get_hourly_usage_sync(year, month, day))
+        self.get_period_usage(PeriodSpec::HoursOf { year, month, day })
             .await
-            .map_err(|e| DbError::Validation(format!("Task join error: {}", e)))?
     }
 }
 
@@ -389,3 +513,341 @@ impl Clone for TimeStatsQueryImpl {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::pool::create_memory_pool;
+    use crate::db::repositories::WindowEventRepositoryImpl;
+    use crate::models::WindowEvent;
+    use crate::traits::WindowEventRepository;
+
+    fn event_on(date: NaiveDate, hour: i64, duration_secs: i64, is_afk: bool) -> WindowEvent {
+        WindowEvent {
+            id: None,
+            timestamp: TimeRangeCalculator::day(date).start + chrono::Duration::hours(hour),
+            app_name: "code".to_string(),
+            window_title: String::new(),
+            workspace: String::new(),
+            duration_secs,
+            is_afk,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_get_daily_activity_gap_filled() {
+        let pool = create_memory_pool();
+        let repo = WindowEventRepositoryImpl::new(pool.clone());
+        let query = TimeStatsQueryImpl::new(Arc::new(pool));
+
+        let monday = NaiveDate::from_ymd_opt(2024, 3, 4).unwrap();
+        let sunday = monday + chrono::Duration::days(6);
+        let tuesday = monday + chrono::Duration::days(1);
+        let friday = monday + chrono::Duration::days(4);
+
+        repo.insert(&event_on(tuesday, 9, 1800, false))
+            .await
+            .unwrap();
+        repo.insert(&event_on(tuesday, 14, 600, false))
+            .await
+            .unwrap();
+        repo.insert(&event_on(friday, 10, 3600, false))
+            .await
+            .unwrap();
+        repo.insert(&event_on(friday, 12, 900, true)).await.unwrap();
+
+        let activity = query
+            .get_daily_activity(
+                TimeRangeCalculator::day(monday).start,
+                TimeRangeCalculator::day(sunday).end,
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(activity.len(), 7);
+        for (i, (date, seconds)) in activity.iter().enumerate() {
+            assert_eq!(*date, monday + chrono::Duration::days(i as i64));
+            let expected = match i {
+                1 => 2400,
+                4 => 3600,
+                _ => 0,
+            };
+            assert_eq!(*seconds, expected, "{}", date);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_get_goal_adherence() {
+        let pool = create_memory_pool();
+        let repo = WindowEventRepositoryImpl::new(pool.clone());
+        let query = TimeStatsQueryImpl::new(Arc::new(pool));
+
+        let monday = NaiveDate::from_ymd_opt(2024, 3, 4).unwrap();
+        let day = |offset| monday + chrono::Duration::days(offset);
+        // 上限 1 小时：周一、周三、周五达成（周五恰好等于上限），周二、周四超出
+        for (offset, hour, duration_secs) in [
+            (0, 9, 1800),
+            (1, 9, 3600),
+            (1, 14, 1200),
+            (2, 10, 600),
+            (3, 9, 5400),
+            (4, 9, 3600),
+        ] {
+            repo.insert(&event_on(day(offset), hour, duration_secs, false))
+                .await
+                .unwrap();
+        }
+        // 周六只有 AFK，不算使用
+        repo.insert(&event_on(day(5), 9, 600, true)).await.unwrap();
+
+        let adherence = query
+            .get_goal_adherence(
+                "code",
+                TimeRangeCalculator::day(monday).start,
+                TimeRangeCalculator::day(day(6)).end,
+                3600,
+            )
+            .await
+            .unwrap();
+        assert_eq!(adherence, (3, 5));
+    }
+
+    #[tokio::test]
+    async fn test_get_daily_app_diversity() {
+        let pool = create_memory_pool();
+        let repo = WindowEventRepositoryImpl::new(pool.clone());
+        let query = TimeStatsQueryImpl::new(Arc::new(pool));
+
+        let monday = NaiveDate::from_ymd_opt(2024, 3, 4).unwrap();
+        let tuesday = monday + chrono::Duration::days(1);
+        let wednesday = monday + chrono::Duration::days(2);
+
+        for (hour, app_name) in [(9, "code"), (10, "firefox"), (11, "kitty"), (12, "code")] {
+            let mut e = event_on(monday, hour, 600, false);
+            e.app_name = app_name.to_string();
+            repo.insert(&e).await.unwrap();
+        }
+        repo.insert(&event_on(wednesday, 9, 600, false))
+            .await
+            .unwrap();
+        // AFK 事件不计入
+        let mut afk = event_on(wednesday, 10, 600, true);
+        afk.app_name = "firefox".to_string();
+        repo.insert(&afk).await.unwrap();
+
+        let diversity = query
+            .get_daily_app_diversity(
+                TimeRangeCalculator::day(monday).start,
+                TimeRangeCalculator::day(wednesday).end,
+            )
+            .await
+            .unwrap();
+        assert_eq!(diversity, vec![(monday, 3), (tuesday, 0), (wednesday, 1)]);
+    }
+
+    #[tokio::test]
+    async fn test_get_daily_hourly_profiles() {
+        let pool = create_memory_pool();
+        let repo = WindowEventRepositoryImpl::new(pool.clone());
+        let query = TimeStatsQueryImpl::new(Arc::new(pool));
+
+        let monday = NaiveDate::from_ymd_opt(2024, 3, 4).unwrap();
+        let tuesday = monday + chrono::Duration::days(1);
+        repo.insert(&event_on(monday, 9, 1800, false))
+            .await
+            .unwrap();
+        // 跨小时的事件按重叠拆分
+        let mut crossing = event_on(tuesday, 9, 3600, false);
+        crossing.timestamp += chrono::Duration::minutes(30);
+        repo.insert(&crossing).await.unwrap();
+
+        let profiles = query
+            .get_daily_hourly_profiles(
+                TimeRangeCalculator::day(monday).start,
+                TimeRangeCalculator::day(tuesday).end,
+            )
+            .await
+            .unwrap();
+        assert_eq!(profiles.len(), 2);
+        assert_eq!(profiles[0][9], 1800);
+        assert_eq!(profiles[0].iter().sum::<i64>(), 1800);
+        assert_eq!(profiles[1][9], 1800);
+        assert_eq!(profiles[1][10], 1800);
+    }
+
+    #[tokio::test]
+    async fn test_count_active_days() {
+        let pool = create_memory_pool();
+        let repo = WindowEventRepositoryImpl::new(pool.clone());
+        let query = TimeStatsQueryImpl::new(Arc::new(pool));
+
+        let monday = NaiveDate::from_ymd_opt(2024, 3, 4).unwrap();
+        let day = |offset| monday + chrono::Duration::days(offset);
+        for (offset, hour) in [(0, 9), (0, 14), (2, 10), (5, 20)] {
+            repo.insert(&event_on(day(offset), hour, 600, false))
+                .await
+                .unwrap();
+        }
+        // 只有 AFK 的日期不算活跃
+        repo.insert(&event_on(day(3), 9, 600, true)).await.unwrap();
+
+        let count = query
+            .count_active_days(
+                TimeRangeCalculator::day(monday).start,
+                TimeRangeCalculator::day(day(6)).end,
+            )
+            .await
+            .unwrap();
+        assert_eq!(count, 3);
+    }
+
+    #[tokio::test]
+    async fn test_get_period_usage_buckets() {
+        let pool = create_memory_pool();
+        let repo = WindowEventRepositoryImpl::new(pool.clone());
+        let query = TimeStatsQueryImpl::new(Arc::new(pool));
+
+        // 2024-03-01 是周五，三月共 5 周
+        let date = |day| NaiveDate::from_ymd_opt(2024, 3, day).unwrap();
+        repo.insert(&event_on(date(1), 10, 600, false))
+            .await
+            .unwrap();
+        repo.insert(&event_on(date(4), 9, 1200, false))
+            .await
+            .unwrap();
+        let mut straddling = event_on(date(4), 9, 1200, false);
+        straddling.timestamp += chrono::Duration::minutes(50);
+        repo.insert(&straddling).await.unwrap();
+        repo.insert(&event_on(date(4), 12, 900, true))
+            .await
+            .unwrap();
+        // 月末最后一秒内的事件
+        let mut last_second = event_on(date(31), 0, 30, false);
+        last_second.timestamp =
+            TimeRangeCalculator::day(date(31)).end - chrono::Duration::milliseconds(1);
+        repo.insert(&last_second).await.unwrap();
+        repo.insert(&event_on(
+            NaiveDate::from_ymd_opt(2024, 4, 1).unwrap(),
+            0,
+            300,
+            false,
+        ))
+        .await
+        .unwrap();
+
+        let months = query
+            .get_period_usage(PeriodSpec::MonthsOf(2024))
+            .await
+            .unwrap();
+        assert_eq!(months.len(), 12);
+        assert_eq!((months[2].label.as_str(), months[2].index), ("3月", 3));
+        assert_eq!(months[2].total_seconds, 3030);
+        assert_eq!(months[3].total_seconds, 300);
+
+        let weeks = query.get_weekly_usage(2024, 3).await.unwrap();
+        assert_eq!(
+            weeks.iter().map(|w| w.total_seconds).collect::<Vec<_>>(),
+            vec![600, 2400, 0, 0, 30]
+        );
+        assert_eq!((weeks[4].label.as_str(), weeks[4].index), ("第5周", 5));
+
+        let iso_weeks = query
+            .get_period_usage(PeriodSpec::WeeksOf {
+                year: 2024,
+                month: 3,
+                numbering: WeekNumbering::Iso,
+            })
+            .await
+            .unwrap();
+        assert_eq!(
+            iso_weeks
+                .iter()
+                .map(|w| (w.label.as_str(), w.total_seconds))
+                .collect::<Vec<_>>(),
+            vec![
+                ("W9", 600),
+                ("W10", 2400),
+                ("W11", 0),
+                ("W12", 0),
+                ("W13", 30)
+            ]
+        );
+
+        let days = query.get_daily_usage_for_week(2024, 3, 2).await.unwrap();
+        assert_eq!(
+            days.iter().map(|d| d.index).collect::<Vec<_>>(),
+            (4..=10).collect::<Vec<_>>()
+        );
+        assert_eq!(
+            (days[0].label.as_str(), days[0].total_seconds),
+            ("周一", 2400)
+        );
+        assert!(days[1..].iter().all(|d| d.total_seconds == 0));
+        assert!(query
+            .get_daily_usage_for_week(2024, 3, 6)
+            .await
+            .unwrap()
+            .is_empty());
+        assert!(matches!(
+            query.get_daily_usage_for_week(2024, 3, 0).await,
+            Err(DbError::Validation(_))
+        ));
+
+        let hours = query.get_hourly_usage(2024, 3, 4).await.unwrap();
+        assert_eq!(hours.len(), 24);
+        assert_eq!(
+            (hours[9].label.as_str(), hours[9].total_seconds),
+            ("9时", 1800)
+        );
+        assert_eq!(hours[10].total_seconds, 600);
+        assert_eq!(hours[12].total_seconds, 0);
+    }
+
+    #[tokio::test]
+    async fn test_get_yearly_usage_ends_at_current_year() {
+        let pool = create_memory_pool();
+        let repo = WindowEventRepositoryImpl::new(pool.clone());
+        let query = TimeStatsQueryImpl::new(Arc::new(pool));
+
+        let mut now = event_on(Local::now().date_naive(), 0, 60, false);
+        now.timestamp = Utc::now();
+        repo.insert(&now).await.unwrap();
+
+        let current_year = Local::now().year();
+        let years = query.get_yearly_usage(2).await.unwrap();
+        assert_eq!(
+            years.iter().map(|y| y.label.clone()).collect::<Vec<_>>(),
+            vec![
+                format!("{}年", current_year - 1),
+                format!("{}年", current_year)
+            ]
+        );
+        assert_eq!(years[0].total_seconds, 0);
+        assert_eq!(years[1].total_seconds, 60);
+    }
+
+    #[tokio::test]
+    async fn test_get_app_hourly_usage() {
+        let pool = create_memory_pool();
+        let repo = WindowEventRepositoryImpl::new(pool.clone());
+        let query = TimeStatsQueryImpl::new(Arc::new(pool));
+
+        let date = NaiveDate::from_ymd_opt(2024, 3, 4).unwrap();
+        repo.insert(&event_on(date, 9, 1200, false)).await.unwrap();
+        repo.insert(&event_on(date, 14, 600, false)).await.unwrap();
+        repo.insert(&event_on(date, 16, 900, true)).await.unwrap();
+        let mut other = event_on(date, 11, 1800, false);
+        other.app_name = "firefox".to_string();
+        repo.insert(&other).await.unwrap();
+
+        let hours = query.get_app_hourly_usage("code", date).await.unwrap();
+        for (hour, seconds) in hours.iter().enumerate() {
+            let expected = match hour {
+                9 => 1200,
+                14 => 600,
+                _ => 0,
+            };
+            assert_eq!(*seconds, expected, "hour {}", hour);
+        }
+    }
+}
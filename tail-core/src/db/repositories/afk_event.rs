@@ -42,6 +42,36 @@ impl AfkEventRepositoryImpl {
         Ok(())
     }
 
+    /// 获取与时间范围有交集的 AFK 会话（同步方法，供内部使用）
+    ///
+    /// 与 `get_by_time_range` 不同，范围开始前就已开始、或尚未结束的会话也会返回。
+    pub fn get_overlapping_sync(
+        &self,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> DbResult<Vec<AfkEvent>> {
+        let conn = self.pool.get()?;
+        let mut stmt = conn.prepare(
+            "SELECT id, start_time, end_time, duration_secs
+             FROM afk_events
+             WHERE start_time < ?2 AND (end_time IS NULL OR end_time > ?1)
+             ORDER BY start_time ASC",
+        )?;
+
+        let events = stmt
+            .query_map(params![start, end], |row| {
+                Ok(AfkEvent {
+                    id: Some(row.get(0)?),
+                    start_time: row.get(1)?,
+                    end_time: row.get(2)?,
+                    duration_secs: row.get(3)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(events)
+    }
+
     fn get_by_time_range_sync(
         &self,
         start: DateTime<Utc>,
@@ -51,7 +81,7 @@ impl AfkEventRepositoryImpl {
         let mut stmt = conn.prepare(
             "SELECT id, start_time, end_time, duration_secs
              FROM afk_events
-             WHERE start_time >= ?1 AND start_time <= ?2
+             WHERE start_time >= ?1 AND start_time < ?2
              ORDER BY start_time ASC",
         )?;
 
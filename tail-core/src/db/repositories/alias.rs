@@ -7,6 +7,38 @@ use async_trait::async_trait;
 use rusqlite::params;
 use std::sync::Arc;
 
+/// 解析 `原始名称=别名` 格式的别名映射文本
+///
+/// 每行一条，按第一个 `=` 分割，两侧去除首尾空白；空行和以 `#` 开头的行被忽略。
+/// 缺少 `=` 或任一侧为空的行返回校验错误（行号从 1 开始）。
+pub fn parse_alias_mapping(text: &str) -> DbResult<Vec<(String, String)>> {
+    let mut pairs = Vec::new();
+    for (index, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let (app_name, alias) = line
+            .split_once('=')
+            .map(|(app_name, alias)| (app_name.trim(), alias.trim()))
+            .filter(|(app_name, alias)| !app_name.is_empty() && !alias.is_empty())
+            .ok_or_else(|| {
+                DbError::Validation(format!("第 {} 行不是有效的别名映射: {:?}", index + 1, line))
+            })?;
+        pairs.push((app_name.to_string(), alias.to_string()));
+    }
+    Ok(pairs)
+}
+
+/// 把别名导出为 `原始名称=别名` 格式的文本，每行一条
+pub fn format_alias_mapping(pairs: &[(String, String)]) -> String {
+    pairs
+        .iter()
+        .map(|(app_name, alias)| format!("{}={}\n", app_name, alias))
+        .collect()
+}
+
 /// 别名仓储实现
 pub struct AliasRepositoryImpl {
     pool: Arc<DbPool>,
@@ -46,6 +78,21 @@ impl AliasRepositoryImpl {
         Ok(aliases)
     }
 
+    /// 在同一事务中写入多条别名，已有别名的应用会被覆盖
+    fn import_sync(&self, pairs: &[(String, String)]) -> DbResult<usize> {
+        let mut conn = self.pool.get()?;
+        let tx = conn.transaction()?;
+        {
+            let mut stmt =
+                tx.prepare("INSERT OR REPLACE INTO app_aliases (app_name, alias) VALUES (?1, ?2)")?;
+            for (app_name, alias) in pairs {
+                stmt.execute(params![app_name, alias])?;
+            }
+        }
+        tx.commit()?;
+        Ok(pairs.len())
+    }
+
     fn delete_sync(&self, app_name: &str) -> DbResult<()> {
         let conn = self.pool.get()?;
         conn.execute(
@@ -56,6 +103,22 @@ impl AliasRepositoryImpl {
     }
 }
 
+impl AliasRepositoryImpl {
+    /// 批量导入 `(原始名称, 别名)`，全部写入或全部不写入，返回导入的条数
+    pub async fn import_aliases(&self, pairs: &[(String, String)]) -> DbResult<usize> {
+        let repo = self.clone();
+        let pairs = pairs.to_vec();
+        tokio::task::spawn_blocking(move || repo.import_sync(&pairs))
+            .await
+            .map_err(|e| DbError::Validation(format!("Task join error: {}", e)))?
+    }
+
+    /// 导出所有别名 `(原始名称, 别名)`，按原始名称排序
+    pub async fn export_aliases(&self) -> DbResult<Vec<(String, String)>> {
+        self.get_all().await
+    }
+}
+
 #[async_trait]
 impl AliasRepository for AliasRepositoryImpl {
     async fn set(&self, app_name: &str, alias: &str) -> DbResult<()> {
@@ -98,3 +161,57 @@ impl Clone for AliasRepositoryImpl {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::pool::create_memory_pool;
+
+    fn pairs(entries: &[(&str, &str)]) -> Vec<(String, String)> {
+        entries
+            .iter()
+            .map(|(app_name, alias)| (app_name.to_string(), alias.to_string()))
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_import_export_round_trip() {
+        let repo = AliasRepositoryImpl::new(Arc::new(create_memory_pool()));
+        let text = "# 浏览器\nfirefox = 火狐\n\norg.kde.dolphin=文件管理器\n";
+
+        let imported = parse_alias_mapping(text).unwrap();
+        assert_eq!(repo.import_aliases(&imported).await.unwrap(), 2);
+
+        let exported = repo.export_aliases().await.unwrap();
+        assert_eq!(
+            exported,
+            pairs(&[("firefox", "火狐"), ("org.kde.dolphin", "文件管理器")])
+        );
+        assert_eq!(
+            parse_alias_mapping(&format_alias_mapping(&exported)).unwrap(),
+            exported
+        );
+    }
+
+    #[tokio::test]
+    async fn test_import_overwrites_existing_alias() {
+        let repo = AliasRepositoryImpl::new(Arc::new(create_memory_pool()));
+        repo.set("firefox", "Firefox").await.unwrap();
+        repo.set("code", "VS Code").await.unwrap();
+
+        repo.import_aliases(&pairs(&[("firefox", "火狐")]))
+            .await
+            .unwrap();
+        assert_eq!(
+            repo.export_aliases().await.unwrap(),
+            pairs(&[("code", "VS Code"), ("firefox", "火狐")])
+        );
+    }
+
+    #[test]
+    fn test_parse_alias_mapping_invalid_line() {
+        let err = parse_alias_mapping("firefox=火狐\ncode\n").unwrap_err();
+        assert!(matches!(err, DbError::Validation(ref msg) if msg.contains("第 2 行")));
+        assert!(parse_alias_mapping("=火狐").is_err());
+    }
+}
@@ -0,0 +1,122 @@
+//! 应用颜色仓储实现
+
+use crate::db::pool::DbPool;
+use crate::errors::{DbError, DbResult};
+use rusqlite::params;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// 应用颜色仓储实现
+///
+/// 为单个应用指定图表中使用的颜色（`#RRGGBB`），优先于按顺序分配的调色板颜色。
+pub struct AppColorRepositoryImpl {
+    pool: Arc<DbPool>,
+}
+
+impl AppColorRepositoryImpl {
+    pub fn new(pool: Arc<DbPool>) -> Self {
+        Self { pool }
+    }
+
+    /// 检查颜色是否为 `#RRGGBB` 格式
+    fn is_hex_color(color: &str) -> bool {
+        color.len() == 7
+            && color.starts_with('#')
+            && color[1..].chars().all(|c| c.is_ascii_hexdigit())
+    }
+
+    fn set_sync(&self, app_name: &str, color: Option<&str>) -> DbResult<()> {
+        let conn = self.pool.get()?;
+        match color {
+            Some(color) => {
+                if !Self::is_hex_color(color) {
+                    return Err(DbError::Validation(format!(
+                        "颜色必须为 #RRGGBB 格式: {}",
+                        color
+                    )));
+                }
+                conn.execute(
+                    "INSERT INTO app_colors (app_name, color) VALUES (?1, ?2)
+                     ON CONFLICT(app_name) DO UPDATE SET color = excluded.color",
+                    params![app_name, color],
+                )?;
+            }
+            None => {
+                conn.execute(
+                    "DELETE FROM app_colors WHERE app_name = ?1",
+                    params![app_name],
+                )?;
+            }
+        }
+        Ok(())
+    }
+
+    fn get_all_sync(&self) -> DbResult<HashMap<String, String>> {
+        let conn = self.pool.get()?;
+        let mut stmt = conn.prepare("SELECT app_name, color FROM app_colors")?;
+
+        let colors = stmt
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+            .collect::<Result<HashMap<_, _>, _>>()?;
+
+        Ok(colors)
+    }
+
+    /// 设置应用的颜色，`None` 表示清除该应用的颜色设置
+    ///
+    /// 颜色必须为 `#RRGGBB` 格式，否则返回校验错误。
+    pub async fn set_app_color(&self, app_name: &str, color: Option<&str>) -> DbResult<()> {
+        let repo = self.clone();
+        let app_name = app_name.to_string();
+        let color = color.map(str::to_string);
+        tokio::task::spawn_blocking(move || repo.set_sync(&app_name, color.as_deref()))
+            .await
+            .map_err(|e| DbError::Validation(format!("Task join error: {}", e)))?
+    }
+
+    /// 获取所有应用的颜色设置（应用名称 -> `#RRGGBB`）
+    pub async fn get_app_colors(&self) -> DbResult<HashMap<String, String>> {
+        let repo = self.clone();
+        tokio::task::spawn_blocking(move || repo.get_all_sync())
+            .await
+            .map_err(|e| DbError::Validation(format!("Task join error: {}", e)))?
+    }
+}
+
+impl Clone for AppColorRepositoryImpl {
+    fn clone(&self) -> Self {
+        Self {
+            pool: Arc::clone(&self.pool),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::pool::create_memory_pool;
+
+    #[tokio::test]
+    async fn test_set_and_clear_app_colors() {
+        let repo = AppColorRepositoryImpl::new(Arc::new(create_memory_pool()));
+
+        repo.set_app_color("code", Some("#34A853")).await.unwrap();
+        repo.set_app_color("firefox", Some("#ff6347"))
+            .await
+            .unwrap();
+        repo.set_app_color("code", Some("#00c896")).await.unwrap();
+        assert!(matches!(
+            repo.set_app_color("kitty", Some("green")).await,
+            Err(DbError::Validation(_))
+        ));
+
+        let colors = repo.get_app_colors().await.unwrap();
+        assert_eq!(colors.len(), 2);
+        assert_eq!(colors["code"], "#00c896");
+        assert_eq!(colors["firefox"], "#ff6347");
+
+        repo.set_app_color("firefox", None).await.unwrap();
+        let colors = repo.get_app_colors().await.unwrap();
+        assert!(!colors.contains_key("firefox"));
+    }
+}
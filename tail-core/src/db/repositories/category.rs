@@ -2,11 +2,14 @@
 
 use crate::db::pool::DbPool;
 use crate::errors::{DbError, DbResult};
-use crate::models::{AppUsageInCategory, Category, CategoryUsage};
+use crate::models::{AppUsageInCategory, Category, CategoryUsage, PeriodUsage};
+use crate::time::range::{TimeRange, TimeRangeCalculator};
+use crate::time::TimeGranularity;
 use crate::traits::CategoryRepository;
 use async_trait::async_trait;
-use chrono::{DateTime, Utc};
-use rusqlite::params;
+use chrono::{DateTime, Datelike, Local, Utc};
+use rusqlite::{params, OptionalExtension};
+use std::collections::HashMap;
 use std::sync::Arc;
 
 /// 分类仓储实现
@@ -43,13 +46,133 @@ impl CategoryRepositoryImpl {
         Ok(())
     }
 
+    /// 在同一事务中删除多个分类及其应用关联，返回实际删除的分类数量
+    ///
+    /// 连接未开启外键约束，`ON DELETE CASCADE` 不会生效，因此显式删除关联。
+    fn delete_categories_sync(&self, ids: &[i64]) -> DbResult<usize> {
+        let mut conn = self.pool.get()?;
+        let tx = conn.transaction()?;
+
+        let mut deleted = 0;
+        for id in ids {
+            tx.execute(
+                "DELETE FROM app_categories WHERE category_id = ?1",
+                params![id],
+            )?;
+            deleted += tx.execute("DELETE FROM categories WHERE id = ?1", params![id])?;
+        }
+        tx.commit()?;
+
+        Ok(deleted)
+    }
+
+    /// 在同一事务中把源分类的所有应用移到目标分类，返回移动的应用数量
+    ///
+    /// 目标分类中已有的应用不会重复关联；`delete_source` 为 `true` 时随后删除源分类。
+    fn move_category_apps_sync(
+        &self,
+        from_id: i64,
+        to_id: i64,
+        delete_source: bool,
+    ) -> DbResult<usize> {
+        if from_id == to_id {
+            return Err(DbError::Validation("源分类与目标分类不能相同".to_string()));
+        }
+
+        let mut conn = self.pool.get()?;
+        let tx = conn.transaction()?;
+
+        let target_exists = tx
+            .query_row(
+                "SELECT 1 FROM categories WHERE id = ?1",
+                params![to_id],
+                |_| Ok(()),
+            )
+            .optional()?
+            .is_some();
+        if !target_exists {
+            return Err(DbError::NotFound(format!("category {}", to_id)));
+        }
+
+        tx.execute(
+            "INSERT OR IGNORE INTO app_categories (app_name, category_id)
+             SELECT app_name, ?2 FROM app_categories WHERE category_id = ?1",
+            params![from_id, to_id],
+        )?;
+        let moved = tx.execute(
+            "DELETE FROM app_categories WHERE category_id = ?1",
+            params![from_id],
+        )?;
+        if delete_source {
+            tx.execute("DELETE FROM categories WHERE id = ?1", params![from_id])?;
+        }
+        tx.commit()?;
+
+        Ok(moved)
+    }
+
+    /// 按名称确保分类存在，返回与输入一一对应的分类 id
+    ///
+    /// 名称已存在（包括已归档）的分类保持原样，不会覆盖其图标和颜色。
+    fn ensure_categories_sync(&self, categories: &[Category]) -> DbResult<Vec<i64>> {
+        let mut conn = self.pool.get()?;
+        let tx = conn.transaction()?;
+
+        let mut ids = Vec::with_capacity(categories.len());
+        {
+            let mut insert = tx.prepare(
+                "INSERT OR IGNORE INTO categories (name, icon, color) VALUES (?1, ?2, ?3)",
+            )?;
+            let mut select = tx.prepare("SELECT id FROM categories WHERE name = ?1")?;
+            for category in categories {
+                insert.execute(params![category.name, category.icon, category.color])?;
+                ids.push(select.query_row(params![category.name], |row| row.get(0))?);
+            }
+        }
+        tx.commit()?;
+
+        Ok(ids)
+    }
+
     fn get_all_sync(&self) -> DbResult<Vec<Category>> {
+        self.get_categories_sync(false)
+    }
+
+    fn get_categories_sync(&self, include_archived: bool) -> DbResult<Vec<Category>> {
         let conn = self.pool.get()?;
-        let mut stmt =
-            conn.prepare("SELECT id, name, icon, color FROM categories ORDER BY name ASC")?;
+        let mut stmt = conn.prepare(
+            "SELECT id, name, icon, color FROM categories
+             WHERE ?1 OR archived = 0
+             ORDER BY name ASC",
+        )?;
+
+        let categories = stmt
+            .query_map(params![include_archived], |row| {
+                Ok(Category {
+                    id: Some(row.get(0)?),
+                    name: row.get(1)?,
+                    icon: row.get(2)?,
+                    color: row.get(3)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(categories)
+    }
+
+    fn get_categories_page_sync(&self, limit: usize, offset: usize) -> DbResult<Vec<Category>> {
+        let conn = self.pool.get()?;
+        let mut stmt = conn.prepare(
+            "SELECT id, name, icon, color FROM categories
+             WHERE archived = 0
+             ORDER BY name ASC, id ASC
+             LIMIT ?1 OFFSET ?2",
+        )?;
 
+        let limit = i64::try_from(limit).unwrap_or(i64::MAX);
+        let offset = i64::try_from(offset).unwrap_or(i64::MAX);
         let categories = stmt
-            .query_map([], |row| {
+            .query_map(params![limit, offset], |row| {
                 Ok(Category {
                     id: Some(row.get(0)?),
                     name: row.get(1)?,
@@ -62,6 +185,18 @@ impl CategoryRepositoryImpl {
         Ok(categories)
     }
 
+    fn set_archived_sync(&self, id: i64, archived: bool) -> DbResult<()> {
+        let conn = self.pool.get()?;
+        let updated = conn.execute(
+            "UPDATE categories SET archived = ?1 WHERE id = ?2",
+            params![archived, id],
+        )?;
+        if updated == 0 {
+            return Err(DbError::NotFound(format!("category {}", id)));
+        }
+        Ok(())
+    }
+
     fn get_by_id_sync(&self, id: i64) -> DbResult<Option<Category>> {
         let conn = self.pool.get()?;
         let mut stmt =
@@ -89,7 +224,7 @@ impl CategoryRepositoryImpl {
             "SELECT c.id, c.name, c.icon, c.color
              FROM categories c
              INNER JOIN app_categories ac ON c.id = ac.category_id
-             WHERE ac.app_name = ?1
+             WHERE ac.app_name = ?1 AND c.archived = 0
              ORDER BY c.name ASC",
         )?;
 
@@ -141,16 +276,18 @@ impl CategoryRepositoryImpl {
     fn set_app_categories_sync(&self, app_name: &str, category_ids: &[i64]) -> DbResult<()> {
         let conn = self.pool.get()?;
 
-        // 先删除该应用的所有分类关联
+        // 先删除该应用在未归档分类中的关联，归档分类的关联保留
         conn.execute(
-            "DELETE FROM app_categories WHERE app_name = ?1",
+            "DELETE FROM app_categories
+             WHERE app_name = ?1
+               AND category_id IN (SELECT id FROM categories WHERE archived = 0)",
             params![app_name],
         )?;
 
         // 添加新的分类关联
         for category_id in category_ids {
             conn.execute(
-                "INSERT INTO app_categories (app_name, category_id) VALUES (?1, ?2)",
+                "INSERT OR IGNORE INTO app_categories (app_name, category_id) VALUES (?1, ?2)",
                 params![app_name, category_id],
             )?;
         }
@@ -170,104 +307,516 @@ impl CategoryRepositoryImpl {
         Ok(apps)
     }
 
+    fn get_all_app_names_since_sync(&self, since: DateTime<Utc>) -> DbResult<Vec<String>> {
+        let conn = self.pool.get()?;
+        let mut stmt = conn.prepare(
+            "SELECT DISTINCT app_name FROM window_events
+             WHERE timestamp >= ?1
+             ORDER BY app_name ASC",
+        )?;
+
+        let apps = stmt
+            .query_map(params![since], |row| row.get(0))?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(apps)
+    }
+
+    fn get_unclassified_app_names_sync(&self) -> DbResult<Vec<String>> {
+        let conn = self.pool.get()?;
+        let mut stmt = conn.prepare(
+            "SELECT DISTINCT app_name FROM window_events
+             WHERE app_name NOT IN (
+                 SELECT ac.app_name FROM app_categories ac
+                 JOIN categories c ON c.id = ac.category_id
+                 WHERE c.archived = 0
+             )
+             ORDER BY app_name ASC",
+        )?;
+
+        let apps = stmt
+            .query_map([], |row| row.get(0))?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(apps)
+    }
+
     /// 获取分类使用统计（辅助方法，供查询模块使用）
     pub fn get_category_usage_sync(
         &self,
         start: DateTime<Utc>,
         end: DateTime<Utc>,
     ) -> DbResult<Vec<CategoryUsage>> {
-        let conn = self.pool.get()?;
         let categories = self.get_all_sync()?;
 
         let mut result = Vec::new();
 
         for category in categories {
-            let category_id = category.id.unwrap();
-            let apps = self.get_category_apps_sync(category_id)?;
+            let Some(category_id) = category.id else {
+                continue;
+            };
+            result.push(self.usage_for_category_sync(category, category_id, start, end)?);
+        }
+
+        // 按总时长排序
+        result.sort_by_key(|c| std::cmp::Reverse(c.total_seconds));
+
+        Ok(result)
+    }
+
+    /// 获取不属于任何未归档分类的应用的使用统计，合并为一个"未分类"分组（辅助方法，供查询模块使用）
+    pub fn get_uncategorized_usage_sync(
+        &self,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> DbResult<CategoryUsage> {
+        let conn = self.pool.get()?;
+        let mut stmt = conn.prepare(
+            "SELECT app_name, SUM(duration_secs) as total
+             FROM window_events
+             WHERE timestamp >= ?1 AND timestamp < ?2
+               AND is_afk = 0
+               AND app_name NOT IN (
+                   SELECT ac.app_name
+                   FROM app_categories ac
+                   JOIN categories c ON c.id = ac.category_id
+                   WHERE c.archived = 0
+               )
+             GROUP BY app_name
+             ORDER BY total DESC, app_name ASC",
+        )?;
+
+        let apps: Vec<AppUsageInCategory> = stmt
+            .query_map(params![start, end], |row| {
+                Ok(AppUsageInCategory {
+                    app_name: row.get(0)?,
+                    total_seconds: row.get(1)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(CategoryUsage {
+            category: Category::uncategorized(),
+            total_seconds: apps.iter().map(|a| a.total_seconds).sum(),
+            app_count: apps.len(),
+            apps,
+        })
+    }
+
+    /// 获取单个分类的使用统计，分类不存在时返回 `None`（同步方法，供内部使用）
+    fn get_single_category_usage_sync(
+        &self,
+        category_id: i64,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> DbResult<Option<CategoryUsage>> {
+        match self.get_by_id_sync(category_id)? {
+            Some(category) => self
+                .usage_for_category_sync(category, category_id, start, end)
+                .map(Some),
+            None => Ok(None),
+        }
+    }
+
+    /// 获取分类中使用时长最多的前 `limit` 个应用（同步方法，供内部使用）
+    ///
+    /// 只返回时间范围内有记录的应用，时长相同时按名称排序。
+    fn get_top_apps_in_category_sync(
+        &self,
+        category_id: i64,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+        limit: usize,
+    ) -> DbResult<Vec<AppUsageInCategory>> {
+        let conn = self.pool.get()?;
+        let mut stmt = conn.prepare(
+            "SELECT we.app_name, SUM(we.duration_secs) as total
+             FROM window_events we
+             JOIN app_categories ac ON ac.app_name = we.app_name
+             WHERE ac.category_id = ?1
+               AND we.timestamp >= ?2 AND we.timestamp < ?3
+               AND we.is_afk = 0
+             GROUP BY we.app_name
+             ORDER BY total DESC, we.app_name ASC
+             LIMIT ?4",
+        )?;
+
+        let apps = stmt
+            .query_map(params![category_id, start, end, limit as i64], |row| {
+                Ok(AppUsageInCategory {
+                    app_name: row.get(0)?,
+                    total_seconds: row.get(1)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(apps)
+    }
+
+    /// 统计一个分类下各应用在时间范围内的使用时长
+    fn usage_for_category_sync(
+        &self,
+        category: Category,
+        category_id: i64,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> DbResult<CategoryUsage> {
+        let apps = self.get_category_apps_sync(category_id)?;
+
+        if apps.is_empty() {
+            return Ok(CategoryUsage {
+                category,
+                total_seconds: 0,
+                app_count: 0,
+                apps: Vec::new(),
+            });
+        }
+
+        // 构建 IN 子句的占位符
+        let placeholders: Vec<String> = apps
+            .iter()
+            .enumerate()
+            .map(|(i, _)| format!("?{}", i + 3))
+            .collect();
+        let in_clause = placeholders.join(", ");
+
+        // 查询该分类下所有应用的使用时间
+        let query = format!(
+            "SELECT app_name, COALESCE(SUM(duration_secs), 0) as total
+             FROM window_events
+             WHERE timestamp >= ?1 AND timestamp < ?2
+               AND is_afk = 0
+               AND app_name IN ({})
+             GROUP BY app_name
+             ORDER BY total DESC",
+            in_clause
+        );
+
+        let conn = self.pool.get()?;
+        let mut stmt = conn.prepare(&query)?;
+
+        // 构建参数
+        let mut params_vec: Vec<Box<dyn rusqlite::ToSql>> = vec![Box::new(start), Box::new(end)];
+        for app in &apps {
+            params_vec.push(Box::new(app.clone()));
+        }
+
+        let params_refs: Vec<&dyn rusqlite::ToSql> =
+            params_vec.iter().map(|p| p.as_ref()).collect();
 
-            if apps.is_empty() {
-                result.push(CategoryUsage {
-                    category,
+        let app_usages_with_time: Vec<AppUsageInCategory> = stmt
+            .query_map(params_refs.as_slice(), |row| {
+                Ok(AppUsageInCategory {
+                    app_name: row.get(0)?,
+                    total_seconds: row.get(1)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        // 创建一个包含所有分类应用的列表，包括没有使用记录的应用
+        let mut all_app_usages: Vec<AppUsageInCategory> = Vec::new();
+        let apps_with_time: std::collections::HashSet<String> = app_usages_with_time
+            .iter()
+            .map(|a| a.app_name.clone())
+            .collect();
+
+        all_app_usages.extend(app_usages_with_time);
+
+        for app_name in &apps {
+            if !apps_with_time.contains(app_name) {
+                all_app_usages.push(AppUsageInCategory {
+                    app_name: app_name.clone(),
                     total_seconds: 0,
-                    app_count: 0,
-                    apps: Vec::new(),
                 });
-                continue;
             }
+        }
 
-            // 构建 IN 子句的占位符
-            let placeholders: Vec<String> = apps
-                .iter()
-                .enumerate()
-                .map(|(i, _)| format!("?{}", i + 3))
-                .collect();
-            let in_clause = placeholders.join(", ");
-
-            // 查询该分类下所有应用的使用时间
-            let query = format!(
-                "SELECT app_name, COALESCE(SUM(duration_secs), 0) as total
+        let total_seconds: i64 = all_app_usages.iter().map(|a| a.total_seconds).sum();
+
+        Ok(CategoryUsage {
+            category,
+            total_seconds,
+            app_count: all_app_usages.len(),
+            apps: all_app_usages,
+        })
+    }
+
+    /// 按时间段获取分类使用统计（同步方法，供内部使用）
+    ///
+    /// 一次读取整个范围内的事件，再按 `unit` 切分的时间段归类，
+    /// 每个事件只计入其开始时间所在的时间段。
+    fn get_category_usage_series_sync(
+        &self,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+        unit: TimeGranularity,
+    ) -> DbResult<Vec<(TimeRange, Vec<CategoryUsage>)>> {
+        let categories = self.get_all_sync()?;
+        let mut category_apps = Vec::with_capacity(categories.len());
+        for category in &categories {
+            let apps = match category.id {
+                Some(id) => self.get_category_apps_sync(id)?,
+                None => Vec::new(),
+            };
+            category_apps.push(apps);
+        }
+
+        let buckets = TimeRangeCalculator::split(&TimeRange::new(start, end), unit);
+
+        // 每个时间段内各应用的使用时长
+        let mut app_seconds: Vec<HashMap<String, i64>> = vec![HashMap::new(); buckets.len()];
+        {
+            let conn = self.pool.get()?;
+            let mut stmt = conn.prepare(
+                "SELECT app_name, timestamp, duration_secs
                  FROM window_events
-                 WHERE timestamp >= ?1 AND timestamp <= ?2
-                   AND is_afk = 0
-                   AND app_name IN ({})
-                 GROUP BY app_name
-                 ORDER BY total DESC",
-                in_clause
-            );
-
-            let mut stmt = conn.prepare(&query)?;
-
-            // 构建参数
-            let mut params_vec: Vec<Box<dyn rusqlite::ToSql>> =
-                vec![Box::new(start), Box::new(end)];
-            for app in &apps {
-                params_vec.push(Box::new(app.clone()));
+                 WHERE timestamp >= ?1 AND timestamp < ?2 AND is_afk = 0",
+            )?;
+            let rows = stmt.query_map(params![start, end], |row| {
+                Ok((
+                    row.get::<_, String>(0)?,
+                    row.get::<_, DateTime<Utc>>(1)?,
+                    row.get::<_, i64>(2)?,
+                ))
+            })?;
+
+            for row in rows {
+                let (app_name, timestamp, duration_secs) = row?;
+                if let Some(idx) = buckets.iter().rposition(|b| b.start <= timestamp) {
+                    *app_seconds[idx].entry(app_name).or_insert(0) += duration_secs;
+                }
             }
+        }
 
-            let params_refs: Vec<&dyn rusqlite::ToSql> =
-                params_vec.iter().map(|p| p.as_ref()).collect();
+        let series = buckets
+            .into_iter()
+            .zip(app_seconds)
+            .map(|(bucket, seconds)| {
+                let mut usages: Vec<CategoryUsage> = categories
+                    .iter()
+                    .zip(&category_apps)
+                    .map(|(category, apps)| {
+                        let mut apps: Vec<AppUsageInCategory> = apps
+                            .iter()
+                            .map(|app_name| AppUsageInCategory {
+                                app_name: app_name.clone(),
+                                total_seconds: seconds.get(app_name).copied().unwrap_or(0),
+                            })
+                            .collect();
+                        apps.sort_by_key(|a| std::cmp::Reverse(a.total_seconds));
 
-            let app_usages_with_time: Vec<AppUsageInCategory> = stmt
-                .query_map(params_refs.as_slice(), |row| {
-                    Ok(AppUsageInCategory {
-                        app_name: row.get(0)?,
-                        total_seconds: row.get(1)?,
+                        CategoryUsage {
+                            category: category.clone(),
+                            total_seconds: apps.iter().map(|a| a.total_seconds).sum(),
+                            app_count: apps.len(),
+                            apps,
+                        }
                     })
-                })?
-                .collect::<Result<Vec<_>, _>>()?;
+                    .collect();
+                usages.sort_by_key(|c| std::cmp::Reverse(c.total_seconds));
+                (bucket, usages)
+            })
+            .collect();
 
-            // 创建一个包含所有分类应用的列表，包括没有使用记录的应用
-            let mut all_app_usages: Vec<AppUsageInCategory> = Vec::new();
-            let apps_with_time: std::collections::HashSet<String> = app_usages_with_time
-                .iter()
-                .map(|a| a.app_name.clone())
-                .collect();
+        Ok(series)
+    }
 
-            all_app_usages.extend(app_usages_with_time);
+    /// 按时间段获取分类使用统计
+    ///
+    /// 将 [start, end] 按 `unit`（每段的时间单位）切分，返回每段内各分类的使用时长，
+    /// 用于按分类堆叠的图表。
+    pub async fn get_category_usage_series(
+        &self,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+        unit: TimeGranularity,
+    ) -> DbResult<Vec<(TimeRange, Vec<CategoryUsage>)>> {
+        let repo = self.clone();
+        tokio::task::spawn_blocking(move || repo.get_category_usage_series_sync(start, end, unit))
+            .await
+            .map_err(|e| DbError::Validation(format!("Task join error: {}", e)))?
+    }
 
-            for app_name in &apps {
-                if !apps_with_time.contains(app_name) {
-                    all_app_usages.push(AppUsageInCategory {
-                        app_name: app_name.clone(),
-                        total_seconds: 0,
-                    });
-                }
-            }
+    /// 获取单个分类每天的使用时长（同步方法，供内部使用）
+    fn get_category_daily_series_sync(
+        &self,
+        category_id: i64,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> DbResult<Vec<PeriodUsage>> {
+        let days = TimeRangeCalculator::split(&TimeRange::new(start, end), TimeGranularity::Day);
+        let mut day_seconds = vec![0i64; days.len()];
 
-            let total_seconds: i64 = all_app_usages.iter().map(|a| a.total_seconds).sum();
+        let conn = self.pool.get()?;
+        let mut stmt = conn.prepare(
+            "SELECT w.timestamp, w.duration_secs
+             FROM window_events w
+             JOIN app_categories ac ON ac.app_name = w.app_name
+             WHERE ac.category_id = ?1
+               AND w.timestamp >= ?2 AND w.timestamp < ?3
+               AND w.is_afk = 0",
+        )?;
+        let rows = stmt.query_map(params![category_id, start, end], |row| {
+            Ok((row.get::<_, DateTime<Utc>>(0)?, row.get::<_, i64>(1)?))
+        })?;
 
-            result.push(CategoryUsage {
-                category,
-                total_seconds,
-                app_count: all_app_usages.len(),
-                apps: all_app_usages,
-            });
+        for row in rows {
+            let (timestamp, duration_secs) = row?;
+            if let Some(idx) = days.iter().rposition(|d| d.start <= timestamp) {
+                day_seconds[idx] += duration_secs;
+            }
         }
 
-        // 按总时长排序
-        result.sort_by(|a, b| b.total_seconds.cmp(&a.total_seconds));
+        let series = days
+            .iter()
+            .zip(day_seconds)
+            .enumerate()
+            .map(|(i, (day, total_seconds))| {
+                let date = day.start.with_timezone(&Local);
+                PeriodUsage {
+                    label: format!("{}月{}日", date.month(), date.day()),
+                    index: i as i32,
+                    total_seconds,
+                }
+            })
+            .collect();
 
-        Ok(result)
+        Ok(series)
+    }
+
+    /// 获取单个分类每天（本地日期）的使用时长
+    ///
+    /// 汇总该分类下所有应用的非 AFK 时长，没有数据的日期时长为 0。
+    pub async fn get_category_daily_series(
+        &self,
+        category_id: i64,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> DbResult<Vec<PeriodUsage>> {
+        let repo = self.clone();
+        tokio::task::spawn_blocking(move || {
+            repo.get_category_daily_series_sync(category_id, start, end)
+        })
+        .await
+        .map_err(|e| DbError::Validation(format!("Task join error: {}", e)))?
+    }
+
+    /// 获取单个分类在时间范围内的使用统计，分类不存在时返回 `None`
+    pub async fn get_single_category_usage(
+        &self,
+        category_id: i64,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> DbResult<Option<CategoryUsage>> {
+        let repo = self.clone();
+        tokio::task::spawn_blocking(move || {
+            repo.get_single_category_usage_sync(category_id, start, end)
+        })
+        .await
+        .map_err(|e| DbError::Validation(format!("Task join error: {}", e)))?
+    }
+
+    /// 获取分类中使用时长最多的前 `limit` 个应用
+    pub async fn get_top_apps_in_category(
+        &self,
+        category_id: i64,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+        limit: usize,
+    ) -> DbResult<Vec<AppUsageInCategory>> {
+        let repo = self.clone();
+        tokio::task::spawn_blocking(move || {
+            repo.get_top_apps_in_category_sync(category_id, start, end, limit)
+        })
+        .await
+        .map_err(|e| DbError::Validation(format!("Task join error: {}", e)))?
+    }
+
+    /// 批量删除分类及其应用关联，返回实际删除的分类数量（不存在的 id 会被忽略）
+    pub async fn delete_categories(&self, ids: &[i64]) -> DbResult<usize> {
+        let repo = self.clone();
+        let ids = ids.to_vec();
+        tokio::task::spawn_blocking(move || repo.delete_categories_sync(&ids))
+            .await
+            .map_err(|e| DbError::Validation(format!("Task join error: {}", e)))?
+    }
+
+    /// 把源分类的所有应用移到目标分类（可选删除源分类），返回移动的应用数量
+    pub async fn move_category_apps(
+        &self,
+        from_id: i64,
+        to_id: i64,
+        delete_source: bool,
+    ) -> DbResult<usize> {
+        let repo = self.clone();
+        tokio::task::spawn_blocking(move || {
+            repo.move_category_apps_sync(from_id, to_id, delete_source)
+        })
+        .await
+        .map_err(|e| DbError::Validation(format!("Task join error: {}", e)))?
+    }
+
+    /// 按名称确保分类存在（已存在的跳过），返回与输入一一对应的分类 id
+    pub async fn ensure_categories(&self, categories: &[Category]) -> DbResult<Vec<i64>> {
+        let repo = self.clone();
+        let categories = categories.to_vec();
+        tokio::task::spawn_blocking(move || repo.ensure_categories_sync(&categories))
+            .await
+            .map_err(|e| DbError::Validation(format!("Task join error: {}", e)))?
+    }
+
+    /// 获取 `since` 之后有记录的应用名称（不包含早已不再使用的应用）
+    pub async fn get_all_app_names_since(&self, since: DateTime<Utc>) -> DbResult<Vec<String>> {
+        let repo = self.clone();
+        tokio::task::spawn_blocking(move || repo.get_all_app_names_since_sync(since))
+            .await
+            .map_err(|e| DbError::Validation(format!("Task join error: {}", e)))?
+    }
+
+    /// 获取分类列表，`include_archived` 为 `false` 时不包含已归档的分类
+    pub async fn get_categories(&self, include_archived: bool) -> DbResult<Vec<Category>> {
+        let repo = self.clone();
+        tokio::task::spawn_blocking(move || repo.get_categories_sync(include_archived))
+            .await
+            .map_err(|e| DbError::Validation(format!("Task join error: {}", e)))?
+    }
+
+    /// 按名称排序分页获取未归档的分类，跳过前 `offset` 个后最多返回 `limit` 个
+    pub async fn get_categories_page(
+        &self,
+        limit: usize,
+        offset: usize,
+    ) -> DbResult<Vec<Category>> {
+        let repo = self.clone();
+        tokio::task::spawn_blocking(move || repo.get_categories_page_sync(limit, offset))
+            .await
+            .map_err(|e| DbError::Validation(format!("Task join error: {}", e)))?
+    }
+
+    /// 归档分类
+    ///
+    /// 归档的分类不再出现在默认分类列表和统计中，但保留其应用关联，可随时恢复。
+    pub async fn archive_category(&self, id: i64) -> DbResult<()> {
+        let repo = self.clone();
+        tokio::task::spawn_blocking(move || repo.set_archived_sync(id, true))
+            .await
+            .map_err(|e| DbError::Validation(format!("Task join error: {}", e)))?
+    }
+
+    /// 取消归档分类
+    pub async fn unarchive_category(&self, id: i64) -> DbResult<()> {
+        let repo = self.clone();
+        tokio::task::spawn_blocking(move || repo.set_archived_sync(id, false))
+            .await
+            .map_err(|e| DbError::Validation(format!("Task join error: {}", e)))?
+    }
+
+    /// 获取有窗口事件记录但尚未加入任何（未归档）分类的应用名称（按名称排序）
+    pub async fn get_unclassified_app_names(&self) -> DbResult<Vec<String>> {
+        let repo = self.clone();
+        tokio::task::spawn_blocking(move || repo.get_unclassified_app_names_sync())
+            .await
+            .map_err(|e| DbError::Validation(format!("Task join error: {}", e)))?
     }
 }
 
@@ -367,3 +916,459 @@ impl Clone for CategoryRepositoryImpl {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::pool::create_memory_pool;
+    use crate::db::repositories::WindowEventRepositoryImpl;
+    use crate::models::WindowEvent;
+    use crate::traits::WindowEventRepository;
+    use chrono::{Local, NaiveDate};
+
+    fn local_time(day: u32, hour: u32) -> DateTime<Utc> {
+        NaiveDate::from_ymd_opt(2024, 3, day)
+            .unwrap()
+            .and_hms_opt(hour, 0, 0)
+            .unwrap()
+            .and_local_timezone(Local)
+            .unwrap()
+            .with_timezone(&Utc)
+    }
+
+    #[tokio::test]
+    async fn test_get_categories_page() {
+        let categories = CategoryRepositoryImpl::new(Arc::new(create_memory_pool()));
+        for name in ["e", "b", "d", "a", "c", "f"] {
+            categories
+                .insert(&Category {
+                    id: None,
+                    name: name.to_string(),
+                    icon: "🗀".to_string(),
+                    color: None,
+                })
+                .await
+                .unwrap();
+        }
+        let archived = categories.get_categories(false).await.unwrap()[1]
+            .id
+            .unwrap();
+        categories.archive_category(archived).await.unwrap();
+
+        let names = |page: Vec<Category>| page.into_iter().map(|c| c.name).collect::<Vec<_>>();
+        assert_eq!(
+            names(categories.get_categories_page(2, 0).await.unwrap()),
+            vec!["a", "c"]
+        );
+        assert_eq!(
+            names(categories.get_categories_page(2, 2).await.unwrap()),
+            vec!["d", "e"]
+        );
+        assert_eq!(
+            names(categories.get_categories_page(2, 4).await.unwrap()),
+            vec!["f"]
+        );
+        assert!(categories
+            .get_categories_page(2, 6)
+            .await
+            .unwrap()
+            .is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_move_category_apps() {
+        let categories = CategoryRepositoryImpl::new(Arc::new(create_memory_pool()));
+        let ids = categories
+            .ensure_categories(&[
+                Category {
+                    id: None,
+                    name: "Misc".to_string(),
+                    icon: "🗀".to_string(),
+                    color: None,
+                },
+                Category {
+                    id: None,
+                    name: "General".to_string(),
+                    icon: "🗀".to_string(),
+                    color: None,
+                },
+            ])
+            .await
+            .unwrap();
+        let (misc, general) = (ids[0], ids[1]);
+        for app_name in ["firefox", "kitty"] {
+            categories
+                .add_app_to_category(app_name, misc)
+                .await
+                .unwrap();
+        }
+        for app_name in ["firefox", "code"] {
+            categories
+                .add_app_to_category(app_name, general)
+                .await
+                .unwrap();
+        }
+
+        assert!(matches!(
+            categories.move_category_apps(misc, 9999, true).await,
+            Err(DbError::NotFound(_))
+        ));
+        assert_eq!(
+            categories
+                .move_category_apps(misc, general, true)
+                .await
+                .unwrap(),
+            2
+        );
+
+        assert_eq!(
+            categories.get_category_apps(general).await.unwrap(),
+            vec!["code", "firefox", "kitty"]
+        );
+        assert!(categories.get_category_apps(misc).await.unwrap().is_empty());
+        assert!(categories.get_by_id(misc).await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_get_category_daily_series() {
+        let pool = Arc::new(create_memory_pool());
+        let categories = CategoryRepositoryImpl::new(Arc::clone(&pool));
+        let events = WindowEventRepositoryImpl::new((*pool).clone());
+
+        let work = categories
+            .insert(&Category {
+                id: None,
+                name: "工作".to_string(),
+                icon: "💼".to_string(),
+                color: None,
+            })
+            .await
+            .unwrap();
+        categories.add_app_to_category("code", work).await.unwrap();
+        categories.add_app_to_category("kitty", work).await.unwrap();
+
+        for (app_name, timestamp, duration_secs) in [
+            ("code", local_time(4, 9), 3600),
+            ("kitty", local_time(4, 15), 600),
+            ("steam", local_time(4, 20), 1800),
+            ("kitty", local_time(6, 10), 900),
+        ] {
+            events
+                .insert(&WindowEvent {
+                    id: None,
+                    timestamp,
+                    app_name: app_name.to_string(),
+                    window_title: String::new(),
+                    workspace: String::new(),
+                    duration_secs,
+                    is_afk: false,
+                })
+                .await
+                .unwrap();
+        }
+
+        let first = TimeRangeCalculator::day(NaiveDate::from_ymd_opt(2024, 3, 4).unwrap());
+        let last = TimeRangeCalculator::day(NaiveDate::from_ymd_opt(2024, 3, 6).unwrap());
+        let series = categories
+            .get_category_daily_series(work, first.start, last.end)
+            .await
+            .unwrap();
+
+        let totals: Vec<_> = series.iter().map(|p| p.total_seconds).collect();
+        assert_eq!(totals, vec![4200, 0, 900]);
+        assert_eq!(series[1].label, "3月5日");
+        assert_eq!(series[2].index, 2);
+    }
+
+    #[tokio::test]
+    async fn test_get_category_usage_series_by_day() {
+        let pool = Arc::new(create_memory_pool());
+        let categories = CategoryRepositoryImpl::new(Arc::clone(&pool));
+        let events = WindowEventRepositoryImpl::new((*pool).clone());
+
+        let work = categories
+            .insert(&Category {
+                id: None,
+                name: "工作".to_string(),
+                icon: "💼".to_string(),
+                color: None,
+            })
+            .await
+            .unwrap();
+        let fun = categories
+            .insert(&Category {
+                id: None,
+                name: "娱乐".to_string(),
+                icon: "🎮".to_string(),
+                color: None,
+            })
+            .await
+            .unwrap();
+        categories.add_app_to_category("code", work).await.unwrap();
+        categories.add_app_to_category("steam", fun).await.unwrap();
+
+        for (app_name, timestamp, duration_secs) in [
+            ("code", local_time(4, 9), 3600),
+            ("steam", local_time(4, 20), 1800),
+            ("code", local_time(5, 10), 600),
+            ("code", local_time(5, 11), 900),
+        ] {
+            events
+                .insert(&WindowEvent {
+                    id: None,
+                    timestamp,
+                    app_name: app_name.to_string(),
+                    window_title: String::new(),
+                    workspace: String::new(),
+                    duration_secs,
+                    is_afk: false,
+                })
+                .await
+                .unwrap();
+        }
+
+        let day1 = TimeRangeCalculator::day(NaiveDate::from_ymd_opt(2024, 3, 4).unwrap());
+        let day2 = TimeRangeCalculator::day(NaiveDate::from_ymd_opt(2024, 3, 5).unwrap());
+        let series = categories
+            .get_category_usage_series(day1.start, day2.end, TimeGranularity::Day)
+            .await
+            .unwrap();
+
+        assert_eq!(series.len(), 2);
+        let totals = |usages: &[CategoryUsage], name: &str| {
+            usages
+                .iter()
+                .find(|c| c.category.name == name)
+                .map(|c| c.total_seconds)
+                .unwrap()
+        };
+
+        let (range1, usages1) = &series[0];
+        assert_eq!(range1, &day1);
+        assert_eq!(totals(usages1, "工作"), 3600);
+        assert_eq!(totals(usages1, "娱乐"), 1800);
+
+        let (range2, usages2) = &series[1];
+        assert_eq!(range2, &day2);
+        assert_eq!(totals(usages2, "工作"), 1500);
+        assert_eq!(totals(usages2, "娱乐"), 0);
+    }
+
+    #[tokio::test]
+    async fn test_archive_category_keeps_links() {
+        let pool = Arc::new(create_memory_pool());
+        let categories = CategoryRepositoryImpl::new(Arc::clone(&pool));
+
+        let work = categories
+            .insert(&Category {
+                id: None,
+                name: "工作".to_string(),
+                icon: "💼".to_string(),
+                color: None,
+            })
+            .await
+            .unwrap();
+        categories.add_app_to_category("code", work).await.unwrap();
+
+        categories.archive_category(work).await.unwrap();
+        assert!(categories.get_all().await.unwrap().is_empty());
+        assert!(categories
+            .get_app_categories("code")
+            .await
+            .unwrap()
+            .is_empty());
+
+        let all = categories.get_categories(true).await.unwrap();
+        assert_eq!(all.len(), 1);
+        assert_eq!(all[0].id, Some(work));
+        assert_eq!(
+            categories.get_category_apps(work).await.unwrap(),
+            vec!["code"]
+        );
+
+        // 修改应用的分类不影响归档分类的关联
+        categories.set_app_categories("code", &[]).await.unwrap();
+        assert_eq!(
+            categories.get_category_apps(work).await.unwrap(),
+            vec!["code"]
+        );
+
+        categories.unarchive_category(work).await.unwrap();
+        let restored = categories.get_all().await.unwrap();
+        assert_eq!(restored.len(), 1);
+        assert_eq!(restored[0].name, "工作");
+        assert_eq!(
+            categories.get_app_categories("code").await.unwrap()[0].id,
+            Some(work)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_archive_missing_category() {
+        let categories = CategoryRepositoryImpl::new(Arc::new(create_memory_pool()));
+        assert!(matches!(
+            categories.archive_category(42).await,
+            Err(DbError::NotFound(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_get_top_apps_in_category() {
+        let pool = Arc::new(create_memory_pool());
+        let categories = CategoryRepositoryImpl::new(Arc::clone(&pool));
+        let events = WindowEventRepositoryImpl::new((*pool).clone());
+
+        let work = categories
+            .insert(&Category {
+                id: None,
+                name: "工作".to_string(),
+                icon: "💼".to_string(),
+                color: None,
+            })
+            .await
+            .unwrap();
+        for app_name in ["code", "kitty", "firefox"] {
+            categories
+                .add_app_to_category(app_name, work)
+                .await
+                .unwrap();
+        }
+
+        for (app_name, timestamp, duration_secs) in [
+            ("code", local_time(4, 9), 1800),
+            ("kitty", local_time(4, 10), 600),
+            ("firefox", local_time(4, 11), 1200),
+            ("code", local_time(4, 14), 1200),
+            ("steam", local_time(4, 20), 7200),
+        ] {
+            events
+                .insert(&WindowEvent {
+                    id: None,
+                    timestamp,
+                    app_name: app_name.to_string(),
+                    window_title: String::new(),
+                    workspace: String::new(),
+                    duration_secs,
+                    is_afk: false,
+                })
+                .await
+                .unwrap();
+        }
+
+        let day = TimeRangeCalculator::day(NaiveDate::from_ymd_opt(2024, 3, 4).unwrap());
+        let top = categories
+            .get_top_apps_in_category(work, day.start, day.end, 2)
+            .await
+            .unwrap();
+
+        let top: Vec<_> = top
+            .iter()
+            .map(|a| (a.app_name.as_str(), a.total_seconds))
+            .collect();
+        assert_eq!(top, vec![("code", 3000), ("firefox", 1200)]);
+    }
+
+    #[tokio::test]
+    async fn test_get_all_app_names_since() {
+        let pool = Arc::new(create_memory_pool());
+        let categories = CategoryRepositoryImpl::new(Arc::clone(&pool));
+        let events = WindowEventRepositoryImpl::new((*pool).clone());
+
+        for (app_name, timestamp) in [
+            ("gimp", local_time(1, 9)),
+            ("code", local_time(20, 9)),
+            ("code", local_time(2, 9)),
+        ] {
+            events
+                .insert(&WindowEvent {
+                    id: None,
+                    timestamp,
+                    app_name: app_name.to_string(),
+                    window_title: String::new(),
+                    workspace: String::new(),
+                    duration_secs: 60,
+                    is_afk: false,
+                })
+                .await
+                .unwrap();
+        }
+
+        let recent = categories
+            .get_all_app_names_since(local_time(15, 0))
+            .await
+            .unwrap();
+        assert_eq!(recent, vec!["code"]);
+        assert_eq!(
+            categories.get_all_app_names().await.unwrap(),
+            vec!["code", "gimp"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_delete_categories() {
+        let repo = CategoryRepositoryImpl::new(Arc::new(create_memory_pool()));
+
+        let mut ids = Vec::new();
+        for (name, app_name) in [("工作", "code"), ("娱乐", "steam"), ("学习", "anki")] {
+            let id = repo
+                .insert(&Category {
+                    id: None,
+                    name: name.to_string(),
+                    icon: "🗀".to_string(),
+                    color: None,
+                })
+                .await
+                .unwrap();
+            repo.add_app_to_category(app_name, id).await.unwrap();
+            ids.push(id);
+        }
+
+        let deleted = repo
+            .delete_categories(&[ids[0], ids[1], 9999])
+            .await
+            .unwrap();
+        assert_eq!(deleted, 2);
+
+        let remaining = repo.get_all().await.unwrap();
+        assert_eq!(remaining.len(), 1);
+        assert_eq!(remaining[0].name, "学习");
+        assert_eq!(repo.get_category_apps(ids[2]).await.unwrap(), vec!["anki"]);
+        assert!(repo.get_category_apps(ids[0]).await.unwrap().is_empty());
+        assert!(repo.get_app_categories("steam").await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_ensure_categories_idempotent() {
+        let repo = CategoryRepositoryImpl::new(Arc::new(create_memory_pool()));
+        let existing = repo
+            .insert(&Category {
+                id: None,
+                name: "开发".to_string(),
+                icon: "💻".to_string(),
+                color: Some("#3366ff".to_string()),
+            })
+            .await
+            .unwrap();
+
+        let seed: Vec<Category> = ["工作", "开发", "娱乐"]
+            .iter()
+            .map(|name| Category {
+                id: None,
+                name: name.to_string(),
+                icon: "🗀".to_string(),
+                color: None,
+            })
+            .collect();
+
+        let first = repo.ensure_categories(&seed).await.unwrap();
+        let second = repo.ensure_categories(&seed).await.unwrap();
+        assert_eq!(first, second);
+        assert_eq!(first[1], existing);
+
+        let all = repo.get_all().await.unwrap();
+        assert_eq!(all.len(), 3);
+        // 已存在的分类不会被覆盖
+        let dev = all.iter().find(|c| c.name == "开发").unwrap();
+        assert_eq!(dev.icon, "💻");
+    }
+}
@@ -3,10 +3,11 @@
 use crate::db::pool::DbPool;
 use crate::errors::{DbError, DbResult};
 use crate::models::DailyGoal;
+use crate::time::range::TimeRangeCalculator;
 use crate::traits::DailyGoalRepository;
 use async_trait::async_trait;
-use chrono::{Local, Utc};
-use rusqlite::params;
+use chrono::{DateTime, Local, NaiveDate, NaiveTime, Utc};
+use rusqlite::{params, OptionalExtension};
 
 /// 每日目标仓储实现
 pub struct DailyGoalRepositoryImpl {
@@ -19,8 +20,21 @@ impl DailyGoalRepositoryImpl {
     }
 
     fn upsert_sync(&self, goal: &DailyGoal) -> DbResult<i64> {
-        let conn = self.pool.get()?;
-        conn.execute(
+        self.upsert_effective_sync(goal, Local::now().date_naive())
+    }
+
+    /// 保存目标，并记录自 `effective_from` 起生效的历史版本
+    fn upsert_effective_sync(&self, goal: &DailyGoal, effective_from: NaiveDate) -> DbResult<i64> {
+        if goal.max_minutes <= 0 {
+            return Err(DbError::Validation(format!(
+                "目标时长必须大于 0: {}",
+                goal.max_minutes
+            )));
+        }
+
+        let mut conn = self.pool.get()?;
+        let tx = conn.transaction()?;
+        tx.execute(
             "INSERT INTO daily_goals (app_name, max_minutes, notify_enabled)
              VALUES (?1, ?2, ?3)
              ON CONFLICT(app_name) DO UPDATE SET
@@ -28,7 +42,73 @@ impl DailyGoalRepositoryImpl {
                 notify_enabled = excluded.notify_enabled",
             params![goal.app_name, goal.max_minutes, goal.notify_enabled],
         )?;
-        Ok(conn.last_insert_rowid())
+        let id = tx.last_insert_rowid();
+        Self::record_history(&tx, &goal.app_name, Some(goal.max_minutes), effective_from)?;
+        tx.commit()?;
+        Ok(id)
+    }
+
+    /// 写入目标历史（同一天内多次修改只保留最后一次）
+    pub(crate) fn record_history(
+        conn: &rusqlite::Connection,
+        app_name: &str,
+        max_minutes: Option<i32>,
+        effective_from: NaiveDate,
+    ) -> DbResult<()> {
+        conn.execute(
+            "INSERT INTO daily_goal_history (app_name, max_minutes, effective_from)
+             VALUES (?1, ?2, ?3)
+             ON CONFLICT(app_name, effective_from) DO UPDATE SET
+                max_minutes = excluded.max_minutes",
+            params![app_name, max_minutes, effective_from],
+        )?;
+        Ok(())
+    }
+
+    fn get_goal_at_sync(&self, app_name: &str, date: NaiveDate) -> DbResult<Option<i64>> {
+        let conn = self.pool.get()?;
+
+        let has_history: bool = conn.query_row(
+            "SELECT EXISTS(SELECT 1 FROM daily_goal_history WHERE app_name = ?1)",
+            params![app_name],
+            |row| row.get(0),
+        )?;
+
+        if !has_history {
+            // 历史表出现之前创建的目标没有历史记录，退回到当前目标
+            let current = conn
+                .query_row(
+                    "SELECT max_minutes FROM daily_goals WHERE app_name = ?1",
+                    params![app_name],
+                    |row| row.get(0),
+                )
+                .optional()?;
+            return Ok(current);
+        }
+
+        let cap: Option<Option<i64>> = conn
+            .query_row(
+                "SELECT max_minutes FROM daily_goal_history
+                 WHERE app_name = ?1 AND effective_from <= ?2
+                 ORDER BY effective_from DESC
+                 LIMIT 1",
+                params![app_name, date],
+                |row| row.get(0),
+            )
+            .optional()?;
+
+        Ok(cap.flatten())
+    }
+
+    /// 获取指定日期生效的每日目标（分钟）
+    ///
+    /// 返回该日期当天或之前最近一次设置的值；若当时没有目标或目标已被删除则返回 `None`。
+    pub async fn get_goal_at(&self, app_name: &str, date: NaiveDate) -> DbResult<Option<i64>> {
+        let repo = self.clone();
+        let app_name = app_name.to_string();
+        tokio::task::spawn_blocking(move || repo.get_goal_at_sync(&app_name, date))
+            .await
+            .map_err(|e| DbError::Validation(format!("Task join error: {}", e)))?
     }
 
     fn get_all_sync(&self) -> DbResult<Vec<DailyGoal>> {
@@ -54,26 +134,127 @@ impl DailyGoalRepositoryImpl {
     }
 
     fn delete_sync(&self, app_name: &str) -> DbResult<()> {
-        let conn = self.pool.get()?;
-        conn.execute(
+        let mut conn = self.pool.get()?;
+        let tx = conn.transaction()?;
+        tx.execute(
             "DELETE FROM daily_goals WHERE app_name = ?1",
             params![app_name],
         )?;
+        Self::record_history(&tx, app_name, None, Local::now().date_naive())?;
+        tx.commit()?;
         Ok(())
     }
 
-    fn get_today_usage_sync(&self, app_name: &str) -> DbResult<i64> {
+    /// 将目标从 `from` 转移到 `to`
+    ///
+    /// 若 `to` 已有目标，保留两者中更严格（`max_minutes` 更小）的一条，另一条被删除。
+    fn rename_goal_app_sync(&self, from: &str, to: &str) -> DbResult<()> {
+        if from == to {
+            return Ok(());
+        }
+
+        let mut conn = self.pool.get()?;
+        let tx = conn.transaction()?;
+
+        let source: i32 = tx
+            .query_row(
+                "SELECT max_minutes FROM daily_goals WHERE app_name = ?1",
+                params![from],
+                |row| row.get(0),
+            )
+            .optional()?
+            .ok_or_else(|| DbError::NotFound(format!("应用 {} 没有每日目标", from)))?;
+        let target: Option<i32> = tx
+            .query_row(
+                "SELECT max_minutes FROM daily_goals WHERE app_name = ?1",
+                params![to],
+                |row| row.get(0),
+            )
+            .optional()?;
+
+        match target {
+            Some(existing) if existing <= source => {
+                // 目标应用的上限更严格，直接丢弃来源目标
+                tx.execute("DELETE FROM daily_goals WHERE app_name = ?1", params![from])?;
+            }
+            _ => {
+                tx.execute("DELETE FROM daily_goals WHERE app_name = ?1", params![to])?;
+                tx.execute(
+                    "UPDATE daily_goals SET app_name = ?2 WHERE app_name = ?1",
+                    params![from, to],
+                )?;
+            }
+        }
+
+        let today = Local::now().date_naive();
+        let cap = target.map_or(source, |existing| existing.min(source));
+        Self::record_history(&tx, from, None, today)?;
+        Self::record_history(&tx, to, Some(cap), today)?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// 将应用的每日目标转移到另一个应用名下（例如应用改名或设置别名后）
+    ///
+    /// 若 `to` 已有目标，保留更严格（分钟数更小）的上限；`from` 没有目标时返回 `NotFound`。
+    pub async fn rename_goal_app(&self, from: &str, to: &str) -> DbResult<()> {
+        let repo = self.clone();
+        let from = from.to_string();
+        let to = to.to_string();
+        tokio::task::spawn_blocking(move || repo.rename_goal_app_sync(&from, &to))
+            .await
+            .map_err(|e| DbError::Validation(format!("Task join error: {}", e)))?
+    }
+
+    /// 今天的开始时间（使用本地时间计算"今天"，然后转换为 UTC）
+    ///
+    /// 夏令时跳过零点的时区中取零点之后第一个有效时刻。
+    fn today_start() -> DateTime<Utc> {
+        TimeRangeCalculator::local_to_utc(Local::now().date_naive().and_time(NaiveTime::MIN))
+    }
+
+    fn get_goals_with_progress_sync(&self) -> DbResult<Vec<(DailyGoal, i64)>> {
         let conn = self.pool.get()?;
+        let mut stmt = conn.prepare(
+            "SELECT g.id, g.app_name, g.max_minutes, g.notify_enabled,
+                    COALESCE(SUM(e.duration_secs), 0)
+             FROM daily_goals g
+             LEFT JOIN window_events e
+                ON e.app_name = g.app_name AND e.timestamp >= ?1 AND e.is_afk = 0
+             GROUP BY g.id
+             ORDER BY g.app_name ASC",
+        )?;
 
-        // 获取今天的开始时间（使用本地时间计算"今天"，然后转换为 UTC）
-        let local_now = Local::now();
-        let today_start = local_now
-            .date_naive()
-            .and_hms_opt(0, 0, 0)
-            .unwrap()
-            .and_local_timezone(Local)
-            .unwrap()
-            .with_timezone(&Utc);
+        let goals = stmt
+            .query_map(params![Self::today_start()], |row| {
+                Ok((
+                    DailyGoal {
+                        id: Some(row.get(0)?),
+                        app_name: row.get(1)?,
+                        max_minutes: row.get(2)?,
+                        notify_enabled: row.get(3)?,
+                    },
+                    row.get(4)?,
+                ))
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(goals)
+    }
+
+    /// 获取所有目标及其今天已使用的秒数（非 AFK）
+    ///
+    /// 一次查询完成，替代逐个应用调用 `get_today_usage`。
+    pub async fn get_goals_with_progress(&self) -> DbResult<Vec<(DailyGoal, i64)>> {
+        let repo = self.clone();
+        tokio::task::spawn_blocking(move || repo.get_goals_with_progress_sync())
+            .await
+            .map_err(|e| DbError::Validation(format!("Task join error: {}", e)))?
+    }
+
+    fn get_today_usage_sync(&self, app_name: &str) -> DbResult<i64> {
+        let conn = self.pool.get()?;
+        let today_start = Self::today_start();
 
         let mut stmt = conn.prepare(
             "SELECT COALESCE(SUM(duration_secs), 0)
@@ -128,3 +309,225 @@ impl Clone for DailyGoalRepositoryImpl {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::pool::create_memory_pool;
+    use crate::db::repositories::WindowEventRepositoryImpl;
+    use crate::models::WindowEvent;
+    use crate::traits::WindowEventRepository;
+
+    fn goal(max_minutes: i32) -> DailyGoal {
+        DailyGoal {
+            id: None,
+            app_name: "firefox".to_string(),
+            max_minutes,
+            notify_enabled: true,
+        }
+    }
+
+    fn event(
+        app_name: &str,
+        timestamp: DateTime<Utc>,
+        duration_secs: i64,
+        is_afk: bool,
+    ) -> WindowEvent {
+        WindowEvent {
+            id: None,
+            timestamp,
+            app_name: app_name.to_string(),
+            window_title: String::new(),
+            workspace: String::new(),
+            duration_secs,
+            is_afk,
+        }
+    }
+
+    fn date(month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2024, month, day).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_get_goal_at_two_versions() {
+        let repo = DailyGoalRepositoryImpl::new(create_memory_pool());
+        repo.upsert_effective_sync(&goal(120), date(3, 1)).unwrap();
+        repo.upsert_effective_sync(&goal(60), date(3, 10)).unwrap();
+
+        // 第一个版本之前没有目标
+        assert_eq!(
+            repo.get_goal_at("firefox", date(2, 28)).await.unwrap(),
+            None
+        );
+        assert_eq!(
+            repo.get_goal_at("firefox", date(3, 1)).await.unwrap(),
+            Some(120)
+        );
+        assert_eq!(
+            repo.get_goal_at("firefox", date(3, 9)).await.unwrap(),
+            Some(120)
+        );
+        assert_eq!(
+            repo.get_goal_at("firefox", date(3, 10)).await.unwrap(),
+            Some(60)
+        );
+        assert_eq!(
+            repo.get_goal_at("firefox", date(4, 1)).await.unwrap(),
+            Some(60)
+        );
+
+        // 当前目标为最新版本
+        let goals = repo.get_all().await.unwrap();
+        assert_eq!(goals.len(), 1);
+        assert_eq!(goals[0].max_minutes, 60);
+    }
+
+    #[tokio::test]
+    async fn test_get_goal_at_after_delete() {
+        let repo = DailyGoalRepositoryImpl::new(create_memory_pool());
+        repo.upsert_effective_sync(&goal(120), date(3, 1)).unwrap();
+        repo.delete("firefox").await.unwrap();
+
+        let today = Local::now().date_naive();
+        assert_eq!(repo.get_goal_at("firefox", today).await.unwrap(), None);
+        assert_eq!(
+            repo.get_goal_at("firefox", date(3, 2)).await.unwrap(),
+            Some(120)
+        );
+    }
+
+    fn app_goal(app_name: &str, max_minutes: i32, notify_enabled: bool) -> DailyGoal {
+        DailyGoal {
+            id: None,
+            app_name: app_name.to_string(),
+            max_minutes,
+            notify_enabled,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_rename_goal_app() {
+        let repo = DailyGoalRepositoryImpl::new(create_memory_pool());
+        repo.upsert(&app_goal("firefox", 90, false)).await.unwrap();
+
+        repo.rename_goal_app("firefox", "librewolf").await.unwrap();
+
+        let goals = repo.get_all().await.unwrap();
+        assert_eq!(goals.len(), 1);
+        assert_eq!(goals[0].app_name, "librewolf");
+        assert_eq!(goals[0].max_minutes, 90);
+        assert!(!goals[0].notify_enabled);
+
+        assert!(matches!(
+            repo.rename_goal_app("firefox", "librewolf").await,
+            Err(DbError::NotFound(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_rename_goal_app_keeps_stricter_cap() {
+        let repo = DailyGoalRepositoryImpl::new(create_memory_pool());
+        repo.upsert(&app_goal("firefox", 60, true)).await.unwrap();
+        repo.upsert(&app_goal("librewolf", 120, false))
+            .await
+            .unwrap();
+
+        repo.rename_goal_app("firefox", "librewolf").await.unwrap();
+
+        let goals = repo.get_all().await.unwrap();
+        assert_eq!(goals.len(), 1);
+        assert_eq!(goals[0].app_name, "librewolf");
+        assert_eq!(goals[0].max_minutes, 60);
+        assert!(goals[0].notify_enabled);
+
+        // 目标应用的上限更严格时保留目标应用的设置
+        repo.upsert(&app_goal("chromium", 180, true)).await.unwrap();
+        repo.rename_goal_app("chromium", "librewolf").await.unwrap();
+
+        let goals = repo.get_all().await.unwrap();
+        assert_eq!(goals.len(), 1);
+        assert_eq!(goals[0].max_minutes, 60);
+    }
+
+    #[tokio::test]
+    async fn test_get_today_usage_per_app() {
+        let pool = create_memory_pool();
+        let events = WindowEventRepositoryImpl::new(pool.clone());
+        let repo = DailyGoalRepositoryImpl::new(pool);
+
+        let now = Utc::now();
+        for (app_name, duration_secs, is_afk) in [
+            ("firefox", 300, false),
+            ("firefox", 200, false),
+            ("firefox", 900, true),
+            ("code", 700, false),
+        ] {
+            events
+                .insert(&event(app_name, now, duration_secs, is_afk))
+                .await
+                .unwrap();
+        }
+
+        assert_eq!(repo.get_today_usage("firefox").await.unwrap(), 500);
+        assert_eq!(repo.get_today_usage("code").await.unwrap(), 700);
+        assert_eq!(repo.get_today_usage("steam").await.unwrap(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_get_goals_with_progress() {
+        let pool = create_memory_pool();
+        let events = WindowEventRepositoryImpl::new(pool.clone());
+        let repo = DailyGoalRepositoryImpl::new(pool);
+
+        repo.upsert(&app_goal("firefox", 60, true)).await.unwrap();
+        repo.upsert(&app_goal("steam", 30, false)).await.unwrap();
+
+        let now = Utc::now();
+        for (app_name, duration_secs, is_afk) in [
+            ("firefox", 300, false),
+            ("firefox", 200, false),
+            ("firefox", 900, true),
+            ("code", 700, false),
+        ] {
+            events
+                .insert(&event(app_name, now, duration_secs, is_afk))
+                .await
+                .unwrap();
+        }
+
+        let progress: Vec<(String, i32, i64)> = repo
+            .get_goals_with_progress()
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|(goal, used)| (goal.app_name, goal.max_minutes, used))
+            .collect();
+        assert_eq!(
+            progress,
+            vec![
+                ("firefox".to_string(), 60, 500),
+                ("steam".to_string(), 30, 0),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_get_goal_at_without_history() {
+        let repo = DailyGoalRepositoryImpl::new(create_memory_pool());
+        assert_eq!(repo.get_goal_at("firefox", date(3, 1)).await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn test_upsert_rejects_non_positive_goal() {
+        let repo = DailyGoalRepositoryImpl::new(create_memory_pool());
+
+        for max_minutes in [0, -10] {
+            let err = repo.upsert(&goal(max_minutes)).await.unwrap_err();
+            assert!(matches!(err, DbError::Validation(_)));
+        }
+        assert!(repo.get_all().await.unwrap().is_empty());
+
+        repo.upsert(&goal(1)).await.unwrap();
+        assert_eq!(repo.get_all().await.unwrap().len(), 1);
+    }
+}
@@ -2,12 +2,23 @@
 
 pub mod afk_event;
 pub mod alias;
+pub mod app_color;
 pub mod category;
 pub mod daily_goal;
+pub mod rollup;
+pub mod saved_view;
+pub mod settings;
 pub mod window_event;
 
 pub use afk_event::AfkEventRepositoryImpl;
-pub use alias::AliasRepositoryImpl;
+pub use alias::{format_alias_mapping, parse_alias_mapping, AliasRepositoryImpl};
+pub use app_color::AppColorRepositoryImpl;
 pub use category::CategoryRepositoryImpl;
 pub use daily_goal::DailyGoalRepositoryImpl;
+pub use rollup::DailyRollupRepositoryImpl;
+pub use saved_view::SavedViewRepositoryImpl;
+pub use settings::{
+    SettingsRepositoryImpl, AFK_THRESHOLD_SECS_KEY, DEFAULT_AFK_THRESHOLD_SECS,
+    TRACKER_HEARTBEAT_KEY,
+};
 pub use window_event::WindowEventRepositoryImpl;
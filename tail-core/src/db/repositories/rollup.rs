@@ -0,0 +1,335 @@
+//! 每日应用使用汇总仓储实现
+//!
+//! 把每个本地日期各应用的总时长预先汇总到 `daily_app_rollup` 表，数据库很大时仪表板等
+//! 按整天统计的查询可以直接读取汇总，而不必扫描原始窗口事件。
+//! 已汇总的日期记录在 `daily_app_rollup_days` 中，没有汇总的日期仍然从原始事件统计。
+//! `window_events` 上的触发器在事件写入时移除所在日期的汇总标记，汇总结果因此不会过期。
+
+use crate::db::pool::DbPool;
+use crate::errors::{DbError, DbResult};
+use crate::time::range::TimeRangeCalculator;
+use chrono::{DateTime, Local, NaiveDate, Utc};
+use rusqlite::{params, Connection};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// 每日应用使用汇总仓储实现
+pub struct DailyRollupRepositoryImpl {
+    pool: Arc<DbPool>,
+}
+
+impl DailyRollupRepositoryImpl {
+    pub fn new(pool: Arc<DbPool>) -> Self {
+        Self { pool }
+    }
+
+    /// 重新计算单个本地日期的汇总，统计口径与 `AppUsageQuery::get_app_usage` 相同
+    fn rollup_day(conn: &Connection, date: NaiveDate) -> DbResult<()> {
+        let range = TimeRangeCalculator::day(date);
+        conn.execute(
+            "DELETE FROM daily_app_rollup WHERE date = ?1",
+            params![date],
+        )?;
+        conn.execute(
+            "INSERT INTO daily_app_rollup (date, app_name, total_seconds)
+             SELECT ?1, app_name, SUM(duration_secs)
+             FROM window_events
+             WHERE timestamp >= ?2 AND timestamp < ?3
+             GROUP BY app_name",
+            params![date, range.start, range.end],
+        )?;
+        conn.execute(
+            "INSERT OR IGNORE INTO daily_app_rollup_days (date) VALUES (?1)",
+            params![date],
+        )?;
+        Ok(())
+    }
+
+    /// 直接从原始窗口事件统计单个本地日期各应用的时长
+    fn scan_day(conn: &Connection, date: NaiveDate) -> DbResult<Vec<(String, i64)>> {
+        let range = TimeRangeCalculator::day(date);
+        let mut stmt = conn.prepare(
+            "SELECT app_name, SUM(duration_secs)
+             FROM window_events
+             WHERE timestamp >= ?1 AND timestamp < ?2
+             GROUP BY app_name",
+        )?;
+        let apps = stmt
+            .query_map(params![range.start, range.end], |row| {
+                Ok((row.get(0)?, row.get(1)?))
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(apps)
+    }
+
+    fn rebuild_sync(&self) -> DbResult<usize> {
+        let mut conn = self.pool.get()?;
+        let tx = conn.transaction()?;
+
+        tx.execute("DELETE FROM daily_app_rollup", [])?;
+        tx.execute("DELETE FROM daily_app_rollup_days", [])?;
+
+        let (first, last): (Option<DateTime<Utc>>, Option<DateTime<Utc>>) = tx.query_row(
+            "SELECT MIN(timestamp), MAX(timestamp) FROM window_events",
+            [],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )?;
+        let mut days = 0;
+        if let (Some(first), Some(last)) = (first, last) {
+            let last = last.with_timezone(&Local).date_naive();
+            let mut date = first.with_timezone(&Local).date_naive();
+            while date <= last {
+                Self::rollup_day(&tx, date)?;
+                days += 1;
+                match date.succ_opt() {
+                    Some(next) => date = next,
+                    None => break,
+                }
+            }
+        }
+        tx.commit()?;
+
+        Ok(days)
+    }
+
+    fn update_day_sync(&self, date: NaiveDate) -> DbResult<()> {
+        let mut conn = self.pool.get()?;
+        let tx = conn.transaction()?;
+        Self::rollup_day(&tx, date)?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    fn get_summary_sync(&self, first: NaiveDate, last: NaiveDate) -> DbResult<Vec<(String, i64)>> {
+        let conn = self.pool.get()?;
+        let today = Local::now().date_naive();
+
+        let mut totals: HashMap<String, i64> = HashMap::new();
+        let mut is_rolled_up =
+            conn.prepare("SELECT 1 FROM daily_app_rollup_days WHERE date = ?1")?;
+        let mut read_rollup =
+            conn.prepare("SELECT app_name, total_seconds FROM daily_app_rollup WHERE date = ?1")?;
+
+        let mut date = first;
+        while date <= last {
+            // 今天的数据仍在变化，总是从原始事件统计
+            let apps = if date < today && is_rolled_up.exists(params![date])? {
+                read_rollup
+                    .query_map(params![date], |row| Ok((row.get(0)?, row.get(1)?)))?
+                    .collect::<Result<Vec<_>, _>>()?
+            } else {
+                Self::scan_day(&conn, date)?
+            };
+            for (app_name, seconds) in apps {
+                *totals.entry(app_name).or_insert(0) += seconds;
+            }
+
+            match date.succ_opt() {
+                Some(next) => date = next,
+                None => break,
+            }
+        }
+
+        let mut summary: Vec<(String, i64)> = totals.into_iter().collect();
+        summary.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        Ok(summary)
+    }
+
+    /// 清空并重新计算所有日期的汇总，返回汇总的日期数量
+    pub async fn rebuild_rollup(&self) -> DbResult<usize> {
+        let repo = self.clone();
+        tokio::task::spawn_blocking(move || repo.rebuild_sync())
+            .await
+            .map_err(|e| DbError::Validation(format!("Task join error: {}", e)))?
+    }
+
+    /// 重新计算单个本地日期的汇总
+    pub async fn update_rollup_for_day(&self, date: NaiveDate) -> DbResult<()> {
+        let repo = self.clone();
+        tokio::task::spawn_blocking(move || repo.update_day_sync(date))
+            .await
+            .map_err(|e| DbError::Validation(format!("Task join error: {}", e)))?
+    }
+
+    /// 获取本地日期 `[first, last]` 内各应用的总时长 `(应用名称, 秒数)`，按时长降序
+    ///
+    /// 今天之前已汇总的日期读取汇总表，其余日期从原始窗口事件统计。
+    pub async fn get_app_usage_summary(
+        &self,
+        first: NaiveDate,
+        last: NaiveDate,
+    ) -> DbResult<Vec<(String, i64)>> {
+        let repo = self.clone();
+        tokio::task::spawn_blocking(move || repo.get_summary_sync(first, last))
+            .await
+            .map_err(|e| DbError::Validation(format!("Task join error: {}", e)))?
+    }
+}
+
+impl Clone for DailyRollupRepositoryImpl {
+    fn clone(&self) -> Self {
+        Self {
+            pool: Arc::clone(&self.pool),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::pool::create_memory_pool;
+    use crate::db::queries::AppUsageQueryImpl;
+    use crate::db::repositories::WindowEventRepositoryImpl;
+    use crate::models::WindowEvent;
+    use crate::traits::WindowEventRepository;
+
+    fn event_on(date: NaiveDate, hour: i64, app_name: &str, duration_secs: i64) -> WindowEvent {
+        WindowEvent {
+            id: None,
+            timestamp: TimeRangeCalculator::day(date).start + chrono::Duration::hours(hour),
+            app_name: app_name.to_string(),
+            window_title: String::new(),
+            workspace: String::new(),
+            duration_secs,
+            is_afk: false,
+        }
+    }
+
+    fn is_rolled_up(rollup: &DailyRollupRepositoryImpl, date: NaiveDate) -> bool {
+        rollup
+            .pool
+            .get()
+            .unwrap()
+            .prepare("SELECT 1 FROM daily_app_rollup_days WHERE date = ?1")
+            .unwrap()
+            .exists(params![date])
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_rollup_matches_direct_aggregation() {
+        let pool = create_memory_pool();
+        let events = WindowEventRepositoryImpl::new(pool.clone());
+        let pool = Arc::new(pool);
+        let rollup = DailyRollupRepositoryImpl::new(Arc::clone(&pool));
+
+        let first = NaiveDate::from_ymd_opt(2024, 3, 4).unwrap();
+        let last = first + chrono::Duration::days(3);
+        for (offset, hour, app_name, duration_secs) in [
+            (0, 9, "code", 3600),
+            (0, 10, "firefox", 600),
+            (1, 9, "code", 1800),
+            (3, 20, "mpv", 2400),
+            (3, 21, "firefox", 300),
+        ] {
+            events
+                .insert(&event_on(
+                    first + chrono::Duration::days(offset),
+                    hour,
+                    app_name,
+                    duration_secs,
+                ))
+                .await
+                .unwrap();
+        }
+
+        let direct: Vec<(String, i64)> = AppUsageQueryImpl::new(Arc::clone(&pool))
+            .get_app_usage_for_dates(&[
+                first,
+                first + chrono::Duration::days(1),
+                first + chrono::Duration::days(2),
+                last,
+            ])
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|u| (u.app_name, u.total_seconds))
+            .collect();
+
+        assert_eq!(rollup.rebuild_rollup().await.unwrap(), 4);
+        assert_eq!(
+            rollup.get_app_usage_summary(first, last).await.unwrap(),
+            direct
+        );
+
+        // 重复重建结果不变
+        assert_eq!(rollup.rebuild_rollup().await.unwrap(), 4);
+        assert_eq!(
+            rollup.get_app_usage_summary(first, last).await.unwrap(),
+            direct
+        );
+    }
+
+    #[tokio::test]
+    async fn test_update_rollup_for_day() {
+        let pool = create_memory_pool();
+        let events = WindowEventRepositoryImpl::new(pool.clone());
+        let rollup = DailyRollupRepositoryImpl::new(Arc::new(pool));
+
+        let date = NaiveDate::from_ymd_opt(2024, 3, 4).unwrap();
+        events
+            .insert(&event_on(date, 9, "code", 600))
+            .await
+            .unwrap();
+        rollup.rebuild_rollup().await.unwrap();
+
+        // 汇总之后写入的事件使当天的汇总失效，立即计入
+        events
+            .insert(&event_on(date, 10, "code", 300))
+            .await
+            .unwrap();
+        assert!(!is_rolled_up(&rollup, date));
+        assert_eq!(
+            rollup.get_app_usage_summary(date, date).await.unwrap(),
+            vec![("code".to_string(), 900)]
+        );
+
+        rollup.update_rollup_for_day(date).await.unwrap();
+        assert!(is_rolled_up(&rollup, date));
+        assert_eq!(
+            rollup.get_app_usage_summary(date, date).await.unwrap(),
+            vec![("code".to_string(), 900)]
+        );
+
+        // 没有汇总的日期从原始事件统计
+        let next = date.succ_opt().unwrap();
+        events
+            .insert(&event_on(next, 9, "kitty", 120))
+            .await
+            .unwrap();
+        assert_eq!(
+            rollup.get_app_usage_summary(next, next).await.unwrap(),
+            vec![("kitty".to_string(), 120)]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_rollup_reflects_later_deletes() {
+        let pool = create_memory_pool();
+        let events = WindowEventRepositoryImpl::new(pool.clone());
+        let rollup = DailyRollupRepositoryImpl::new(Arc::new(pool));
+
+        let date = NaiveDate::from_ymd_opt(2024, 3, 4).unwrap();
+        let next = date.succ_opt().unwrap();
+        for (day, hour, app_name, duration_secs) in [
+            (date, 9, "code", 600),
+            (date, 10, "steam", 1200),
+            (next, 9, "code", 300),
+        ] {
+            events
+                .insert(&event_on(day, hour, app_name, duration_secs))
+                .await
+                .unwrap();
+        }
+        rollup.rebuild_rollup().await.unwrap();
+
+        events.delete_app_events("steam", false).await.unwrap();
+        // 只有受影响的日期失效
+        assert!(!is_rolled_up(&rollup, date));
+        assert!(is_rolled_up(&rollup, next));
+        assert_eq!(
+            rollup.get_app_usage_summary(date, next).await.unwrap(),
+            vec![("code".to_string(), 900)]
+        );
+    }
+}
@@ -0,0 +1,154 @@
+//! 保存的视图仓储实现
+
+use crate::db::pool::DbPool;
+use crate::errors::{DbError, DbResult};
+use crate::models::{SavedView, ViewSpec};
+use rusqlite::params;
+use std::sync::Arc;
+
+/// 保存的视图仓储实现
+pub struct SavedViewRepositoryImpl {
+    pool: Arc<DbPool>,
+}
+
+impl SavedViewRepositoryImpl {
+    pub fn new(pool: Arc<DbPool>) -> Self {
+        Self { pool }
+    }
+
+    fn save_sync(&self, view: &SavedView) -> DbResult<()> {
+        let name = view.name.trim();
+        if name.is_empty() {
+            return Err(DbError::Validation("视图名称不能为空".to_string()));
+        }
+        let config_json = serde_json::to_string(&view.spec)
+            .map_err(|e| DbError::Validation(format!("无法序列化视图配置: {}", e)))?;
+
+        let conn = self.pool.get()?;
+        conn.execute(
+            "INSERT INTO saved_views (name, config_json) VALUES (?1, ?2)
+             ON CONFLICT(name) DO UPDATE SET config_json = excluded.config_json",
+            params![name, config_json],
+        )?;
+        Ok(())
+    }
+
+    fn list_sync(&self) -> DbResult<Vec<SavedView>> {
+        let conn = self.pool.get()?;
+        let mut stmt =
+            conn.prepare("SELECT name, config_json FROM saved_views ORDER BY name ASC")?;
+
+        let rows = stmt
+            .query_map([], |row| {
+                Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        rows.into_iter()
+            .map(|(name, config_json)| {
+                let spec = serde_json::from_str::<ViewSpec>(&config_json)
+                    .map_err(|e| DbError::Validation(format!("视图 {} 的配置无效: {}", name, e)))?;
+                Ok(SavedView { name, spec })
+            })
+            .collect()
+    }
+
+    fn delete_sync(&self, name: &str) -> DbResult<bool> {
+        let conn = self.pool.get()?;
+        let deleted = conn.execute("DELETE FROM saved_views WHERE name = ?1", params![name])?;
+        Ok(deleted > 0)
+    }
+
+    /// 保存视图，同名视图已存在时覆盖其配置
+    ///
+    /// 名称会去除首尾空白，空名称返回校验错误。
+    pub async fn save_view(&self, view: &SavedView) -> DbResult<()> {
+        let repo = self.clone();
+        let view = view.clone();
+        tokio::task::spawn_blocking(move || repo.save_sync(&view))
+            .await
+            .map_err(|e| DbError::Validation(format!("Task join error: {}", e)))?
+    }
+
+    /// 获取所有保存的视图，按名称排序
+    pub async fn list_views(&self) -> DbResult<Vec<SavedView>> {
+        let repo = self.clone();
+        tokio::task::spawn_blocking(move || repo.list_sync())
+            .await
+            .map_err(|e| DbError::Validation(format!("Task join error: {}", e)))?
+    }
+
+    /// 删除视图，返回是否存在该视图
+    pub async fn delete_view(&self, name: &str) -> DbResult<bool> {
+        let repo = self.clone();
+        let name = name.to_string();
+        tokio::task::spawn_blocking(move || repo.delete_sync(&name))
+            .await
+            .map_err(|e| DbError::Validation(format!("Task join error: {}", e)))?
+    }
+}
+
+impl Clone for SavedViewRepositoryImpl {
+    fn clone(&self) -> Self {
+        Self {
+            pool: Arc::clone(&self.pool),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::pool::create_memory_pool;
+    use crate::models::TimeRange;
+
+    fn view(name: &str, time_range: TimeRange, app_names: &[&str]) -> SavedView {
+        SavedView {
+            name: name.to_string(),
+            spec: ViewSpec {
+                time_range,
+                category_ids: vec![1],
+                app_names: app_names.iter().map(|s| s.to_string()).collect(),
+            },
+        }
+    }
+
+    #[tokio::test]
+    async fn test_save_list_delete_views() {
+        let repo = SavedViewRepositoryImpl::new(Arc::new(create_memory_pool()));
+
+        let work = view(
+            "工作应用，近 30 天",
+            TimeRange::Last30Days,
+            &["code", "kitty"],
+        );
+        let today = view("今天", TimeRange::Today, &[]);
+        repo.save_view(&work).await.unwrap();
+        repo.save_view(&today).await.unwrap();
+
+        assert_eq!(repo.list_views().await.unwrap(), vec![today.clone(), work]);
+
+        assert!(repo.delete_view("工作应用，近 30 天").await.unwrap());
+        assert!(!repo.delete_view("工作应用，近 30 天").await.unwrap());
+        assert_eq!(repo.list_views().await.unwrap(), vec![today]);
+    }
+
+    #[tokio::test]
+    async fn test_save_view_overwrites_same_name() {
+        let repo = SavedViewRepositoryImpl::new(Arc::new(create_memory_pool()));
+
+        repo.save_view(&view("浏览器", TimeRange::Today, &["firefox"]))
+            .await
+            .unwrap();
+        let updated = view("浏览器", TimeRange::Last7Days, &["firefox", "chromium"]);
+        repo.save_view(&updated).await.unwrap();
+
+        assert_eq!(repo.list_views().await.unwrap(), vec![updated]);
+
+        let err = repo
+            .save_view(&view("  ", TimeRange::Today, &[]))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, DbError::Validation(_)));
+    }
+}
@@ -0,0 +1,239 @@
+//! 设置仓储实现
+
+use crate::db::pool::DbPool;
+use crate::errors::{DbError, DbResult};
+use crate::traits::SettingsRepository;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use rusqlite::{params, OptionalExtension};
+use std::sync::Arc;
+
+/// AFK 判定阈值（秒）的设置键
+pub const AFK_THRESHOLD_SECS_KEY: &str = "afk_threshold_secs";
+
+/// 默认 AFK 判定阈值（秒）
+pub const DEFAULT_AFK_THRESHOLD_SECS: i64 = 300;
+
+/// 追踪服务最近一次心跳时间（RFC 3339）的设置键
+pub const TRACKER_HEARTBEAT_KEY: &str = "tracker_last_heartbeat";
+
+/// 设置仓储实现
+pub struct SettingsRepositoryImpl {
+    pool: Arc<DbPool>,
+}
+
+impl SettingsRepositoryImpl {
+    pub fn new(pool: Arc<DbPool>) -> Self {
+        Self { pool }
+    }
+
+    fn get_sync(&self, key: &str) -> DbResult<Option<String>> {
+        let conn = self.pool.get()?;
+        let value = conn
+            .query_row(
+                "SELECT value FROM settings WHERE key = ?1",
+                params![key],
+                |row| row.get(0),
+            )
+            .optional()?;
+        Ok(value)
+    }
+
+    fn set_sync(&self, key: &str, value: &str) -> DbResult<()> {
+        let conn = self.pool.get()?;
+        conn.execute(
+            "INSERT INTO settings (key, value) VALUES (?1, ?2)
+             ON CONFLICT(key) DO UPDATE SET value = excluded.value",
+            params![key, value],
+        )?;
+        Ok(())
+    }
+
+    /// 记录追踪服务心跳（当前时间）
+    pub async fn touch_heartbeat(&self) -> DbResult<()> {
+        self.set_setting(TRACKER_HEARTBEAT_KEY, &Utc::now().to_rfc3339())
+            .await
+    }
+
+    /// 获取追踪服务最近一次心跳时间，从未记录时返回 `None`
+    pub async fn get_last_heartbeat(&self) -> DbResult<Option<DateTime<Utc>>> {
+        let Some(value) = self.get_setting(TRACKER_HEARTBEAT_KEY).await? else {
+            return Ok(None);
+        };
+        DateTime::parse_from_rfc3339(&value)
+            .map(|t| Some(t.with_timezone(&Utc)))
+            .map_err(|_| {
+                DbError::Validation(format!(
+                    "设置 {} 的值不是有效的时间: {:?}",
+                    TRACKER_HEARTBEAT_KEY, value
+                ))
+            })
+    }
+}
+
+#[async_trait]
+impl SettingsRepository for SettingsRepositoryImpl {
+    async fn get_setting(&self, key: &str) -> DbResult<Option<String>> {
+        let repo = self.clone();
+        let key = key.to_string();
+        tokio::task::spawn_blocking(move || repo.get_sync(&key))
+            .await
+            .map_err(|e| DbError::Validation(format!("Task join error: {}", e)))?
+    }
+
+    async fn set_setting(&self, key: &str, value: &str) -> DbResult<()> {
+        let repo = self.clone();
+        let key = key.to_string();
+        let value = value.to_string();
+        tokio::task::spawn_blocking(move || repo.set_sync(&key, &value))
+            .await
+            .map_err(|e| DbError::Validation(format!("Task join error: {}", e)))?
+    }
+}
+
+impl Clone for SettingsRepositoryImpl {
+    fn clone(&self) -> Self {
+        Self {
+            pool: Arc::clone(&self.pool),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::pool::create_memory_pool;
+    use crate::db::Repository;
+
+    fn memory_repo() -> Repository {
+        Repository::with_pool(create_memory_pool())
+    }
+
+    #[tokio::test]
+    async fn test_get_setting_with_default() {
+        let repo = memory_repo();
+
+        assert_eq!(
+            repo.get_setting(AFK_THRESHOLD_SECS_KEY).await.unwrap(),
+            None
+        );
+        assert_eq!(
+            repo.get_setting_or(AFK_THRESHOLD_SECS_KEY, "300")
+                .await
+                .unwrap(),
+            "300"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_set_setting() {
+        let repo = memory_repo();
+
+        repo.set_setting(AFK_THRESHOLD_SECS_KEY, "120")
+            .await
+            .unwrap();
+        assert_eq!(
+            repo.get_setting(AFK_THRESHOLD_SECS_KEY).await.unwrap(),
+            Some("120".to_string())
+        );
+        assert_eq!(
+            repo.get_setting_or(AFK_THRESHOLD_SECS_KEY, "300")
+                .await
+                .unwrap(),
+            "120"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_overwrite_setting() {
+        let repo = memory_repo();
+
+        repo.set_setting(AFK_THRESHOLD_SECS_KEY, "120")
+            .await
+            .unwrap();
+        repo.set_setting(AFK_THRESHOLD_SECS_KEY, "600")
+            .await
+            .unwrap();
+        assert_eq!(
+            repo.get_setting(AFK_THRESHOLD_SECS_KEY).await.unwrap(),
+            Some("600".to_string())
+        );
+    }
+
+    #[tokio::test]
+    async fn test_typed_setting_defaults() {
+        let repo = memory_repo();
+
+        assert_eq!(repo.get_setting_i64("top_n", 10).await.unwrap(), 10);
+        assert!(repo.get_setting_bool("use_24h", true).await.unwrap());
+        assert_eq!(
+            repo.get_setting_string("time_format", "%H:%M")
+                .await
+                .unwrap(),
+            "%H:%M"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_typed_setting_roundtrip() {
+        let repo = memory_repo();
+
+        repo.set_setting_i64("day_start_hour", 4).await.unwrap();
+        repo.set_setting_bool("use_24h", false).await.unwrap();
+        repo.set_setting_string("time_format", "%I:%M %p")
+            .await
+            .unwrap();
+
+        assert_eq!(repo.get_setting_i64("day_start_hour", 0).await.unwrap(), 4);
+        assert!(!repo.get_setting_bool("use_24h", true).await.unwrap());
+        assert_eq!(
+            repo.get_setting_string("time_format", "%H:%M")
+                .await
+                .unwrap(),
+            "%I:%M %p"
+        );
+        assert_eq!(
+            repo.get_setting("use_24h").await.unwrap(),
+            Some("false".to_string())
+        );
+
+        assert!(repo.get_setting_i64_list("ids").await.unwrap().is_empty());
+        repo.set_setting_i64_list("ids", &[3, 1, 42]).await.unwrap();
+        assert_eq!(
+            repo.get_setting_i64_list("ids").await.unwrap(),
+            vec![3, 1, 42]
+        );
+        repo.set_setting_i64_list("ids", &[]).await.unwrap();
+        assert!(repo.get_setting_i64_list("ids").await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_typed_setting_malformed_value() {
+        let repo = memory_repo();
+
+        repo.set_setting("top_n", "ten").await.unwrap();
+        repo.set_setting("use_24h", "yes").await.unwrap();
+
+        let err = repo.get_setting_i64("top_n", 10).await.unwrap_err();
+        assert!(matches!(err, DbError::Validation(ref msg) if msg.contains("top_n")));
+
+        let err = repo.get_setting_bool("use_24h", true).await.unwrap_err();
+        assert!(matches!(err, DbError::Validation(ref msg) if msg.contains("use_24h")));
+
+        repo.set_setting("ids", "1,two").await.unwrap();
+        let err = repo.get_setting_i64_list("ids").await.unwrap_err();
+        assert!(matches!(err, DbError::Validation(ref msg) if msg.contains("ids")));
+    }
+
+    #[tokio::test]
+    async fn test_heartbeat() {
+        let repo = memory_repo();
+        assert_eq!(repo.get_last_heartbeat().await.unwrap(), None);
+
+        let before = Utc::now();
+        repo.touch_heartbeat().await.unwrap();
+        let last = repo.get_last_heartbeat().await.unwrap().unwrap();
+        assert!(last >= before - chrono::Duration::seconds(1));
+        assert!(last <= Utc::now());
+    }
+}
@@ -1,12 +1,14 @@
 //! 窗口事件仓储实现
 
 use crate::db::pool::DbPool;
+use crate::db::repositories::DailyGoalRepositoryImpl;
 use crate::errors::{DbError, DbResult};
-use crate::models::WindowEvent;
+use crate::models::{Category, EventFilter, SortOrder, TimelineEntry, WindowEvent};
+use crate::time::range::{TimeRange, TimeRangeCalculator};
 use crate::traits::WindowEventRepository;
 use async_trait::async_trait;
-use chrono::{DateTime, Utc};
-use rusqlite::params;
+use chrono::{DateTime, Local, NaiveDate, Utc};
+use rusqlite::{params, TransactionBehavior};
 use tracing::{debug, error};
 
 /// 窗口事件仓储实现
@@ -20,8 +22,20 @@ impl WindowEventRepositoryImpl {
         Self { pool }
     }
 
+    /// 校验事件时长，拒绝负数（避免错误数据污染统计）
+    fn validate_duration(duration_secs: i64) -> DbResult<()> {
+        if duration_secs < 0 {
+            return Err(DbError::Validation(format!(
+                "事件时长不能为负数: {}",
+                duration_secs
+            )));
+        }
+        Ok(())
+    }
+
     /// 插入窗口事件（同步方法，供内部使用）
     fn insert_sync(&self, event: &WindowEvent) -> DbResult<i64> {
+        Self::validate_duration(event.duration_secs)?;
         let conn = self.pool.get()?;
 
         debug!(
@@ -66,35 +80,631 @@ impl WindowEventRepositoryImpl {
         &self,
         start: DateTime<Utc>,
         end: DateTime<Utc>,
+    ) -> DbResult<Vec<WindowEvent>> {
+        self.get_by_time_range_ordered_sync(start, end, SortOrder::Asc)
+    }
+
+    /// 按指定排序方向获取时间范围内的窗口事件（同步方法，供内部使用）
+    fn get_by_time_range_ordered_sync(
+        &self,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+        order: SortOrder,
     ) -> DbResult<Vec<WindowEvent>> {
         let conn = self.pool.get()?;
+        Ok(Self::select_time_range(&conn, start, end, order, None)?)
+    }
 
-        let mut stmt = conn.prepare(
+    /// 按指定排序方向获取时间范围内最多 `max_events` 个窗口事件（同步方法，供内部使用）
+    ///
+    /// 多查询一行用于判断是否还有更多事件，返回 `(事件, 是否被截断)`。
+    pub fn get_by_time_range_capped_sync(
+        &self,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+        order: SortOrder,
+        max_events: usize,
+    ) -> DbResult<(Vec<WindowEvent>, bool)> {
+        let conn = self.pool.get()?;
+        let mut events =
+            Self::select_time_range(&conn, start, end, order, Some(max_events.saturating_add(1)))?;
+        let truncated = events.len() > max_events;
+        events.truncate(max_events);
+        Ok((events, truncated))
+    }
+
+    /// 在给定连接上查询时间范围内的窗口事件（可在事务中使用），`limit` 为 `None` 时不限制行数
+    pub(crate) fn select_time_range(
+        conn: &rusqlite::Connection,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+        order: SortOrder,
+        limit: Option<usize>,
+    ) -> rusqlite::Result<Vec<WindowEvent>> {
+        let order_clause = match order {
+            SortOrder::Asc => "timestamp ASC, id ASC",
+            SortOrder::Desc => "timestamp DESC, id DESC",
+        };
+        let mut stmt = conn.prepare(&format!(
             "SELECT id, timestamp, app_name, window_title, workspace, duration_secs, is_afk
              FROM window_events
-             WHERE timestamp >= ?1 AND timestamp <= ?2
-             ORDER BY timestamp ASC",
-        )?;
+             WHERE timestamp >= ?1 AND timestamp < ?2
+             ORDER BY {}
+             LIMIT ?3",
+            order_clause
+        ))?;
+
+        // SQLite 中 LIMIT -1 表示不限制
+        let limit = limit.map_or(-1, |limit| i64::try_from(limit).unwrap_or(i64::MAX));
+        let events = stmt
+            .query_map(params![start, end, limit], Self::map_event_row)?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(events)
+    }
+
+    /// 按游标分页获取更早的窗口事件（同步方法，供内部使用）
+    fn get_before_sync(
+        &self,
+        cursor: Option<DateTime<Utc>>,
+        limit: usize,
+    ) -> DbResult<Vec<WindowEvent>> {
+        if limit == 0 {
+            return Ok(Vec::new());
+        }
+
+        let conn = self.pool.get()?;
+
+        let mut events = match cursor {
+            Some(cursor) => conn
+                .prepare(
+                    "SELECT id, timestamp, app_name, window_title, workspace, duration_secs, is_afk
+                     FROM window_events
+                     WHERE timestamp < ?1
+                     ORDER BY timestamp DESC, id DESC
+                     LIMIT ?2",
+                )?
+                .query_map(params![cursor, limit as i64], Self::map_event_row)?
+                .collect::<Result<Vec<_>, _>>()?,
+            None => conn
+                .prepare(
+                    "SELECT id, timestamp, app_name, window_title, workspace, duration_secs, is_afk
+                     FROM window_events
+                     ORDER BY timestamp DESC, id DESC
+                     LIMIT ?1",
+                )?
+                .query_map(params![limit as i64], Self::map_event_row)?
+                .collect::<Result<Vec<_>, _>>()?,
+        };
+
+        // 页尾时间戳相同的事件必须在同一页返回，否则下一页的 `timestamp < cursor` 会跳过它们
+        if events.len() == limit {
+            if let Some(last) = events.last().cloned() {
+                let mut stmt = conn.prepare(
+                    "SELECT id, timestamp, app_name, window_title, workspace, duration_secs, is_afk
+                     FROM window_events
+                     WHERE timestamp = ?1 AND id < ?2
+                     ORDER BY id DESC",
+                )?;
+                let ties = stmt
+                    .query_map(params![last.timestamp, last.id], Self::map_event_row)?
+                    .collect::<Result<Vec<_>, _>>()?;
+                events.extend(ties);
+            }
+        }
+
+        Ok(events)
+    }
+
+    /// 将查询结果行映射为窗口事件
+    fn map_event_row(row: &rusqlite::Row<'_>) -> rusqlite::Result<WindowEvent> {
+        Ok(WindowEvent {
+            id: Some(row.get(0)?),
+            timestamp: row.get(1)?,
+            app_name: row.get(2)?,
+            window_title: row.get(3)?,
+            workspace: row.get(4)?,
+            duration_secs: row.get(5)?,
+            is_afk: row.get(6)?,
+        })
+    }
+
+    /// 统计若干应用在时间范围内的非 AFK 总时长（同步方法，供内部使用）
+    fn get_combined_usage_sync(
+        &self,
+        app_names: &[String],
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> DbResult<i64> {
+        if app_names.is_empty() {
+            return Ok(0);
+        }
+
+        let placeholders: Vec<String> = (0..app_names.len())
+            .map(|i| format!("?{}", i + 3))
+            .collect();
+        let query = format!(
+            "SELECT COALESCE(SUM(duration_secs), 0)
+             FROM window_events
+             WHERE timestamp >= ?1 AND timestamp < ?2
+               AND is_afk = 0
+               AND app_name IN ({})",
+            placeholders.join(", ")
+        );
+
+        let mut params_vec: Vec<&dyn rusqlite::ToSql> = vec![&start, &end];
+        for app_name in app_names {
+            params_vec.push(app_name);
+        }
+
+        let conn = self.pool.get()?;
+        let total = conn.query_row(&query, params_vec.as_slice(), |row| row.get(0))?;
+        Ok(total)
+    }
+
+    /// 构造 `LIKE ... ESCAPE '\'` 的包含匹配模式，关键词中的 `%`、`_` 按字面匹配
+    fn contains_pattern(keyword: &str) -> String {
+        let escaped = keyword
+            .replace('\\', "\\\\")
+            .replace('%', "\\%")
+            .replace('_', "\\_");
+        format!("%{}%", escaped)
+    }
+
+    /// 按查询条件获取窗口事件（同步方法，供内部使用）
+    fn query_events_sync(&self, filter: &EventFilter) -> DbResult<Vec<WindowEvent>> {
+        let mut query = String::from(
+            "SELECT id, timestamp, app_name, window_title, workspace, duration_secs, is_afk
+             FROM window_events
+             WHERE timestamp >= ?1 AND timestamp < ?2",
+        );
+        let mut params_vec: Vec<Box<dyn rusqlite::ToSql>> =
+            vec![Box::new(filter.range.start), Box::new(filter.range.end)];
+
+        if !filter.include_afk {
+            query.push_str(" AND is_afk = 0");
+        }
+        if let Some(app_name) = &filter.app_name {
+            params_vec.push(Box::new(app_name.clone()));
+            query.push_str(&format!(" AND app_name = ?{}", params_vec.len()));
+        }
+        if let Some(keyword) = filter.title_contains.as_deref().map(str::trim) {
+            if !keyword.is_empty() {
+                params_vec.push(Box::new(Self::contains_pattern(keyword)));
+                query.push_str(&format!(
+                    " AND window_title LIKE ?{} ESCAPE '\\'",
+                    params_vec.len()
+                ));
+            }
+        }
 
+        query.push_str(match filter.order {
+            SortOrder::Asc => " ORDER BY timestamp ASC, id ASC",
+            SortOrder::Desc => " ORDER BY timestamp DESC, id DESC",
+        });
+        // SQLite 中 LIMIT -1 表示不限制数量
+        let limit = filter.limit.map_or(-1, |limit| limit as i64);
+        params_vec.push(Box::new(limit));
+        params_vec.push(Box::new(filter.offset as i64));
+        query.push_str(&format!(
+            " LIMIT ?{} OFFSET ?{}",
+            params_vec.len() - 1,
+            params_vec.len()
+        ));
+
+        let conn = self.pool.get()?;
+        let mut stmt = conn.prepare(&query)?;
+        let params_refs: Vec<&dyn rusqlite::ToSql> =
+            params_vec.iter().map(|p| p.as_ref()).collect();
         let events = stmt
+            .query_map(params_refs.as_slice(), Self::map_event_row)?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(events)
+    }
+
+    /// 统计窗口标题包含关键词的非 AFK 总时长（同步方法，供内部使用）
+    ///
+    /// 关键词中的 `%`、`_` 按字面匹配；SQLite 的 LIKE 只对 ASCII 字母忽略大小写。
+    fn get_usage_by_title_keyword_sync(
+        &self,
+        keyword: &str,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> DbResult<i64> {
+        let keyword = keyword.trim();
+        if keyword.is_empty() {
+            return Ok(0);
+        }
+
+        let pattern = Self::contains_pattern(keyword);
+        let conn = self.pool.get()?;
+        let total = conn.query_row(
+            "SELECT COALESCE(SUM(duration_secs), 0)
+             FROM window_events
+             WHERE timestamp >= ?1 AND timestamp < ?2
+               AND is_afk = 0
+               AND window_title LIKE ?3 ESCAPE '\\'",
+            params![start, end, pattern],
+            |row| row.get(0),
+        )?;
+        Ok(total)
+    }
+
+    /// 获取时间范围内的窗口事件及其应用所属分类（同步方法，供内部使用）
+    ///
+    /// 左连接使属于多个分类的应用每个事件对应多行，这里按事件 id 合并回一行；
+    /// 已归档的分类不计入，未分类的事件分类列表为空。
+    fn get_events_with_categories_sync(
+        &self,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> DbResult<Vec<(WindowEvent, Vec<Category>)>> {
+        let conn = self.pool.get()?;
+        let mut stmt = conn.prepare(
+            "SELECT e.id, e.timestamp, e.app_name, e.window_title, e.workspace,
+                    e.duration_secs, e.is_afk, c.id, c.name, c.icon, c.color
+             FROM window_events e
+             LEFT JOIN app_categories ac ON ac.app_name = e.app_name
+             LEFT JOIN categories c ON c.id = ac.category_id AND c.archived = 0
+             WHERE e.timestamp >= ?1 AND e.timestamp < ?2
+             ORDER BY e.timestamp ASC, e.id ASC, c.name ASC",
+        )?;
+
+        let rows = stmt.query_map(params![start, end], |row| {
+            let event = Self::map_event_row(row)?;
+            let category = match row.get::<_, Option<i64>>(7)? {
+                Some(id) => Some(Category {
+                    id: Some(id),
+                    name: row.get(8)?,
+                    icon: row.get(9)?,
+                    color: row.get(10)?,
+                }),
+                None => None,
+            };
+            Ok((event, category))
+        })?;
+
+        let mut events: Vec<(WindowEvent, Vec<Category>)> = Vec::new();
+        for row in rows {
+            let (event, category) = row?;
+            match events.last_mut() {
+                Some((last, categories)) if last.id == event.id => {
+                    categories.extend(category);
+                }
+                _ => events.push((event, category.into_iter().collect())),
+            }
+        }
+
+        Ok(events)
+    }
+
+    /// 统计时间范围内各工作区的非 AFK 总时长（同步方法，供内部使用）
+    ///
+    /// 工作区为 NULL 的事件与空字符串归为同一组。
+    fn get_workspace_usage_sync(
+        &self,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> DbResult<Vec<(String, i64)>> {
+        let conn = self.pool.get()?;
+        let mut stmt = conn.prepare(
+            "SELECT COALESCE(workspace, '') as ws, SUM(duration_secs) as total
+             FROM window_events
+             WHERE timestamp >= ?1 AND timestamp < ?2 AND is_afk = 0
+             GROUP BY ws
+             ORDER BY total DESC, ws ASC",
+        )?;
+
+        let usage = stmt
+            .query_map(params![start, end], |row| Ok((row.get(0)?, row.get(1)?)))?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(usage)
+    }
+
+    /// 按工作区和应用统计时间范围内的非 AFK 总时长（同步方法，供内部使用）
+    ///
+    /// 工作区为 NULL 的事件与空字符串归为同一组，只返回时长不为 0 的组合。
+    fn get_workspace_app_usage_sync(
+        &self,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> DbResult<Vec<(String, String, i64)>> {
+        let conn = self.pool.get()?;
+        let mut stmt = conn.prepare(
+            "SELECT COALESCE(workspace, '') as ws, app_name, SUM(duration_secs) as total
+             FROM window_events
+             WHERE timestamp >= ?1 AND timestamp < ?2 AND is_afk = 0
+             GROUP BY ws, app_name
+             HAVING total != 0
+             ORDER BY ws ASC, total DESC, app_name ASC",
+        )?;
+
+        let usage = stmt
             .query_map(params![start, end], |row| {
-                Ok(WindowEvent {
-                    id: Some(row.get(0)?),
-                    timestamp: row.get(1)?,
-                    app_name: row.get(2)?,
-                    window_title: row.get(3)?,
-                    workspace: row.get(4)?,
-                    duration_secs: row.get(5)?,
-                    is_afk: row.get(6)?,
-                })
+                Ok((row.get(0)?, row.get(1)?, row.get(2)?))
             })?
             .collect::<Result<Vec<_>, _>>()?;
 
-        Ok(events)
+        Ok(usage)
+    }
+
+    /// 查找时间范围内相邻事件之间的记录空白（同步方法，供内部使用）
+    ///
+    /// 窗口事件与 AFK 会话都视为"正在记录"，两者的区间合并后，
+    /// 间隔超过 `min_gap_secs` 的空白说明守护进程很可能没有运行。
+    fn find_tracking_gaps_sync(
+        &self,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+        min_gap_secs: i64,
+    ) -> DbResult<Vec<TimeRange>> {
+        let conn = self.pool.get()?;
+
+        let mut intervals: Vec<(DateTime<Utc>, DateTime<Utc>)> = Vec::new();
+        for event in Self::select_time_range(&conn, start, end, SortOrder::Asc, None)? {
+            intervals.push((
+                event.timestamp,
+                event.timestamp + chrono::Duration::seconds(event.duration_secs),
+            ));
+        }
+
+        let mut stmt = conn.prepare(
+            "SELECT start_time, end_time, duration_secs
+             FROM afk_events
+             WHERE start_time < ?2 AND (end_time IS NULL OR end_time > ?1)",
+        )?;
+        let afk_intervals = stmt
+            .query_map(params![start, end], |row| {
+                let afk_start: DateTime<Utc> = row.get(0)?;
+                let afk_end: Option<DateTime<Utc>> = row.get(1)?;
+                let duration_secs: i64 = row.get(2)?;
+                Ok((
+                    afk_start,
+                    afk_end.unwrap_or(afk_start + chrono::Duration::seconds(duration_secs)),
+                ))
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        intervals.extend(afk_intervals);
+
+        Ok(Self::tracking_gaps(intervals, min_gap_secs))
+    }
+
+    /// 合并记录区间，返回相邻区间之间长于 `min_gap_secs` 的空白
+    fn tracking_gaps(
+        mut intervals: Vec<(DateTime<Utc>, DateTime<Utc>)>,
+        min_gap_secs: i64,
+    ) -> Vec<TimeRange> {
+        intervals.sort_by_key(|&(start, _)| start);
+
+        let mut gaps = Vec::new();
+        let mut iter = intervals.into_iter();
+        let Some((_, mut covered_until)) = iter.next() else {
+            return gaps;
+        };
+        for (start, end) in iter {
+            if (start - covered_until).num_seconds() > min_gap_secs {
+                gaps.push(TimeRange::new(covered_until, start));
+            }
+            covered_until = covered_until.max(end);
+        }
+        gaps
+    }
+
+    /// 将工作区标签 `from` 的全部事件改为 `to`（同步方法，供内部使用）
+    ///
+    /// 标签会去除首尾空白；`from` 为空时匹配工作区为 NULL 或空字符串的事件。
+    fn rename_workspace_sync(&self, from: &str, to: &str) -> DbResult<usize> {
+        let (from, to) = (from.trim(), to.trim());
+        if from == to {
+            return Ok(0);
+        }
+
+        let conn = self.pool.get()?;
+        let renamed = if from.is_empty() {
+            conn.execute(
+                "UPDATE window_events SET workspace = ?1
+                 WHERE workspace IS NULL OR workspace = ''",
+                params![to],
+            )?
+        } else {
+            conn.execute(
+                "UPDATE window_events SET workspace = ?1 WHERE workspace = ?2",
+                params![to, from],
+            )?
+        };
+
+        debug!(from, to, renamed, "已重命名工作区");
+        Ok(renamed)
+    }
+
+    /// 查找时间范围内重复的相邻事件（同步方法，供内部使用）
+    fn find_duplicate_events_sync(
+        &self,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> DbResult<Vec<(i64, i64)>> {
+        let events = self.get_by_time_range_sync(start, end)?;
+        Ok(Self::duplicate_pairs(&events))
+    }
+
+    /// 删除时间范围内重复的相邻事件，每组只保留第一个（同步方法，供内部使用）
+    fn remove_duplicate_events_sync(
+        &self,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> DbResult<usize> {
+        let mut conn = self.pool.get()?;
+        let tx = conn.transaction()?;
+
+        let events = Self::select_time_range(&tx, start, end, SortOrder::Asc, None)?;
+        let duplicates = Self::duplicate_pairs(&events);
+        {
+            let mut stmt = tx.prepare("DELETE FROM window_events WHERE id = ?1")?;
+            for (_, duplicate) in &duplicates {
+                stmt.execute(params![duplicate])?;
+            }
+        }
+        tx.commit()?;
+
+        debug!(removed = duplicates.len(), "已删除重复的窗口事件");
+        Ok(duplicates.len())
+    }
+
+    /// 从按时间升序排列的事件中找出重复事件
+    ///
+    /// 按时间顺序相邻、且时间戳、应用名称和窗口标题都相同的事件视为重复。
+    /// 返回 `(保留的事件 id, 重复的事件 id)`，同一组内的重复事件都与该组第一个事件配对。
+    fn duplicate_pairs(events: &[WindowEvent]) -> Vec<(i64, i64)> {
+        let mut duplicates = Vec::new();
+        let mut group_first: Option<&WindowEvent> = None;
+        for event in events {
+            match group_first {
+                Some(first)
+                    if first.timestamp == event.timestamp
+                        && first.app_name == event.app_name
+                        && first.window_title == event.window_title =>
+                {
+                    if let (Some(kept), Some(duplicate)) = (first.id, event.id) {
+                        duplicates.push((kept, duplicate));
+                    }
+                }
+                _ => group_first = Some(event),
+            }
+        }
+        duplicates
+    }
+
+    /// 删除某个应用的全部窗口事件（同步方法，供内部使用）
+    ///
+    /// `include_metadata` 为 true 时同时删除该应用的分类关联和每日目标，所有删除在同一事务中完成。
+    fn delete_app_events_sync(&self, app_name: &str, include_metadata: bool) -> DbResult<usize> {
+        let mut conn = self.pool.get()?;
+        let tx = conn.transaction()?;
+
+        let removed = tx.execute(
+            "DELETE FROM window_events WHERE app_name = ?1",
+            params![app_name],
+        )?;
+        if include_metadata {
+            tx.execute(
+                "DELETE FROM app_categories WHERE app_name = ?1",
+                params![app_name],
+            )?;
+            let goals = tx.execute(
+                "DELETE FROM daily_goals WHERE app_name = ?1",
+                params![app_name],
+            )?;
+            if goals > 0 {
+                DailyGoalRepositoryImpl::record_history(
+                    &tx,
+                    app_name,
+                    None,
+                    Local::now().date_naive(),
+                )?;
+            }
+        }
+        tx.commit()?;
+
+        debug!(app_name, removed, include_metadata, "已删除应用的窗口事件");
+        Ok(removed)
+    }
+
+    /// 删除时间范围内时长为 0 的占位事件（同步方法，供内部使用）
+    ///
+    /// 追踪器在下一次窗口切换前以时长 0 写入当前事件，因此最新的一条事件总是保留。
+    fn delete_zero_duration_events_sync(
+        &self,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+        include_afk: bool,
+    ) -> DbResult<usize> {
+        let conn = self.pool.get()?;
+        let removed = conn.execute(
+            "DELETE FROM window_events
+             WHERE duration_secs = 0 AND timestamp >= ?1 AND timestamp < ?2
+               AND (?3 OR is_afk = 0)
+               AND id != (SELECT MAX(id) FROM window_events)",
+            params![start, end, include_afk],
+        )?;
+
+        debug!(removed, include_afk, "已删除时长为 0 的窗口事件");
+        Ok(removed)
+    }
+
+    /// 找出应用名称不是有效 UTF-8 的事件，返回 `(id, 原始字节)`
+    fn select_invalid_utf8_apps(conn: &rusqlite::Connection) -> DbResult<Vec<(i64, Vec<u8>)>> {
+        let mut stmt =
+            conn.prepare("SELECT id, CAST(app_name AS BLOB) FROM window_events ORDER BY id")?;
+        let rows = stmt
+            .query_map([], |row| {
+                Ok((row.get::<_, i64>(0)?, row.get::<_, Vec<u8>>(1)?))
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(rows
+            .into_iter()
+            .filter(|(_, bytes)| std::str::from_utf8(bytes).is_err())
+            .collect())
+    }
+
+    fn find_invalid_utf8_apps_sync(&self) -> DbResult<Vec<i64>> {
+        let conn = self.pool.get()?;
+        Ok(Self::select_invalid_utf8_apps(&conn)?
+            .into_iter()
+            .map(|(id, _)| id)
+            .collect())
+    }
+
+    /// 在同一事务中把无效的 UTF-8 序列替换为 U+FFFD，返回修复的事件数量
+    fn sanitize_app_names_sync(&self) -> DbResult<usize> {
+        let mut conn = self.pool.get()?;
+        let tx = conn.transaction()?;
+
+        let invalid = Self::select_invalid_utf8_apps(&tx)?;
+        {
+            let mut stmt = tx.prepare("UPDATE window_events SET app_name = ?1 WHERE id = ?2")?;
+            for (id, bytes) in &invalid {
+                stmt.execute(params![String::from_utf8_lossy(bytes), id])?;
+            }
+        }
+        tx.commit()?;
+
+        debug!(sanitized = invalid.len(), "已修复应用名称编码");
+        Ok(invalid.len())
+    }
+
+    fn has_any_events_sync(&self) -> DbResult<bool> {
+        let conn = self.pool.get()?;
+        let exists = conn.query_row(
+            "SELECT EXISTS(SELECT 1 FROM window_events LIMIT 1)",
+            [],
+            |row| row.get(0),
+        )?;
+        Ok(exists)
+    }
+
+    /// 获取每个应用最近一次事件的时间，按时间升序（同步方法，供内部使用）
+    fn get_last_seen_sync(&self) -> DbResult<Vec<(String, DateTime<Utc>)>> {
+        let conn = self.pool.get()?;
+        let mut stmt = conn.prepare(
+            "SELECT app_name, MAX(timestamp) as last_seen
+             FROM window_events
+             GROUP BY app_name
+             ORDER BY last_seen ASC, app_name ASC",
+        )?;
+
+        let apps = stmt
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(apps)
     }
 
     /// 更新窗口事件时长（同步方法，供内部使用）
     fn update_duration_sync(&self, id: i64, duration_secs: i64) -> DbResult<()> {
+        Self::validate_duration(duration_secs)?;
         let conn = self.pool.get()?;
         conn.execute(
             "UPDATE window_events SET duration_secs = ?1 WHERE id = ?2",
@@ -102,31 +712,440 @@ impl WindowEventRepositoryImpl {
         )?;
         Ok(())
     }
-}
 
-#[async_trait]
-impl WindowEventRepository for WindowEventRepositoryImpl {
-    async fn insert(&self, event: &WindowEvent) -> DbResult<i64> {
-        let repo = self.clone();
-        // 使用 tokio task pool 在异步上下文中执行同步代码
-        let event = event.clone();
-        tokio::task::spawn_blocking(move || repo.insert_sync(&event))
-            .await
-            .map_err(|e| DbError::Validation(format!("Task join error: {}", e)))?
+    /// 在同一事务中结束上一个事件并插入新事件（同步方法，供内部使用）
+    fn record_focus_change_sync(
+        &self,
+        finalize: Option<(i64, i64)>,
+        new_event: &WindowEvent,
+    ) -> DbResult<i64> {
+        if let Some((_, duration_secs)) = finalize {
+            Self::validate_duration(duration_secs)?;
+        }
+        Self::validate_duration(new_event.duration_secs)?;
+
+        let mut conn = self.pool.get()?;
+        let tx = conn.transaction()?;
+
+        if let Some((id, duration_secs)) = finalize {
+            let updated = tx.execute(
+                "UPDATE window_events SET duration_secs = ?1 WHERE id = ?2",
+                params![duration_secs, id],
+            )?;
+            if updated == 0 {
+                // 事务在 drop 时自动回滚
+                return Err(DbError::NotFound(format!("window event {}", id)));
+            }
+        }
+
+        tx.execute(
+            "INSERT INTO window_events (timestamp, app_name, window_title, workspace, duration_secs, is_afk)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            params![
+                new_event.timestamp,
+                new_event.app_name,
+                new_event.window_title,
+                new_event.workspace,
+                new_event.duration_secs,
+                new_event.is_afk,
+            ],
+        )?;
+        let id = tx.last_insert_rowid();
+        tx.commit()?;
+
+        debug!(event_id = id, finalize = ?finalize, "窗口切换已记录");
+        Ok(id)
     }
 
-    async fn get_by_time_range(
+    /// 获取指定日期开始、跨过次日零点的事件（同步方法，供内部使用）
+    fn get_midnight_crossing_events_sync(&self, date: NaiveDate) -> DbResult<Vec<WindowEvent>> {
+        let conn = self.pool.get()?;
+        Self::select_midnight_crossings(&conn, date)
+    }
+
+    /// 在给定连接上查询指定日期开始、跨过次日零点的事件（可在事务中使用）
+    fn select_midnight_crossings(
+        conn: &rusqlite::Connection,
+        date: NaiveDate,
+    ) -> DbResult<Vec<WindowEvent>> {
+        let day = TimeRangeCalculator::day(date);
+        let next_midnight = Self::next_midnight(date);
+
+        let events = Self::select_time_range(conn, day.start, day.end, SortOrder::Asc, None)?
+            .into_iter()
+            .filter(|e| e.timestamp + chrono::Duration::seconds(e.duration_secs) > next_midnight)
+            .collect();
+
+        Ok(events)
+    }
+
+    /// 将跨过零点的事件拆分为两条记录（同步方法，供内部使用）
+    ///
+    /// 查询和写入在同一个 `BEGIN IMMEDIATE` 事务中进行，期间追踪器的时长更新或另一次拆分
+    /// 会等待事务结束，不会拆分出过期的时长或重复的零点后记录。
+    fn split_midnight_crossings_sync(&self, date: NaiveDate) -> DbResult<usize> {
+        let next_midnight = Self::next_midnight(date);
+
+        let mut conn = self.pool.get()?;
+        let tx = conn.transaction_with_behavior(TransactionBehavior::Immediate)?;
+        let events = Self::select_midnight_crossings(&tx, date)?;
+
+        for event in &events {
+            let Some(id) = event.id else { continue };
+            let before_secs = (next_midnight - event.timestamp).num_seconds();
+            let after_secs = event.duration_secs - before_secs;
+
+            tx.execute(
+                "UPDATE window_events SET duration_secs = ?1 WHERE id = ?2",
+                params![before_secs, id],
+            )?;
+            tx.execute(
+                "INSERT INTO window_events (timestamp, app_name, window_title, workspace, duration_secs, is_afk)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                params![
+                    next_midnight,
+                    event.app_name,
+                    event.window_title,
+                    event.workspace,
+                    after_secs,
+                    event.is_afk,
+                ],
+            )?;
+        }
+
+        tx.commit()?;
+        debug!(date = %date, count = events.len(), "跨零点事件已拆分");
+        Ok(events.len())
+    }
+
+    /// 指定日期之后的第一个零点（本地时间）
+    fn next_midnight(date: NaiveDate) -> DateTime<Utc> {
+        TimeRangeCalculator::day(date + chrono::Duration::days(1)).start
+    }
+
+    /// 在给定连接上查询指定日期的时间线条目（可在事务中使用）
+    pub(crate) fn select_day_timeline(
+        conn: &rusqlite::Connection,
+        date: NaiveDate,
+    ) -> DbResult<Vec<TimelineEntry>> {
+        let day = TimeRangeCalculator::day(date);
+        let next_midnight = Self::next_midnight(date);
+
+        let entries = Self::select_time_range(conn, day.start, day.end, SortOrder::Asc, None)?
+            .into_iter()
+            .map(|e| TimelineEntry {
+                start: e.timestamp,
+                // 跨过零点的部分属于次日，截断在零点
+                end: (e.timestamp + chrono::Duration::seconds(e.duration_secs)).min(next_midnight),
+                app: e.app_name,
+                is_afk: e.is_afk,
+            })
+            .collect();
+
+        Ok(entries)
+    }
+
+    /// 获取指定日期（本地时间）开始、持续到次日零点之后的事件
+    pub async fn get_midnight_crossing_events(
         &self,
-        start: DateTime<Utc>,
-        end: DateTime<Utc>,
+        date: NaiveDate,
     ) -> DbResult<Vec<WindowEvent>> {
         let repo = self.clone();
-        tokio::task::spawn_blocking(move || repo.get_by_time_range_sync(start, end))
+        tokio::task::spawn_blocking(move || repo.get_midnight_crossing_events_sync(date))
             .await
             .map_err(|e| DbError::Validation(format!("Task join error: {}", e)))?
     }
 
-    async fn update_duration(&self, id: i64, duration_secs: i64) -> DbResult<()> {
+    /// 将指定日期跨过零点的事件在零点处拆分为两条记录
+    ///
+    /// 原记录保留零点前的时长，新记录从零点开始记录剩余时长，全部在一个事务中完成。
+    /// 返回被拆分的事件数量。
+    pub async fn split_midnight_crossings(&self, date: NaiveDate) -> DbResult<usize> {
+        let repo = self.clone();
+        tokio::task::spawn_blocking(move || repo.split_midnight_crossings_sync(date))
+            .await
+            .map_err(|e| DbError::Validation(format!("Task join error: {}", e)))?
+    }
+
+    /// 查找时间范围内重复的相邻事件，返回 `(保留的事件 id, 重复的事件 id)`
+    pub async fn find_duplicate_events(
+        &self,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> DbResult<Vec<(i64, i64)>> {
+        let repo = self.clone();
+        tokio::task::spawn_blocking(move || repo.find_duplicate_events_sync(start, end))
+            .await
+            .map_err(|e| DbError::Validation(format!("Task join error: {}", e)))?
+    }
+
+    /// 删除时间范围内重复的相邻事件（每组保留第一个），返回删除的数量
+    pub async fn remove_duplicate_events(
+        &self,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> DbResult<usize> {
+        let repo = self.clone();
+        tokio::task::spawn_blocking(move || repo.remove_duplicate_events_sync(start, end))
+            .await
+            .map_err(|e| DbError::Validation(format!("Task join error: {}", e)))?
+    }
+
+    /// 删除某个应用的全部窗口事件，返回删除的事件数量
+    ///
+    /// `include_metadata` 为 true 时一并删除该应用的分类关联和每日目标。
+    pub async fn delete_app_events(
+        &self,
+        app_name: &str,
+        include_metadata: bool,
+    ) -> DbResult<usize> {
+        let repo = self.clone();
+        let app_name = app_name.to_string();
+        tokio::task::spawn_blocking(move || {
+            repo.delete_app_events_sync(&app_name, include_metadata)
+        })
+        .await
+        .map_err(|e| DbError::Validation(format!("Task join error: {}", e)))?
+    }
+
+    /// 删除时间范围内 `duration_secs = 0` 的占位事件，返回删除的事件数量
+    ///
+    /// `include_afk` 为 false 时保留 AFK 事件。最新的一条事件可能是追踪器正在记录的事件，总是保留。
+    pub async fn delete_zero_duration_events(
+        &self,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+        include_afk: bool,
+    ) -> DbResult<usize> {
+        let repo = self.clone();
+        tokio::task::spawn_blocking(move || {
+            repo.delete_zero_duration_events_sync(start, end, include_afk)
+        })
+        .await
+        .map_err(|e| DbError::Validation(format!("Task join error: {}", e)))?
+    }
+
+    /// 获取应用名称不是有效 UTF-8 的事件 id（按 id 升序）
+    pub async fn find_invalid_utf8_apps(&self) -> DbResult<Vec<i64>> {
+        let repo = self.clone();
+        tokio::task::spawn_blocking(move || repo.find_invalid_utf8_apps_sync())
+            .await
+            .map_err(|e| DbError::Validation(format!("Task join error: {}", e)))?
+    }
+
+    /// 把应用名称中无效的 UTF-8 序列替换为 U+FFFD，其余字符保持不变，返回修复的事件数量
+    pub async fn sanitize_app_names(&self) -> DbResult<usize> {
+        let repo = self.clone();
+        tokio::task::spawn_blocking(move || repo.sanitize_app_names_sync())
+            .await
+            .map_err(|e| DbError::Validation(format!("Task join error: {}", e)))?
+    }
+
+    /// 数据库中是否有任何窗口事件（只检查是否存在，不统计数量）
+    pub async fn has_any_events(&self) -> DbResult<bool> {
+        let repo = self.clone();
+        tokio::task::spawn_blocking(move || repo.has_any_events_sync())
+            .await
+            .map_err(|e| DbError::Validation(format!("Task join error: {}", e)))?
+    }
+
+    /// 获取每个应用最近一次事件的时间，最久未使用的应用排在前面
+    pub async fn get_last_seen(&self) -> DbResult<Vec<(String, DateTime<Utc>)>> {
+        let repo = self.clone();
+        tokio::task::spawn_blocking(move || repo.get_last_seen_sync())
+            .await
+            .map_err(|e| DbError::Validation(format!("Task join error: {}", e)))?
+    }
+
+    /// 获取若干应用在时间范围内合计的非 AFK 时长（秒），空列表直接返回 0
+    pub async fn get_combined_usage(
+        &self,
+        app_names: &[String],
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> DbResult<i64> {
+        let repo = self.clone();
+        let app_names = app_names.to_vec();
+        tokio::task::spawn_blocking(move || repo.get_combined_usage_sync(&app_names, start, end))
+            .await
+            .map_err(|e| DbError::Validation(format!("Task join error: {}", e)))?
+    }
+
+    /// 按查询条件（应用、标题关键词、时间范围、AFK、分页）获取窗口事件
+    pub async fn query_events(&self, filter: EventFilter) -> DbResult<Vec<WindowEvent>> {
+        let repo = self.clone();
+        tokio::task::spawn_blocking(move || repo.query_events_sync(&filter))
+            .await
+            .map_err(|e| DbError::Validation(format!("Task join error: {}", e)))?
+    }
+
+    /// 获取窗口标题包含关键词（忽略 ASCII 大小写）的非 AFK 总时长（秒），空关键词返回 0
+    pub async fn get_usage_by_title_keyword(
+        &self,
+        keyword: &str,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> DbResult<i64> {
+        let repo = self.clone();
+        let keyword = keyword.to_string();
+        tokio::task::spawn_blocking(move || {
+            repo.get_usage_by_title_keyword_sync(&keyword, start, end)
+        })
+        .await
+        .map_err(|e| DbError::Validation(format!("Task join error: {}", e)))?
+    }
+
+    /// 获取时间范围内的窗口事件（按时间升序）及其应用所属的未归档分类
+    pub async fn get_events_with_categories(
+        &self,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> DbResult<Vec<(WindowEvent, Vec<Category>)>> {
+        let repo = self.clone();
+        tokio::task::spawn_blocking(move || repo.get_events_with_categories_sync(start, end))
+            .await
+            .map_err(|e| DbError::Validation(format!("Task join error: {}", e)))?
+    }
+
+    /// 获取时间范围内各工作区的非 AFK 时长（秒），按时长降序
+    pub async fn get_workspace_usage(
+        &self,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> DbResult<Vec<(String, i64)>> {
+        let repo = self.clone();
+        tokio::task::spawn_blocking(move || repo.get_workspace_usage_sync(start, end))
+            .await
+            .map_err(|e| DbError::Validation(format!("Task join error: {}", e)))?
+    }
+
+    /// 获取时间范围内每个 `(工作区, 应用)` 组合的非 AFK 时长（秒）
+    ///
+    /// 按工作区名称排序，同一工作区内按时长降序。
+    pub async fn get_workspace_app_usage(
+        &self,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> DbResult<Vec<(String, String, i64)>> {
+        let repo = self.clone();
+        tokio::task::spawn_blocking(move || repo.get_workspace_app_usage_sync(start, end))
+            .await
+            .map_err(|e| DbError::Validation(format!("Task join error: {}", e)))?
+    }
+
+    /// 查找时间范围内超过 `min_gap_secs` 的记录空白（窗口事件与 AFK 会话之间都没有记录）
+    pub async fn find_tracking_gaps(
+        &self,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+        min_gap_secs: i64,
+    ) -> DbResult<Vec<TimeRange>> {
+        let repo = self.clone();
+        tokio::task::spawn_blocking(move || repo.find_tracking_gaps_sync(start, end, min_gap_secs))
+            .await
+            .map_err(|e| DbError::Validation(format!("Task join error: {}", e)))?
+    }
+
+    /// 将工作区标签 `from` 的全部事件改为 `to`，返回修改的事件数量
+    ///
+    /// `from` 为空时重命名没有工作区标签的事件。
+    pub async fn rename_workspace(&self, from: &str, to: &str) -> DbResult<usize> {
+        let repo = self.clone();
+        let (from, to) = (from.to_string(), to.to_string());
+        tokio::task::spawn_blocking(move || repo.rename_workspace_sync(&from, &to))
+            .await
+            .map_err(|e| DbError::Validation(format!("Task join error: {}", e)))?
+    }
+
+    /// 获取时间范围内最多 `max_events` 个窗口事件，返回 `(事件, 是否被截断)`
+    ///
+    /// 用于防止超大时间范围把全部历史事件读入内存。
+    pub async fn get_by_time_range_capped(
+        &self,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+        order: SortOrder,
+        max_events: usize,
+    ) -> DbResult<(Vec<WindowEvent>, bool)> {
+        let repo = self.clone();
+        tokio::task::spawn_blocking(move || {
+            repo.get_by_time_range_capped_sync(start, end, order, max_events)
+        })
+        .await
+        .map_err(|e| DbError::Validation(format!("Task join error: {}", e)))?
+    }
+
+    /// 获取时间范围内的窗口事件，按 `order` 指定的时间方向排序
+    pub async fn get_by_time_range_ordered(
+        &self,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+        order: SortOrder,
+    ) -> DbResult<Vec<WindowEvent>> {
+        let repo = self.clone();
+        tokio::task::spawn_blocking(move || repo.get_by_time_range_ordered_sync(start, end, order))
+            .await
+            .map_err(|e| DbError::Validation(format!("Task join error: {}", e)))?
+    }
+
+    /// 按时间倒序分页获取早于 `cursor` 的窗口事件
+    ///
+    /// `cursor` 为 `None` 时从最新的事件开始；下一页以本页最后一条事件的时间戳作为游标。
+    /// 与页尾时间戳相同的事件会一并返回，因此一页可能略多于 `limit` 条。
+    pub async fn get_before(
+        &self,
+        cursor: Option<DateTime<Utc>>,
+        limit: usize,
+    ) -> DbResult<Vec<WindowEvent>> {
+        let repo = self.clone();
+        tokio::task::spawn_blocking(move || repo.get_before_sync(cursor, limit))
+            .await
+            .map_err(|e| DbError::Validation(format!("Task join error: {}", e)))?
+    }
+
+    /// 记录一次窗口切换
+    ///
+    /// 在单个事务中更新上一个事件的时长（`finalize` 为 `(id, duration_secs)`）
+    /// 并插入新事件，返回新事件的 ID。任一步失败时两者一起回滚。
+    ///
+    /// 上一个事件已被删除时返回 `DbError::NotFound`，新事件也不会写入；
+    /// 调用方应以 `finalize = None` 重试，只记录新事件。
+    pub async fn record_focus_change(
+        &self,
+        finalize: Option<(i64, i64)>,
+        new_event: &WindowEvent,
+    ) -> DbResult<i64> {
+        let repo = self.clone();
+        let new_event = new_event.clone();
+        tokio::task::spawn_blocking(move || repo.record_focus_change_sync(finalize, &new_event))
+            .await
+            .map_err(|e| DbError::Validation(format!("Task join error: {}", e)))?
+    }
+}
+
+#[async_trait]
+impl WindowEventRepository for WindowEventRepositoryImpl {
+    async fn insert(&self, event: &WindowEvent) -> DbResult<i64> {
+        let repo = self.clone();
+        // 使用 tokio task pool 在异步上下文中执行同步代码
+        let event = event.clone();
+        tokio::task::spawn_blocking(move || repo.insert_sync(&event))
+            .await
+            .map_err(|e| DbError::Validation(format!("Task join error: {}", e)))?
+    }
+
+    async fn get_by_time_range(
+        &self,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> DbResult<Vec<WindowEvent>> {
+        let repo = self.clone();
+        tokio::task::spawn_blocking(move || repo.get_by_time_range_sync(start, end))
+            .await
+            .map_err(|e| DbError::Validation(format!("Task join error: {}", e)))?
+    }
+
+    async fn update_duration(&self, id: i64, duration_secs: i64) -> DbResult<()> {
         let repo = self.clone();
         tokio::task::spawn_blocking(move || repo.update_duration_sync(id, duration_secs))
             .await
@@ -141,3 +1160,833 @@ impl Clone for WindowEventRepositoryImpl {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::pool::create_memory_pool;
+    use chrono::Duration;
+
+    fn event(app_name: &str, timestamp: DateTime<Utc>) -> WindowEvent {
+        WindowEvent {
+            id: None,
+            timestamp,
+            app_name: app_name.to_string(),
+            window_title: String::new(),
+            workspace: String::new(),
+            duration_secs: 0,
+            is_afk: false,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_has_any_events() {
+        let repo = WindowEventRepositoryImpl::new(create_memory_pool());
+        assert!(!repo.has_any_events().await.unwrap());
+
+        repo.insert(&event("firefox", Utc::now())).await.unwrap();
+        assert!(repo.has_any_events().await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_sanitize_app_names() {
+        let repo = WindowEventRepositoryImpl::new(create_memory_pool());
+        let now = Utc::now();
+        repo.insert(&event("firefox", now)).await.unwrap();
+        {
+            let conn = repo.pool.get().unwrap();
+            // "fire\xFFfox"
+            conn.execute(
+                "INSERT INTO window_events (timestamp, app_name, window_title, workspace, duration_secs, is_afk)
+                 VALUES (?1, CAST(X'66697265FF666F78' AS TEXT), '', '', 0, 0)",
+                params![now],
+            )
+            .unwrap();
+        }
+
+        let invalid = repo.find_invalid_utf8_apps().await.unwrap();
+        assert_eq!(invalid.len(), 1);
+
+        assert_eq!(repo.sanitize_app_names().await.unwrap(), 1);
+        assert!(repo.find_invalid_utf8_apps().await.unwrap().is_empty());
+        let apps: Vec<String> = repo
+            .get_by_time_range(now - Duration::minutes(1), now + Duration::minutes(1))
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|e| e.app_name)
+            .collect();
+        assert!(apps.contains(&"fire\u{FFFD}fox".to_string()));
+        assert!(apps.contains(&"firefox".to_string()));
+    }
+
+    #[tokio::test]
+    async fn test_record_focus_change_applies_both() {
+        let repo = WindowEventRepositoryImpl::new(create_memory_pool());
+        let start = Utc::now() - Duration::minutes(5);
+
+        let first = repo
+            .record_focus_change(None, &event("firefox", start))
+            .await
+            .unwrap();
+        let second = repo
+            .record_focus_change(
+                Some((first, 120)),
+                &event("code", start + Duration::minutes(2)),
+            )
+            .await
+            .unwrap();
+        assert_ne!(first, second);
+
+        let events = repo
+            .get_by_time_range(start, start + Duration::minutes(5))
+            .await
+            .unwrap();
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0].id, Some(first));
+        assert_eq!(events[0].duration_secs, 120);
+        assert_eq!(events[1].id, Some(second));
+        assert_eq!(events[1].app_name, "code");
+    }
+
+    #[tokio::test]
+    async fn test_record_focus_change_rolls_back_on_failure() {
+        let repo = WindowEventRepositoryImpl::new(create_memory_pool());
+        let start = Utc::now() - Duration::minutes(5);
+
+        let first = repo
+            .record_focus_change(None, &event("firefox", start))
+            .await
+            .unwrap();
+
+        // 结束一个不存在的事件会失败，新事件也不应被插入
+        let result = repo
+            .record_focus_change(
+                Some((first + 100, 120)),
+                &event("code", start + Duration::minutes(2)),
+            )
+            .await;
+        assert!(matches!(result, Err(DbError::NotFound(_))));
+
+        let events = repo
+            .get_by_time_range(start, start + Duration::minutes(5))
+            .await
+            .unwrap();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].duration_secs, 0);
+    }
+
+    #[tokio::test]
+    async fn test_record_focus_change_missing_previous_event() {
+        let repo = WindowEventRepositoryImpl::new(create_memory_pool());
+        let start = Utc::now() - Duration::minutes(10);
+
+        // 追踪器正在记录的事件在结束前被删除（例如删除该应用的全部数据）
+        let previous = repo.insert(&event("code", start)).await.unwrap();
+        repo.delete_app_events("code", false).await.unwrap();
+
+        let next = event("firefox", start + Duration::minutes(5));
+        let err = repo
+            .record_focus_change(Some((previous, 300)), &next)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, DbError::NotFound(_)));
+        assert!(!repo.has_any_events().await.unwrap());
+
+        // 以 finalize = None 重试时新事件照常写入
+        let id = repo.record_focus_change(None, &next).await.unwrap();
+        let events = repo.get_by_time_range(start, Utc::now()).await.unwrap();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].id, Some(id));
+        assert_eq!(events[0].app_name, "firefox");
+    }
+
+    #[tokio::test]
+    async fn test_split_midnight_crossings() {
+        let repo = WindowEventRepositoryImpl::new(create_memory_pool());
+        let date = NaiveDate::from_ymd_opt(2024, 3, 4).unwrap();
+        let late = date
+            .and_hms_opt(23, 30, 0)
+            .unwrap()
+            .and_local_timezone(chrono::Local)
+            .unwrap()
+            .with_timezone(&Utc);
+
+        let mut crossing = event("code", late);
+        crossing.duration_secs = 3600;
+        repo.insert(&crossing).await.unwrap();
+        let mut inside = event("firefox", late - Duration::hours(2));
+        inside.duration_secs = 600;
+        repo.insert(&inside).await.unwrap();
+
+        let found = repo.get_midnight_crossing_events(date).await.unwrap();
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].app_name, "code");
+
+        assert_eq!(repo.split_midnight_crossings(date).await.unwrap(), 1);
+        assert!(repo
+            .get_midnight_crossing_events(date)
+            .await
+            .unwrap()
+            .is_empty());
+        // 再次拆分不会插入重复的零点后记录
+        assert_eq!(repo.split_midnight_crossings(date).await.unwrap(), 0);
+
+        let midnight = late + Duration::minutes(30);
+        let events = repo
+            .get_by_time_range(late, midnight + Duration::hours(1))
+            .await
+            .unwrap();
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0].timestamp, late);
+        assert_eq!(events[0].duration_secs, 1800);
+        assert_eq!(events[1].timestamp, midnight);
+        assert_eq!(events[1].duration_secs, 1800);
+        assert_eq!(events[1].app_name, "code");
+        assert_eq!(
+            events.iter().map(|e| e.duration_secs).sum::<i64>(),
+            crossing.duration_secs
+        );
+    }
+
+    #[tokio::test]
+    async fn test_get_before_pages_without_gaps() {
+        let repo = WindowEventRepositoryImpl::new(create_memory_pool());
+        let base = Utc::now() - Duration::hours(1);
+
+        // 25 个事件，其中第 10、11 个时间戳相同，落在页边界上
+        let mut expected = Vec::new();
+        for i in 0..25 {
+            let offset = if i == 11 { 10 } else { i };
+            let id = repo
+                .insert(&event("app", base + Duration::seconds(offset)))
+                .await
+                .unwrap();
+            expected.push(id);
+        }
+
+        let mut seen = Vec::new();
+        let mut cursor = None;
+        loop {
+            let page = repo.get_before(cursor, 7).await.unwrap();
+            let Some(last) = page.last() else { break };
+            assert!(page.windows(2).all(|w| w[0].timestamp >= w[1].timestamp));
+            cursor = Some(last.timestamp);
+            seen.extend(page.iter().map(|e| e.id.unwrap()));
+        }
+
+        let mut sorted = seen.clone();
+        sorted.sort();
+        sorted.dedup();
+        assert_eq!(sorted.len(), seen.len(), "分页结果中出现重复事件");
+        expected.sort();
+        assert_eq!(sorted, expected, "分页结果中缺少事件");
+    }
+
+    #[tokio::test]
+    async fn test_get_by_time_range_capped_reports_truncation() {
+        let repo = WindowEventRepositoryImpl::new(create_memory_pool());
+        let start = Utc::now() - Duration::hours(1);
+        for minutes in 0..5 {
+            repo.insert(&event("code", start + Duration::minutes(minutes)))
+                .await
+                .unwrap();
+        }
+        let end = start + Duration::hours(1);
+
+        let (events, truncated) = repo
+            .get_by_time_range_capped(start, end, SortOrder::Desc, 3)
+            .await
+            .unwrap();
+        assert!(truncated);
+        assert_eq!(events.len(), 3);
+        assert_eq!(events[0].timestamp, start + Duration::minutes(4));
+
+        let (events, truncated) = repo
+            .get_by_time_range_capped(start, end, SortOrder::Asc, 5)
+            .await
+            .unwrap();
+        assert!(!truncated);
+        assert_eq!(events.len(), 5);
+    }
+
+    #[tokio::test]
+    async fn test_get_by_time_range_ordered_desc() {
+        let repo = WindowEventRepositoryImpl::new(create_memory_pool());
+        let base = Utc::now() - Duration::hours(1);
+
+        // 乱序插入
+        for (app_name, minute) in [("b", 20), ("a", 5), ("c", 40)] {
+            repo.insert(&event(app_name, base + Duration::minutes(minute)))
+                .await
+                .unwrap();
+        }
+
+        let end = base + Duration::hours(1);
+        let desc = repo
+            .get_by_time_range_ordered(base, end, SortOrder::Desc)
+            .await
+            .unwrap();
+        let names: Vec<_> = desc.iter().map(|e| e.app_name.as_str()).collect();
+        assert_eq!(names, vec!["c", "b", "a"]);
+
+        let asc = repo
+            .get_by_time_range_ordered(base, end, SortOrder::Asc)
+            .await
+            .unwrap();
+        let names: Vec<_> = asc.iter().map(|e| e.app_name.as_str()).collect();
+        assert_eq!(names, vec!["a", "b", "c"]);
+    }
+
+    #[tokio::test]
+    async fn test_insert_rejects_negative_duration() {
+        let repo = WindowEventRepositoryImpl::new(create_memory_pool());
+        let start = Utc::now() - Duration::minutes(5);
+
+        let mut negative = event("firefox", start);
+        negative.duration_secs = -30;
+        let err = repo.insert(&negative).await.unwrap_err();
+        assert!(matches!(err, DbError::Validation(_)));
+
+        let zero = event("firefox", start);
+        let id = repo.insert(&zero).await.unwrap();
+        assert!(matches!(
+            repo.update_duration(id, -1).await,
+            Err(DbError::Validation(_))
+        ));
+
+        let events = repo
+            .get_by_time_range(start, start + Duration::minutes(5))
+            .await
+            .unwrap();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].duration_secs, 0);
+    }
+
+    #[tokio::test]
+    async fn test_get_combined_usage() {
+        let repo = WindowEventRepositoryImpl::new(create_memory_pool());
+        let start = Utc::now() - Duration::hours(1);
+
+        for (app_name, minutes, duration_secs, is_afk) in [
+            ("firefox", 0, 600, false),
+            ("chromium", 10, 300, false),
+            ("firefox", 20, 900, true),
+            ("code", 30, 1200, false),
+        ] {
+            let mut e = event(app_name, start + Duration::minutes(minutes));
+            e.duration_secs = duration_secs;
+            e.is_afk = is_afk;
+            repo.insert(&e).await.unwrap();
+        }
+
+        let browsers = vec!["firefox".to_string(), "chromium".to_string()];
+        let end = start + Duration::hours(1);
+        assert_eq!(
+            repo.get_combined_usage(&browsers, start, end)
+                .await
+                .unwrap(),
+            900
+        );
+        assert_eq!(repo.get_combined_usage(&[], start, end).await.unwrap(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_query_events_combined_filters() {
+        use crate::time::range::TimeRange;
+
+        let repo = WindowEventRepositoryImpl::new(create_memory_pool());
+        let start = Utc::now() - Duration::hours(2);
+
+        for (minutes, app_name, title, is_afk) in [
+            (0, "firefox", "TaiL · GitHub", false),
+            (10, "firefox", "Rust docs", false),
+            (20, "code", "github.rs - TaiL", false),
+            (30, "firefox", "Issues · GitHub", true),
+            (40, "firefox", "Pulls · GitHub", false),
+            (50, "firefox", "Actions · GitHub", false),
+            (90, "firefox", "Releases · GitHub", false),
+        ] {
+            let mut e = event(app_name, start + Duration::minutes(minutes));
+            e.window_title = title.to_string();
+            e.is_afk = is_afk;
+            repo.insert(&e).await.unwrap();
+        }
+
+        let titles = |events: Vec<WindowEvent>| -> Vec<String> {
+            events.into_iter().map(|e| e.window_title).collect()
+        };
+
+        let mut filter = EventFilter::new(TimeRange::new(start, start + Duration::hours(1)));
+        filter.app_name = Some("firefox".to_string());
+        filter.title_contains = Some("github".to_string());
+        assert_eq!(
+            titles(repo.query_events(filter.clone()).await.unwrap()),
+            vec![
+                "TaiL · GitHub",
+                "Issues · GitHub",
+                "Pulls · GitHub",
+                "Actions · GitHub"
+            ]
+        );
+
+        filter.include_afk = false;
+        filter.order = SortOrder::Desc;
+        filter.limit = Some(2);
+        filter.offset = 1;
+        assert_eq!(
+            titles(repo.query_events(filter).await.unwrap()),
+            vec!["Pulls · GitHub", "TaiL · GitHub"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_get_usage_by_title_keyword() {
+        let repo = WindowEventRepositoryImpl::new(create_memory_pool());
+        let start = Utc::now() - Duration::hours(1);
+
+        for (minutes, title, duration_secs, is_afk) in [
+            (0, "Pull requests · GitHub — Mozilla Firefox", 600, false),
+            (10, "github.com/Vitus213/TaiL", 300, false),
+            (20, "GitHub Notifications", 900, true),
+            (30, "Rust Documentation", 1200, false),
+            (40, "100% done", 60, false),
+        ] {
+            let mut e = event("firefox", start + Duration::minutes(minutes));
+            e.window_title = title.to_string();
+            e.duration_secs = duration_secs;
+            e.is_afk = is_afk;
+            repo.insert(&e).await.unwrap();
+        }
+
+        let end = start + Duration::hours(1);
+        assert_eq!(
+            repo.get_usage_by_title_keyword("GitHub", start, end)
+                .await
+                .unwrap(),
+            900
+        );
+        assert_eq!(
+            repo.get_usage_by_title_keyword("0%", start, end)
+                .await
+                .unwrap(),
+            60
+        );
+        assert_eq!(
+            repo.get_usage_by_title_keyword("%", start, end)
+                .await
+                .unwrap(),
+            60
+        );
+        assert_eq!(
+            repo.get_usage_by_title_keyword("  ", start, end)
+                .await
+                .unwrap(),
+            0
+        );
+    }
+
+    #[tokio::test]
+    async fn test_get_events_with_categories() {
+        use crate::db::repositories::CategoryRepositoryImpl;
+        use crate::traits::CategoryRepository;
+        use std::sync::Arc;
+
+        let pool = create_memory_pool();
+        let repo = WindowEventRepositoryImpl::new(pool.clone());
+        let categories = CategoryRepositoryImpl::new(Arc::new(pool));
+        let start = Utc::now() - Duration::hours(1);
+
+        let mut ids = Vec::new();
+        for name in ["开发", "工作"] {
+            let id = categories
+                .insert(&Category {
+                    id: None,
+                    name: name.to_string(),
+                    icon: "📁".to_string(),
+                    color: None,
+                })
+                .await
+                .unwrap();
+            categories.add_app_to_category("code", id).await.unwrap();
+            ids.push(id);
+        }
+
+        repo.insert(&event("code", start)).await.unwrap();
+        repo.insert(&event("firefox", start + Duration::minutes(5)))
+            .await
+            .unwrap();
+        repo.insert(&event("code", start + Duration::minutes(10)))
+            .await
+            .unwrap();
+
+        let events = repo
+            .get_events_with_categories(start, start + Duration::hours(1))
+            .await
+            .unwrap();
+        let summary: Vec<(&str, Vec<&str>)> = events
+            .iter()
+            .map(|(e, cats)| {
+                (
+                    e.app_name.as_str(),
+                    cats.iter().map(|c| c.name.as_str()).collect(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("code", vec!["工作", "开发"]),
+                ("firefox", vec![]),
+                ("code", vec!["工作", "开发"]),
+            ]
+        );
+        assert_eq!(events[0].1[0].id, Some(ids[1]));
+    }
+
+    #[tokio::test]
+    async fn test_get_workspace_app_usage() {
+        let repo = WindowEventRepositoryImpl::new(create_memory_pool());
+        let start = Utc::now() - Duration::hours(1);
+
+        for (minutes, workspace, app_name, duration_secs, is_afk) in [
+            (0, "1", "code", 600, false),
+            (10, "1", "firefox", 300, false),
+            (20, "2", "firefox", 900, false),
+            (30, "1", "code", 120, false),
+            (40, "2", "code", 60, true),
+        ] {
+            let mut e = event(app_name, start + Duration::minutes(minutes));
+            e.workspace = workspace.to_string();
+            e.duration_secs = duration_secs;
+            e.is_afk = is_afk;
+            repo.insert(&e).await.unwrap();
+        }
+
+        let usage = repo
+            .get_workspace_app_usage(start, start + Duration::hours(1))
+            .await
+            .unwrap();
+        assert_eq!(
+            usage,
+            vec![
+                ("1".to_string(), "code".to_string(), 720),
+                ("1".to_string(), "firefox".to_string(), 300),
+                ("2".to_string(), "firefox".to_string(), 900),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_find_tracking_gaps() {
+        let pool = create_memory_pool();
+        let repo = WindowEventRepositoryImpl::new(pool.clone());
+        let start = Utc::now() - Duration::hours(5);
+
+        // 事件之间有 2 分钟的小空隙，code 之后有 2 小时空白
+        for (minutes, app_name) in [(0, "firefox"), (12, "kitty"), (24, "code")] {
+            let mut e = event(app_name, start + Duration::minutes(minutes));
+            e.duration_secs = 600;
+            repo.insert(&e).await.unwrap();
+        }
+        let resumed = start + Duration::minutes(34) + Duration::hours(2);
+        let mut e = event("firefox", resumed);
+        e.duration_secs = 300;
+        repo.insert(&e).await.unwrap();
+
+        // AFK 会话算作守护进程在运行，不会产生空白
+        pool.get()
+            .unwrap()
+            .execute(
+                "INSERT INTO afk_events (start_time, end_time, duration_secs) VALUES (?1, ?2, 600)",
+                params![
+                    resumed + Duration::minutes(5),
+                    resumed + Duration::minutes(15)
+                ],
+            )
+            .unwrap();
+        let mut e = event("code", resumed + Duration::minutes(16));
+        e.duration_secs = 60;
+        repo.insert(&e).await.unwrap();
+
+        let gaps = repo
+            .find_tracking_gaps(start, Utc::now(), 300)
+            .await
+            .unwrap();
+        assert_eq!(gaps.len(), 1);
+        assert_eq!(gaps[0].start, start + Duration::minutes(34));
+        assert_eq!(gaps[0].end, resumed);
+        assert_eq!(gaps[0].duration_seconds(), 7200);
+    }
+
+    #[tokio::test]
+    async fn test_rename_workspace() {
+        let repo = WindowEventRepositoryImpl::new(create_memory_pool());
+        let start = Utc::now() - Duration::hours(1);
+
+        for (minutes, workspace, duration_secs) in [
+            (0, "1", 600),
+            (10, "dev", 300),
+            (20, "1", 900),
+            (30, "", 120),
+        ] {
+            let mut e = event("code", start + Duration::minutes(minutes));
+            e.workspace = workspace.to_string();
+            e.duration_secs = duration_secs;
+            repo.insert(&e).await.unwrap();
+        }
+        {
+            let conn = repo.pool.get().unwrap();
+            conn.execute(
+                "UPDATE window_events SET workspace = NULL WHERE duration_secs = 120",
+                [],
+            )
+            .unwrap();
+        }
+
+        assert_eq!(repo.rename_workspace("1", "code").await.unwrap(), 2);
+        assert_eq!(repo.rename_workspace("missing", "x").await.unwrap(), 0);
+        assert_eq!(repo.rename_workspace("dev", "dev").await.unwrap(), 0);
+        assert_eq!(repo.rename_workspace("", "scratch").await.unwrap(), 1);
+
+        let end = start + Duration::hours(1);
+        assert_eq!(
+            repo.get_workspace_usage(start, end).await.unwrap(),
+            vec![
+                ("code".to_string(), 1500),
+                ("dev".to_string(), 300),
+                ("scratch".to_string(), 120),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_find_duplicate_events() {
+        let repo = WindowEventRepositoryImpl::new(create_memory_pool());
+        let start = Utc::now() - Duration::hours(1);
+
+        let first = repo.insert(&event("firefox", start)).await.unwrap();
+        let duplicate = repo.insert(&event("firefox", start)).await.unwrap();
+        // 同一时间的不同应用、不同时间的同一应用都不算重复
+        repo.insert(&event("code", start)).await.unwrap();
+        repo.insert(&event("firefox", start + Duration::minutes(1)))
+            .await
+            .unwrap();
+        let mut other_title = event("firefox", start + Duration::minutes(2));
+        repo.insert(&other_title).await.unwrap();
+        other_title.window_title = "GitHub".to_string();
+        repo.insert(&other_title).await.unwrap();
+
+        let pairs = repo
+            .find_duplicate_events(start, start + Duration::hours(1))
+            .await
+            .unwrap();
+        assert_eq!(pairs, vec![(first, duplicate)]);
+    }
+
+    #[tokio::test]
+    async fn test_remove_duplicate_events() {
+        let repo = WindowEventRepositoryImpl::new(create_memory_pool());
+        let start = Utc::now() - Duration::hours(1);
+        let end = start + Duration::hours(1);
+
+        let mut firefox = event("firefox", start);
+        firefox.duration_secs = 300;
+        let mut code = event("code", start + Duration::minutes(5));
+        code.duration_secs = 600;
+        let mut kitty = event("kitty", start + Duration::minutes(15));
+        kitty.duration_secs = 120;
+
+        // firefox 重复两次，code 重复一次，kitty 不重复
+        for e in [&firefox, &firefox, &firefox, &code, &code, &kitty] {
+            repo.insert(e).await.unwrap();
+        }
+
+        let before = repo.get_by_time_range(start, end).await.unwrap().len();
+        let removed = repo.remove_duplicate_events(start, end).await.unwrap();
+        assert_eq!(removed, 3);
+
+        let events = repo.get_by_time_range(start, end).await.unwrap();
+        assert_eq!(events.len(), before - removed);
+        for (app_name, expected) in [("firefox", 300), ("code", 600), ("kitty", 120)] {
+            let total: i64 = events
+                .iter()
+                .filter(|e| e.app_name == app_name)
+                .map(|e| e.duration_secs)
+                .sum();
+            assert_eq!(total, expected, "{}", app_name);
+        }
+
+        // 再次清理不会删除任何数据
+        assert_eq!(repo.remove_duplicate_events(start, end).await.unwrap(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_get_last_seen() {
+        let repo = WindowEventRepositoryImpl::new(create_memory_pool());
+        let now = Utc::now();
+        let three_days_ago = now - Duration::days(3);
+        let yesterday = now - Duration::days(1);
+
+        repo.insert(&event("steam", three_days_ago - Duration::days(2)))
+            .await
+            .unwrap();
+        repo.insert(&event("steam", three_days_ago)).await.unwrap();
+        repo.insert(&event("firefox", three_days_ago))
+            .await
+            .unwrap();
+        repo.insert(&event("firefox", yesterday)).await.unwrap();
+
+        let last_seen = repo.get_last_seen().await.unwrap();
+        assert_eq!(
+            last_seen,
+            vec![
+                ("steam".to_string(), three_days_ago),
+                ("firefox".to_string(), yesterday),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_delete_zero_duration_events() {
+        let repo = WindowEventRepositoryImpl::new(create_memory_pool());
+        let start = Utc::now() - Duration::hours(1);
+
+        for (minutes, duration_secs, is_afk) in [
+            (0, 0, false),
+            (5, 300, false),
+            (10, 0, true),
+            (20, 0, false),
+        ] {
+            let mut e = event("code", start + Duration::minutes(minutes));
+            e.duration_secs = duration_secs;
+            e.is_afk = is_afk;
+            repo.insert(&e).await.unwrap();
+        }
+        // 范围之外的占位事件不受影响
+        repo.insert(&event("code", start - Duration::hours(2)))
+            .await
+            .unwrap();
+
+        let end = start + Duration::hours(1);
+        assert_eq!(
+            repo.delete_zero_duration_events(start, end, false)
+                .await
+                .unwrap(),
+            2
+        );
+
+        let remaining = repo.get_by_time_range(start, end).await.unwrap();
+        assert_eq!(remaining.len(), 2);
+        assert!(remaining.iter().all(|e| e.duration_secs > 0 || e.is_afk));
+
+        assert_eq!(
+            repo.delete_zero_duration_events(start, end, true)
+                .await
+                .unwrap(),
+            1
+        );
+        assert_eq!(
+            repo.get_by_time_range(start - Duration::hours(3), end)
+                .await
+                .unwrap()
+                .len(),
+            2
+        );
+    }
+
+    #[tokio::test]
+    async fn test_delete_zero_duration_events_keeps_live_event() {
+        let repo = WindowEventRepositoryImpl::new(create_memory_pool());
+        let now = Utc::now();
+
+        repo.insert(&event("code", now - Duration::minutes(10)))
+            .await
+            .unwrap();
+        // 追踪器刚写入的当前事件，时长在下一次窗口切换时才会更新
+        let live = repo.insert(&event("firefox", now)).await.unwrap();
+
+        let removed = repo
+            .delete_zero_duration_events(now - Duration::hours(1), now + Duration::hours(1), true)
+            .await
+            .unwrap();
+        assert_eq!(removed, 1);
+
+        let remaining = repo
+            .get_by_time_range(now - Duration::hours(1), now + Duration::hours(1))
+            .await
+            .unwrap();
+        assert_eq!(remaining.len(), 1);
+        assert_eq!(remaining[0].id, Some(live));
+    }
+
+    #[tokio::test]
+    async fn test_delete_app_events() {
+        use crate::db::repositories::CategoryRepositoryImpl;
+        use crate::models::{Category, DailyGoal};
+        use crate::traits::{CategoryRepository, DailyGoalRepository};
+        use std::sync::Arc;
+
+        let pool = create_memory_pool();
+        let repo = WindowEventRepositoryImpl::new(pool.clone());
+        let goals = DailyGoalRepositoryImpl::new(pool.clone());
+        let categories = CategoryRepositoryImpl::new(Arc::new(pool));
+        let now = Utc::now();
+
+        for app_name in ["steam", "firefox", "steam", "code"] {
+            repo.insert(&event(app_name, now)).await.unwrap();
+        }
+        goals
+            .upsert(&DailyGoal {
+                id: None,
+                app_name: "steam".to_string(),
+                max_minutes: 30,
+                notify_enabled: true,
+            })
+            .await
+            .unwrap();
+        let games = categories
+            .insert(&Category {
+                id: None,
+                name: "游戏".to_string(),
+                icon: "🎮".to_string(),
+                color: None,
+            })
+            .await
+            .unwrap();
+        categories
+            .add_app_to_category("steam", games)
+            .await
+            .unwrap();
+
+        // 默认只删除事件，保留目标和分类关联
+        assert_eq!(repo.delete_app_events("steam", false).await.unwrap(), 2);
+        let mut remaining: Vec<String> = repo
+            .get_by_time_range(now - Duration::minutes(1), now + Duration::minutes(1))
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|e| e.app_name)
+            .collect();
+        remaining.sort();
+        assert_eq!(remaining, vec!["code", "firefox"]);
+        assert_eq!(goals.get_all().await.unwrap().len(), 1);
+        assert_eq!(
+            categories.get_category_apps(games).await.unwrap(),
+            vec!["steam"]
+        );
+
+        repo.insert(&event("steam", now)).await.unwrap();
+        assert_eq!(repo.delete_app_events("steam", true).await.unwrap(), 1);
+        assert!(goals.get_all().await.unwrap().is_empty());
+        assert!(categories
+            .get_category_apps(games)
+            .await
+            .unwrap()
+            .is_empty());
+        assert_eq!(
+            categories.get_all_app_names().await.unwrap(),
+            vec!["code", "firefox"]
+        );
+    }
+}
@@ -0,0 +1,58 @@
+//! 只读快照
+//!
+//! 生成由多次查询组成的报告（如导出）时，追踪器可能在查询之间写入新事件，
+//! 导致各部分数据不一致。快照在同一个连接上开启读事务，事务内的所有查询看到同一份数据。
+
+use chrono::{DateTime, NaiveDate, Utc};
+use rusqlite::{Connection, TransactionBehavior};
+
+use crate::db::pool::DbPool;
+use crate::db::repositories::WindowEventRepositoryImpl;
+use crate::errors::DbResult;
+use crate::models::{SortOrder, TimelineEntry, WindowEvent};
+
+/// 只读快照中的查询入口
+///
+/// 只能通过 [`crate::db::Repository::with_read_snapshot`] 获得，生命周期不超过所在的读事务。
+pub struct ReadRepo<'conn> {
+    conn: &'conn Connection,
+}
+
+impl ReadRepo<'_> {
+    /// 获取时间范围内的窗口事件，按 `order` 指定的时间方向排序
+    pub fn window_events(
+        &self,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+        order: SortOrder,
+    ) -> DbResult<Vec<WindowEvent>> {
+        Ok(WindowEventRepositoryImpl::select_time_range(
+            self.conn, start, end, order, None,
+        )?)
+    }
+
+    /// 获取指定日期（本地时间）的时间线
+    pub fn day_timeline(&self, date: NaiveDate) -> DbResult<Vec<TimelineEntry>> {
+        WindowEventRepositoryImpl::select_day_timeline(self.conn, date)
+    }
+}
+
+/// 在单个读事务中执行 `f`（同步方法，供内部使用）
+///
+/// 事务以 `BEGIN DEFERRED` 开启，并立即执行一次读取以确定快照。
+/// WAL 模式下其他连接的写入在事务结束前对 `f` 不可见；回滚日志模式下写入方会等待事务结束。
+pub(crate) fn with_read_snapshot_sync<T>(
+    pool: &DbPool,
+    f: impl FnOnce(&ReadRepo) -> DbResult<T>,
+) -> DbResult<T> {
+    let mut conn = pool.get()?;
+    let tx = conn.transaction_with_behavior(TransactionBehavior::Deferred)?;
+    tx.query_row("SELECT COUNT(*) FROM sqlite_master", [], |row| {
+        row.get::<_, i64>(0)
+    })?;
+
+    let result = f(&ReadRepo { conn: &tx });
+    // 只读事务，提交与回滚效果相同；回滚可以避免 `f` 出错时的提交失败掩盖原始错误
+    tx.rollback()?;
+    result
+}
@@ -24,3 +24,37 @@ pub enum DbError {
 
 /// DbResult 类型别名
 pub type DbResult<T> = Result<T, DbError>;
+
+// 编译期保证错误可以跨线程传递（spawn_blocking、通道等）
+const _: () = {
+    fn assert_send_sync<T: Send + Sync + 'static>() {}
+    fn assert_all() {
+        assert_send_sync::<DbError>();
+        assert_send_sync::<DbResult<()>>();
+    }
+    let _ = assert_all;
+};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::mpsc;
+
+    #[test]
+    fn test_db_error_crosses_threads() {
+        let (tx, rx) = mpsc::channel::<DbResult<i64>>();
+        std::thread::spawn(move || {
+            tx.send(Err(DbError::NotFound("window event 1".to_string())))
+                .unwrap();
+        })
+        .join()
+        .unwrap();
+
+        let err = rx.recv().unwrap().unwrap_err();
+        assert!(matches!(err, DbError::NotFound(ref msg) if msg == "window event 1"));
+
+        // 可以作为通用错误类型使用
+        let boxed: Box<dyn std::error::Error + Send + Sync> = Box::new(err);
+        assert_eq!(boxed.to_string(), "Not found: window event 1");
+    }
+}
@@ -14,7 +14,7 @@ pub use errors::{DbError, DbResult};
 pub use logging::*;
 pub use models::*;
 pub use traits::*;
-pub use utils::{duration, filter, time_range};
+pub use utils::{compare, day_kind, display, duration, filter, flow, normalize, time_range};
 
 // 重新导出服务层的数据类型
 pub use services::{
@@ -15,6 +15,19 @@ pub struct WindowEvent {
     pub is_afk: bool,
 }
 
+/// 时间线条目（用于导出某天的活动时间线）
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TimelineEntry {
+    /// 开始时间
+    pub start: DateTime<Utc>,
+    /// 结束时间
+    pub end: DateTime<Utc>,
+    /// 应用名称
+    pub app: String,
+    /// 是否为 AFK 时段
+    pub is_afk: bool,
+}
+
 /// AFK 事件
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AfkEvent {
@@ -24,6 +37,18 @@ pub struct AfkEvent {
     pub duration_secs: i64,
 }
 
+/// 应用使用统计（区分活跃时长与 AFK 时长）
+///
+/// 应用事件的时间区间为 `[timestamp, timestamp + duration_secs]`。
+/// 与 AFK 会话重叠的部分计入 `afk_seconds`，其余部分计入 `active_seconds`；
+/// 被标记为 AFK 的事件整段计入 `afk_seconds`。
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AppUsageWithAfk {
+    pub app_name: String,
+    pub active_seconds: i64,
+    pub afk_seconds: i64,
+}
+
 /// 每日目标
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DailyGoal {
@@ -41,6 +66,29 @@ pub struct AppUsage {
     pub window_events: Vec<WindowEvent>,
 }
 
+impl AppUsage {
+    /// 非 AFK 事件的总时长（秒），`total_seconds` 包含 AFK 事件
+    pub fn active_seconds(&self) -> i64 {
+        self.window_events
+            .iter()
+            .filter(|e| !e.is_afk)
+            .map(|e| e.duration_secs)
+            .sum()
+    }
+
+    /// 把多个应用的窗口事件展开为按时间升序的事件列表
+    ///
+    /// 便于直接在已缓存的统计数据上运行 `TimeSeriesAnalyzer`，无需再次查询数据库。
+    pub fn flatten_events(usages: &[AppUsage]) -> Vec<WindowEvent> {
+        let mut events: Vec<WindowEvent> = usages
+            .iter()
+            .flat_map(|usage| usage.window_events.iter().cloned())
+            .collect();
+        events.sort_by_key(|e| e.timestamp);
+        events
+    }
+}
+
 /// 时间范围
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
 pub enum TimeRange {
@@ -51,6 +99,50 @@ pub enum TimeRange {
     Custom(DateTime<Utc>, DateTime<Utc>),
 }
 
+impl TimeRange {
+    /// 在 `now` 时刻对应的半开区间 `[start, end)`，按本地日期计算
+    ///
+    /// 今天、最近 N 天截至 `now`；最近 N 天包含今天在内共 N 个本地日期。
+    pub fn bounds_at(&self, now: DateTime<Utc>) -> (DateTime<Utc>, DateTime<Utc>) {
+        use crate::time::range::TimeRangeCalculator;
+
+        let today = now.with_timezone(&chrono::Local).date_naive();
+        let days_ago = |days: i64| TimeRangeCalculator::day(today - chrono::Duration::days(days));
+        match *self {
+            TimeRange::Today => (days_ago(0).start, now),
+            TimeRange::Yesterday => {
+                let yesterday = days_ago(1);
+                (yesterday.start, yesterday.end)
+            }
+            TimeRange::Last7Days => (days_ago(6).start, now),
+            TimeRange::Last30Days => (days_ago(29).start, now),
+            TimeRange::Custom(start, end) => (start, end),
+        }
+    }
+}
+
+/// 视图（筛选预设）的配置
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ViewSpec {
+    /// 时间范围
+    pub time_range: TimeRange,
+    /// 只显示这些分类下的应用，为空表示不限
+    #[serde(default)]
+    pub category_ids: Vec<i64>,
+    /// 只显示这些应用，为空表示不限
+    #[serde(default)]
+    pub app_names: Vec<String>,
+}
+
+/// 用户保存的视图
+#[derive(Debug, Clone, PartialEq)]
+pub struct SavedView {
+    /// 视图名称（唯一）
+    pub name: String,
+    /// 视图配置
+    pub spec: ViewSpec,
+}
+
 /// 时间导航层级
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
 pub enum TimeNavigationLevel {
@@ -67,6 +159,42 @@ pub enum TimeNavigationLevel {
     Hour,
 }
 
+impl TimeNavigationLevel {
+    /// 所有层级（从上到下）
+    pub fn all() -> &'static [TimeNavigationLevel] {
+        &[
+            TimeNavigationLevel::Year,
+            TimeNavigationLevel::Month,
+            TimeNavigationLevel::Week,
+            TimeNavigationLevel::Day,
+            TimeNavigationLevel::Hour,
+        ]
+    }
+
+    /// 稳定的英文键，用于持久化和日志
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TimeNavigationLevel::Year => "year",
+            TimeNavigationLevel::Month => "month",
+            TimeNavigationLevel::Week => "week",
+            TimeNavigationLevel::Day => "day",
+            TimeNavigationLevel::Hour => "hour",
+        }
+    }
+}
+
+impl std::str::FromStr for TimeNavigationLevel {
+    type Err = crate::time::ParseTimeKeyError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        Self::all()
+            .iter()
+            .copied()
+            .find(|level| level.as_str() == value)
+            .ok_or_else(|| crate::time::ParseTimeKeyError(value.to_string()))
+    }
+}
+
 /// 时间导航状态
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct TimeNavigationState {
@@ -80,6 +208,9 @@ pub struct TimeNavigationState {
     pub selected_week: Option<u32>,
     /// 选中的日期
     pub selected_day: Option<u32>,
+    /// 周编号方式（决定 `selected_week` 的含义）
+    #[serde(default)]
+    pub week_numbering: crate::time::WeekNumbering,
 }
 
 impl TimeNavigationState {
@@ -91,9 +222,15 @@ impl TimeNavigationState {
             selected_month: None,
             selected_week: None,
             selected_day: None,
+            week_numbering: crate::time::WeekNumbering::default(),
         }
     }
 
+    /// 当前导航层级
+    pub fn current_level(&self) -> TimeNavigationLevel {
+        self.level
+    }
+
     /// 返回上一级
     pub fn go_back(&mut self) {
         match self.level {
@@ -160,14 +297,13 @@ impl TimeNavigationState {
 
     /// 切换到本周（日视图）- 计算当前是第几周
     pub fn switch_to_this_week(&mut self, year: i32, month: u32) {
-        use chrono::{Datelike, Local};
+        use chrono::{Datelike, Local, NaiveDate};
         let now = Local::now();
         let day = now.day();
 
-        // 计算今天是该月的第几周
-        let first_day = chrono::NaiveDate::from_ymd_opt(year, month, 1).unwrap();
-        let first_weekday = first_day.weekday().num_days_from_monday();
-        let current_week = ((day + first_weekday - 1) / 7) + 1;
+        // 按周编号方式计算今天是第几周
+        let current_week = NaiveDate::from_ymd_opt(year, month, day)
+            .map_or(1, |date| self.week_numbering.week_of(date));
 
         self.selected_year = year;
         self.selected_month = Some(month);
@@ -238,16 +374,16 @@ impl TimeNavigationState {
 
     /// 获取当前导航状态对应的时间范围
     pub fn to_time_range(&self) -> TimeRange {
-        use chrono::{Datelike, Duration, NaiveDate, TimeZone, Utc};
+        use chrono::{Duration, TimeZone, Utc};
 
         match self.level {
             TimeNavigationLevel::Year => {
-                // 整年：1月1日 00:00 - 12月31日 23:59
+                // 整年：[1月1日 00:00, 次年1月1日 00:00)
                 let start = Utc
                     .with_ymd_and_hms(self.selected_year, 1, 1, 0, 0, 0)
                     .unwrap();
                 let end = Utc
-                    .with_ymd_and_hms(self.selected_year, 12, 31, 23, 59, 59)
+                    .with_ymd_and_hms(self.selected_year + 1, 1, 1, 0, 0, 0)
                     .unwrap();
                 TimeRange::Custom(start, end)
             }
@@ -266,50 +402,26 @@ impl TimeNavigationState {
                     Utc.with_ymd_and_hms(self.selected_year, month + 1, 1, 0, 0, 0)
                         .unwrap()
                 };
-                let end = next_month - Duration::seconds(1);
-
-                TimeRange::Custom(start, end)
+                TimeRange::Custom(start, next_month)
             }
             TimeNavigationLevel::Week => {
                 let month = self.selected_month.unwrap_or(1);
 
-                if self.selected_week.is_none() {
-                    // 整月（显示所有周）
-                    let start = Utc
-                        .with_ymd_and_hms(self.selected_year, month, 1, 0, 0, 0)
-                        .unwrap();
-
-                    // 计算月末
-                    let next_month = if month == 12 {
-                        Utc.with_ymd_and_hms(self.selected_year + 1, 1, 1, 0, 0, 0)
-                            .unwrap()
-                    } else {
-                        Utc.with_ymd_and_hms(self.selected_year, month + 1, 1, 0, 0, 0)
-                            .unwrap()
-                    };
-                    let end = next_month - Duration::seconds(1);
-
-                    TimeRange::Custom(start, end)
-                } else {
+                if let Some(week) = self.selected_week {
                     // 特定周
-                    let week = self.selected_week.unwrap();
-
-                    // 计算该周的起止日期
-                    let first_day = NaiveDate::from_ymd_opt(self.selected_year, month, 1).unwrap();
-                    let first_weekday = first_day.weekday().num_days_from_monday();
-
-                    // 计算该周的第一天（周一）
-                    let week_start_day = ((week - 1) * 7) as i64 - first_weekday as i64 + 1;
-                    let week_start = if week_start_day < 1 {
-                        first_day
-                    } else {
-                        first_day + Duration::days(week_start_day - 1)
-                    };
-
-                    let week_end = week_start + Duration::days(6);
-
-                    let start = Utc.from_utc_datetime(&week_start.and_hms_opt(0, 0, 0).unwrap());
-                    let end = Utc.from_utc_datetime(&week_end.and_hms_opt(23, 59, 59).unwrap());
+                    self.week_time_range(month, week)
+                } else {
+                    // 整月（显示所有周），ISO 周跨月时包含首尾两周的完整日期
+                    let weeks = self
+                        .week_numbering
+                        .weeks_in_month(self.selected_year, month);
+                    let first_day = weeks.first().map(|(_, start, _)| *start).unwrap();
+                    let last_day = weeks.last().map(|(_, _, end)| *end).unwrap();
+
+                    let start = Utc.from_utc_datetime(&first_day.and_hms_opt(0, 0, 0).unwrap());
+                    let end = Utc.from_utc_datetime(
+                        &(last_day + Duration::days(1)).and_hms_opt(0, 0, 0).unwrap(),
+                    );
 
                     TimeRange::Custom(start, end)
                 }
@@ -318,24 +430,7 @@ impl TimeNavigationState {
                 // 整周的7天
                 let month = self.selected_month.unwrap_or(1);
                 let week = self.selected_week.unwrap_or(1);
-
-                // 计算该周的起止日期
-                let first_day = NaiveDate::from_ymd_opt(self.selected_year, month, 1).unwrap();
-                let first_weekday = first_day.weekday().num_days_from_monday();
-
-                let week_start_day = ((week - 1) * 7) as i64 - first_weekday as i64 + 1;
-                let week_start = if week_start_day < 1 {
-                    first_day
-                } else {
-                    first_day + Duration::days(week_start_day - 1)
-                };
-
-                let week_end = week_start + Duration::days(6);
-
-                let start = Utc.from_utc_datetime(&week_start.and_hms_opt(0, 0, 0).unwrap());
-                let end = Utc.from_utc_datetime(&week_end.and_hms_opt(23, 59, 59).unwrap());
-
-                TimeRange::Custom(start, end)
+                self.week_time_range(month, week)
             }
             TimeNavigationLevel::Hour => {
                 // 整天的24小时
@@ -345,15 +440,28 @@ impl TimeNavigationState {
                 let start = Utc
                     .with_ymd_and_hms(self.selected_year, month, day, 0, 0, 0)
                     .unwrap();
-                let end = Utc
-                    .with_ymd_and_hms(self.selected_year, month, day, 23, 59, 59)
-                    .unwrap();
+                let end = start + Duration::days(1);
 
                 TimeRange::Custom(start, end)
             }
         }
     }
 
+    /// 选中年份中指定月份第 `week` 周的时间范围（从该周第一天起的 7 天）
+    fn week_time_range(&self, month: u32, week: u32) -> TimeRange {
+        use chrono::{Duration, NaiveDate, TimeZone, Utc};
+
+        let first_day = NaiveDate::from_ymd_opt(self.selected_year, month, 1).unwrap();
+        let week_start = self
+            .week_numbering
+            .week_dates(self.selected_year, month, week)
+            .map_or(first_day, |(start, _)| start);
+        let start = Utc.from_utc_datetime(&week_start.and_hms_opt(0, 0, 0).unwrap());
+        let end = start + Duration::days(7);
+
+        TimeRange::Custom(start, end)
+    }
+
     /// 获取当前路径的显示文本
     pub fn get_breadcrumb(&self) -> String {
         let mut parts = vec![format!("{}年", self.selected_year)];
@@ -362,7 +470,7 @@ impl TimeNavigationState {
             parts.push(format!("{}月", month));
         }
         if let Some(week) = self.selected_week {
-            parts.push(format!("第{}周", week));
+            parts.push(self.week_numbering.week_label(week));
         }
         if let Some(day) = self.selected_day {
             parts.push(format!("{}日", day));
@@ -382,6 +490,50 @@ pub enum StatisticsViewMode {
     ByCategory,
 }
 
+/// 查询结果的时间排序方向
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub enum SortOrder {
+    /// 按时间升序（最早的在前）
+    #[default]
+    Asc,
+    /// 按时间降序（最新的在前）
+    Desc,
+}
+
+/// 窗口事件查询条件（在数据库端筛选和分页）
+#[derive(Debug, Clone, PartialEq)]
+pub struct EventFilter {
+    /// 应用名称（精确匹配），`None` 表示不限
+    pub app_name: Option<String>,
+    /// 窗口标题需包含的文字（忽略 ASCII 大小写），`None` 表示不限
+    pub title_contains: Option<String>,
+    /// 时间范围（半开区间，不含结束时间）
+    pub range: crate::time::range::TimeRange,
+    /// 是否包含 AFK 事件
+    pub include_afk: bool,
+    /// 最多返回的事件数量，`None` 表示不限
+    pub limit: Option<usize>,
+    /// 跳过的事件数量
+    pub offset: usize,
+    /// 按时间排序的方向
+    pub order: SortOrder,
+}
+
+impl EventFilter {
+    /// 创建只限定时间范围的查询条件（包含 AFK 事件，不分页）
+    pub fn new(range: crate::time::range::TimeRange) -> Self {
+        Self {
+            app_name: None,
+            title_contains: None,
+            range,
+            include_afk: true,
+            limit: None,
+            offset: 0,
+            order: SortOrder::default(),
+        }
+    }
+}
+
 /// 时间段使用统计（用于柱形图）
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PeriodUsage {
@@ -393,6 +545,30 @@ pub struct PeriodUsage {
     pub total_seconds: i64,
 }
 
+/// 分段使用统计的查询规格
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PeriodSpec {
+    /// 最近 N 年（含今年），每年一段
+    Years(i32),
+    /// 指定年份的 12 个月
+    MonthsOf(i32),
+    /// 指定月份的每一周，周的编号和边界由 `numbering` 决定
+    WeeksOf {
+        year: i32,
+        month: u32,
+        numbering: crate::time::WeekNumbering,
+    },
+    /// 指定月份第 N 周中属于该月的每一天
+    DaysOf {
+        year: i32,
+        month: u32,
+        week: u32,
+        numbering: crate::time::WeekNumbering,
+    },
+    /// 指定日期的 24 个小时
+    HoursOf { year: i32, month: u32, day: u32 },
+}
+
 /// 应用分类
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Category {
@@ -402,6 +578,21 @@ pub struct Category {
     pub color: Option<String>,
 }
 
+impl Category {
+    /// "未分类"分组的名称
+    pub const UNCATEGORIZED_NAME: &'static str = "未分类";
+
+    /// 表示"未分类"分组的合成分类（没有 id，不存在于数据库中）
+    pub fn uncategorized() -> Self {
+        Self {
+            id: None,
+            name: Self::UNCATEGORIZED_NAME.to_string(),
+            icon: "📋".to_string(),
+            color: None,
+        }
+    }
+}
+
 /// 应用-分类关联
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AppCategory {
@@ -419,6 +610,16 @@ pub struct CategoryUsage {
     pub apps: Vec<AppUsageInCategory>,
 }
 
+impl CategoryUsage {
+    /// 占总时长的百分比（0-100），总时长为 0 时返回 0
+    pub fn percentage_of(&self, grand_total: i64) -> f32 {
+        if grand_total <= 0 {
+            return 0.0;
+        }
+        self.total_seconds as f32 / grand_total as f32 * 100.0
+    }
+}
+
 /// 分类中的应用使用统计
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AppUsageInCategory {
@@ -426,6 +627,18 @@ pub struct AppUsageInCategory {
     pub total_seconds: i64,
 }
 
+/// 分类在两个时间段之间的使用时长变化（用于周回顾等对比）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CategoryChange {
+    pub category: Category,
+    /// 当前时间段的使用时长（秒）
+    pub current_seconds: i64,
+    /// 对比时间段的使用时长（秒）
+    pub previous_seconds: i64,
+    /// 变化量 `current_seconds - previous_seconds`（秒）
+    pub delta_seconds: i64,
+}
+
 /// 预设的分类图标列表（使用 egui 默认字体支持的 emoji）
 /// 参考: https://docs.rs/egui/latest/egui/special_emojis/index.html
 pub const CATEGORY_ICONS: &[&str] = &[
@@ -443,3 +656,120 @@ pub const CATEGORY_ICONS: &[&str] = &[
     "🗑", "🗙", "🚫", "❓", "∞", "⊗", // 传输
     "📤", "📥", "🔀", "🔁", "🔃",
 ];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn usage(name: &str, total_seconds: i64) -> CategoryUsage {
+        CategoryUsage {
+            category: Category {
+                id: None,
+                name: name.to_string(),
+                icon: "🗀".to_string(),
+                color: None,
+            },
+            total_seconds,
+            app_count: 0,
+            apps: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_breadcrumb_follows_week_numbering() {
+        use crate::time::WeekNumbering;
+
+        // 2024-03-13：月内第 3 周，ISO 第 11 周
+        let date = chrono::NaiveDate::from_ymd_opt(2024, 3, 13).unwrap();
+        let breadcrumb = |numbering: WeekNumbering| {
+            let mut state = TimeNavigationState::new(2024);
+            state.week_numbering = numbering;
+            state.drill_into_month(3);
+            state.drill_into_week(numbering.week_of(date));
+            (state.get_breadcrumb(), state.to_time_range())
+        };
+
+        let (month_relative, month_relative_range) = breadcrumb(WeekNumbering::MonthRelative);
+        let (iso, iso_range) = breadcrumb(WeekNumbering::Iso);
+        assert_eq!(month_relative, "2024年 > 3月 > 第3周");
+        assert_eq!(iso, "2024年 > 3月 > W11");
+        // 月中的周两种方式指向同一段日期
+        assert_eq!(month_relative_range, iso_range);
+    }
+
+    #[test]
+    fn test_navigation_level_str_round_trip() {
+        for &level in TimeNavigationLevel::all() {
+            assert_eq!(level.as_str().parse(), Ok(level));
+        }
+        assert!("Year".parse::<TimeNavigationLevel>().is_err());
+        assert!("".parse::<TimeNavigationLevel>().is_err());
+    }
+
+    #[test]
+    fn test_category_percentage_of() {
+        let usages = [
+            usage("工作", 3000),
+            usage("娱乐", 1000),
+            usage("学习", 2000),
+        ];
+        let total: i64 = usages.iter().map(|u| u.total_seconds).sum();
+
+        assert_eq!(usages[0].percentage_of(total), 50.0);
+        let sum: f32 = usages.iter().map(|u| u.percentage_of(total)).sum();
+        assert!((sum - 100.0).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_category_percentage_of_zero_total() {
+        assert_eq!(usage("工作", 0).percentage_of(0), 0.0);
+    }
+
+    fn window_event(app_name: &str, minutes: i64, duration_secs: i64, is_afk: bool) -> WindowEvent {
+        let base = DateTime::parse_from_rfc3339("2024-03-01T09:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        WindowEvent {
+            id: None,
+            timestamp: base + chrono::Duration::minutes(minutes),
+            app_name: app_name.to_string(),
+            window_title: String::new(),
+            workspace: String::new(),
+            duration_secs,
+            is_afk,
+        }
+    }
+
+    #[test]
+    fn test_app_usage_flatten_events() {
+        let usages = [
+            AppUsage {
+                app_name: "firefox".to_string(),
+                total_seconds: 900,
+                window_events: vec![
+                    window_event("firefox", 0, 300, false),
+                    window_event("firefox", 20, 600, true),
+                ],
+            },
+            AppUsage {
+                app_name: "code".to_string(),
+                total_seconds: 400,
+                window_events: vec![window_event("code", 10, 400, false)],
+            },
+        ];
+
+        let events = AppUsage::flatten_events(&usages);
+        let flattened: Vec<_> = events
+            .iter()
+            .map(|e| (e.app_name.as_str(), e.duration_secs, e.is_afk))
+            .collect();
+        assert_eq!(
+            flattened,
+            vec![
+                ("firefox", 300, false),
+                ("code", 400, false),
+                ("firefox", 600, true),
+            ]
+        );
+    }
+}
@@ -1,13 +1,42 @@
 //! 分类服务实现
 
 use crate::db::pool::DbPool;
-use crate::db::repositories::CategoryRepositoryImpl;
-use crate::errors::DbResult;
-use crate::models::{Category, CategoryUsage};
-use crate::traits::CategoryRepository;
+use crate::db::repositories::{CategoryRepositoryImpl, SettingsRepositoryImpl};
+use crate::errors::{DbError, DbResult};
+use crate::models::{Category, CategoryUsage, CATEGORY_ICONS};
+use crate::traits::{CategoryRepository, SettingsRepository};
 use async_trait::async_trait;
 use chrono::{DateTime, Utc};
+use regex::RegexBuilder;
+use serde::Deserialize;
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
+use tracing::{info, warn};
+
+/// 是否已处理过默认分类初始化的设置键
+pub const DEFAULT_CATEGORIES_SEEDED_KEY: &str = "default_categories_seeded";
+
+/// 默认分类（名称、图标、颜色），颜色与图表的分类配色一致
+const DEFAULT_CATEGORIES: &[(&str, &str, &str)] = &[
+    ("工作", "📋", "#4A90E2"),
+    ("开发", "🖮", "#34A853"),
+    ("娱乐", "▶", "#FF6347"),
+    ("社交", "🔗", "#9B59B6"),
+    ("学习", "🔍", "#FFCD56"),
+];
+
+/// 首次启动时创建的默认分类
+pub fn default_categories() -> Vec<Category> {
+    DEFAULT_CATEGORIES
+        .iter()
+        .map(|(name, icon, color)| Category {
+            id: None,
+            name: name.to_string(),
+            icon: icon.to_string(),
+            color: Some(color.to_string()),
+        })
+        .collect()
+}
 
 /// 分类管理数据
 #[derive(Debug, Clone)]
@@ -18,20 +47,81 @@ pub struct CategoryManagementData {
     pub category_usage: Vec<CategoryUsage>,
     /// 所有应用名称
     pub all_app_names: Vec<String>,
+    /// 未加入任何分类的应用名称
+    pub unclassified_apps: Vec<String>,
+    /// 已加入分类的应用数量
+    pub classified_app_count: usize,
+}
+
+/// ActivityWatch 导出的分类规则文件（`{"categories": [...]}` 或直接是规则数组）
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum AwCategoryRules {
+    Export { categories: Vec<AwCategoryRule> },
+    List(Vec<AwCategoryRule>),
+}
+
+/// ActivityWatch 的单条分类规则
+#[derive(Debug, Deserialize)]
+struct AwCategoryRule {
+    /// 分类路径，如 `["Work", "Programming"]`
+    name: Vec<String>,
+    rule: AwRule,
+}
+
+#[derive(Debug, Deserialize)]
+struct AwRule {
+    /// 规则类型，只有 `regex` 类型带匹配模式
+    #[serde(rename = "type")]
+    rule_type: String,
+    regex: Option<String>,
+    #[serde(default)]
+    ignore_case: bool,
 }
 
 /// 分类服务实现
 pub struct CategoryServiceImpl {
     category_repo: CategoryRepositoryImpl,
+    settings_repo: SettingsRepositoryImpl,
 }
 
 impl CategoryServiceImpl {
     pub fn new(pool: Arc<DbPool>) -> Self {
         Self {
-            category_repo: CategoryRepositoryImpl::new(pool),
+            category_repo: CategoryRepositoryImpl::new(Arc::clone(&pool)),
+            settings_repo: SettingsRepositoryImpl::new(pool),
         }
     }
 
+    /// 数据库中没有任何分类时创建默认分类，返回是否进行了创建
+    ///
+    /// 只在第一次调用时检查：之后设置标记，用户删除默认分类后不会再次创建。
+    pub async fn seed_default_categories(&self) -> DbResult<bool> {
+        if self
+            .settings_repo
+            .get_setting(DEFAULT_CATEGORIES_SEEDED_KEY)
+            .await?
+            .is_some()
+        {
+            return Ok(false);
+        }
+
+        let seeded = if self.category_repo.get_categories(true).await?.is_empty() {
+            self.category_repo
+                .ensure_categories(&default_categories())
+                .await?;
+            info!("已创建默认分类");
+            true
+        } else {
+            false
+        };
+
+        self.settings_repo
+            .set_setting(DEFAULT_CATEGORIES_SEEDED_KEY, "true")
+            .await?;
+        Ok(seeded)
+    }
+
     /// 获取分类管理所需的所有数据
     pub async fn get_category_management_data(
         &self,
@@ -41,13 +131,94 @@ impl CategoryServiceImpl {
         let categories = self.category_repo.get_all().await?;
         let category_usage = self.category_repo.get_category_usage_sync(start, end)?;
         let all_app_names = self.category_repo.get_all_app_names().await?;
+        let unclassified_apps = self.category_repo.get_unclassified_app_names().await?;
+        // 两次查询之间追踪器可能写入新应用，按名称比对而不是直接相减，避免计数错误或下溢
+        let unclassified: HashSet<&str> = unclassified_apps.iter().map(String::as_str).collect();
+        let classified_app_count = all_app_names
+            .iter()
+            .filter(|app_name| !unclassified.contains(app_name.as_str()))
+            .count();
 
         Ok(CategoryManagementData {
             categories,
             category_usage,
             all_app_names,
+            unclassified_apps,
+            classified_app_count,
         })
     }
+
+    /// 从 ActivityWatch 的分类规则 JSON 导入分类
+    ///
+    /// 每条正则规则对应一个分类（多级名称用 ` > ` 连接，已存在的同名分类直接复用），
+    /// 并把名称匹配该正则的已有应用加入分类。无效的正则会被跳过并记录警告。
+    /// 返回新建的分类数量。
+    pub async fn import_aw_category_rules(&self, json: &str) -> DbResult<usize> {
+        let rules = match serde_json::from_str::<AwCategoryRules>(json) {
+            Ok(AwCategoryRules::Export { categories }) => categories,
+            Ok(AwCategoryRules::List(rules)) => rules,
+            Err(e) => {
+                return Err(DbError::Validation(format!(
+                    "无效的 ActivityWatch 分类规则: {}",
+                    e
+                )))
+            }
+        };
+
+        let app_names = self.category_repo.get_all_app_names().await?;
+        let mut existing: HashMap<String, i64> = self
+            .category_repo
+            .get_all()
+            .await?
+            .into_iter()
+            .filter_map(|c| c.id.map(|id| (c.name, id)))
+            .collect();
+
+        let mut created = 0;
+        for rule in rules {
+            let name = rule.name.join(" > ");
+            let pattern = match (rule.rule.rule_type.as_str(), rule.rule.regex) {
+                ("regex", Some(pattern)) if !name.is_empty() => pattern,
+                _ => continue,
+            };
+            let regex = match RegexBuilder::new(&pattern)
+                .case_insensitive(rule.rule.ignore_case)
+                .build()
+            {
+                Ok(regex) => regex,
+                Err(e) => {
+                    warn!(category = %name, pattern = %pattern, error = %e, "跳过无效的分类规则");
+                    continue;
+                }
+            };
+
+            let category_id = match existing.get(&name) {
+                Some(&id) => id,
+                None => {
+                    let id = self
+                        .category_repo
+                        .insert(&Category {
+                            id: None,
+                            name: name.clone(),
+                            icon: CATEGORY_ICONS[0].to_string(),
+                            color: None,
+                        })
+                        .await?;
+                    existing.insert(name, id);
+                    created += 1;
+                    id
+                }
+            };
+
+            for app_name in app_names.iter().filter(|app| regex.is_match(app)) {
+                self.category_repo
+                    .add_app_to_category(app_name, category_id)
+                    .await?;
+            }
+        }
+
+        Ok(created)
+    }
 }
 
 #[async_trait]
@@ -107,6 +278,167 @@ impl Clone for CategoryServiceImpl {
     fn clone(&self) -> Self {
         Self {
             category_repo: self.category_repo.clone(),
+            settings_repo: self.settings_repo.clone(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::pool::create_memory_pool;
+    use crate::db::repositories::WindowEventRepositoryImpl;
+    use crate::models::WindowEvent;
+    use crate::traits::WindowEventRepository;
+
+    fn event(app_name: &str, timestamp: DateTime<Utc>) -> WindowEvent {
+        WindowEvent {
+            id: None,
+            timestamp,
+            app_name: app_name.to_string(),
+            window_title: String::new(),
+            workspace: String::new(),
+            duration_secs: 60,
+            is_afk: false,
         }
     }
+
+    #[tokio::test]
+    async fn test_management_data_unclassified_apps() {
+        let pool = Arc::new(create_memory_pool());
+        let service = CategoryServiceImpl::new(Arc::clone(&pool));
+        let events = WindowEventRepositoryImpl::new((*pool).clone());
+
+        let now = Utc::now();
+        for app_name in ["code", "firefox", "mpv", "code"] {
+            events
+                .insert(&event(app_name, now - chrono::Duration::minutes(10)))
+                .await
+                .unwrap();
+        }
+
+        let work = service
+            .insert(&Category {
+                id: None,
+                name: "工作".to_string(),
+                icon: "💼".to_string(),
+                color: None,
+            })
+            .await
+            .unwrap();
+        service.add_app_to_category("code", work).await.unwrap();
+
+        let data = service
+            .get_category_management_data(now - chrono::Duration::hours(1), now)
+            .await
+            .unwrap();
+        assert_eq!(data.all_app_names, vec!["code", "firefox", "mpv"]);
+        assert_eq!(data.unclassified_apps, vec!["firefox", "mpv"]);
+        assert_eq!(data.classified_app_count, 1);
+    }
+
+    #[tokio::test]
+    async fn test_seed_default_categories_on_empty_db() {
+        let service = CategoryServiceImpl::new(Arc::new(create_memory_pool()));
+
+        assert!(service.seed_default_categories().await.unwrap());
+        let names: Vec<String> = service
+            .get_all()
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|c| c.name)
+            .collect();
+        assert_eq!(names.len(), DEFAULT_CATEGORIES.len());
+        assert!(names.iter().any(|name| name == "开发"));
+
+        // 删除默认分类后不会再次创建
+        for category in service.get_all().await.unwrap() {
+            service.delete(category.id.unwrap()).await.unwrap();
+        }
+        assert!(!service.seed_default_categories().await.unwrap());
+        assert!(service.get_all().await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_seed_default_categories_skipped_with_existing() {
+        let service = CategoryServiceImpl::new(Arc::new(create_memory_pool()));
+        service
+            .insert(&Category {
+                id: None,
+                name: "游戏".to_string(),
+                icon: "▶".to_string(),
+                color: None,
+            })
+            .await
+            .unwrap();
+
+        assert!(!service.seed_default_categories().await.unwrap());
+        assert_eq!(service.get_all().await.unwrap().len(), 1);
+
+        // 已设置标记时即使分类为空也不创建
+        let service = CategoryServiceImpl::new(Arc::new(create_memory_pool()));
+        service
+            .settings_repo
+            .set_setting(DEFAULT_CATEGORIES_SEEDED_KEY, "true")
+            .await
+            .unwrap();
+        assert!(!service.seed_default_categories().await.unwrap());
+        assert!(service.get_all().await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_import_aw_category_rules() {
+        let pool = Arc::new(create_memory_pool());
+        let service = CategoryServiceImpl::new(Arc::clone(&pool));
+        let events = WindowEventRepositoryImpl::new((*pool).clone());
+
+        for app_name in ["code", "kitty", "Firefox", "steam"] {
+            events.insert(&event(app_name, Utc::now())).await.unwrap();
+        }
+
+        let json = r#"{
+            "categories": [
+                {"name": ["Work", "Programming"], "rule": {"type": "regex", "regex": "code|kitty"}},
+                {"name": ["Media"], "rule": {"type": "regex", "regex": "firefox", "ignore_case": true}},
+                {"name": ["Broken"], "rule": {"type": "regex", "regex": "(unclosed"}},
+                {"name": ["Uncategorized"], "rule": {"type": "none"}}
+            ]
+        }"#;
+        let created = service.import_aw_category_rules(json).await.unwrap();
+        assert_eq!(created, 2);
+
+        let categories = service.get_all().await.unwrap();
+        let names: Vec<_> = categories.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(names.len(), 2);
+        assert!(names.contains(&"Work > Programming"));
+        assert!(names.contains(&"Media"));
+
+        let id_of = |name: &str| {
+            categories
+                .iter()
+                .find(|c| c.name == name)
+                .unwrap()
+                .id
+                .unwrap()
+        };
+        assert_eq!(
+            service
+                .get_category_apps(id_of("Work > Programming"))
+                .await
+                .unwrap(),
+            vec!["code", "kitty"]
+        );
+        assert_eq!(
+            service.get_category_apps(id_of("Media")).await.unwrap(),
+            vec!["Firefox"]
+        );
+
+        // 再次导入复用已有分类
+        assert_eq!(service.import_aw_category_rules(json).await.unwrap(), 0);
+        assert!(matches!(
+            service.import_aw_category_rules("not json").await,
+            Err(DbError::Validation(_))
+        ));
+    }
 }
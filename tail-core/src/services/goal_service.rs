@@ -5,7 +5,9 @@ use crate::db::repositories::DailyGoalRepositoryImpl;
 use crate::errors::{DbError, DbResult};
 use crate::models::DailyGoal;
 use crate::traits::DailyGoalRepository;
+use crate::utils::normalize::NameNormalizer;
 use async_trait::async_trait;
+use std::collections::BTreeMap;
 
 /// 目标进度
 #[derive(Debug, Clone)]
@@ -22,6 +24,26 @@ pub struct GoalProgress {
     pub progress_percent: u32,
 }
 
+/// 目标冲突：多个每日目标在名称规范化后指向同一个应用
+#[derive(Debug, Clone)]
+pub struct GoalConflict {
+    /// 规范化后的应用名称
+    pub app_name: String,
+    /// 指向该应用的目标，按原应用名称排序
+    pub goals: Vec<DailyGoal>,
+}
+
+/// 建议每日上限的计算方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SuggestStrategy {
+    /// 历史每日使用时长的平均值
+    Average,
+    /// 历史每日使用时长的中位数
+    Median,
+    /// 在平均值基础上减少指定百分比（0-100）
+    PercentReduction(u32),
+}
+
 /// 目标服务实现
 pub struct GoalServiceImpl {
     goal_repo: DailyGoalRepositoryImpl,
@@ -63,13 +85,89 @@ impl GoalServiceImpl {
         })
     }
 
+    /// 按今天目前的使用速度，预测还要多少秒会超过上限
+    ///
+    /// 假设一天剩余时间内保持 `current_secs / elapsed_today_secs` 的平均速度（一天按 24 小时计）。
+    /// 已经超过上限时返回 `Some(0.0)`；今天还没有使用、或按当前速度到今天结束都达不到上限时返回 `None`。
+    pub fn project_exceed_time(
+        current_secs: i64,
+        cap_secs: i64,
+        elapsed_today_secs: i64,
+    ) -> Option<f64> {
+        const SECS_PER_DAY: i64 = 24 * 60 * 60;
+
+        if current_secs >= cap_secs {
+            return Some(0.0);
+        }
+        if current_secs <= 0 || elapsed_today_secs <= 0 {
+            return None;
+        }
+
+        let pace = current_secs as f64 / elapsed_today_secs as f64;
+        let until_exceed = (cap_secs - current_secs) as f64 / pace;
+        let remaining_today = (SECS_PER_DAY - elapsed_today_secs).max(0) as f64;
+
+        (until_exceed <= remaining_today).then_some(until_exceed)
+    }
+
+    /// 根据历史每日使用时长（秒）建议每日上限（分钟）
+    ///
+    /// 结果四舍五入到整分钟；没有历史数据时返回 0。
+    pub fn suggest_cap_minutes(daily_totals: &[i64], strategy: SuggestStrategy) -> i64 {
+        if daily_totals.is_empty() {
+            return 0;
+        }
+
+        let average = || daily_totals.iter().sum::<i64>() as f64 / daily_totals.len() as f64;
+        let seconds = match strategy {
+            SuggestStrategy::Average => average(),
+            SuggestStrategy::Median => {
+                let mut sorted = daily_totals.to_vec();
+                sorted.sort_unstable();
+                let mid = sorted.len() / 2;
+                if sorted.len().is_multiple_of(2) {
+                    (sorted[mid - 1] + sorted[mid]) as f64 / 2.0
+                } else {
+                    sorted[mid] as f64
+                }
+            }
+            SuggestStrategy::PercentReduction(percent) => {
+                average() * (100 - percent.min(100)) as f64 / 100.0
+            }
+        };
+
+        (seconds / 60.0).round() as i64
+    }
+
+    /// 找出名称规范化后重复的每日目标
+    ///
+    /// 合并应用（如 `firefox` 与 `Firefox.desktop`）后，原先分别设置的目标会同时作用于同一个应用，
+    /// 上限不同时实际生效的上限不明确。结果按规范名称排序，没有冲突时返回空列表。
+    pub fn find_conflicts(goals: &[DailyGoal], normalizer: &NameNormalizer) -> Vec<GoalConflict> {
+        let mut by_app: BTreeMap<String, Vec<DailyGoal>> = BTreeMap::new();
+        for goal in goals {
+            by_app
+                .entry(normalizer.canonical(&goal.app_name))
+                .or_default()
+                .push(goal.clone());
+        }
+
+        by_app
+            .into_iter()
+            .filter(|(_, goals)| goals.len() > 1)
+            .map(|(app_name, mut goals)| {
+                goals.sort_by(|a, b| a.app_name.cmp(&b.app_name));
+                GoalConflict { app_name, goals }
+            })
+            .collect()
+    }
+
     /// 获取所有目标及其进度
     pub async fn get_all_goal_progress(&self) -> DbResult<Vec<GoalProgress>> {
-        let goals = self.goal_repo.get_all().await?;
+        let goals = self.goal_repo.get_goals_with_progress().await?;
         let mut result = Vec::new();
 
-        for goal in goals {
-            let used_seconds = self.goal_repo.get_today_usage(&goal.app_name).await?;
+        for (goal, used_seconds) in goals {
             let goal_seconds = goal.max_minutes as i64 * 60;
 
             let progress_percent = if goal_seconds > 0 {
@@ -120,3 +218,112 @@ impl Clone for GoalServiceImpl {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_project_exceed_time_on_pace() {
+        // 上午 10 点已用 1 小时，按这个速度再过 10 小时（20:00）会超过 2 小时上限
+        let projected = GoalServiceImpl::project_exceed_time(3600, 7200, 10 * 3600).unwrap();
+        assert_eq!(projected, 10.0 * 3600.0);
+    }
+
+    #[test]
+    fn test_project_exceed_time_already_exceeded() {
+        assert_eq!(
+            GoalServiceImpl::project_exceed_time(7200, 7200, 3600),
+            Some(0.0)
+        );
+        assert_eq!(
+            GoalServiceImpl::project_exceed_time(9000, 7200, 3600),
+            Some(0.0)
+        );
+    }
+
+    #[test]
+    fn test_project_exceed_time_wont_reach() {
+        // 下午 4 点才用了 30 分钟，今天结束前到不了 2 小时
+        assert_eq!(
+            GoalServiceImpl::project_exceed_time(1800, 7200, 16 * 3600),
+            None
+        );
+        // 还没有使用
+        assert_eq!(GoalServiceImpl::project_exceed_time(0, 7200, 3600), None);
+    }
+
+    fn goal(app_name: &str, max_minutes: i32) -> DailyGoal {
+        DailyGoal {
+            id: None,
+            app_name: app_name.to_string(),
+            max_minutes,
+            notify_enabled: true,
+        }
+    }
+
+    #[test]
+    fn test_find_conflicts() {
+        let normalizer = NameNormalizer::default().alias("firefox-esr", "firefox");
+        let goals = vec![
+            goal("firefox", 60),
+            goal("code", 240),
+            goal("Firefox.desktop", 30),
+            goal("firefox-esr", 45),
+        ];
+
+        let conflicts = GoalServiceImpl::find_conflicts(&goals, &normalizer);
+        assert_eq!(conflicts.len(), 1);
+        assert_eq!(conflicts[0].app_name, "firefox");
+        let caps: Vec<(&str, i32)> = conflicts[0]
+            .goals
+            .iter()
+            .map(|g| (g.app_name.as_str(), g.max_minutes))
+            .collect();
+        assert_eq!(
+            caps,
+            vec![
+                ("Firefox.desktop", 30),
+                ("firefox", 60),
+                ("firefox-esr", 45)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_find_conflicts_none() {
+        let goals = vec![goal("firefox", 60), goal("code", 240), goal("kitty", 30)];
+        assert!(GoalServiceImpl::find_conflicts(&goals, &NameNormalizer::default()).is_empty());
+    }
+
+    #[test]
+    fn test_suggest_cap_minutes() {
+        // 30、60、90、180 分钟
+        let totals = [1800, 3600, 5400, 10800];
+
+        assert_eq!(
+            GoalServiceImpl::suggest_cap_minutes(&totals, SuggestStrategy::Average),
+            90
+        );
+        assert_eq!(
+            GoalServiceImpl::suggest_cap_minutes(&totals, SuggestStrategy::Median),
+            75
+        );
+        assert_eq!(
+            GoalServiceImpl::suggest_cap_minutes(&totals[..3], SuggestStrategy::Median),
+            60
+        );
+        assert_eq!(
+            GoalServiceImpl::suggest_cap_minutes(&totals, SuggestStrategy::PercentReduction(20)),
+            72
+        );
+        assert_eq!(
+            GoalServiceImpl::suggest_cap_minutes(&totals, SuggestStrategy::PercentReduction(150)),
+            0
+        );
+        assert_eq!(
+            GoalServiceImpl::suggest_cap_minutes(&[], SuggestStrategy::Average),
+            0
+        );
+    }
+}
@@ -5,5 +5,5 @@ pub mod goal_service;
 pub mod usage_service;
 
 pub use category_service::CategoryServiceImpl;
-pub use goal_service::GoalServiceImpl;
+pub use goal_service::{GoalConflict, GoalServiceImpl, SuggestStrategy};
 pub use usage_service::UsageServiceImpl;
@@ -2,13 +2,23 @@
 
 use crate::db::pool::DbPool;
 use crate::db::queries::{AppUsageQueryImpl, CategoryUsageQueryImpl, TimeStatsQueryImpl};
+use crate::db::repositories::SettingsRepositoryImpl;
 use crate::errors::DbResult;
 use crate::models::*;
-use crate::traits::{AppUsageQuery, CategoryUsageQuery, TimeStatsQuery};
+use crate::time::analysis::TimeSeriesAnalyzer;
+use crate::time::range::TimeRangeCalculator;
+use crate::traits::{AppUsageQuery, CategoryUsageQuery, SettingsRepository, TimeStatsQuery};
 use async_trait::async_trait;
-use chrono::{DateTime, Local, Utc};
+use chrono::{DateTime, Local, NaiveDate, Utc};
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 
+/// 被标记为"高效"的分类 id 列表的设置键
+pub const PRODUCTIVE_CATEGORY_IDS_KEY: &str = "productive_category_ids";
+
+/// 判定应用使用异常的默认 z 分数阈值（高于个人均值的标准差倍数）
+pub const UNUSUAL_USAGE_Z_THRESHOLD: f64 = 2.0;
+
 /// 仪表板数据
 #[derive(Debug, Clone)]
 pub struct DashboardData {
@@ -16,11 +26,42 @@ pub struct DashboardData {
     pub app_usage: Vec<AppUsage>,
     /// 每日目标
     pub daily_goals: Vec<DailyGoal>,
+    /// 非 AFK 事件时长直接累加的总和（秒），事件重叠时会高于实际经过的时间
+    pub summed_seconds: i64,
+    /// 实际活跃的墙钟时长（秒），重叠的事件只计一次
+    pub wall_clock_seconds: i64,
     /// 统计时间
     pub start: DateTime<Utc>,
     pub end: DateTime<Utc>,
 }
 
+impl DashboardData {
+    /// 由应用使用统计构造仪表板数据，累加时长和墙钟时长直接在已有的事件上计算
+    pub fn new(
+        app_usage: Vec<AppUsage>,
+        daily_goals: Vec<DailyGoal>,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> Self {
+        let events = AppUsage::flatten_events(&app_usage);
+        let summed_seconds = events
+            .iter()
+            .filter(|e| !e.is_afk)
+            .map(|e| e.duration_secs)
+            .sum();
+        let wall_clock_seconds = TimeSeriesAnalyzer::total_active_seconds(&events);
+
+        Self {
+            app_usage,
+            daily_goals,
+            summed_seconds,
+            wall_clock_seconds,
+            start,
+            end,
+        }
+    }
+}
+
 /// 统计数据
 #[derive(Debug, Clone)]
 pub struct StatsData {
@@ -40,6 +81,7 @@ pub struct UsageServiceImpl {
     app_usage_query: AppUsageQueryImpl,
     category_usage_query: CategoryUsageQueryImpl,
     time_stats_query: TimeStatsQueryImpl,
+    settings_repo: SettingsRepositoryImpl,
 }
 
 impl UsageServiceImpl {
@@ -47,8 +89,217 @@ impl UsageServiceImpl {
         Self {
             app_usage_query: AppUsageQueryImpl::new(Arc::clone(&pool)),
             category_usage_query: CategoryUsageQueryImpl::new(Arc::clone(&pool)),
-            time_stats_query: TimeStatsQueryImpl::new(pool),
+            time_stats_query: TimeStatsQueryImpl::new(Arc::clone(&pool)),
+            settings_repo: SettingsRepositoryImpl::new(pool),
+        }
+    }
+
+    /// 获取被标记为高效的分类 id
+    pub async fn get_productive_categories(&self) -> DbResult<Vec<i64>> {
+        self.settings_repo
+            .get_setting_i64_list(PRODUCTIVE_CATEGORY_IDS_KEY)
+            .await
+    }
+
+    /// 设置被标记为高效的分类 id
+    pub async fn set_productive_categories(&self, category_ids: &[i64]) -> DbResult<()> {
+        self.settings_repo
+            .set_setting_i64_list(PRODUCTIVE_CATEGORY_IDS_KEY, category_ids)
+            .await
+    }
+
+    /// 找出今天使用时长明显高于个人基线的应用，返回 `(应用名, z 分数)`
+    ///
+    /// 基线为今天之前 `lookback_days` 天的每日使用时长，阈值为 [`UNUSUAL_USAGE_Z_THRESHOLD`]。
+    pub async fn unusual_apps_today(&self, lookback_days: u32) -> DbResult<Vec<(String, f64)>> {
+        self.unusual_apps_on(
+            Local::now().date_naive(),
+            lookback_days,
+            UNUSUAL_USAGE_Z_THRESHOLD,
+        )
+        .await
+    }
+
+    /// 找出 `date` 当天使用时长超过基线均值 `z_threshold` 个标准差的应用
+    ///
+    /// 基线为 `date` 之前 `lookback_days` 天（不含当天）每天的使用时长，没有使用的日期计为 0；
+    /// 当天和基线的时长都不含 AFK。
+    /// 基线没有波动（标准差为 0，例如此前从未使用）的应用无法计算 z 分数，不会被列出。
+    /// 结果按 z 分数降序排列。
+    pub async fn unusual_apps_on(
+        &self,
+        date: NaiveDate,
+        lookback_days: u32,
+        z_threshold: f64,
+    ) -> DbResult<Vec<(String, f64)>> {
+        if lookback_days == 0 {
+            return Ok(Vec::new());
+        }
+
+        let day = TimeRangeCalculator::day(date);
+        let baseline_start =
+            TimeRangeCalculator::day(date - chrono::Duration::days(lookback_days as i64)).start;
+
+        let mut unusual = Vec::new();
+        for app in self.get_app_usage(day.start, day.end).await? {
+            let baseline: Vec<i64> = self
+                .time_stats_query
+                .get_app_daily_series(&app.app_name, baseline_start, day.start)
+                .await?
+                .into_iter()
+                .map(|(_, seconds)| seconds)
+                .collect();
+            let Some((mean, stddev)) = TimeSeriesAnalyzer::mean_and_stddev(&baseline) else {
+                continue;
+            };
+            if stddev == 0.0 {
+                continue;
+            }
+
+            // 与基线使用同一口径（不含 AFK），AFK 期间保持焦点的应用不应被当作异常
+            let today = app.active_seconds();
+            let z = (today as f64 - mean) / stddev;
+            if z > z_threshold {
+                unusual.push((app.app_name, z));
+            }
+        }
+
+        unusual.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        Ok(unusual)
+    }
+
+    /// 计算时间范围内的高效时长占比（0.0-1.0）
+    ///
+    /// 分母是所有应用的非 AFK 总时长（包括未分类的应用）；分子是属于任一高效分类的应用时长，
+    /// 同时属于多个高效分类的应用只计一次。总时长为 0 时返回 0。
+    pub async fn productivity_ratio(
+        &self,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> DbResult<f32> {
+        let productive_ids: HashSet<i64> = self
+            .get_productive_categories()
+            .await?
+            .into_iter()
+            .collect();
+        let app_usage = self.get_app_usage(start, end).await?;
+
+        let total: i64 = app_usage.iter().map(AppUsage::active_seconds).sum();
+        if total <= 0 || productive_ids.is_empty() {
+            return Ok(0.0);
+        }
+
+        let productive_apps: HashSet<String> = self
+            .get_category_usage(start, end)
+            .await?
+            .into_iter()
+            .filter(|usage| {
+                usage
+                    .category
+                    .id
+                    .is_some_and(|id| productive_ids.contains(&id))
+            })
+            .flat_map(|usage| usage.apps.into_iter().map(|app| app.app_name))
+            .collect();
+        let productive: i64 = app_usage
+            .iter()
+            .filter(|app| productive_apps.contains(&app.app_name))
+            .map(AppUsage::active_seconds)
+            .sum();
+
+        Ok(productive as f32 / total as f32)
+    }
+
+    /// 计算 `date` 当天（本地时间）每小时的高效时长占比（0.0-1.0）
+    ///
+    /// `productive` 为高效分类的名称，属于其中任一分类的应用计为高效。
+    /// 跨小时的事件按重叠部分计入各小时（同 `get_app_hourly_usage`）；没有使用记录的小时为 0。
+    pub async fn hourly_productivity(
+        &self,
+        date: NaiveDate,
+        productive: &HashSet<String>,
+    ) -> DbResult<[f32; 24]> {
+        let day = TimeRangeCalculator::day(date);
+        let productive_apps: HashSet<String> = self
+            .get_category_usage(day.start, day.end)
+            .await?
+            .into_iter()
+            .filter(|usage| productive.contains(&usage.category.name))
+            .flat_map(|usage| usage.apps.into_iter().map(|app| app.app_name))
+            .collect();
+
+        let mut totals = [0i64; 24];
+        let mut productive_totals = [0i64; 24];
+        for app in self.get_app_usage(day.start, day.end).await? {
+            let hours = self
+                .time_stats_query
+                .get_app_hourly_usage(&app.app_name, date)
+                .await?;
+            let is_productive = productive_apps.contains(&app.app_name);
+            for (hour, seconds) in hours.into_iter().enumerate() {
+                totals[hour] += seconds;
+                if is_productive {
+                    productive_totals[hour] += seconds;
+                }
+            }
+        }
+
+        let mut fractions = [0.0f32; 24];
+        for hour in 0..24 {
+            if totals[hour] > 0 {
+                fractions[hour] = productive_totals[hour] as f32 / totals[hour] as f32;
+            }
+        }
+        Ok(fractions)
+    }
+
+    /// 对比两周（或任意两个时间段）各分类的使用时长变化
+    ///
+    /// 两个时间段分别统计分类使用时长后按分类 id 合并，只在一个时间段出现的分类另一侧计为 0。
+    /// 结果按变化量的绝对值降序排列，绝对值相同时按分类名排序。
+    pub async fn weekly_category_changes(
+        &self,
+        week: crate::time::range::TimeRange,
+        prev_week: crate::time::range::TimeRange,
+    ) -> DbResult<Vec<CategoryChange>> {
+        let current = self.get_category_usage(week.start, week.end).await?;
+        let previous = self
+            .get_category_usage(prev_week.start, prev_week.end)
+            .await?;
+
+        let mut changes: Vec<CategoryChange> = Vec::new();
+        let mut index_by_id: HashMap<Option<i64>, usize> = HashMap::new();
+        for usage in current {
+            index_by_id.insert(usage.category.id, changes.len());
+            changes.push(CategoryChange {
+                category: usage.category,
+                current_seconds: usage.total_seconds,
+                previous_seconds: 0,
+                delta_seconds: 0,
+            });
+        }
+        for usage in previous {
+            match index_by_id.get(&usage.category.id) {
+                Some(&index) => changes[index].previous_seconds = usage.total_seconds,
+                None => changes.push(CategoryChange {
+                    category: usage.category,
+                    current_seconds: 0,
+                    previous_seconds: usage.total_seconds,
+                    delta_seconds: 0,
+                }),
+            }
+        }
+
+        for change in &mut changes {
+            change.delta_seconds = change.current_seconds - change.previous_seconds;
         }
+        changes.sort_by(|a, b| {
+            b.delta_seconds
+                .abs()
+                .cmp(&a.delta_seconds.abs())
+                .then_with(|| a.category.name.cmp(&b.category.name))
+        });
+        Ok(changes)
     }
 
     /// 获取仪表板数据
@@ -65,12 +316,8 @@ impl UsageServiceImpl {
 
         let app_usage = self.get_app_usage(today_start, now).await?;
 
-        Ok(DashboardData {
-            app_usage,
-            daily_goals: Vec::new(), // 由 GoalService 提供
-            start: today_start,
-            end: now,
-        })
+        // 每日目标由 GoalService 提供
+        Ok(DashboardData::new(app_usage, Vec::new(), today_start, now))
     }
 
     /// 获取统计数据（根据时间导航状态）
@@ -97,13 +344,7 @@ impl UsageServiceImpl {
                     .and_local_timezone(Local)
                     .unwrap()
                     .with_timezone(&Utc);
-                let yesterday_end = local_yesterday
-                    .date_naive()
-                    .and_hms_opt(23, 59, 59)
-                    .unwrap()
-                    .and_local_timezone(Local)
-                    .unwrap()
-                    .with_timezone(&Utc);
+                let yesterday_end = TimeRangeCalculator::day(local_yesterday.date_naive()).end;
                 (yesterday_start, yesterday_end)
             }
             TimeRange::Custom(s, e) => (s, e),
@@ -133,12 +374,24 @@ impl UsageServiceImpl {
             }
             crate::models::TimeNavigationLevel::Week => {
                 let month = state.selected_month.unwrap_or(1);
-                self.get_weekly_usage(state.selected_year, month).await?
+                self.time_stats_query
+                    .get_period_usage(PeriodSpec::WeeksOf {
+                        year: state.selected_year,
+                        month,
+                        numbering: state.week_numbering,
+                    })
+                    .await?
             }
             crate::models::TimeNavigationLevel::Day => {
                 let month = state.selected_month.unwrap_or(1);
                 let week = state.selected_week.unwrap_or(1);
-                self.get_daily_usage_for_week(state.selected_year, month, week)
+                self.time_stats_query
+                    .get_period_usage(PeriodSpec::DaysOf {
+                        year: state.selected_year,
+                        month,
+                        week,
+                        numbering: state.week_numbering,
+                    })
                     .await?
             }
             crate::models::TimeNavigationLevel::Hour => {
@@ -226,6 +479,287 @@ impl Clone for UsageServiceImpl {
             app_usage_query: self.app_usage_query.clone(),
             category_usage_query: self.category_usage_query.clone(),
             time_stats_query: self.time_stats_query.clone(),
+            settings_repo: self.settings_repo.clone(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::pool::create_memory_pool;
+    use crate::db::repositories::{CategoryRepositoryImpl, WindowEventRepositoryImpl};
+    use crate::traits::{CategoryRepository, WindowEventRepository};
+
+    fn event(
+        app_name: &str,
+        timestamp: DateTime<Utc>,
+        duration_secs: i64,
+        is_afk: bool,
+    ) -> WindowEvent {
+        WindowEvent {
+            id: None,
+            timestamp,
+            app_name: app_name.to_string(),
+            window_title: String::new(),
+            workspace: String::new(),
+            duration_secs,
+            is_afk,
+        }
+    }
+
+    /// 为每个 `(分类名称, 应用名称)` 创建分类并加入应用，返回分类 ID
+    async fn insert_categories(
+        categories: &CategoryRepositoryImpl,
+        entries: &[(&str, &str)],
+    ) -> Vec<i64> {
+        let mut ids = Vec::new();
+        for &(name, app_name) in entries {
+            let id = categories
+                .insert(&Category {
+                    id: None,
+                    name: name.to_string(),
+                    icon: "🗀".to_string(),
+                    color: None,
+                })
+                .await
+                .unwrap();
+            categories.add_app_to_category(app_name, id).await.unwrap();
+            ids.push(id);
+        }
+        ids
+    }
+
+    #[test]
+    fn test_dashboard_wall_clock_below_summed_with_overlap() {
+        let start = Utc::now() - chrono::Duration::hours(1);
+        let at = |app_name: &str, minutes: i64, duration_secs: i64| {
+            event(
+                app_name,
+                start + chrono::Duration::minutes(minutes),
+                duration_secs,
+                false,
+            )
+        };
+        let usage = |app_name: &str, events: Vec<WindowEvent>| AppUsage {
+            app_name: app_name.to_string(),
+            total_seconds: events.iter().map(|e| e.duration_secs).sum(),
+            window_events: events,
+        };
+
+        // firefox 与 code 在第 5-10 分钟重叠
+        let data = DashboardData::new(
+            vec![
+                usage("code", vec![at("code", 0, 600)]),
+                usage("firefox", vec![at("firefox", 5, 600)]),
+            ],
+            Vec::new(),
+            start,
+            Utc::now(),
+        );
+        assert_eq!(data.summed_seconds, 1200);
+        assert_eq!(data.wall_clock_seconds, 900);
+        assert!(data.wall_clock_seconds < data.summed_seconds);
+    }
+
+    #[tokio::test]
+    async fn test_unusual_apps_flags_spike() {
+        let pool = Arc::new(create_memory_pool());
+        let service = UsageServiceImpl::new(Arc::clone(&pool));
+        let events = WindowEventRepositoryImpl::new((*pool).clone());
+
+        let today = NaiveDate::from_ymd_opt(2024, 5, 15).unwrap();
+        let insert = |days_ago: i64, app_name: &'static str, duration_secs: i64, is_afk: bool| {
+            let events = events.clone();
+            async move {
+                let start =
+                    TimeRangeCalculator::day(today - chrono::Duration::days(days_ago)).start;
+                events
+                    .insert(&event(
+                        app_name,
+                        start + chrono::Duration::hours(10),
+                        duration_secs,
+                        is_afk,
+                    ))
+                    .await
+                    .unwrap();
+            }
+        };
+
+        // 过去 7 天：code 每天 2 小时左右，twitter 每天 10 分钟左右
+        for (days_ago, code, twitter) in [
+            (1, 7200, 600),
+            (2, 6800, 540),
+            (3, 7500, 660),
+            (4, 7000, 600),
+            (5, 7300, 480),
+            (6, 6900, 720),
+            (7, 7100, 600),
+        ] {
+            insert(days_ago, "code", code, false).await;
+            insert(days_ago, "twitter", twitter, false).await;
+        }
+        // 今天 twitter 用了 2 小时，code 正常，mpv 此前从未使用
+        insert(0, "code", 7400, false).await;
+        insert(0, "twitter", 7200, false).await;
+        insert(0, "mpv", 3600, false).await;
+        // code 在长时间 AFK 期间保持焦点，不应被当作异常
+        insert(0, "code", 14400, true).await;
+
+        let unusual = service.unusual_apps_on(today, 7, 2.0).await.unwrap();
+        assert_eq!(unusual.len(), 1);
+        assert_eq!(unusual[0].0, "twitter");
+        assert!(unusual[0].1 > 10.0);
+
+        assert!(service
+            .unusual_apps_on(today, 0, 2.0)
+            .await
+            .unwrap()
+            .is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_productivity_ratio() {
+        let pool = Arc::new(create_memory_pool());
+        let service = UsageServiceImpl::new(Arc::clone(&pool));
+        let categories = CategoryRepositoryImpl::new(Arc::clone(&pool));
+        let events = WindowEventRepositoryImpl::new((*pool).clone());
+
+        let start = Utc::now() - chrono::Duration::hours(2);
+        // code 在 AFK 期间保持焦点的 30 分钟不计入任何一方
+        for (minutes, app_name, duration_secs, is_afk) in [
+            (0, "code", 3600, false),
+            (60, "steam", 1800, false),
+            (90, "mpv", 1800, false),
+            (100, "code", 1800, true),
+        ] {
+            events
+                .insert(&event(
+                    app_name,
+                    start + chrono::Duration::minutes(minutes),
+                    duration_secs,
+                    is_afk,
+                ))
+                .await
+                .unwrap();
+        }
+
+        let ids = insert_categories(&categories, &[("开发", "code"), ("娱乐", "steam")]).await;
+
+        let end = Utc::now();
+        // 未设置高效分类时占比为 0
+        assert_eq!(service.productivity_ratio(start, end).await.unwrap(), 0.0);
+
+        service.set_productive_categories(&[ids[0]]).await.unwrap();
+        assert_eq!(
+            service.get_productive_categories().await.unwrap(),
+            vec![ids[0]]
+        );
+        // code 1 小时 / 总计 2 小时（未分类的 mpv 也计入总时长）
+        assert_eq!(service.productivity_ratio(start, end).await.unwrap(), 0.5);
+    }
+
+    #[tokio::test]
+    async fn test_hourly_productivity() {
+        let pool = Arc::new(create_memory_pool());
+        let service = UsageServiceImpl::new(Arc::clone(&pool));
+        let categories = CategoryRepositoryImpl::new(Arc::clone(&pool));
+        let events = WindowEventRepositoryImpl::new((*pool).clone());
+
+        insert_categories(&categories, &[("开发", "code"), ("娱乐", "steam")]).await;
+
+        let date = NaiveDate::from_ymd_opt(2024, 5, 15).unwrap();
+        let day_start = TimeRangeCalculator::day(date).start;
+        // 9 点：code 30 分钟 + 未分类的 firefox 10 分钟；21 点：steam 30 分钟
+        for (minutes, app_name, duration_secs) in [
+            (9 * 60, "code", 1800),
+            (9 * 60 + 40, "firefox", 600),
+            (21 * 60, "steam", 1800),
+        ] {
+            events
+                .insert(&event(
+                    app_name,
+                    day_start + chrono::Duration::minutes(minutes),
+                    duration_secs,
+                    false,
+                ))
+                .await
+                .unwrap();
+        }
+
+        let productive: HashSet<String> = ["开发".to_string()].into_iter().collect();
+        let fractions = service
+            .hourly_productivity(date, &productive)
+            .await
+            .unwrap();
+        assert_eq!(fractions[9], 0.75);
+        assert_eq!(fractions[21], 0.0);
+        assert_eq!(fractions[10], 0.0);
+    }
+
+    #[tokio::test]
+    async fn test_weekly_category_changes() {
+        let pool = Arc::new(create_memory_pool());
+        let service = UsageServiceImpl::new(Arc::clone(&pool));
+        let categories = CategoryRepositoryImpl::new(Arc::clone(&pool));
+        let events = WindowEventRepositoryImpl::new((*pool).clone());
+
+        insert_categories(
+            &categories,
+            &[("开发", "code"), ("娱乐", "steam"), ("阅读", "zathura")],
+        )
+        .await;
+
+        let week_from = |monday: NaiveDate| {
+            crate::time::range::TimeRange::new(
+                TimeRangeCalculator::day(monday).start,
+                TimeRangeCalculator::day(monday + chrono::Duration::days(6)).end,
+            )
+        };
+        let week = week_from(NaiveDate::from_ymd_opt(2024, 5, 13).unwrap());
+        let prev_week = week_from(NaiveDate::from_ymd_opt(2024, 5, 6).unwrap());
+        // 开发：1 小时 -> 3 小时；娱乐：2 小时 -> 30 分钟；阅读：不变
+        for (range, app_name, duration_secs) in [
+            (&prev_week, "code", 3600),
+            (&prev_week, "steam", 7200),
+            (&prev_week, "zathura", 600),
+            (&week, "code", 10800),
+            (&week, "steam", 1800),
+            (&week, "zathura", 600),
+        ] {
+            events
+                .insert(&event(
+                    app_name,
+                    range.start + chrono::Duration::hours(10),
+                    duration_secs,
+                    false,
+                ))
+                .await
+                .unwrap();
         }
+
+        let changes = service
+            .weekly_category_changes(week, prev_week)
+            .await
+            .unwrap();
+        let summary: Vec<(&str, i64, i64, i64)> = changes
+            .iter()
+            .map(|c| {
+                (
+                    c.category.name.as_str(),
+                    c.current_seconds,
+                    c.previous_seconds,
+                    c.delta_seconds,
+                )
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("开发", 10800, 3600, 7200),
+                ("娱乐", 1800, 7200, -5400),
+                ("阅读", 600, 600, 0),
+            ]
+        );
     }
 }
@@ -12,15 +12,22 @@
 //! - `types`: 时间相关的核心类型定义
 //! - `format`: 时间格式化工具
 //! - `aggregate`: 时间聚合计算
+//! - `filter`: 事件过滤（跳过 AFK、限定范围）
 //! - `range`: 时间范围计算
+//! - `analysis`: 时间序列统计分析
 
 pub mod aggregate;
+pub mod analysis;
+pub mod filter;
 pub mod format;
 pub mod range;
 pub mod types;
 
 // 重新导出常用类型
-pub use types::{Duration, TimeGranularity, TimeSlot, TimeSlots};
+pub use types::{
+    Duration, ParseTimeKeyError, TimeGranularity, TimeSlot, TimeSlots, WeekNumbering,
+    WEEK_NUMBERING_KEY,
+};
 
 /// 时间记录模块的预导出
 pub mod prelude {
@@ -8,10 +8,11 @@
 //! 2. **时间范围过滤**: 支持按时间范围过滤事件
 //! 3. **总时长计算**: total_seconds 必须反映实际聚合的数据
 
-use chrono::{DateTime, Datelike, Local, Timelike, Utc};
+use chrono::{Datelike, Local};
 use std::collections::HashMap;
 
 use crate::models::AppUsage;
+use crate::time::filter::WindowEventFilterExt;
 use crate::time::range::TimeRange;
 use crate::time::types::{TimeGranularity, TimeSlot, TimeSlots};
 
@@ -34,6 +35,8 @@ pub struct TimeAggregator<'a> {
     time_range: Option<TimeRange>,
     /// 分组模式
     group_mode: GroupMode,
+    /// 是否为没有数据的时间段补齐空槽
+    fill_gaps: bool,
 }
 
 impl<'a> TimeAggregator<'a> {
@@ -43,6 +46,7 @@ impl<'a> TimeAggregator<'a> {
             app_usage,
             time_range: None,
             group_mode: GroupMode::ByApp,
+            fill_gaps: false,
         }
     }
 
@@ -58,6 +62,15 @@ impl<'a> TimeAggregator<'a> {
         self
     }
 
+    /// 设置是否补齐空槽
+    ///
+    /// 按月聚合时，为没有数据的周生成时长为 0 的时间槽，保证周序号连续。
+    /// 若设置了时间范围，则补齐到该范围起始月份的全部周数；否则补齐到出现数据的最后一周。
+    pub fn with_fill_gaps(mut self, fill_gaps: bool) -> Self {
+        self.fill_gaps = fill_gaps;
+        self
+    }
+
     /// 按日聚合（24小时）
     ///
     /// 返回 24 个时间槽，每个代表一小时
@@ -71,18 +84,13 @@ impl<'a> TimeAggregator<'a> {
                 continue;
             }
 
-            for event in &usage.window_events {
-                if event.is_afk {
-                    continue;
-                }
-
-                // 时间范围过滤
-                if !self.is_event_in_range(event.timestamp) {
-                    continue;
-                }
-
+            for event in usage
+                .window_events
+                .iter()
+                .active_in_range(self.time_range.as_ref())
+            {
                 let local_time = event.timestamp.with_timezone(&Local);
-                let hour = local_time.hour() as usize;
+                let hour = TimeGranularity::Day.bucket_index(local_time);
 
                 if hour < slots.len() {
                     let seconds = event.duration_secs;
@@ -135,17 +143,13 @@ impl<'a> TimeAggregator<'a> {
                 continue;
             }
 
-            for event in &usage.window_events {
-                if event.is_afk {
-                    continue;
-                }
-
-                if !self.is_event_in_range(event.timestamp) {
-                    continue;
-                }
-
+            for event in usage
+                .window_events
+                .iter()
+                .active_in_range(self.time_range.as_ref())
+            {
                 let local_time = event.timestamp.with_timezone(&Local);
-                let weekday = local_time.weekday().num_days_from_monday() as usize;
+                let weekday = TimeGranularity::Week.bucket_index(local_time);
 
                 if weekday < slots.len() {
                     let seconds = event.duration_secs;
@@ -177,8 +181,45 @@ impl<'a> TimeAggregator<'a> {
 
     /// 按月聚合（最多6周）
     ///
-    /// 返回该月的周数时间槽（最多6个）
+    /// 返回该月的周数时间槽（最多6个）。默认只包含有数据的周，
+    /// 开启 `with_fill_gaps` 后缺失的周以空槽补齐。
     pub fn aggregate_by_month(&self) -> TimeSlots {
+        let week_count = if self.fill_gaps {
+            self.time_range.as_ref().map(|range| {
+                let local_start = range.start.with_timezone(&Local);
+                crate::time::range::TimeRangeCalculator::weeks_in_month(
+                    local_start.year(),
+                    local_start.month(),
+                )
+            })
+        } else {
+            None
+        };
+        self.aggregate_weeks(week_count)
+    }
+
+    /// 按指定月份的周聚合
+    ///
+    /// 只统计该月内的事件，并始终返回该月全部的周时间槽（无数据的周时长为 0）
+    pub fn aggregate_by_week_in_month(&self, year: i32, month: u32) -> TimeSlots {
+        let aggregator = TimeAggregator {
+            app_usage: self.app_usage,
+            time_range: Some(crate::time::range::TimeRangeCalculator::month_range(
+                year, month,
+            )),
+            group_mode: self.group_mode,
+            fill_gaps: true,
+        };
+        aggregator.aggregate_weeks(Some(
+            crate::time::range::TimeRangeCalculator::weeks_in_month(year, month),
+        ))
+    }
+
+    /// 按月内的周聚合
+    ///
+    /// `week_count` 为 `Some(n)` 时补齐第 1 到第 n 周；
+    /// 为 `None` 且开启 `fill_gaps` 时补齐到出现数据的最后一周。
+    fn aggregate_weeks(&self, week_count: Option<u32>) -> TimeSlots {
         let mut weekly_data: HashMap<u32, TimeSlot> = HashMap::new();
         let mut total_seconds = 0i64;
 
@@ -187,30 +228,15 @@ impl<'a> TimeAggregator<'a> {
                 continue;
             }
 
-            for event in &usage.window_events {
-                if event.is_afk {
-                    continue;
-                }
-
-                if !self.is_event_in_range(event.timestamp) {
-                    continue;
-                }
-
+            for event in usage
+                .window_events
+                .iter()
+                .active_in_range(self.time_range.as_ref())
+            {
                 let local_time = event.timestamp.with_timezone(&Local);
-                let day = local_time.day();
-
-                if day == 0 {
-                    continue;
-                }
-
-                // 使用统一的周计算逻辑
-                let week = crate::time::range::TimeRangeCalculator::week_of_month(
-                    local_time.year(),
-                    local_time.month(),
-                    day,
-                );
+                let week = TimeGranularity::Month.bucket_index(local_time) as u32 + 1;
 
-                if week == 0 || week > 6 {
+                if week > 6 {
                     continue;
                 }
 
@@ -232,6 +258,17 @@ impl<'a> TimeAggregator<'a> {
             }
         }
 
+        if self.fill_gaps {
+            let last_week = week_count
+                .or_else(|| weekly_data.keys().max().copied())
+                .unwrap_or(0);
+            for week in 1..=last_week {
+                weekly_data
+                    .entry(week)
+                    .or_insert_with(|| TimeSlot::new(format!("第{}周", week), (week - 1) as usize));
+            }
+        }
+
         let mut slots = TimeSlots::new(TimeGranularity::Month);
         let mut slot_vec: Vec<_> = weekly_data.into_values().collect();
         slot_vec.sort_by_key(|s| s.index());
@@ -266,23 +303,13 @@ impl<'a> TimeAggregator<'a> {
                 continue;
             }
 
-            for event in &usage.window_events {
-                if event.is_afk {
-                    continue;
-                }
-
-                if !self.is_event_in_range(event.timestamp) {
-                    continue;
-                }
-
+            for event in usage
+                .window_events
+                .iter()
+                .active_in_range(self.time_range.as_ref())
+            {
                 let local_time = event.timestamp.with_timezone(&Local);
-                let month = local_time.month() as usize;
-
-                if month == 0 || month > 12 {
-                    continue;
-                }
-
-                let month_idx = month - 1;
+                let month_idx = TimeGranularity::Year.bucket_index(local_time);
                 let seconds = event.duration_secs;
                 total_seconds += seconds;
 
@@ -309,6 +336,59 @@ impl<'a> TimeAggregator<'a> {
         slots
     }
 
+    /// 按年份聚合（多年）
+    ///
+    /// 返回 `year_count` 个连续的年份时间槽（包括没有数据的年份），按年份升序排列，
+    /// 最后一个槽为 `anchor_year`；未指定锚点时以最晚事件所在年份为准，没有事件时为今年。
+    /// 时间槽的粒度记为 `Year`，标签为"2024年"形式。
+    pub fn aggregate_by_years(&self, year_count: u32, anchor_year: Option<i32>) -> TimeSlots {
+        let events: Vec<_> = self
+            .app_usage
+            .iter()
+            .filter(|usage| !usage.app_name.is_empty())
+            .flat_map(|usage| {
+                usage
+                    .window_events
+                    .iter()
+                    .active_in_range(self.time_range.as_ref())
+                    .map(move |event| (usage.app_name.as_str(), event))
+            })
+            .collect();
+
+        let last_year = anchor_year.unwrap_or_else(|| {
+            events
+                .iter()
+                .map(|(_, event)| event.timestamp.with_timezone(&Local).year())
+                .max()
+                .unwrap_or_else(|| Local::now().year())
+        });
+        let first_year = last_year - year_count as i32 + 1;
+
+        let mut slots = TimeSlots::new(TimeGranularity::Year);
+        for (i, year) in (first_year..=last_year).enumerate() {
+            slots.add_slot(TimeSlot::new(format!("{}年", year), i));
+        }
+
+        for (app_name, event) in events {
+            let year = event.timestamp.with_timezone(&Local).year();
+            if year < first_year || year > last_year {
+                continue;
+            }
+
+            let slot = slots.get_slot_mut((year - first_year) as usize).unwrap();
+            match self.group_mode {
+                GroupMode::ByApp => {
+                    slot.add_duration(app_name, event.duration_secs);
+                }
+                GroupMode::ByCategory => {
+                    slot.add_duration("未分类", event.duration_secs);
+                }
+            }
+        }
+
+        slots
+    }
+
     /// 按小时聚合（60分钟）
     ///
     /// 返回 60 个时间槽，每个代表一分钟
@@ -321,17 +401,13 @@ impl<'a> TimeAggregator<'a> {
                 continue;
             }
 
-            for event in &usage.window_events {
-                if event.is_afk {
-                    continue;
-                }
-
-                if !self.is_event_in_range(event.timestamp) {
-                    continue;
-                }
-
+            for event in usage
+                .window_events
+                .iter()
+                .active_in_range(self.time_range.as_ref())
+            {
                 let local_time = event.timestamp.with_timezone(&Local);
-                let minute = local_time.minute() as usize;
+                let minute = TimeGranularity::Hour.bucket_index(local_time);
 
                 if minute < slots.len() {
                     let seconds = event.duration_secs;
@@ -372,15 +448,6 @@ impl<'a> TimeAggregator<'a> {
         }
     }
 
-    /// 检查事件是否在时间范围内
-    fn is_event_in_range(&self, timestamp: DateTime<Utc>) -> bool {
-        if let Some(range) = &self.time_range {
-            range.contains(timestamp)
-        } else {
-            true
-        }
-    }
-
     /// 创建指定粒度的空时间槽
     fn create_slots_by_granularity(granularity: TimeGranularity) -> TimeSlots {
         let mut slots = TimeSlots::new(granularity);
@@ -397,7 +464,8 @@ impl<'a> TimeAggregator<'a> {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use chrono::{NaiveDate, NaiveTime};
+    use crate::models::WindowEvent;
+    use chrono::{NaiveDate, NaiveTime, Utc};
 
     fn create_test_app_usage() -> Vec<AppUsage> {
         // 创建测试数据：2024-01-15 (周一) 的一些事件
@@ -487,10 +555,6 @@ mod tests {
     fn test_time_range_filter() {
         let data = create_test_app_usage();
 
-        // 设置时间范围：只包含上午
-        use crate::time::range::TimeRangeCalculator;
-        let range = TimeRangeCalculator::day(NaiveDate::from_ymd_opt(2024, 1, 15).unwrap());
-
         // 创建只包含上午的时间范围
         let start = NaiveDate::from_ymd_opt(2024, 1, 15)
             .unwrap()
@@ -521,6 +585,121 @@ mod tests {
         assert_eq!(slots.total_duration().as_seconds(), 3665);
     }
 
+    fn create_usage_on_days(days: &[u32]) -> Vec<AppUsage> {
+        let window_events = days
+            .iter()
+            .map(|&day| WindowEvent {
+                id: None,
+                timestamp: NaiveDate::from_ymd_opt(2024, 1, day)
+                    .unwrap()
+                    .and_time(NaiveTime::from_hms_opt(10, 0, 0).unwrap())
+                    .and_local_timezone(Local)
+                    .unwrap()
+                    .with_timezone(&Utc),
+                duration_secs: 600,
+                app_name: "App1".to_string(),
+                window_title: "Test".to_string(),
+                workspace: String::new(),
+                is_afk: false,
+            })
+            .collect::<Vec<_>>();
+
+        vec![AppUsage {
+            app_name: "App1".to_string(),
+            total_seconds: 600 * days.len() as i64,
+            window_events,
+        }]
+    }
+
+    #[test]
+    fn test_aggregate_by_month_fill_gaps() {
+        // 2024-01-02 在第1周，2024-01-16 在第3周
+        let data = create_usage_on_days(&[2, 16]);
+
+        let sparse = TimeAggregator::new(&data).aggregate_by_month();
+        assert_eq!(sparse.len(), 2);
+
+        let filled = TimeAggregator::new(&data)
+            .with_fill_gaps(true)
+            .aggregate_by_month();
+        assert_eq!(filled.len(), 3);
+        for (i, slot) in filled.slots().iter().enumerate() {
+            assert_eq!(slot.index(), i);
+        }
+        assert_eq!(filled.get_slot(1).unwrap().duration().as_seconds(), 0);
+        assert_eq!(filled.total_duration().as_seconds(), 1200);
+    }
+
+    #[test]
+    fn test_aggregate_by_week_in_month() {
+        let data = create_usage_on_days(&[2, 16]);
+        let slots = TimeAggregator::new(&data).aggregate_by_week_in_month(2024, 1);
+
+        // 2024年1月共5周：1-7, 8-14, 15-21, 22-28, 29-31
+        assert_eq!(slots.len(), 5);
+        let indices: Vec<_> = slots.slots().iter().map(|s| s.index()).collect();
+        assert_eq!(indices, vec![0, 1, 2, 3, 4]);
+        assert_eq!(slots.get_slot(0).unwrap().duration().as_seconds(), 600);
+        assert_eq!(slots.get_slot(1).unwrap().duration().as_seconds(), 0);
+        assert_eq!(slots.get_slot(1).unwrap().label(), "第2周");
+        assert_eq!(slots.get_slot(2).unwrap().duration().as_seconds(), 600);
+
+        // 其他月份的事件不计入
+        let other = TimeAggregator::new(&data).aggregate_by_week_in_month(2024, 2);
+        assert_eq!(other.total_duration().as_seconds(), 0);
+    }
+
+    #[test]
+    fn test_aggregate_by_years_window() {
+        let at_year = |year: i32| {
+            NaiveDate::from_ymd_opt(year, 6, 1)
+                .unwrap()
+                .and_time(NaiveTime::from_hms_opt(10, 0, 0).unwrap())
+                .and_local_timezone(Local)
+                .unwrap()
+                .with_timezone(&Utc)
+        };
+        let window_events = [2019, 2021, 2024, 2024]
+            .iter()
+            .map(|&year| WindowEvent {
+                id: None,
+                timestamp: at_year(year),
+                duration_secs: 600,
+                app_name: "App1".to_string(),
+                window_title: "Test".to_string(),
+                workspace: String::new(),
+                is_afk: false,
+            })
+            .collect();
+        let data = vec![AppUsage {
+            app_name: "App1".to_string(),
+            total_seconds: 2400,
+            window_events,
+        }];
+
+        // 以最晚事件所在年份（2024）为结尾，2019 年的事件不在窗口内
+        let slots = TimeAggregator::new(&data).aggregate_by_years(5, None);
+        let labels: Vec<_> = slots.slots().iter().map(|s| s.label()).collect();
+        assert_eq!(
+            labels,
+            vec!["2020年", "2021年", "2022年", "2023年", "2024年"]
+        );
+        let durations: Vec<_> = slots
+            .slots()
+            .iter()
+            .map(|s| s.duration().as_seconds())
+            .collect();
+        assert_eq!(durations, vec![0, 600, 0, 0, 1200]);
+
+        // 指定锚点年份
+        let slots = TimeAggregator::new(&data).aggregate_by_years(5, Some(2022));
+        assert_eq!(slots.len(), 5);
+        assert_eq!(slots.get_slot(0).unwrap().label(), "2018年");
+        assert_eq!(slots.get_slot(1).unwrap().duration().as_seconds(), 600);
+        assert_eq!(slots.get_slot(4).unwrap().label(), "2022年");
+        assert_eq!(slots.total_duration().as_seconds(), 1200);
+    }
+
     #[test]
     fn test_week_of_month_consistency() {
         // 测试周计算的一致性
@@ -0,0 +1,813 @@
+//! 时间序列分析
+//!
+//! 对按时间段聚合后的时长序列（如每日应用总时长）做简单的统计分析，
+//! 以及将窗口事件划分为连续的活动会话
+
+use chrono::{DateTime, Local, NaiveDate, Utc};
+use std::collections::HashMap;
+
+use crate::models::{AfkEvent, WindowEvent};
+use crate::time::filter::WindowEventFilterExt;
+use crate::time::range::TimeRange;
+use crate::time::types::TimeSlot;
+
+/// 连续活动会话
+#[derive(Debug, Clone, PartialEq)]
+pub struct Session {
+    /// 会话开始时间（第一个事件的开始）
+    pub start: DateTime<Utc>,
+    /// 会话结束时间（最后一个事件的结束）
+    pub end: DateTime<Utc>,
+    /// 各应用在会话内的使用时长（秒）
+    pub app_breakdown: HashMap<String, i64>,
+    /// 会话总使用时长（秒）
+    pub total_seconds: i64,
+}
+
+impl Session {
+    /// 会话内使用时长最多的应用（时长相同时取名称较小者）
+    pub fn dominant_app(&self) -> Option<&str> {
+        self.app_breakdown
+            .iter()
+            .max_by(|a, b| a.1.cmp(b.1).then_with(|| b.0.cmp(a.0)))
+            .map(|(app_name, _)| app_name.as_str())
+    }
+
+    fn from_event(event: &WindowEvent) -> Self {
+        Self {
+            start: event.timestamp,
+            end: event_end(event),
+            app_breakdown: HashMap::from([(event.app_name.clone(), event.duration_secs)]),
+            total_seconds: event.duration_secs,
+        }
+    }
+
+    fn push(&mut self, event: &WindowEvent) {
+        self.end = self.end.max(event_end(event));
+        *self
+            .app_breakdown
+            .entry(event.app_name.clone())
+            .or_insert(0) += event.duration_secs;
+        self.total_seconds += event.duration_secs;
+    }
+}
+
+/// 每日序列中的一个数据点
+#[derive(Debug, Clone, PartialEq)]
+pub struct DailyPoint {
+    /// 本地日期
+    pub date: NaiveDate,
+    /// 使用时长（秒）；缺失的日期为前后最近的已知值线性插值后的估计
+    pub seconds: i64,
+    /// 当天是否没有数据（守护进程未运行），图表应以虚线或灰色显示
+    pub missing: bool,
+}
+
+/// 事件的结束时间
+fn event_end(event: &WindowEvent) -> DateTime<Utc> {
+    event.timestamp + chrono::Duration::seconds(event.duration_secs)
+}
+
+/// 划分深度工作时段时，同一会话内允许的最大事件间隔（秒）
+pub const DEEP_WORK_MAX_GAP_SECS: i64 = 300;
+
+/// 时间序列分析器
+pub struct TimeSeriesAnalyzer;
+
+impl TimeSeriesAnalyzer {
+    /// 计算序列的均值和（总体）标准差
+    ///
+    /// 空序列返回 `None`。
+    pub fn mean_and_stddev(series: &[i64]) -> Option<(f64, f64)> {
+        if series.is_empty() {
+            return None;
+        }
+
+        let n = series.len() as f64;
+        let mean = series.iter().map(|&v| v as f64).sum::<f64>() / n;
+        let variance = series
+            .iter()
+            .map(|&v| {
+                let diff = v as f64 - mean;
+                diff * diff
+            })
+            .sum::<f64>()
+            / n;
+
+        Some((mean, variance.sqrt()))
+    }
+
+    /// 检测异常峰值，返回值超过 `mean + z_threshold * stddev` 的下标
+    ///
+    /// 方差为 0（所有值相同）或序列为空时不存在峰值。
+    pub fn detect_spikes(series: &[i64], z_threshold: f64) -> Vec<usize> {
+        let Some((mean, stddev)) = Self::mean_and_stddev(series) else {
+            return Vec::new();
+        };
+        if stddev == 0.0 {
+            return Vec::new();
+        }
+
+        let threshold = mean + z_threshold * stddev;
+        series
+            .iter()
+            .enumerate()
+            .filter(|&(_, &v)| v as f64 > threshold)
+            .map(|(i, _)| i)
+            .collect()
+    }
+
+    /// 计算尾随滑动平均，结果与输入一一对应
+    ///
+    /// 第 i 项为 `series[i + 1 - window ..= i]` 的平均值；前 `window - 1` 项窗口不满，
+    /// 使用已有的部分取平均。`window` 为 0 时按 1 处理。
+    pub fn rolling_average(series: &[i64], window: usize) -> Vec<f64> {
+        let window = window.max(1);
+        let mut sum = 0i64;
+        series
+            .iter()
+            .enumerate()
+            .map(|(i, &v)| {
+                sum += v;
+                if i >= window {
+                    sum -= series[i - window];
+                }
+                sum as f64 / (i + 1).min(window) as f64
+            })
+            .collect()
+    }
+
+    /// 将窗口事件划分为连续的活动会话
+    ///
+    /// 事件按时间排序后依次合并；当与上一个事件结束之间的空隙超过 `max_gap_secs`，
+    /// 或中间出现 AFK 事件时开始新的会话。AFK 事件本身不计入任何会话。
+    pub fn sessionize(events: &[WindowEvent], max_gap_secs: i64) -> Vec<Session> {
+        let mut sorted: Vec<&WindowEvent> = events.iter().collect();
+        sorted.sort_by_key(|e| e.timestamp);
+
+        let mut sessions = Vec::new();
+        let mut current: Option<Session> = None;
+
+        for event in sorted {
+            if event.is_afk {
+                sessions.extend(current.take());
+                continue;
+            }
+
+            match current.as_mut() {
+                Some(session) if (event.timestamp - session.end).num_seconds() <= max_gap_secs => {
+                    session.push(event);
+                }
+                _ => {
+                    sessions.extend(current.replace(Session::from_event(event)));
+                }
+            }
+        }
+        sessions.extend(current);
+
+        sessions
+    }
+
+    /// 找出最长的连续活动会话
+    ///
+    /// 会话划分规则同 [`Self::sessionize`]，按会话总使用时长比较；时长相同时取开始较早者。
+    /// 没有非 AFK 事件时返回 `None`。
+    pub fn longest_session(events: &[WindowEvent], max_gap_secs: i64) -> Option<Session> {
+        Self::sessionize(events, max_gap_secs)
+            .into_iter()
+            .reduce(|longest, session| {
+                if session.total_seconds > longest.total_seconds {
+                    session
+                } else {
+                    longest
+                }
+            })
+    }
+
+    /// 找出"深度工作"时段：持续时间长且切换应用少的会话
+    ///
+    /// 会话按 [`DEEP_WORK_MAX_GAP_SECS`] 的间隔划分（规则同 [`Self::sessionize`]），
+    /// 保留总使用时长不少于 `min_duration_secs` 且涉及的应用不超过 `max_apps` 个的会话，按时间排序。
+    pub fn deep_work_blocks(
+        events: &[WindowEvent],
+        min_duration_secs: i64,
+        max_apps: usize,
+    ) -> Vec<Session> {
+        Self::sessionize(events, DEEP_WORK_MAX_GAP_SECS)
+            .into_iter()
+            .filter(|session| {
+                session.total_seconds >= min_duration_secs
+                    && session.app_breakdown.len() <= max_apps
+            })
+            .collect()
+    }
+
+    /// 区分真实的 0 与缺失的数据，得到可直接绘制的每日序列
+    ///
+    /// `None` 表示当天没有数据，标记为缺失，并用前后最近的已知值做线性插值（只有一侧时沿用该侧的值，
+    /// 两侧都没有时为 0）；`Some(0)` 是守护进程在运行但确实没有使用，保持为 0。
+    pub fn mark_missing(daily: &[(NaiveDate, Option<i64>)]) -> Vec<DailyPoint> {
+        daily
+            .iter()
+            .enumerate()
+            .map(|(i, &(date, value))| {
+                let seconds = value.unwrap_or_else(|| {
+                    let before = daily[..i]
+                        .iter()
+                        .enumerate()
+                        .rev()
+                        .find_map(|(j, &(_, v))| v.map(|v| (j, v)));
+                    let after = daily[i + 1..]
+                        .iter()
+                        .enumerate()
+                        .find_map(|(j, &(_, v))| v.map(|v| (i + 1 + j, v)));
+                    match (before, after) {
+                        (Some((j0, v0)), Some((j1, v1))) => {
+                            let t = (i - j0) as f64 / (j1 - j0) as f64;
+                            (v0 as f64 + (v1 - v0) as f64 * t).round() as i64
+                        }
+                        (Some((_, v)), None) | (None, Some((_, v))) => v,
+                        (None, None) => 0,
+                    }
+                });
+                DailyPoint {
+                    date,
+                    seconds,
+                    missing: value.is_none(),
+                }
+            })
+            .collect()
+    }
+
+    /// 把多天的每小时使用时长逐小时取平均，得到"典型一天"的作息分布
+    ///
+    /// 没有输入时返回全 0。
+    pub fn average_hourly_profile(daily_profiles: &[[i64; 24]]) -> [f64; 24] {
+        let mut profile = [0.0; 24];
+        if daily_profiles.is_empty() {
+            return profile;
+        }
+
+        for day in daily_profiles {
+            for (sum, &seconds) in profile.iter_mut().zip(day) {
+                *sum += seconds as f64;
+            }
+        }
+        let days = daily_profiles.len() as f64;
+        for value in &mut profile {
+            *value /= days;
+        }
+        profile
+    }
+
+    /// 找出时长最长的 AFK 会话区间
+    ///
+    /// 已结束的会话取 `[start_time, end_time]`；尚未结束的会话按已记录的 `duration_secs` 计算结束时间。
+    /// 时长相同时取开始较早者。没有 AFK 会话时返回 `None`。
+    pub fn longest_idle(afk_events: &[AfkEvent]) -> Option<TimeRange> {
+        afk_events
+            .iter()
+            .map(|e| {
+                let end = e.end_time.unwrap_or_else(|| {
+                    e.start_time + chrono::Duration::seconds(e.duration_secs.max(0))
+                });
+                TimeRange::new(e.start_time, end.max(e.start_time))
+            })
+            .reduce(|longest, range| {
+                let longer = range.duration_seconds() > longest.duration_seconds();
+                let earlier_tie = range.duration_seconds() == longest.duration_seconds()
+                    && range.start < longest.start;
+                if longer || earlier_tie {
+                    range
+                } else {
+                    longest
+                }
+            })
+    }
+
+    /// 计算活动窗口：非 AFK 事件中最早的开始时间和最晚的结束时间（本地时间）
+    ///
+    /// 结束时间为 `timestamp + duration_secs`，取所有事件中的最大值而不是最后一个事件的结束。
+    /// 多天的作息推断由调用方按天调用后自行平均。没有非 AFK 事件时返回 `None`。
+    pub fn active_window(events: &[WindowEvent]) -> Option<(DateTime<Local>, DateTime<Local>)> {
+        let mut active = events.iter().active_in_range(None);
+        let first = active.next()?;
+        let first_end = first.timestamp + chrono::Duration::seconds(first.duration_secs.max(0));
+
+        let (start, end) = active.fold((first.timestamp, first_end), |(start, end), e| {
+            let event_end = e.timestamp + chrono::Duration::seconds(e.duration_secs.max(0));
+            (start.min(e.timestamp), end.max(event_end))
+        });
+        Some((start.with_timezone(&Local), end.with_timezone(&Local)))
+    }
+
+    /// 计算实际活跃的墙钟时长（秒）：所有非 AFK 事件时间区间的并集长度
+    ///
+    /// 事件之间可能重叠，直接累加 `duration_secs` 会重复计算重叠部分；这里先合并重叠区间再求和。
+    /// 时长不为正的事件被忽略。
+    pub fn total_active_seconds(events: &[WindowEvent]) -> i64 {
+        let mut intervals: Vec<(DateTime<Utc>, DateTime<Utc>)> = events
+            .iter()
+            .active_in_range(None)
+            .filter(|e| e.duration_secs > 0)
+            .map(|e| {
+                (
+                    e.timestamp,
+                    e.timestamp + chrono::Duration::seconds(e.duration_secs),
+                )
+            })
+            .collect();
+        intervals.sort_by_key(|&(start, _)| start);
+
+        let mut total = 0;
+        let mut current: Option<(DateTime<Utc>, DateTime<Utc>)> = None;
+        for (start, end) in intervals {
+            match current.as_mut() {
+                Some((_, current_end)) if start <= *current_end => {
+                    *current_end = (*current_end).max(end);
+                }
+                _ => {
+                    if let Some((s, e)) = current.replace((start, end)) {
+                        total += (e - s).num_seconds();
+                    }
+                }
+            }
+        }
+        if let Some((s, e)) = current {
+            total += (e - s).num_seconds();
+        }
+
+        total
+    }
+
+    /// 按固定长度（如 10 分钟、15 分钟）的时间箱聚合非 AFK 事件，时间箱从 `range.start` 开始对齐
+    ///
+    /// 事件按开始时间归入第 `(timestamp - range.start) / bin_secs` 个时间箱，时长不跨箱拆分；
+    /// 范围按半开区间 `[start, end)` 过滤，恰好位于 `range.end` 的事件不计入。时间箱按应用分组，标签为本地开始时间 `HH:MM`。
+    /// `bin_secs` 不为正时返回 `None`。
+    pub fn aggregate_fixed_bins(
+        events: &[WindowEvent],
+        bin_secs: i64,
+        range: &TimeRange,
+    ) -> Option<Vec<TimeSlot>> {
+        if bin_secs <= 0 {
+            return None;
+        }
+
+        let bin_millis = bin_secs * 1000;
+        let range_millis = (range.end - range.start).num_milliseconds();
+        let bin_count = ((range_millis + bin_millis - 1) / bin_millis).max(1) as usize;
+
+        let mut slots: Vec<TimeSlot> = (0..bin_count)
+            .map(|index| {
+                let bin_start = range.start + chrono::Duration::seconds(bin_secs * index as i64);
+                TimeSlot::new(
+                    bin_start
+                        .with_timezone(&chrono::Local)
+                        .format("%H:%M")
+                        .to_string(),
+                    index,
+                )
+            })
+            .collect();
+
+        for event in events.iter().active_in_range(Some(range)) {
+            let offset_millis = (event.timestamp - range.start).num_milliseconds();
+            let index = ((offset_millis / bin_millis) as usize).min(bin_count - 1);
+            slots[index].add_duration(&event.app_name, event.duration_secs);
+        }
+
+        Some(slots)
+    }
+
+    /// 计算使用时长的集中度（赫芬达尔指数）：各应用时长占比的平方和，范围 0-1
+    ///
+    /// 所有时间都花在一个应用上时为 1，平均分给 n 个应用时为 1/n，应用越多越分散越接近 0。
+    /// 不为正的时长被忽略；没有时长时返回 0。
+    pub fn usage_concentration(app_totals: &[i64]) -> f64 {
+        let positive = || app_totals.iter().copied().filter(|&t| t > 0);
+        let total: i64 = positive().sum();
+        if total <= 0 {
+            return 0.0;
+        }
+
+        positive()
+            .map(|t| {
+                let share = t as f64 / total as f64;
+                share * share
+            })
+            .sum()
+    }
+
+    /// 计算注意力碎片化程度：每活跃小时内的应用切换次数
+    ///
+    /// 忽略 AFK 事件后按时间排序，切换次数为相邻两个事件应用名称不同的次数，
+    /// 活跃小时数为剩余事件的总时长。没有活跃时长时返回 0。
+    pub fn fragmentation(events: &[WindowEvent]) -> f64 {
+        let mut active: Vec<&WindowEvent> = events.iter().active_in_range(None).collect();
+        active.sort_by_key(|e| e.timestamp);
+
+        let active_secs: i64 = active.iter().map(|e| e.duration_secs).sum();
+        if active_secs <= 0 {
+            return 0.0;
+        }
+
+        let switches = active
+            .windows(2)
+            .filter(|pair| pair[0].app_name != pair[1].app_name)
+            .count();
+
+        switches as f64 / (active_secs as f64 / 3600.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn event(app_name: &str, minute: i64, duration_secs: i64, is_afk: bool) -> WindowEvent {
+        WindowEvent {
+            id: None,
+            timestamp: Utc.with_ymd_and_hms(2024, 3, 1, 9, 0, 0).unwrap()
+                + chrono::Duration::minutes(minute),
+            app_name: app_name.to_string(),
+            window_title: String::new(),
+            workspace: String::new(),
+            duration_secs,
+            is_afk,
+        }
+    }
+
+    #[test]
+    fn test_sessionize_continuous() {
+        let events = vec![
+            event("code", 0, 600, false),
+            event("firefox", 10, 300, false),
+            event("code", 16, 240, false),
+        ];
+        let sessions = TimeSeriesAnalyzer::sessionize(&events, 120);
+
+        assert_eq!(sessions.len(), 1);
+        let session = &sessions[0];
+        assert_eq!(session.start, events[0].timestamp);
+        assert_eq!(
+            session.end,
+            events[2].timestamp + chrono::Duration::seconds(240)
+        );
+        assert_eq!(session.total_seconds, 1140);
+        assert_eq!(session.app_breakdown["code"], 840);
+        assert_eq!(session.app_breakdown["firefox"], 300);
+        assert_eq!(session.dominant_app(), Some("code"));
+    }
+
+    #[test]
+    fn test_sessionize_gap_splits() {
+        // 第二个事件在第一个结束 30 分钟后才开始
+        let events = vec![
+            event("firefox", 40, 300, false),
+            event("code", 0, 600, false),
+        ];
+        let sessions = TimeSeriesAnalyzer::sessionize(&events, 300);
+
+        assert_eq!(sessions.len(), 2);
+        assert_eq!(
+            sessions[0].app_breakdown.keys().collect::<Vec<_>>(),
+            vec!["code"]
+        );
+        assert_eq!(sessions[1].total_seconds, 300);
+    }
+
+    #[test]
+    fn test_sessionize_afk_breaks() {
+        let events = vec![
+            event("code", 0, 600, false),
+            event("code", 10, 60, true),
+            event("code", 11, 600, false),
+        ];
+        let sessions = TimeSeriesAnalyzer::sessionize(&events, 3600);
+
+        assert_eq!(sessions.len(), 2);
+        assert_eq!(sessions[0].total_seconds, 600);
+        assert_eq!(sessions[1].total_seconds, 600);
+    }
+
+    #[test]
+    fn test_average_hourly_profile() {
+        assert_eq!(TimeSeriesAnalyzer::average_hourly_profile(&[]), [0.0; 24]);
+
+        let mut monday = [0i64; 24];
+        monday[9] = 3600;
+        monday[14] = 1200;
+        let mut tuesday = [0i64; 24];
+        tuesday[9] = 1800;
+        tuesday[21] = 600;
+
+        let profile = TimeSeriesAnalyzer::average_hourly_profile(&[monday, tuesday]);
+        assert_eq!(profile[9], 2700.0);
+        assert_eq!(profile[14], 600.0);
+        assert_eq!(profile[21], 300.0);
+        assert_eq!(profile[0], 0.0);
+    }
+
+    #[test]
+    fn test_mark_missing() {
+        let day = |d| NaiveDate::from_ymd_opt(2024, 3, d).unwrap();
+        let daily = [
+            (day(4), Some(3600)),
+            (day(5), Some(0)),
+            (day(6), None),
+            (day(7), Some(1800)),
+            (day(8), None),
+        ];
+        let points = TimeSeriesAnalyzer::mark_missing(&daily);
+
+        assert_eq!(points.len(), 5);
+        assert_eq!(points[0].date, day(4));
+        // 真实的 0：守护进程在运行但没有使用
+        assert_eq!(points[1].seconds, 0);
+        assert!(!points[1].missing);
+        // 没有数据：在前后已知值之间插值
+        assert!(points[2].missing);
+        assert_eq!(points[2].seconds, 900);
+        assert!(!points[3].missing);
+        // 末尾缺失沿用前一个已知值
+        assert!(points[4].missing);
+        assert_eq!(points[4].seconds, 1800);
+
+        let all_missing = TimeSeriesAnalyzer::mark_missing(&[(day(4), None)]);
+        assert_eq!(all_missing[0].seconds, 0);
+        assert!(all_missing[0].missing);
+    }
+
+    fn afk(minute: i64, duration_secs: i64, closed: bool) -> AfkEvent {
+        let start_time =
+            Utc.with_ymd_and_hms(2024, 3, 1, 9, 0, 0).unwrap() + chrono::Duration::minutes(minute);
+        AfkEvent {
+            id: None,
+            start_time,
+            end_time: closed.then(|| start_time + chrono::Duration::seconds(duration_secs)),
+            duration_secs,
+        }
+    }
+
+    #[test]
+    fn test_longest_idle() {
+        assert_eq!(TimeSeriesAnalyzer::longest_idle(&[]), None);
+
+        let events = vec![afk(0, 600, true), afk(30, 1800, true), afk(90, 2400, false)];
+        let longest = TimeSeriesAnalyzer::longest_idle(&events).unwrap();
+        assert_eq!(longest.start, events[2].start_time);
+        assert_eq!(longest.duration_seconds(), 2400);
+
+        let longest = TimeSeriesAnalyzer::longest_idle(&events[..2]).unwrap();
+        assert_eq!(longest.start, events[1].start_time);
+        assert_eq!(longest.end, events[1].end_time.unwrap());
+    }
+
+    #[test]
+    fn test_longest_session_empty() {
+        assert_eq!(TimeSeriesAnalyzer::longest_session(&[], 300), None);
+        assert_eq!(
+            TimeSeriesAnalyzer::longest_session(&[event("code", 0, 600, true)], 300),
+            None
+        );
+    }
+
+    #[test]
+    fn test_longest_session_single() {
+        let events = vec![event("code", 0, 600, false), event("code", 10, 300, false)];
+        let longest = TimeSeriesAnalyzer::longest_session(&events, 120).unwrap();
+
+        assert_eq!(longest.start, events[0].timestamp);
+        assert_eq!(longest.total_seconds, 900);
+    }
+
+    #[test]
+    fn test_longest_session_picks_longer() {
+        let events = vec![
+            event("firefox", 0, 600, false),
+            event("code", 30, 1200, false),
+            event("code", 50, 300, false),
+            event("kitty", 120, 1500, false),
+        ];
+        let longest = TimeSeriesAnalyzer::longest_session(&events, 300).unwrap();
+        assert_eq!(longest.start, events[1].timestamp);
+        assert_eq!(longest.total_seconds, 1500);
+        assert_eq!(longest.dominant_app(), Some("code"));
+    }
+
+    #[test]
+    fn test_deep_work_blocks_long_single_app() {
+        let events = vec![
+            event("code", 0, 1800, false),
+            event("code", 31, 1800, false),
+        ];
+        let blocks = TimeSeriesAnalyzer::deep_work_blocks(&events, 3000, 2);
+
+        assert_eq!(blocks.len(), 1);
+        assert_eq!(blocks[0].start, events[0].timestamp);
+        assert_eq!(blocks[0].total_seconds, 3600);
+        assert_eq!(blocks[0].dominant_app(), Some("code"));
+    }
+
+    #[test]
+    fn test_deep_work_blocks_scattered_apps() {
+        let events = vec![
+            event("code", 0, 900, false),
+            event("firefox", 15, 900, false),
+            event("telegram", 30, 900, false),
+            event("kitty", 45, 900, false),
+        ];
+        assert!(TimeSeriesAnalyzer::deep_work_blocks(&events, 3000, 2).is_empty());
+        assert_eq!(
+            TimeSeriesAnalyzer::deep_work_blocks(&events, 3000, 4).len(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_deep_work_blocks_short_focus() {
+        // 单应用但只有 20 分钟；之后间隔过长，不与下一段合并
+        let events = vec![event("code", 0, 1200, false), event("code", 60, 600, false)];
+        assert!(TimeSeriesAnalyzer::deep_work_blocks(&events, 1500, 1).is_empty());
+    }
+
+    #[test]
+    fn test_total_active_seconds_disjoint() {
+        let events = vec![
+            event("code", 0, 600, false),
+            event("firefox", 20, 300, false),
+            event("code", 10, 600, false),
+        ];
+        // 首尾相接的事件与不相交的事件一样，并集等于累加
+        assert_eq!(TimeSeriesAnalyzer::total_active_seconds(&events), 1500);
+        assert_eq!(TimeSeriesAnalyzer::total_active_seconds(&[]), 0);
+    }
+
+    #[test]
+    fn test_total_active_seconds_fully_overlapping() {
+        let events = vec![
+            event("code", 0, 3600, false),
+            event("firefox", 10, 600, false),
+            event("kitty", 30, 1200, false),
+        ];
+        assert_eq!(TimeSeriesAnalyzer::total_active_seconds(&events), 3600);
+    }
+
+    #[test]
+    fn test_total_active_seconds_partial_overlap() {
+        let events = vec![
+            event("code", 0, 600, false),
+            event("firefox", 5, 600, false),
+            // AFK 事件不计入
+            event("code", 30, 600, true),
+            event("kitty", 40, 120, false),
+        ];
+        assert_eq!(TimeSeriesAnalyzer::total_active_seconds(&events), 900 + 120);
+    }
+
+    #[test]
+    fn test_fragmentation_single_app() {
+        let events: Vec<_> = (0..4).map(|i| event("code", i * 30, 1800, false)).collect();
+        assert_eq!(TimeSeriesAnalyzer::fragmentation(&events), 0.0);
+        assert_eq!(TimeSeriesAnalyzer::fragmentation(&[]), 0.0);
+    }
+
+    #[test]
+    fn test_fragmentation_rapid_alternation() {
+        // 一小时内 code 与 firefox 每分钟交替一次，中间的 AFK 不计入
+        let mut events: Vec<_> = (0..60)
+            .map(|i| {
+                let app = if i % 2 == 0 { "code" } else { "firefox" };
+                event(app, i, 60, false)
+            })
+            .collect();
+        events.push(event("firefox", 30, 600, true));
+
+        assert_eq!(TimeSeriesAnalyzer::fragmentation(&events), 59.0);
+    }
+
+    #[test]
+    fn test_rolling_average_constant() {
+        let series = [1800; 10];
+        assert_eq!(
+            TimeSeriesAnalyzer::rolling_average(&series, 7),
+            vec![1800.0; 10]
+        );
+        assert!(TimeSeriesAnalyzer::rolling_average(&[], 7).is_empty());
+    }
+
+    #[test]
+    fn test_rolling_average_ramp() {
+        let series = [0, 10, 20, 30, 40, 50];
+        assert_eq!(
+            TimeSeriesAnalyzer::rolling_average(&series, 3),
+            vec![0.0, 5.0, 10.0, 20.0, 30.0, 40.0]
+        );
+        assert_eq!(
+            TimeSeriesAnalyzer::rolling_average(&series, 1),
+            vec![0.0, 10.0, 20.0, 30.0, 40.0, 50.0]
+        );
+    }
+
+    #[test]
+    fn test_rolling_average_window_exceeds_length() {
+        let series = [100, 200, 600];
+        assert_eq!(
+            TimeSeriesAnalyzer::rolling_average(&series, 7),
+            vec![100.0, 150.0, 300.0]
+        );
+    }
+
+    #[test]
+    fn test_detect_spikes_finds_outlier() {
+        let series = [3600, 3500, 3700, 3600, 14400, 3550, 3650];
+        assert_eq!(TimeSeriesAnalyzer::detect_spikes(&series, 2.0), vec![4]);
+    }
+
+    #[test]
+    fn test_detect_spikes_flat_series() {
+        let series = [1800; 7];
+        assert!(TimeSeriesAnalyzer::detect_spikes(&series, 1.0).is_empty());
+        assert!(TimeSeriesAnalyzer::detect_spikes(&[], 1.0).is_empty());
+    }
+
+    #[test]
+    fn test_aggregate_fixed_bins_quarter_hours() {
+        let start = Utc.with_ymd_and_hms(2024, 3, 1, 9, 0, 0).unwrap();
+        let range = TimeRange::new(start, start + chrono::Duration::hours(1));
+        let mut edge = event("kitty", 15, 60, false);
+        edge.timestamp -= chrono::Duration::seconds(1);
+        let events = vec![
+            event("code", 0, 300, false),
+            // 09:14:59 开始的 kitty 仍属于第一个时间箱
+            edge,
+            event("firefox", 15, 120, false),
+            event("code", 44, 60, false),
+            event("code", 45, 600, false),
+            event("steam", 30, 900, true),
+            event("code", 60, 30, false),
+            event("code", 61, 30, false),
+        ];
+
+        let slots = TimeSeriesAnalyzer::aggregate_fixed_bins(&events, 15 * 60, &range).unwrap();
+        assert_eq!(slots.len(), 4);
+        assert_eq!(slots[0].get_group_duration("code"), 300);
+        assert_eq!(slots[0].get_group_duration("kitty"), 60);
+        assert_eq!(slots[1].get_group_duration("firefox"), 120);
+        assert_eq!(slots[1].duration().as_seconds(), 120);
+        // AFK 事件不计入，09:44 开始的 code 仍属于第三个时间箱
+        assert_eq!(slots[2].duration().as_seconds(), 60);
+        assert_eq!(slots[2].get_group_duration("steam"), 0);
+        // 恰好位于范围结束的事件和范围外的事件都被忽略
+        assert_eq!(slots[3].get_group_duration("code"), 600);
+    }
+
+    #[test]
+    fn test_aggregate_fixed_bins_rejects_non_positive_bin() {
+        let start = Utc.with_ymd_and_hms(2024, 3, 1, 9, 0, 0).unwrap();
+        let range = TimeRange::new(start, start + chrono::Duration::hours(1));
+        assert!(TimeSeriesAnalyzer::aggregate_fixed_bins(&[], 0, &range).is_none());
+        assert!(TimeSeriesAnalyzer::aggregate_fixed_bins(&[], -60, &range).is_none());
+    }
+
+    #[test]
+    fn test_active_window_within_day() {
+        let events = vec![
+            event("firefox", 30, 600, false),
+            event("code", 0, 1200, false),
+            // 开始较早但 AFK 的事件被忽略
+            event("code", -60, 3600, true),
+            // 较早开始但持续更久的事件决定结束时间
+            event("kitty", 40, 7200, false),
+            event("mpv", 100, 60, false),
+        ];
+
+        let (start, end) = TimeSeriesAnalyzer::active_window(&events).unwrap();
+        let base = Utc.with_ymd_and_hms(2024, 3, 1, 9, 0, 0).unwrap();
+        assert_eq!(start, base.with_timezone(&Local));
+        assert_eq!(
+            end,
+            (base + chrono::Duration::minutes(160)).with_timezone(&Local)
+        );
+    }
+
+    #[test]
+    fn test_active_window_empty() {
+        assert!(TimeSeriesAnalyzer::active_window(&[]).is_none());
+        assert!(TimeSeriesAnalyzer::active_window(&[event("code", 0, 60, true)]).is_none());
+    }
+
+    #[test]
+    fn test_usage_concentration() {
+        assert_eq!(TimeSeriesAnalyzer::usage_concentration(&[]), 0.0);
+        assert_eq!(TimeSeriesAnalyzer::usage_concentration(&[0, -5]), 0.0);
+        assert_eq!(TimeSeriesAnalyzer::usage_concentration(&[3600]), 1.0);
+        assert_eq!(TimeSeriesAnalyzer::usage_concentration(&[3600, 0]), 1.0);
+        assert!((TimeSeriesAnalyzer::usage_concentration(&[1800, 1800]) - 0.5).abs() < 1e-9);
+
+        let scattered = vec![60; 200];
+        assert!(TimeSeriesAnalyzer::usage_concentration(&scattered) < 0.01);
+    }
+}
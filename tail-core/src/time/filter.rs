@@ -0,0 +1,80 @@
+//! 事件过滤
+//!
+//! 聚合、图表和导出都需要"跳过 AFK、限定时间范围"的事件遍历，统一在这里实现
+
+use crate::models::WindowEvent;
+use crate::time::range::TimeRange;
+
+/// 只保留非 AFK 且在时间范围内的事件的迭代器
+pub struct ActiveInRange<'a, I> {
+    inner: I,
+    range: Option<&'a TimeRange>,
+}
+
+impl<'a, I> Iterator for ActiveInRange<'a, I>
+where
+    I: Iterator<Item = &'a WindowEvent>,
+{
+    type Item = &'a WindowEvent;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let range = self.range;
+        self.inner.find(|event| {
+            !event.is_afk && range.is_none_or(|range| range.contains(event.timestamp))
+        })
+    }
+}
+
+/// 窗口事件迭代器的过滤扩展
+pub trait WindowEventFilterExt<'a>: Iterator<Item = &'a WindowEvent> + Sized {
+    /// 跳过 AFK 事件，并只保留开始时间在 `range` 内的事件（`None` 表示不限范围）
+    fn active_in_range(self, range: Option<&'a TimeRange>) -> ActiveInRange<'a, Self> {
+        ActiveInRange { inner: self, range }
+    }
+}
+
+impl<'a, I> WindowEventFilterExt<'a> for I where I: Iterator<Item = &'a WindowEvent> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{DateTime, Duration, Utc};
+
+    fn event(app_name: &str, timestamp: DateTime<Utc>, is_afk: bool) -> WindowEvent {
+        WindowEvent {
+            id: None,
+            timestamp,
+            app_name: app_name.to_string(),
+            window_title: String::new(),
+            workspace: String::new(),
+            duration_secs: 60,
+            is_afk,
+        }
+    }
+
+    #[test]
+    fn test_active_in_range() {
+        let start = DateTime::parse_from_rfc3339("2024-03-01T09:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        let range = TimeRange::new(start, start + Duration::hours(1));
+        let events = [
+            event("before", start - Duration::minutes(1), false),
+            event("first", start, false),
+            event("afk", start + Duration::minutes(10), true),
+            event("last", range.end - Duration::seconds(1), false),
+            event("end", range.end, false),
+            event("after", range.end + Duration::seconds(1), false),
+        ];
+
+        let kept: Vec<_> = events
+            .iter()
+            .active_in_range(Some(&range))
+            .map(|e| e.app_name.as_str())
+            .collect();
+        assert_eq!(kept, vec!["first", "last"]);
+
+        // 不限范围时只跳过 AFK
+        assert_eq!(events.iter().active_in_range(None).count(), 5);
+    }
+}
@@ -4,20 +4,24 @@
 //!
 //! # 设计原则
 //!
-//! 1. **明确的边界**: 所有时间范围都是闭区间 [start, end]
+//! 1. **明确的边界**: 所有时间范围都是半开区间 [start, end)，结束时间为下一段的开始
 //! 2. **本地时间优先**: 所有计算基于本地时间，存储时再转为UTC
 //! 3. **周一起始**: 一周从周一开始，到周日结束
 
-use chrono::{DateTime, Datelike, Local, NaiveDate, NaiveTime, Utc};
+use chrono::{
+    DateTime, Datelike, Local, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Timelike, Utc,
+};
+
+use crate::time::types::TimeGranularity;
 
 /// 时间范围
 ///
-/// 表示一个闭区间的时间范围 [start, end]
+/// 表示一个半开区间的时间范围 [start, end)
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct TimeRange {
-    /// 开始时间（UTC）
+    /// 开始时间（UTC，包含）
     pub start: DateTime<Utc>,
-    /// 结束时间（UTC）
+    /// 结束时间（UTC，不包含）
     pub end: DateTime<Utc>,
 }
 
@@ -32,8 +36,17 @@ impl TimeRange {
     }
 
     /// 检查是否包含某个时间点
+    ///
+    /// 按半开区间 `[start, end)` 判断：包含 `start`，不包含 `end`，
+    /// 与数据库查询的 `timestamp >= start AND timestamp < end` 一致。
+    /// 相邻时间段首尾相接（如当天的结束即次日 00:00:00），因此一个时间点只会落在其中一段。
     pub fn contains(&self, timestamp: DateTime<Utc>) -> bool {
-        timestamp >= self.start && timestamp <= self.end
+        timestamp >= self.start && timestamp < self.end
+    }
+
+    /// 检查另一个时间范围是否完全落在本范围内（`other` 的结束可以等于本范围的结束）
+    pub fn contains_range(&self, other: &TimeRange) -> bool {
+        other.start >= self.start && other.end <= self.end
     }
 
     /// 计算时间跨度（秒）
@@ -41,6 +54,79 @@ impl TimeRange {
         (self.end - self.start).num_seconds().max(0)
     }
 
+    /// 紧邻在前、长度相同的时间段
+    ///
+    /// 恰好是若干个完整的本地自然月（如本月）时，结果为之前相同数量的自然月；
+    /// 否则按长度 `end - start` 向前平移，结果的结束时间即本范围的开始。
+    pub fn previous_period(&self) -> TimeRange {
+        self.previous_period_in(&Local)
+    }
+
+    /// 在给定时区下计算 [`Self::previous_period`]
+    pub fn previous_period_in<Tz: TimeZone>(&self, tz: &Tz) -> TimeRange {
+        if let Some((first, months)) = self.whole_months_in(tz) {
+            return Self::months_from(first - chrono::Months::new(months), months, tz);
+        }
+        let length = self.end - self.start;
+        TimeRange::new(self.start - length, self.start)
+    }
+
+    /// 紧邻在后、长度相同的时间段，规则同 [`Self::previous_period`]
+    pub fn next_period(&self) -> TimeRange {
+        self.next_period_in(&Local)
+    }
+
+    /// 在给定时区下计算 [`Self::next_period`]
+    pub fn next_period_in<Tz: TimeZone>(&self, tz: &Tz) -> TimeRange {
+        if let Some((first, months)) = self.whole_months_in(tz) {
+            return Self::months_from(first + chrono::Months::new(months), months, tz);
+        }
+        let length = self.end - self.start;
+        TimeRange::new(self.end, self.end + length)
+    }
+
+    /// 判断是否恰好是若干个完整的本地自然月，返回 `(首月第一天, 月数)`
+    fn whole_months_in<Tz: TimeZone>(&self, tz: &Tz) -> Option<(NaiveDate, u32)> {
+        let first = self.start.with_timezone(tz).date_naive();
+        if first.day() != 1
+            || TimeRangeCalculator::local_to_utc_in(first.and_time(NaiveTime::MIN), tz)
+                != self.start
+        {
+            return None;
+        }
+
+        let boundary_date = self.end.with_timezone(tz).date_naive();
+        if boundary_date.day() != 1
+            || TimeRangeCalculator::local_to_utc_in(boundary_date.and_time(NaiveTime::MIN), tz)
+                != self.end
+        {
+            return None;
+        }
+
+        let months = (boundary_date.year() - first.year()) * 12 + boundary_date.month() as i32
+            - first.month() as i32;
+        (months > 0).then_some((first, months as u32))
+    }
+
+    /// 从 `first` 开始的 `months` 个本地自然月
+    fn months_from<Tz: TimeZone>(first: NaiveDate, months: u32, tz: &Tz) -> TimeRange {
+        let start = TimeRangeCalculator::local_to_utc_in(first.and_time(NaiveTime::MIN), tz);
+        let next = TimeRangeCalculator::local_to_utc_in(
+            (first + chrono::Months::new(months)).and_time(NaiveTime::MIN),
+            tz,
+        );
+        TimeRange::new(start, next)
+    }
+
+    /// 时间范围包含的实际小时数（四舍五入）
+    ///
+    /// 对 `TimeRangeCalculator::day` 返回的范围即为该本地日期的小时数：通常为 24，
+    /// 夏令时开始的那天为 23，结束的那天为 25。
+    pub fn local_hours(&self) -> usize {
+        let millis = (self.end - self.start).num_milliseconds();
+        ((millis + 1_800_000) / 3_600_000).max(0) as usize
+    }
+
     /// 转换为本地时间的字符串表示
     pub fn to_local_string(&self) -> String {
         let start_local = self.start.with_timezone(&Local);
@@ -51,6 +137,38 @@ impl TimeRange {
             end_local.format("%Y-%m-%d %H:%M")
         )
     }
+
+    /// 范围内最后一个时刻（结束前 1ms）所在的本地日期，空范围为开始所在的日期
+    fn last_local_date(&self) -> NaiveDate {
+        let last = (self.end - chrono::Duration::milliseconds(1)).max(self.start);
+        last.with_timezone(&Local).date_naive()
+    }
+
+    /// 开始和结束是否在同一个本地日期内
+    pub fn is_single_day(&self) -> bool {
+        self.start.with_timezone(&Local).date_naive() == self.last_local_date()
+    }
+
+    /// 生成用于界面显示的简洁标签
+    ///
+    /// - 整年: `2024年`
+    /// - 整月: `2024年1月`
+    /// - 单日: `2024-01-15`
+    /// - 其他: `2024-01-15 ~ 2024-01-21`
+    pub fn human_label(&self) -> String {
+        let start = self.start.with_timezone(&Local).date_naive();
+        let end = self.last_local_date();
+
+        if *self == TimeRangeCalculator::year_range(start.year()) {
+            format!("{}年", start.year())
+        } else if *self == TimeRangeCalculator::month_range(start.year(), start.month()) {
+            format!("{}年{}月", start.year(), start.month())
+        } else if start == end {
+            start.format("%Y-%m-%d").to_string()
+        } else {
+            format!("{} ~ {}", start.format("%Y-%m-%d"), end.format("%Y-%m-%d"))
+        }
+    }
 }
 
 /// 时间范围计算器
@@ -61,66 +179,29 @@ pub struct TimeRangeCalculator;
 impl TimeRangeCalculator {
     /// 获取今天的范围（本地时间）
     ///
-    /// 返回 [今天 00:00:00, 今天 23:59:59.999]
+    /// 返回 [今天 00:00:00, 明天 00:00:00)
     pub fn today() -> TimeRange {
-        let now = Local::now();
-        let start = now
-            .date_naive()
-            .and_time(NaiveTime::from_hms_milli_opt(0, 0, 0, 0).unwrap())
-            .and_local_timezone(Local)
-            .unwrap()
-            .with_timezone(&Utc);
-        let end = now
-            .date_naive()
-            .and_time(NaiveTime::from_hms_milli_opt(23, 59, 59, 999).unwrap())
-            .and_local_timezone(Local)
-            .unwrap()
-            .with_timezone(&Utc);
-        TimeRange::new(start, end)
+        Self::day(Local::now().date_naive())
     }
 
     /// 获取昨天的范围（本地时间）
     pub fn yesterday() -> TimeRange {
-        let now = Local::now();
-        let yesterday = now.date_naive() - chrono::Duration::days(1);
-        let start = yesterday
-            .and_time(NaiveTime::from_hms_milli_opt(0, 0, 0, 0).unwrap())
-            .and_local_timezone(Local)
-            .unwrap()
-            .with_timezone(&Utc);
-        let end = yesterday
-            .and_time(NaiveTime::from_hms_milli_opt(23, 59, 59, 999).unwrap())
-            .and_local_timezone(Local)
-            .unwrap()
-            .with_timezone(&Utc);
-        TimeRange::new(start, end)
+        Self::day(Local::now().date_naive() - chrono::Duration::days(1))
     }
 
     /// 获取本周的范围（本地时间，周一到周日）
     ///
-    /// 返回 [本周一 00:00:00, 本周日 23:59:59.999]
+    /// 返回 [本周一 00:00:00, 下周一 00:00:00)
     pub fn this_week() -> TimeRange {
-        let now = Local::now();
-        let weekday_offset = now.date_naive().weekday().num_days_from_monday() as i64;
-        let monday = now.date_naive() - chrono::Duration::days(weekday_offset);
-        let sunday = monday + chrono::Duration::days(6);
-
-        let start = monday
-            .and_time(NaiveTime::from_hms_milli_opt(0, 0, 0, 0).unwrap())
-            .and_local_timezone(Local)
-            .unwrap()
-            .with_timezone(&Utc);
-        let end = sunday
-            .and_time(NaiveTime::from_hms_milli_opt(23, 59, 59, 999).unwrap())
-            .and_local_timezone(Local)
-            .unwrap()
-            .with_timezone(&Utc);
-        TimeRange::new(start, end)
+        let today = Local::now().date_naive();
+        let weekday_offset = today.weekday().num_days_from_monday() as i64;
+        let monday = today - chrono::Duration::days(weekday_offset);
+        Self::dates(monday, monday + chrono::Duration::days(7))
     }
 
     /// 获取本月的范围（本地时间）
     ///
-    /// 返回 [本月1日 00:00:00, 本月最后一天 23:59:59.999]
+    /// 返回 [本月1日 00:00:00, 下月1日 00:00:00)
     pub fn this_month() -> TimeRange {
         let now = Local::now();
         Self::month_range(now.year(), now.month())
@@ -128,7 +209,7 @@ impl TimeRangeCalculator {
 
     /// 获取本年的范围（本地时间）
     ///
-    /// 返回 [本年1月1日 00:00:00, 本年12月31日 23:59:59.999]
+    /// 返回 [本年1月1日 00:00:00, 次年1月1日 00:00:00)
     pub fn this_year() -> TimeRange {
         let now = Local::now();
         Self::year_range(now.year())
@@ -136,70 +217,79 @@ impl TimeRangeCalculator {
 
     /// 获取指定日期的范围
     ///
-    /// 返回 [该日 00:00:00, 该日 23:59:59.999]
+    /// 返回 [该日 00:00:00, 次日 00:00:00)，夏令时切换日的跨度为 23 或 25 小时
     pub fn day(date: NaiveDate) -> TimeRange {
-        let start = date
-            .and_time(NaiveTime::from_hms_milli_opt(0, 0, 0, 0).unwrap())
-            .and_local_timezone(Local)
-            .unwrap()
-            .with_timezone(&Utc);
-        let end = date
-            .and_time(NaiveTime::from_hms_milli_opt(23, 59, 59, 999).unwrap())
-            .and_local_timezone(Local)
-            .unwrap()
-            .with_timezone(&Utc);
-        TimeRange::new(start, end)
+        Self::day_in(date, &Local)
+    }
+
+    /// 获取指定日期在给定时区下的范围，规则同 [`Self::day`]
+    pub fn day_in<Tz: TimeZone>(date: NaiveDate, tz: &Tz) -> TimeRange {
+        TimeRange::new(
+            Self::local_to_utc_in(date.and_time(NaiveTime::MIN), tz),
+            Self::local_to_utc_in(
+                (date + chrono::Duration::days(1)).and_time(NaiveTime::MIN),
+                tz,
+            ),
+        )
+    }
+
+    /// 本地日期 `first` 零点到 `next` 零点的范围 [first 00:00:00, next 00:00:00)
+    fn dates(first: NaiveDate, next: NaiveDate) -> TimeRange {
+        TimeRange::new(
+            Self::local_to_utc(first.and_time(NaiveTime::MIN)),
+            Self::local_to_utc(next.and_time(NaiveTime::MIN)),
+        )
+    }
+
+    /// 把本地时间转换为 UTC
+    pub fn local_to_utc(naive: NaiveDateTime) -> DateTime<Utc> {
+        Self::local_to_utc_in(naive, &Local)
+    }
+
+    /// 把给定时区的本地时间转换为 UTC
+    ///
+    /// 夏令时回拨造成的重复时刻取较早的一个；夏令时跳过的时刻取跳变后的第一个有效时刻。
+    /// 因此按整点切分时，被跳过的小时长度为 0，重复的小时长度为 2 小时。
+    pub fn local_to_utc_in<Tz: TimeZone>(naive: NaiveDateTime, tz: &Tz) -> DateTime<Utc> {
+        if let Some(dt) = naive.and_local_timezone(tz.clone()).earliest() {
+            return dt.with_timezone(&Utc);
+        }
+
+        // 落在跳变空档中：按 15 分钟对齐后向后查找（实际的时区跳变都是 15 分钟的整数倍）
+        let mut probe = naive
+            - chrono::Duration::minutes((naive.minute() % 15) as i64)
+            - chrono::Duration::seconds(naive.second() as i64)
+            - chrono::Duration::nanoseconds(naive.nanosecond() as i64);
+        for _ in 0..(4 * 24) {
+            probe += chrono::Duration::minutes(15);
+            if let Some(dt) = probe.and_local_timezone(tz.clone()).earliest() {
+                return dt.with_timezone(&Utc);
+            }
+        }
+        naive.and_utc()
     }
 
     /// 获取指定月份的范围
     ///
-    /// 返回 [该月1日 00:00:00, 该月最后一天 23:59:59.999]
+    /// 返回 [该月1日 00:00:00, 下月1日 00:00:00)
     pub fn month_range(year: i32, month: u32) -> TimeRange {
         let first_day = NaiveDate::from_ymd_opt(year, month, 1).unwrap();
-
-        // 计算月末日期
-        let last_day = if month == 12 {
-            NaiveDate::from_ymd_opt(year + 1, 1, 1).unwrap() - chrono::Duration::days(1)
-        } else {
-            NaiveDate::from_ymd_opt(year, month + 1, 1).unwrap() - chrono::Duration::days(1)
-        };
-
-        let start = first_day
-            .and_time(NaiveTime::from_hms_milli_opt(0, 0, 0, 0).unwrap())
-            .and_local_timezone(Local)
-            .unwrap()
-            .with_timezone(&Utc);
-        let end = last_day
-            .and_time(NaiveTime::from_hms_milli_opt(23, 59, 59, 999).unwrap())
-            .and_local_timezone(Local)
-            .unwrap()
-            .with_timezone(&Utc);
-        TimeRange::new(start, end)
+        Self::dates(first_day, first_day + chrono::Months::new(1))
     }
 
     /// 获取指定年份的范围
     ///
-    /// 返回 [该年1月1日 00:00:00, 该年12月31日 23:59:59.999]
+    /// 返回 [该年1月1日 00:00:00, 次年1月1日 00:00:00)
     pub fn year_range(year: i32) -> TimeRange {
-        let first_day = NaiveDate::from_ymd_opt(year, 1, 1).unwrap();
-        let last_day = NaiveDate::from_ymd_opt(year, 12, 31).unwrap();
-
-        let start = first_day
-            .and_time(NaiveTime::from_hms_milli_opt(0, 0, 0, 0).unwrap())
-            .and_local_timezone(Local)
-            .unwrap()
-            .with_timezone(&Utc);
-        let end = last_day
-            .and_time(NaiveTime::from_hms_milli_opt(23, 59, 59, 999).unwrap())
-            .and_local_timezone(Local)
-            .unwrap()
-            .with_timezone(&Utc);
-        TimeRange::new(start, end)
+        Self::dates(
+            NaiveDate::from_ymd_opt(year, 1, 1).unwrap(),
+            NaiveDate::from_ymd_opt(year + 1, 1, 1).unwrap(),
+        )
     }
 
     /// 获取指定月份中第几周的范围
     ///
-    /// 返回 [该周周一 00:00:00, 该周周日 23:59:59.999]
+    /// 返回 [该周周一 00:00:00, 该周周日次日 00:00:00)，不超出该月
     ///
     /// # 参数
     /// - `year`: 年份
@@ -220,62 +310,101 @@ impl TimeRangeCalculator {
         let week_start = if week == 1 {
             first_day
         } else {
-            // 计算第2周及之后的周一开始
-            let days_to_first_monday = if first_weekday == 0 {
-                0 // 1日就是周一
-            } else {
-                7 - first_weekday as i64 // 下一个周一
-            };
+            // 第2周从第1周之后的第一个周一开始（1日是周一时为8日）
+            let days_to_first_monday = 7 - first_weekday as i64;
             let first_monday = first_day + chrono::Duration::days(days_to_first_monday);
             let target_week_offset = (week as i64 - 2) * 7;
             first_monday + chrono::Duration::days(target_week_offset)
         };
 
-        // 周日 = 周一 + 6天
-        let week_end = week_start + chrono::Duration::days(6);
+        // 周日 = 周一 + 6天，结束于其次日零点
+        let week_end = week_start + chrono::Duration::days(7);
 
         // 确保不超出该月范围
-        let month_end = if month == 12 {
-            NaiveDate::from_ymd_opt(year + 1, 1, 1).unwrap() - chrono::Duration::days(1)
-        } else {
-            NaiveDate::from_ymd_opt(year, month + 1, 1).unwrap() - chrono::Duration::days(1)
-        };
-
-        let actual_end = week_end.min(month_end);
-
-        let start = week_start
-            .and_time(NaiveTime::from_hms_milli_opt(0, 0, 0, 0).unwrap())
-            .and_local_timezone(Local)
-            .unwrap()
-            .with_timezone(&Utc);
-        let end = actual_end
-            .and_time(NaiveTime::from_hms_milli_opt(23, 59, 59, 999).unwrap())
-            .and_local_timezone(Local)
-            .unwrap()
-            .with_timezone(&Utc);
-        TimeRange::new(start, end)
+        let next_month = first_day + chrono::Months::new(1);
+        Self::dates(week_start, week_end.min(next_month))
     }
 
     /// 计算某日期是该月的第几周
     ///
+    /// 这是全项目唯一的"月内第几周"规则，图表分桶、导航和周范围都以它为准：
+    /// - 周一为一周的第一天
+    /// - 第1周从1日到第一个周日（1日不是周一时不足7天）
+    /// - 之后每周从周一开始，因此一个月最多有6周
+    ///
     /// # 返回
     /// 周数（从1开始）
     pub fn week_of_month(year: i32, month: u32, day: u32) -> u32 {
-        let _date = NaiveDate::from_ymd_opt(year, month, day).unwrap();
         let first_day = NaiveDate::from_ymd_opt(year, month, 1).unwrap();
         let first_weekday = first_day.weekday().num_days_from_monday();
-        let day_offset = day as i64 - 1;
+        (day - 1 + first_weekday) / 7 + 1
+    }
+
+    /// 按时间单位将时间范围切分为连续的时间段
+    ///
+    /// `unit` 表示每段的长度：`Hour` 为一小时、`Day` 为一天、`Week` 为周一开始的一周、
+    /// `Month` 为自然月、`Year` 为自然年，边界按本地时间对齐。
+    /// 首尾两段会被裁剪到 `range` 内，每段为 [段开始, 下一段开始)。
+    pub fn split(range: &TimeRange, unit: TimeGranularity) -> Vec<TimeRange> {
+        let local_start = range.start.with_timezone(&Local).naive_local();
+        let date = local_start.date();
+        let midnight = NaiveTime::from_hms_opt(0, 0, 0).unwrap();
+        let mut cursor = match unit {
+            TimeGranularity::Hour => date.and_hms_opt(local_start.hour(), 0, 0).unwrap(),
+            TimeGranularity::Day => date.and_time(midnight),
+            TimeGranularity::Week => {
+                let monday =
+                    date - chrono::Duration::days(date.weekday().num_days_from_monday() as i64);
+                monday.and_time(midnight)
+            }
+            TimeGranularity::Month => NaiveDate::from_ymd_opt(date.year(), date.month(), 1)
+                .unwrap()
+                .and_time(midnight),
+            TimeGranularity::Year => NaiveDate::from_ymd_opt(date.year(), 1, 1)
+                .unwrap()
+                .and_time(midnight),
+        };
 
-        // 第1周：从1日到第一个周日
-        // 第2周及之后：从周一开始
-        let days_in_first_week = 7 - first_weekday as i64;
+        let mut ranges = Vec::new();
+        loop {
+            let next = match unit {
+                TimeGranularity::Hour => cursor + chrono::Duration::hours(1),
+                TimeGranularity::Day => cursor + chrono::Duration::days(1),
+                TimeGranularity::Week => cursor + chrono::Duration::days(7),
+                TimeGranularity::Month => {
+                    let (year, month) = if cursor.month() == 12 {
+                        (cursor.year() + 1, 1)
+                    } else {
+                        (cursor.year(), cursor.month() + 1)
+                    };
+                    NaiveDate::from_ymd_opt(year, month, 1)
+                        .unwrap()
+                        .and_time(midnight)
+                }
+                TimeGranularity::Year => NaiveDate::from_ymd_opt(cursor.year() + 1, 1, 1)
+                    .unwrap()
+                    .and_time(midnight),
+            };
 
-        if day_offset < days_in_first_week {
-            1
-        } else {
-            let remaining_days = day_offset - days_in_first_week;
-            2 + (remaining_days / 7) as u32
+            let next_start = Self::local_to_utc(next);
+            let start = Self::local_to_utc(cursor).max(range.start);
+            let end = next_start.min(range.end);
+            if start < end {
+                ranges.push(TimeRange::new(start, end));
+            }
+
+            if next_start >= range.end {
+                break;
+            }
+            cursor = next;
         }
+
+        ranges
+    }
+
+    /// 获取指定月份的周数（按 `week_of_month` 的规则，最多6周）
+    pub fn weeks_in_month(year: i32, month: u32) -> u32 {
+        Self::week_of_month(year, month, Self::days_in_month(year, month))
     }
 
     /// 获取指定月份的天数
@@ -290,27 +419,189 @@ impl TimeRangeCalculator {
     }
 
     /// 获取最近N天的范围（包括今天）
+    ///
+    /// 返回 [N-1 天前 00:00:00, 明天 00:00:00)
     pub fn last_n_days(n: u32) -> TimeRange {
-        let now = Local::now();
-        let end = now
-            .date_naive()
-            .and_time(NaiveTime::from_hms_milli_opt(23, 59, 59, 999).unwrap())
-            .and_local_timezone(Local)
-            .unwrap()
-            .with_timezone(&Utc);
-        let start_date = now.date_naive() - chrono::Duration::days(n as i64 - 1);
-        let start = start_date
-            .and_time(NaiveTime::from_hms_milli_opt(0, 0, 0, 0).unwrap())
-            .and_local_timezone(Local)
-            .unwrap()
-            .with_timezone(&Utc);
-        TimeRange::new(start, end)
+        let today = Local::now().date_naive();
+        Self::dates(
+            today - chrono::Duration::days(n as i64 - 1),
+            today + chrono::Duration::days(1),
+        )
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use chrono::{FixedOffset, LocalResult, Weekday};
+
+    /// 2024 年中欧时间：3 月 31 日 02:00 跳到 03:00，10 月 27 日 03:00 回拨到 02:00
+    #[derive(Debug, Clone, Copy)]
+    struct CentralEurope2024;
+
+    impl CentralEurope2024 {
+        fn summer_range() -> (NaiveDateTime, NaiveDateTime) {
+            let utc = |month, day| {
+                NaiveDate::from_ymd_opt(2024, month, day)
+                    .unwrap()
+                    .and_hms_opt(1, 0, 0)
+                    .unwrap()
+            };
+            (utc(3, 31), utc(10, 27))
+        }
+    }
+
+    impl TimeZone for CentralEurope2024 {
+        type Offset = FixedOffset;
+
+        fn from_offset(_offset: &FixedOffset) -> Self {
+            CentralEurope2024
+        }
+
+        fn offset_from_local_date(&self, local: &NaiveDate) -> LocalResult<FixedOffset> {
+            self.offset_from_local_datetime(&local.and_time(NaiveTime::MIN))
+        }
+
+        fn offset_from_local_datetime(&self, local: &NaiveDateTime) -> LocalResult<FixedOffset> {
+            let candidates: Vec<FixedOffset> = [2, 1]
+                .into_iter()
+                .map(|hours| FixedOffset::east_opt(hours * 3600).unwrap())
+                .filter(|offset| {
+                    let utc = *local - chrono::Duration::seconds(offset.local_minus_utc() as i64);
+                    self.offset_from_utc_datetime(&utc) == *offset
+                })
+                .collect();
+            match candidates[..] {
+                [] => LocalResult::None,
+                [offset] => LocalResult::Single(offset),
+                [earliest, latest] => LocalResult::Ambiguous(earliest, latest),
+                _ => unreachable!(),
+            }
+        }
+
+        fn offset_from_utc_date(&self, utc: &NaiveDate) -> FixedOffset {
+            self.offset_from_utc_datetime(&utc.and_time(NaiveTime::MIN))
+        }
+
+        fn offset_from_utc_datetime(&self, utc: &NaiveDateTime) -> FixedOffset {
+            let (summer_start, summer_end) = Self::summer_range();
+            let hours = if (summer_start..summer_end).contains(utc) {
+                2
+            } else {
+                1
+            };
+            FixedOffset::east_opt(hours * 3600).unwrap()
+        }
+    }
+
+    #[test]
+    fn test_local_hours_across_dst() {
+        let date = |month, day| NaiveDate::from_ymd_opt(2024, month, day).unwrap();
+        let hours = |month, day| {
+            TimeRangeCalculator::day_in(date(month, day), &CentralEurope2024).local_hours()
+        };
+
+        assert_eq!(hours(3, 30), 24);
+        assert_eq!(hours(3, 31), 23);
+        assert_eq!(hours(10, 27), 25);
+        assert_eq!(hours(10, 28), 24);
+    }
+
+    #[test]
+    fn test_hour_buckets_across_dst() {
+        let hour_length = |month, day, hour| {
+            let local = |h: u32| {
+                NaiveDate::from_ymd_opt(2024, month, day)
+                    .unwrap()
+                    .and_hms_opt(0, 0, 0)
+                    .unwrap()
+                    + chrono::Duration::hours(h as i64)
+            };
+            let start = TimeRangeCalculator::local_to_utc_in(local(hour), &CentralEurope2024);
+            let end = TimeRangeCalculator::local_to_utc_in(local(hour + 1), &CentralEurope2024);
+            (end - start).num_minutes()
+        };
+
+        // 被跳过的 2 点长度为 0，前后的小时不受影响
+        assert_eq!(hour_length(3, 31, 1), 60);
+        assert_eq!(hour_length(3, 31, 2), 0);
+        assert_eq!(hour_length(3, 31, 3), 60);
+        // 重复的 2 点长度为 2 小时
+        assert_eq!(hour_length(10, 27, 2), 120);
+        assert_eq!(hour_length(10, 27, 3), 60);
+        // 24 个整点桶的总长度等于当天的实际长度
+        let total: i64 = (0..24).map(|h| hour_length(10, 27, h)).sum();
+        assert_eq!(total, 25 * 60);
+    }
+
+    #[test]
+    fn test_previous_and_next_period_week() {
+        let utc = |day, h, m, s, ms| {
+            Utc.with_ymd_and_hms(2024, 5, day, h, m, s).unwrap()
+                + chrono::Duration::milliseconds(ms)
+        };
+        let week = TimeRange::new(utc(13, 0, 0, 0, 0), utc(20, 0, 0, 0, 0));
+
+        let previous = week.previous_period();
+        assert_eq!(previous.start, utc(6, 0, 0, 0, 0));
+        assert_eq!(previous.end, week.start);
+
+        let next = week.next_period();
+        assert_eq!(next.start, utc(20, 0, 0, 0, 0));
+        assert_eq!(next.previous_period(), week);
+    }
+
+    #[test]
+    fn test_previous_period_whole_months() {
+        // UTC 整月：2024年3月（31天）-> 2024年2月（29天）
+        let march = TimeRange::new(
+            Utc.with_ymd_and_hms(2024, 3, 1, 0, 0, 0).unwrap(),
+            Utc.with_ymd_and_hms(2024, 4, 1, 0, 0, 0).unwrap(),
+        );
+        assert_eq!(
+            march.previous_period_in(&Utc),
+            TimeRange::new(
+                Utc.with_ymd_and_hms(2024, 2, 1, 0, 0, 0).unwrap(),
+                Utc.with_ymd_and_hms(2024, 3, 1, 0, 0, 0).unwrap()
+            )
+        );
+
+        // 东八区的本月（本地零点开始）同样按自然月计算
+        let tz = chrono::FixedOffset::east_opt(8 * 3600).unwrap();
+        let local_month = |year, month| {
+            let first = NaiveDate::from_ymd_opt(year, month, 1).unwrap();
+            TimeRangeCalculator::local_to_utc_in(first.and_time(NaiveTime::MIN), &tz)
+        };
+        let march = TimeRange::new(local_month(2024, 3), local_month(2024, 4));
+        assert_eq!(
+            march.previous_period_in(&tz),
+            TimeRange::new(local_month(2024, 2), local_month(2024, 3))
+        );
+        assert_eq!(
+            march.next_period_in(&tz),
+            TimeRange::new(local_month(2024, 4), local_month(2024, 5))
+        );
+
+        // 同一范围在 UTC 下不是整月，按固定时长平移
+        let previous = march.previous_period_in(&Utc);
+        assert_eq!(previous.end, march.start);
+        assert_eq!(previous.end - previous.start, march.end - march.start);
+    }
+
+    #[test]
+    fn test_previous_and_next_period_day() {
+        let date = NaiveDate::from_ymd_opt(2024, 5, 15).unwrap();
+        let day = TimeRangeCalculator::day(date);
+
+        assert_eq!(
+            day.previous_period(),
+            TimeRangeCalculator::day(date.pred_opt().unwrap())
+        );
+        assert_eq!(
+            day.next_period(),
+            TimeRangeCalculator::day(date.succ_opt().unwrap())
+        );
+    }
 
     #[test]
     fn test_today_range() {
@@ -318,21 +609,21 @@ mod tests {
         assert!(range.start < range.end);
         // 验证范围跨度约为24小时
         let duration = range.duration_seconds();
-        assert!(duration >= 86399 && duration <= 86401); // 考虑闰秒
+        assert!((82800..=90000).contains(&duration)); // 夏令时切换日为 23 或 25 小时
     }
 
     #[test]
     fn test_month_range() {
         let range = TimeRangeCalculator::month_range(2024, 2);
         // 2024年是闰年，2月有29天
-        assert_eq!(range.duration_seconds() / 86400, 28); // 29天 - 1 = 28个完整的24小时
+        assert_eq!(range.duration_seconds() / 86400, 29);
     }
 
     #[test]
     fn test_year_range() {
         let range = TimeRangeCalculator::year_range(2024);
         // 2024年是闰年，366天
-        assert_eq!(range.duration_seconds() / 86400, 365); // 366天 - 1 = 365
+        assert_eq!(range.duration_seconds() / 86400, 366);
     }
 
     #[test]
@@ -351,6 +642,40 @@ mod tests {
         assert_eq!(TimeRangeCalculator::week_of_month(2024, 1, 8), 2); // 下周一
     }
 
+    #[test]
+    fn test_week_of_month_pinned_dates() {
+        // 2024年2月1日是周四：第1周只有1-4日
+        assert_eq!(TimeRangeCalculator::week_of_month(2024, 2, 4), 1);
+        assert_eq!(TimeRangeCalculator::week_of_month(2024, 2, 5), 2);
+        assert_eq!(TimeRangeCalculator::week_of_month(2024, 2, 29), 5);
+        // 2024年9月1日是周日：第1周只有1天，30日落在第6周
+        assert_eq!(TimeRangeCalculator::week_of_month(2024, 9, 1), 1);
+        assert_eq!(TimeRangeCalculator::week_of_month(2024, 9, 2), 2);
+        assert_eq!(TimeRangeCalculator::week_of_month(2024, 9, 30), 6);
+        assert_eq!(TimeRangeCalculator::weeks_in_month(2024, 9), 6);
+    }
+
+    #[test]
+    fn test_week_in_month_matches_week_of_month() {
+        // 每一天都应落在 week_of_month 所给周的范围内（包括1日是周一的月份）
+        for (year, month) in [(2024, 1), (2024, 2), (2024, 4), (2024, 9)] {
+            for day in 1..=TimeRangeCalculator::days_in_month(year, month) {
+                let week = TimeRangeCalculator::week_of_month(year, month, day);
+                let range = TimeRangeCalculator::week_in_month(year, month, week);
+                let date =
+                    TimeRangeCalculator::day(NaiveDate::from_ymd_opt(year, month, day).unwrap());
+                assert!(
+                    range.contains(date.start),
+                    "{}-{}-{} 不在第{}周范围内",
+                    year,
+                    month,
+                    day,
+                    week
+                );
+            }
+        }
+    }
+
     #[test]
     fn test_range_contains() {
         let range = TimeRangeCalculator::day(NaiveDate::from_ymd_opt(2024, 1, 1).unwrap());
@@ -359,11 +684,41 @@ mod tests {
         let mid = start + chrono::Duration::seconds(3600);
 
         assert!(range.contains(start));
-        assert!(range.contains(end));
         assert!(range.contains(mid));
+        // 半开区间：结束时间点不包含在内
+        assert!(!range.contains(end));
+        assert!(range.contains(end - chrono::Duration::milliseconds(1)));
 
         let outside = start - chrono::Duration::seconds(1);
         assert!(!range.contains(outside));
+
+        // 次日零点属于下一天
+        let next_day = TimeRangeCalculator::day(NaiveDate::from_ymd_opt(2024, 1, 2).unwrap());
+        assert!(!range.contains(next_day.start));
+        assert!(next_day.contains(next_day.start));
+
+        // 首尾相接的两个范围，衔接点只属于后一个
+        let first = TimeRange::new(start, mid);
+        let second = TimeRange::new(mid, end);
+        assert!(!first.contains(mid));
+        assert!(second.contains(mid));
+    }
+
+    #[test]
+    fn test_range_contains_range() {
+        let month = TimeRangeCalculator::month_range(2024, 1);
+        let day = TimeRangeCalculator::day(NaiveDate::from_ymd_opt(2024, 1, 15).unwrap());
+        let last_day = TimeRangeCalculator::day(NaiveDate::from_ymd_opt(2024, 1, 31).unwrap());
+        let next_day = TimeRangeCalculator::day(NaiveDate::from_ymd_opt(2024, 2, 1).unwrap());
+
+        assert!(month.contains_range(&day));
+        assert!(month.contains_range(&last_day));
+        assert!(month.contains_range(&month));
+        assert!(!month.contains_range(&next_day));
+        assert!(!day.contains_range(&month));
+
+        let straddling = TimeRange::new(last_day.start, next_day.end);
+        assert!(!month.contains_range(&straddling));
     }
 
     #[test]
@@ -374,7 +729,66 @@ mod tests {
 
         // 验证开始是周一
         assert_eq!(start_local.weekday(), Weekday::Mon);
-        // 验证结束是周日
-        assert_eq!(end_local.weekday(), Weekday::Sun);
+        // 验证结束是下周一零点（不含），即包含整个周日
+        assert_eq!(end_local.weekday(), Weekday::Mon);
+        assert_eq!(
+            end_local.date_naive() - start_local.date_naive(),
+            chrono::Duration::days(7)
+        );
+    }
+
+    #[test]
+    fn test_split_by_day() {
+        let day1 = TimeRangeCalculator::day(NaiveDate::from_ymd_opt(2024, 3, 4).unwrap());
+        let day3 = TimeRangeCalculator::day(NaiveDate::from_ymd_opt(2024, 3, 6).unwrap());
+        let ranges =
+            TimeRangeCalculator::split(&TimeRange::new(day1.start, day3.end), TimeGranularity::Day);
+
+        assert_eq!(ranges.len(), 3);
+        assert_eq!(ranges[0], day1);
+        assert_eq!(ranges[2], day3);
+        // 相邻时间段首尾相接
+        for pair in ranges.windows(2) {
+            assert_eq!(pair[1].start, pair[0].end);
+        }
+    }
+
+    #[test]
+    fn test_split_clips_to_range() {
+        let month = TimeRangeCalculator::month_range(2024, 2);
+        let ranges = TimeRangeCalculator::split(&month, TimeGranularity::Week);
+
+        // 2024年2月1日是周四，共跨 5 个自然周
+        assert_eq!(ranges.len(), 5);
+        assert_eq!(ranges[0].start, month.start);
+        assert_eq!(ranges[4].end, month.end);
+
+        let months = TimeRangeCalculator::split(
+            &TimeRangeCalculator::year_range(2024),
+            TimeGranularity::Month,
+        );
+        assert_eq!(months.len(), 12);
+        assert_eq!(months[1], month);
+    }
+
+    #[test]
+    fn test_human_label() {
+        let month = TimeRangeCalculator::month_range(2024, 1);
+        assert_eq!(month.human_label(), "2024年1月");
+        assert!(!month.is_single_day());
+
+        let day = TimeRangeCalculator::day(NaiveDate::from_ymd_opt(2024, 1, 15).unwrap());
+        assert_eq!(day.human_label(), "2024-01-15");
+        assert!(day.is_single_day());
+
+        let week = TimeRange::new(
+            day.start,
+            TimeRangeCalculator::day(NaiveDate::from_ymd_opt(2024, 1, 21).unwrap()).end,
+        );
+        assert_eq!(week.human_label(), "2024-01-15 ~ 2024-01-21");
+        assert!(!week.is_single_day());
+
+        let year = TimeRangeCalculator::year_range(2024);
+        assert_eq!(year.human_label(), "2024年");
     }
 }
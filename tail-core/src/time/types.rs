@@ -2,8 +2,14 @@
 //!
 //! 提供强类型的时间表示，避免原始值混淆
 
+use chrono::{DateTime, Datelike, Local, NaiveDate, Timelike};
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::fmt;
+use std::str::FromStr;
+
+use crate::models::TimeNavigationLevel;
+use crate::time::range::TimeRangeCalculator;
 
 /// 时间粒度
 ///
@@ -22,7 +28,47 @@ pub enum TimeGranularity {
     Hour,
 }
 
+/// 解析时间粒度或导航层级的字符串键失败
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("无法识别的时间键: {0:?}")]
+pub struct ParseTimeKeyError(pub String);
+
 impl TimeGranularity {
+    /// 所有粒度（从粗到细）
+    pub fn all() -> &'static [TimeGranularity] {
+        &[
+            TimeGranularity::Year,
+            TimeGranularity::Month,
+            TimeGranularity::Week,
+            TimeGranularity::Day,
+            TimeGranularity::Hour,
+        ]
+    }
+
+    /// 稳定的英文键，用于持久化和日志
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TimeGranularity::Year => "year",
+            TimeGranularity::Month => "month",
+            TimeGranularity::Week => "week",
+            TimeGranularity::Day => "day",
+            TimeGranularity::Hour => "hour",
+        }
+    }
+
+    /// 导航层级所显示的时间范围对应的柱形粒度
+    ///
+    /// 层级表示"正在显示什么"：`Hour` 显示一天的 24 小时，`Day` 显示一周的 7 天，
+    /// `Week` 显示一个月的各周，`Month` 和 `Year` 显示一年的 12 个月。
+    pub fn for_range(level: TimeNavigationLevel) -> Self {
+        match level {
+            TimeNavigationLevel::Year | TimeNavigationLevel::Month => Self::Year,
+            TimeNavigationLevel::Week => Self::Month,
+            TimeNavigationLevel::Day => Self::Week,
+            TimeNavigationLevel::Hour => Self::Day,
+        }
+    }
+
     /// 获取该粒度下的槽数量
     pub fn slot_count(&self) -> usize {
         match self {
@@ -34,6 +80,27 @@ impl TimeGranularity {
         }
     }
 
+    /// 计算本地时间在该粒度下所属的时间槽索引
+    ///
+    /// - `Year`: 月份（0-11）
+    /// - `Month`: 该月的第几周（0-5，规则同 `TimeRangeCalculator::week_of_month`）
+    /// - `Week`: 星期几（0 为周一）
+    /// - `Day`: 小时（0-23）
+    /// - `Hour`: 分钟（0-59）
+    pub fn bucket_index(&self, local: DateTime<Local>) -> usize {
+        match self {
+            Self::Year => local.month0() as usize,
+            Self::Month => {
+                TimeRangeCalculator::week_of_month(local.year(), local.month(), local.day())
+                    as usize
+                    - 1
+            }
+            Self::Week => local.weekday().num_days_from_monday() as usize,
+            Self::Day => local.hour() as usize,
+            Self::Hour => local.minute() as usize,
+        }
+    }
+
     /// 获取该粒度的默认标签
     pub fn default_slot_label(&self, index: usize) -> String {
         match self {
@@ -58,6 +125,113 @@ impl TimeGranularity {
     }
 }
 
+impl FromStr for TimeGranularity {
+    type Err = ParseTimeKeyError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        Self::all()
+            .iter()
+            .copied()
+            .find(|g| g.as_str() == value)
+            .ok_or_else(|| ParseTimeKeyError(value.to_string()))
+    }
+}
+
+/// 周编号方式的设置键
+pub const WEEK_NUMBERING_KEY: &str = "week_numbering";
+
+/// 周编号方式
+///
+/// 两种方式都以周一作为一周的开始，区别在于周数从哪里开始计数以及周的边界。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+pub enum WeekNumbering {
+    /// 月内周序：每月 1 日所在的周为第 1 周，周的范围截断在当月之内
+    #[default]
+    MonthRelative,
+    /// ISO 8601 周数：按全年计数，每周都是完整的周一到周日，可能跨月
+    Iso,
+}
+
+impl WeekNumbering {
+    /// 所有编号方式
+    pub fn all() -> &'static [WeekNumbering] {
+        &[WeekNumbering::MonthRelative, WeekNumbering::Iso]
+    }
+
+    /// 持久化到设置表时使用的字符串
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            WeekNumbering::MonthRelative => "month_relative",
+            WeekNumbering::Iso => "iso",
+        }
+    }
+
+    /// 日期所在的周数
+    pub fn week_of(&self, date: NaiveDate) -> u32 {
+        match self {
+            WeekNumbering::MonthRelative => {
+                TimeRangeCalculator::week_of_month(date.year(), date.month(), date.day())
+            }
+            WeekNumbering::Iso => date.iso_week().week(),
+        }
+    }
+
+    /// 周的显示标签（如 "第3周"、"W11"）
+    pub fn week_label(&self, week: u32) -> String {
+        match self {
+            WeekNumbering::MonthRelative => format!("第{}周", week),
+            WeekNumbering::Iso => format!("W{}", week),
+        }
+    }
+
+    /// 指定月份包含的各周 `(周数, 开始日期, 结束日期)`，按时间升序
+    ///
+    /// 年月无效时返回空列表。
+    pub fn weeks_in_month(&self, year: i32, month: u32) -> Vec<(u32, NaiveDate, NaiveDate)> {
+        let Some(first_day) = NaiveDate::from_ymd_opt(year, month, 1) else {
+            return Vec::new();
+        };
+
+        let mut weeks: Vec<(u32, NaiveDate, NaiveDate)> = Vec::new();
+        for date in first_day.iter_days().take_while(|d| d.month() == month) {
+            let week = self.week_of(date);
+            match weeks.last_mut() {
+                Some((current, _, end)) if *current == week => *end = date,
+                _ => weeks.push((week, date, date)),
+            }
+        }
+
+        if *self == WeekNumbering::Iso {
+            // ISO 周不截断在月内，扩展到完整的周一到周日
+            for (_, start, end) in &mut weeks {
+                *start -= chrono::Duration::days(start.weekday().num_days_from_monday() as i64);
+                *end += chrono::Duration::days(6 - end.weekday().num_days_from_monday() as i64);
+            }
+        }
+        weeks
+    }
+
+    /// 指定月份中第 `week` 周的起止日期，该月不包含这一周时返回 `None`
+    pub fn week_dates(&self, year: i32, month: u32, week: u32) -> Option<(NaiveDate, NaiveDate)> {
+        self.weeks_in_month(year, month)
+            .into_iter()
+            .find(|(w, _, _)| *w == week)
+            .map(|(_, start, end)| (start, end))
+    }
+}
+
+impl FromStr for WeekNumbering {
+    type Err = ParseTimeKeyError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        Self::all()
+            .iter()
+            .copied()
+            .find(|n| n.as_str() == value)
+            .ok_or_else(|| ParseTimeKeyError(value.to_string()))
+    }
+}
+
 /// 时长
 ///
 /// 内部存储为秒，提供便捷的转换和格式化方法
@@ -239,14 +413,15 @@ impl TimeSlot {
 
     /// 获取时长最高的N个分组
     ///
-    /// 返回 Vec<(分组名, 秒数)>，按时长降序排列
+    /// 返回 Vec<(分组名, 秒数)>，按时长降序排列，时长相同时按名称升序
     pub fn top_groups(&self, limit: usize) -> Vec<(String, i64)> {
         let mut groups: Vec<_> = self
             .group_durations
             .iter()
             .map(|(k, v)| (k.clone(), *v))
             .collect();
-        groups.sort_by(|a, b| b.1.cmp(&a.1));
+        // 时长相同时按名称排序，避免 HashMap 迭代顺序导致结果不稳定
+        groups.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
         groups.truncate(limit);
         groups
     }
@@ -255,6 +430,13 @@ impl TimeSlot {
     pub fn get_group_duration(&self, group_name: &str) -> i64 {
         self.group_durations.get(group_name).copied().unwrap_or(0)
     }
+
+    /// 将另一个时间槽的各分组时长累加到当前时间槽
+    pub fn merge(&mut self, other: &TimeSlot) {
+        for (group_name, &seconds) in &other.group_durations {
+            self.add_duration(group_name, seconds);
+        }
+    }
 }
 
 /// 时间槽集合
@@ -332,6 +514,62 @@ impl TimeSlots {
             .unwrap_or(Duration::zero())
     }
 
+    /// 将另一个相同粒度的聚合结果累加到当前结果
+    ///
+    /// 按时间槽索引对齐：索引相同的时间槽合并分组时长，当前结果中没有的时间槽会被补入，
+    /// 合并后时间槽按索引排序。粒度不同的结果无法对齐，调试构建下会断言失败，
+    /// 发布构建下忽略这次合并。
+    pub fn merge(&mut self, other: &TimeSlots) {
+        debug_assert_eq!(
+            self.granularity, other.granularity,
+            "只能合并相同粒度的时间槽"
+        );
+        if self.granularity != other.granularity {
+            return;
+        }
+
+        for slot in &other.slots {
+            match self.slots.iter_mut().find(|s| s.index == slot.index) {
+                Some(existing) => existing.merge(slot),
+                None => self.slots.push(slot.clone()),
+            }
+        }
+        self.slots.sort_by_key(|s| s.index);
+    }
+
+    /// 将总占比低于 `threshold_ratio` 的分组合并为一个"其他"分组
+    ///
+    /// 占比按分组在所有时间槽中的总时长计算；被合并的分组在每个时间槽内的时长都计入"其他"，
+    /// 因此各时间槽的总时长保持不变。原有名为"其他"的分组与合并结果累加。
+    pub fn collapse_small_groups(&self, threshold_ratio: f64) -> TimeSlots {
+        let total = self.total_duration().as_seconds();
+        let mut group_totals: HashMap<&str, i64> = HashMap::new();
+        for slot in &self.slots {
+            for (group_name, &seconds) in &slot.group_durations {
+                *group_totals.entry(group_name.as_str()).or_insert(0) += seconds;
+            }
+        }
+        let is_small = |group_name: &str| {
+            total > 0
+                && (group_totals.get(group_name).copied().unwrap_or(0) as f64 / total as f64)
+                    < threshold_ratio
+        };
+
+        let mut collapsed = TimeSlots::new(self.granularity);
+        for slot in &self.slots {
+            let mut new_slot = TimeSlot::new(slot.label.clone(), slot.index);
+            for (group_name, &seconds) in &slot.group_durations {
+                if is_small(group_name) {
+                    new_slot.add_duration(crate::utils::filter::OTHER_APP_NAME, seconds);
+                } else {
+                    new_slot.add_duration(group_name, seconds);
+                }
+            }
+            collapsed.add_slot(new_slot);
+        }
+        collapsed
+    }
+
     /// 获取所有出现过的分组名称
     pub fn all_groups(&self) -> Vec<String> {
         let groups: std::collections::HashSet<_> = self
@@ -365,6 +603,106 @@ where
 #[cfg(test)]
 mod tests {
     use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_week_numbering_mid_month_label() {
+        // 2024-03-13 是周三：三月的第 3 周，ISO 第 11 周
+        let date = NaiveDate::from_ymd_opt(2024, 3, 13).unwrap();
+        let month_relative = WeekNumbering::MonthRelative;
+        let iso = WeekNumbering::Iso;
+
+        assert_eq!(month_relative.week_of(date), 3);
+        assert_eq!(iso.week_of(date), 11);
+        assert_eq!(
+            month_relative.week_label(month_relative.week_of(date)),
+            "第3周"
+        );
+        assert_eq!(iso.week_label(iso.week_of(date)), "W11");
+
+        // 月中的周两种方式边界相同，只是周数不同
+        let monday = NaiveDate::from_ymd_opt(2024, 3, 11).unwrap();
+        let sunday = NaiveDate::from_ymd_opt(2024, 3, 17).unwrap();
+        assert_eq!(
+            month_relative.week_dates(2024, 3, 3),
+            Some((monday, sunday))
+        );
+        assert_eq!(iso.week_dates(2024, 3, 11), Some((monday, sunday)));
+    }
+
+    #[test]
+    fn test_week_numbering_month_edges() {
+        // 2024-03-01 是周五
+        let date = |month, day| NaiveDate::from_ymd_opt(2024, month, day).unwrap();
+
+        let month_relative = WeekNumbering::MonthRelative.weeks_in_month(2024, 3);
+        assert_eq!(month_relative.first(), Some(&(1, date(3, 1), date(3, 3))));
+        assert_eq!(month_relative.last(), Some(&(5, date(3, 25), date(3, 31))));
+
+        let iso = WeekNumbering::Iso.weeks_in_month(2024, 3);
+        assert_eq!(iso.first(), Some(&(9, date(2, 26), date(3, 3))));
+        assert_eq!(iso.last(), Some(&(13, date(3, 25), date(3, 31))));
+        assert_eq!(WeekNumbering::Iso.week_dates(2024, 3, 14), None);
+
+        for numbering in WeekNumbering::all() {
+            assert_eq!(numbering.as_str().parse::<WeekNumbering>(), Ok(*numbering));
+        }
+    }
+
+    #[test]
+    fn test_top_groups_ties_sorted_by_name() {
+        let mut slot = TimeSlot::new("9h".to_string(), 9);
+        for (group, seconds) in [
+            ("zed", 600),
+            ("code", 900),
+            ("alacritty", 600),
+            ("mpv", 600),
+        ] {
+            slot.add_duration(group, seconds);
+        }
+
+        assert_eq!(
+            slot.top_groups(3),
+            vec![
+                ("code".to_string(), 900),
+                ("alacritty".to_string(), 600),
+                ("mpv".to_string(), 600),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_granularity_str_round_trip() {
+        for &granularity in TimeGranularity::all() {
+            assert_eq!(granularity.as_str().parse(), Ok(granularity));
+        }
+        assert_eq!(
+            "fortnight".parse::<TimeGranularity>(),
+            Err(ParseTimeKeyError("fortnight".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_bucket_index() {
+        // 2024-01-17 是周三，位于1月第3周
+        let local = Local.with_ymd_and_hms(2024, 1, 17, 14, 35, 20).unwrap();
+
+        assert_eq!(TimeGranularity::Year.bucket_index(local), 0);
+        assert_eq!(TimeGranularity::Month.bucket_index(local), 2);
+        assert_eq!(TimeGranularity::Week.bucket_index(local), 2);
+        assert_eq!(TimeGranularity::Day.bucket_index(local), 14);
+        assert_eq!(TimeGranularity::Hour.bucket_index(local), 35);
+
+        // 2024-03-01 是周五，属于3月第1周；3月4日周一开始第2周
+        let first = Local.with_ymd_and_hms(2024, 3, 1, 0, 0, 0).unwrap();
+        let monday = Local.with_ymd_and_hms(2024, 3, 4, 23, 59, 59).unwrap();
+        assert_eq!(TimeGranularity::Month.bucket_index(first), 0);
+        assert_eq!(TimeGranularity::Month.bucket_index(monday), 1);
+        assert_eq!(TimeGranularity::Year.bucket_index(first), 2);
+        assert_eq!(TimeGranularity::Week.bucket_index(first), 4);
+        assert_eq!(TimeGranularity::Day.bucket_index(monday), 23);
+        assert_eq!(TimeGranularity::Hour.bucket_index(monday), 59);
+    }
 
     #[test]
     fn test_duration_arithmetic() {
@@ -400,6 +738,78 @@ mod tests {
         assert_eq!(top[1], ("app3".to_string(), 200));
     }
 
+    fn hour_slots(entries: &[(usize, &str, i64)]) -> TimeSlots {
+        let mut slots = TimeSlots::new(TimeGranularity::Day);
+        for &(index, app, seconds) in entries {
+            if !slots.slots.iter().any(|s| s.index == index) {
+                slots.add_slot(TimeSlot::new(format!("{}h", index), index));
+            }
+            let slot = slots.slots.iter_mut().find(|s| s.index == index).unwrap();
+            slot.add_duration(app, seconds);
+        }
+        slots
+    }
+
+    #[test]
+    fn test_time_slots_merge_sums_buckets_and_groups() {
+        let mut monday = hour_slots(&[(9, "code", 1800), (9, "firefox", 600), (10, "code", 3600)]);
+        let tuesday = hour_slots(&[(9, "code", 1200), (14, "kitty", 900)]);
+
+        monday.merge(&tuesday);
+
+        let indices: Vec<usize> = monday.slots().iter().map(|s| s.index()).collect();
+        assert_eq!(indices, vec![9, 10, 14]);
+
+        let nine = &monday.slots()[0];
+        assert_eq!(nine.get_group_duration("code"), 3000);
+        assert_eq!(nine.get_group_duration("firefox"), 600);
+        assert_eq!(nine.duration().as_seconds(), 3600);
+        assert_eq!(monday.slots()[2].label(), "14h");
+        assert_eq!(monday.slots()[2].get_group_duration("kitty"), 900);
+        assert_eq!(monday.total_duration().as_seconds(), 8100);
+    }
+
+    #[test]
+    fn test_time_slots_collapse_small_groups() {
+        // 总计 10000 秒：code 60%、firefox 30%、kitty 6%、mpv 4%
+        let slots = hour_slots(&[
+            (9, "code", 4000),
+            (9, "kitty", 400),
+            (9, "mpv", 100),
+            (10, "code", 2000),
+            (10, "firefox", 3000),
+            (10, "kitty", 200),
+            (10, "mpv", 300),
+        ]);
+
+        let collapsed = slots.collapse_small_groups(0.1);
+        assert_eq!(collapsed.all_groups(), vec!["code", "firefox", "其他"]);
+
+        let nine = &collapsed.slots()[0];
+        assert_eq!(nine.get_group_duration("code"), 4000);
+        assert_eq!(nine.get_group_duration("其他"), 500);
+        assert_eq!(nine.duration(), slots.slots()[0].duration());
+
+        let ten = &collapsed.slots()[1];
+        assert_eq!(ten.get_group_duration("firefox"), 3000);
+        assert_eq!(ten.get_group_duration("其他"), 500);
+        assert_eq!(ten.duration(), slots.slots()[1].duration());
+
+        assert_eq!(collapsed.total_duration(), slots.total_duration());
+        assert_eq!(slots.collapse_small_groups(0.0).all_groups().len(), 4);
+    }
+
+    #[test]
+    fn test_time_slots_merge_empty() {
+        let mut empty = TimeSlots::new(TimeGranularity::Day);
+        let other = hour_slots(&[(3, "code", 60)]);
+        empty.merge(&other);
+        assert_eq!(empty.len(), 1);
+
+        empty.merge(&TimeSlots::new(TimeGranularity::Day));
+        assert_eq!(empty.total_duration().as_seconds(), 60);
+    }
+
     #[test]
     fn test_granularity_labels() {
         assert_eq!(TimeGranularity::Day.default_slot_label(0), "0h");
@@ -1,6 +1,8 @@
 //! TaiL Core - 核心 Trait 定义
+//!
+//! 所有接受 `start`/`end` 的查询都按半开区间 `[start, end)` 筛选，与 `TimeRange` 一致。
 
-use crate::errors::DbResult;
+use crate::errors::{DbError, DbResult};
 use crate::models::*;
 use async_trait::async_trait;
 use chrono::{DateTime, Utc};
@@ -133,6 +135,95 @@ pub trait AliasRepository: Send + Sync {
     async fn delete(&self, app_name: &str) -> DbResult<()>;
 }
 
+// ============================================================================
+// 设置仓储
+// ============================================================================
+
+/// 设置仓储（键值对）
+#[async_trait]
+pub trait SettingsRepository: Send + Sync {
+    /// 获取设置值，不存在时返回 None
+    async fn get_setting(&self, key: &str) -> DbResult<Option<String>>;
+
+    /// 获取设置值，不存在时返回默认值
+    async fn get_setting_or(&self, key: &str, default: &str) -> DbResult<String> {
+        Ok(self
+            .get_setting(key)
+            .await?
+            .unwrap_or_else(|| default.to_string()))
+    }
+
+    /// 获取字符串设置，不存在时返回默认值
+    async fn get_setting_string(&self, key: &str, default: &str) -> DbResult<String> {
+        self.get_setting_or(key, default).await
+    }
+
+    /// 获取整数设置，不存在时返回默认值，存储值无法解析时返回错误
+    async fn get_setting_i64(&self, key: &str, default: i64) -> DbResult<i64> {
+        match self.get_setting(key).await? {
+            Some(value) => value.trim().parse().map_err(|_| {
+                DbError::Validation(format!("设置 {} 的值不是有效的整数: {:?}", key, value))
+            }),
+            None => Ok(default),
+        }
+    }
+
+    /// 获取布尔设置，不存在时返回默认值，存储值无法解析时返回错误
+    async fn get_setting_bool(&self, key: &str, default: bool) -> DbResult<bool> {
+        match self.get_setting(key).await? {
+            Some(value) => value.trim().parse().map_err(|_| {
+                DbError::Validation(format!("设置 {} 的值不是有效的布尔值: {:?}", key, value))
+            }),
+            None => Ok(default),
+        }
+    }
+
+    /// 获取整数列表设置（逗号分隔），不存在时返回空列表，存储值无法解析时返回错误
+    async fn get_setting_i64_list(&self, key: &str) -> DbResult<Vec<i64>> {
+        let Some(value) = self.get_setting(key).await? else {
+            return Ok(Vec::new());
+        };
+        value
+            .split(',')
+            .map(str::trim)
+            .filter(|item| !item.is_empty())
+            .map(|item| {
+                item.parse().map_err(|_| {
+                    DbError::Validation(format!("设置 {} 的值不是有效的整数列表: {:?}", key, value))
+                })
+            })
+            .collect()
+    }
+
+    /// 保存字符串设置
+    async fn set_setting_string(&self, key: &str, value: &str) -> DbResult<()> {
+        self.set_setting(key, value).await
+    }
+
+    /// 保存整数设置
+    async fn set_setting_i64(&self, key: &str, value: i64) -> DbResult<()> {
+        self.set_setting(key, &value.to_string()).await
+    }
+
+    /// 保存整数列表设置（序列化为逗号分隔的字符串）
+    async fn set_setting_i64_list(&self, key: &str, values: &[i64]) -> DbResult<()> {
+        let value = values
+            .iter()
+            .map(|v| v.to_string())
+            .collect::<Vec<_>>()
+            .join(",");
+        self.set_setting(key, &value).await
+    }
+
+    /// 保存布尔设置（序列化为 "true" / "false"）
+    async fn set_setting_bool(&self, key: &str, value: bool) -> DbResult<()> {
+        self.set_setting(key, &value.to_string()).await
+    }
+
+    /// 设置值（已存在则覆盖）
+    async fn set_setting(&self, key: &str, value: &str) -> DbResult<()>;
+}
+
 // ============================================================================
 // 查询服务
 // ============================================================================
@@ -168,10 +259,10 @@ pub trait TimeStatsQuery: Send + Sync {
     /// 获取某年按月份汇总的使用统计
     async fn get_monthly_usage(&self, year: i32) -> DbResult<Vec<PeriodUsage>>;
 
-    /// 获取某年某月按周汇总的使用统计
+    /// 获取某年某月按周汇总的使用统计（月内周序）
     async fn get_weekly_usage(&self, year: i32, month: u32) -> DbResult<Vec<PeriodUsage>>;
 
-    /// 获取某年某月某周按天汇总的使用统计
+    /// 获取某年某月某周按天汇总的使用统计（月内周序）
     async fn get_daily_usage_for_week(
         &self,
         year: i32,
@@ -97,11 +97,9 @@ pub mod time_range {
         .num_days() as u32
     }
 
-    /// 计算某日期是该月的第几周
+    /// 计算某日期是该月的第几周（规则见 `TimeRangeCalculator::week_of_month`）
     pub fn week_of_month(year: i32, month: u32, day: u32) -> u32 {
-        let first_day = NaiveDate::from_ymd_opt(year, month, 1).unwrap();
-        let first_weekday = first_day.weekday().num_days_from_monday();
-        ((day + first_weekday - 1) / 7) + 1
+        crate::time::range::TimeRangeCalculator::week_of_month(year, month, day)
     }
 }
 
@@ -120,6 +118,51 @@ pub mod duration {
         Minimal,
     }
 
+    /// 时长单位的语言
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+    pub enum DurationLocale {
+        /// 英文单位：2h 3m
+        #[default]
+        En,
+        /// 中文单位：2小时3分
+        Zh,
+    }
+
+    impl DurationLocale {
+        /// 所有可选语言
+        pub fn all() -> &'static [DurationLocale] {
+            &[DurationLocale::En, DurationLocale::Zh]
+        }
+
+        /// 持久化到设置表时使用的字符串
+        pub fn as_str(&self) -> &'static str {
+            match self {
+                DurationLocale::En => "en",
+                DurationLocale::Zh => "zh",
+            }
+        }
+
+        /// 从设置表中的字符串解析，无法识别时返回 `None`
+        pub fn parse(value: &str) -> Option<Self> {
+            match value {
+                "en" => Some(DurationLocale::En),
+                "zh" => Some(DurationLocale::Zh),
+                _ => None,
+            }
+        }
+
+        /// 小时、分钟、秒的单位及单位之间的分隔符
+        fn units(&self) -> (&'static str, &'static str, &'static str, &'static str) {
+            match self {
+                DurationLocale::En => ("h", "m", "s", " "),
+                DurationLocale::Zh => ("小时", "分", "秒", ""),
+            }
+        }
+    }
+
+    /// 时长单位语言的设置键
+    pub const DURATION_LOCALE_KEY: &str = "duration_locale";
+
     /// 格式化秒数为可读时长
     ///
     /// # 示例
@@ -132,27 +175,53 @@ pub mod duration {
     /// assert_eq!(format_duration(3665, DurationStyle::Minimal), "1h");
     /// ```
     pub fn format_duration(seconds: i64, style: DurationStyle) -> String {
+        format_duration_localized(seconds, style, DurationLocale::default())
+    }
+
+    /// 按指定单位语言格式化秒数
+    ///
+    /// `locale` 只影响 `Short`、`Full`、`Minimal` 风格，`Chinese` 风格始终使用中文。
+    ///
+    /// # 示例
+    /// ```
+    /// use tail_core::utils::duration::{format_duration_localized, DurationLocale, DurationStyle};
+    ///
+    /// assert_eq!(
+    ///     format_duration_localized(7380, DurationStyle::Short, DurationLocale::En),
+    ///     "2h 3m"
+    /// );
+    /// assert_eq!(
+    ///     format_duration_localized(7380, DurationStyle::Short, DurationLocale::Zh),
+    ///     "2小时3分"
+    /// );
+    /// ```
+    pub fn format_duration_localized(
+        seconds: i64,
+        style: DurationStyle,
+        locale: DurationLocale,
+    ) -> String {
         let hours = seconds / 3600;
         let minutes = (seconds % 3600) / 60;
         let secs = seconds % 60;
+        let (h, m, s, sep) = locale.units();
 
         match style {
             DurationStyle::Short => {
                 if hours > 0 {
-                    format!("{}h {}m", hours, minutes)
+                    format!("{}{h}{sep}{}{m}", hours, minutes)
                 } else if minutes > 0 {
-                    format!("{}m {}s", minutes, secs)
+                    format!("{}{m}{sep}{}{s}", minutes, secs)
                 } else {
-                    format!("{}s", secs)
+                    format!("{}{s}", secs)
                 }
             }
             DurationStyle::Full => {
                 if hours > 0 {
-                    format!("{}h {}m {}s", hours, minutes, secs)
+                    format!("{}{h}{sep}{}{m}{sep}{}{s}", hours, minutes, secs)
                 } else if minutes > 0 {
-                    format!("{}m {}s", minutes, secs)
+                    format!("{}{m}{sep}{}{s}", minutes, secs)
                 } else {
-                    format!("{}s", secs)
+                    format!("{}{s}", secs)
                 }
             }
             DurationStyle::Chinese => {
@@ -164,9 +233,9 @@ pub mod duration {
             }
             DurationStyle::Minimal => {
                 if hours > 0 {
-                    format!("{}h", hours)
+                    format!("{}{h}", hours)
                 } else {
-                    format!("{}m", minutes)
+                    format!("{}{m}", minutes)
                 }
             }
         }
@@ -193,14 +262,516 @@ pub mod filter {
             .filter(|u| !u.app_name.is_empty())
             .collect()
     }
+
+    /// 汇总条目"其他"的应用名称
+    pub const OTHER_APP_NAME: &str = "其他";
+
+    /// 保留使用时长最多的 N 个应用，其余合并为一个"其他"条目
+    ///
+    /// "其他"条目的 `window_events` 为空；没有剩余应用时不生成该条目。
+    pub fn top_n_with_other(apps: Vec<AppUsage>, n: usize) -> Vec<AppUsage> {
+        let mut apps = filter_empty_apps_owned(apps);
+        apps.sort_by(|a, b| {
+            b.total_seconds
+                .cmp(&a.total_seconds)
+                .then_with(|| a.app_name.cmp(&b.app_name))
+        });
+
+        if apps.len() <= n {
+            return apps;
+        }
+
+        let rest = apps.split_off(n);
+        apps.push(AppUsage {
+            app_name: OTHER_APP_NAME.to_string(),
+            total_seconds: rest.iter().map(|u| u.total_seconds).sum(),
+            window_events: Vec::new(),
+        });
+        apps
+    }
+}
+
+/// 时间段对比工具
+pub mod compare {
+    use super::*;
+    use std::collections::HashMap;
+
+    /// 单个应用在两个时间段内的使用对比
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub struct AppComparison {
+        pub app_name: String,
+        /// 当前时间段的使用时长（秒，不含 AFK）
+        pub current_seconds: i64,
+        /// 对比时间段的使用时长（秒，不含 AFK）
+        pub previous_seconds: i64,
+    }
+
+    impl AppComparison {
+        /// 时长变化（当前 - 对比）
+        pub fn delta_seconds(&self) -> i64 {
+            self.current_seconds - self.previous_seconds
+        }
+    }
+
+    /// 计算应用的非 AFK 使用时长
+    fn active_seconds(usage: &AppUsage) -> i64 {
+        usage
+            .window_events
+            .iter()
+            .filter(|e| !e.is_afk)
+            .map(|e| e.duration_secs)
+            .sum()
+    }
+
+    /// 合并两个时间段的应用使用数据
+    ///
+    /// 结果包含两个时间段中出现过的所有应用（缺失的一方时长为 0），
+    /// 按当前时长降序排列，其次按对比时长降序、应用名称升序。
+    pub fn compare_app_usage(current: &[AppUsage], previous: &[AppUsage]) -> Vec<AppComparison> {
+        let mut map: HashMap<&str, (i64, i64)> = HashMap::new();
+
+        for usage in current.iter().filter(|u| !u.app_name.is_empty()) {
+            map.entry(&usage.app_name).or_default().0 += active_seconds(usage);
+        }
+        for usage in previous.iter().filter(|u| !u.app_name.is_empty()) {
+            map.entry(&usage.app_name).or_default().1 += active_seconds(usage);
+        }
+
+        let mut result: Vec<AppComparison> = map
+            .into_iter()
+            .map(
+                |(app_name, (current_seconds, previous_seconds))| AppComparison {
+                    app_name: app_name.to_string(),
+                    current_seconds,
+                    previous_seconds,
+                },
+            )
+            .collect();
+
+        result.sort_by(|a, b| {
+            b.current_seconds
+                .cmp(&a.current_seconds)
+                .then(b.previous_seconds.cmp(&a.previous_seconds))
+                .then_with(|| a.app_name.cmp(&b.app_name))
+        });
+
+        result
+    }
+
+    /// 对比模式中与当前时间段对照的时间段
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+    pub enum ComparisonPeriod {
+        /// 紧邻的上一个同类时间段：今天（截至此刻）对比昨天的同一时刻之前，
+        /// 最近 7 天对比再往前的 7 天；自定义范围按 [`TimeRange::previous_period`] 计算
+        ///
+        /// [`TimeRange::previous_period`]: crate::time::range::TimeRange::previous_period
+        #[default]
+        Previous,
+        /// 一周前的同一时间段
+        LastWeek,
+        /// 一年前的同一时间段
+        LastYear,
+    }
+
+    impl ComparisonPeriod {
+        /// 所有对比方式（按界面显示顺序）
+        pub const ALL: [ComparisonPeriod; 3] = [
+            ComparisonPeriod::Previous,
+            ComparisonPeriod::LastWeek,
+            ComparisonPeriod::LastYear,
+        ];
+
+        /// 界面显示的名称
+        pub fn label(self) -> &'static str {
+            match self {
+                ComparisonPeriod::Previous => "上一周期",
+                ComparisonPeriod::LastWeek => "上周同期",
+                ComparisonPeriod::LastYear => "去年同期",
+            }
+        }
+
+        /// 计算与 `range` 的 `[start, end)` 对照的时间段
+        ///
+        /// 按本地日历平移开始和结束时间，保持相同的钟点，
+        /// 因此"今天截至 10:00"对应"昨天截至 10:00"，而不是按固定时长向前平移。
+        pub fn counterpart(
+            self,
+            range: &crate::models::TimeRange,
+            start: DateTime<Utc>,
+            end: DateTime<Utc>,
+        ) -> (DateTime<Utc>, DateTime<Utc>) {
+            use crate::models::TimeRange as Quick;
+
+            let days = match (self, range) {
+                (ComparisonPeriod::LastYear, _) => {
+                    let shift = |t| shift_local(t, |n| n - chrono::Months::new(12));
+                    return (shift(start), shift(end));
+                }
+                (ComparisonPeriod::LastWeek, _) => 7,
+                (ComparisonPeriod::Previous, Quick::Today | Quick::Yesterday) => 1,
+                (ComparisonPeriod::Previous, Quick::Last7Days) => 7,
+                (ComparisonPeriod::Previous, Quick::Last30Days) => 30,
+                (ComparisonPeriod::Previous, Quick::Custom(..)) => {
+                    let previous = crate::time::range::TimeRange::new(start, end).previous_period();
+                    return (previous.start, previous.end);
+                }
+            };
+            let shift = |t| shift_local(t, |n| n - ChronoDuration::days(days));
+            (shift(start), shift(end))
+        }
+    }
+
+    /// 把 UTC 时间转为本地时间后平移，再转回 UTC
+    fn shift_local(
+        time: DateTime<Utc>,
+        shift: impl Fn(chrono::NaiveDateTime) -> chrono::NaiveDateTime,
+    ) -> DateTime<Utc> {
+        let local = time.with_timezone(&chrono::Local).naive_local();
+        crate::time::range::TimeRangeCalculator::local_to_utc(shift(local))
+    }
+}
+
+/// 应用显示名称工具
+pub mod display {
+    use std::collections::HashMap;
+
+    /// 显示名称模式的设置键
+    pub const DISPLAY_NAME_MODE_KEY: &str = "display_name_mode";
+
+    /// 应用名称的显示方式
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+    pub enum DisplayNameMode {
+        /// 原始进程名
+        #[default]
+        Raw,
+        /// 用户设置的别名
+        Alias,
+        /// `.desktop` 文件中的 Name
+        Desktop,
+    }
+
+    impl DisplayNameMode {
+        /// 所有模式
+        pub fn all() -> &'static [DisplayNameMode] {
+            &[
+                DisplayNameMode::Raw,
+                DisplayNameMode::Alias,
+                DisplayNameMode::Desktop,
+            ]
+        }
+
+        /// 持久化到设置表时使用的字符串
+        pub fn as_str(&self) -> &'static str {
+            match self {
+                DisplayNameMode::Raw => "raw",
+                DisplayNameMode::Alias => "alias",
+                DisplayNameMode::Desktop => "desktop",
+            }
+        }
+
+        /// 从设置表中的字符串解析，无法识别时返回 `None`
+        pub fn parse(value: &str) -> Option<Self> {
+            Self::all().iter().copied().find(|m| m.as_str() == value)
+        }
+    }
+
+    /// 按显示模式解析应用的显示名称
+    ///
+    /// 首选形式不可用时依次回退：`Alias` 回退到 `.desktop` 名称，`Desktop` 回退到别名，
+    /// 最终回退到原始进程名。空字符串视为不可用。
+    pub fn resolve_display_name(
+        app_name: &str,
+        aliases: &HashMap<String, String>,
+        desktop_lookup: impl FnOnce(&str) -> Option<String>,
+        mode: DisplayNameMode,
+    ) -> String {
+        let alias = || aliases.get(app_name).filter(|a| !a.is_empty()).cloned();
+        let desktop = || desktop_lookup(app_name).filter(|n| !n.is_empty());
+
+        let resolved = match mode {
+            DisplayNameMode::Raw => None,
+            DisplayNameMode::Alias => alias().or_else(desktop),
+            DisplayNameMode::Desktop => desktop().or_else(alias),
+        };
+
+        resolved.unwrap_or_else(|| app_name.to_string())
+    }
+}
+
+/// 应用名称规范化工具
+pub mod normalize {
+    use std::collections::HashMap;
+
+    /// 默认去除的后缀（启动器常见的报告形式）
+    pub const DEFAULT_SUFFIXES: &[&str] = &[".desktop", ".exe", ".bin", ".appimage"];
+
+    /// 应用名称规范化规则
+    ///
+    /// 依次执行：转小写（可关闭）→ 去除首个匹配的后缀 → 应用显式别名，
+    /// 得到用于分组的规范名称。别名的键也经过前两步处理，因此 `Firefox.desktop`
+    /// 与 `firefox` 命中同一条别名。
+    #[derive(Debug, Clone)]
+    pub struct NameNormalizer {
+        lowercase: bool,
+        suffixes: Vec<String>,
+        aliases: HashMap<String, String>,
+    }
+
+    impl Default for NameNormalizer {
+        fn default() -> Self {
+            Self {
+                lowercase: true,
+                suffixes: DEFAULT_SUFFIXES.iter().map(|s| s.to_string()).collect(),
+                aliases: HashMap::new(),
+            }
+        }
+    }
+
+    impl NameNormalizer {
+        /// 不做任何处理的规范化器
+        pub fn empty() -> Self {
+            Self {
+                lowercase: false,
+                suffixes: Vec::new(),
+                aliases: HashMap::new(),
+            }
+        }
+
+        /// 设置是否转为小写
+        pub fn lowercase(mut self, enabled: bool) -> Self {
+            self.lowercase = enabled;
+            self
+        }
+
+        /// 添加需要去除的后缀
+        pub fn strip_suffix(mut self, suffix: impl Into<String>) -> Self {
+            self.suffixes.push(suffix.into());
+            self
+        }
+
+        /// 添加显式别名：`from` 规范化后映射为 `to`
+        pub fn alias(mut self, from: &str, to: impl Into<String>) -> Self {
+            let key = self.base_key(from);
+            self.aliases.insert(key, to.into());
+            self
+        }
+
+        /// 计算应用名称的规范名称
+        pub fn canonical(&self, app_name: &str) -> String {
+            let key = self.base_key(app_name);
+            self.aliases.get(&key).cloned().unwrap_or(key)
+        }
+
+        /// 转小写并去除后缀（不含别名）
+        fn base_key(&self, app_name: &str) -> String {
+            let mut key = app_name.trim().to_string();
+            if self.lowercase {
+                key = key.to_lowercase();
+            }
+
+            for suffix in &self.suffixes {
+                let matches = if self.lowercase {
+                    key.ends_with(&suffix.to_lowercase())
+                } else {
+                    key.ends_with(suffix.as_str())
+                };
+                // 不把整个名称都去掉
+                if matches && key.len() > suffix.len() {
+                    key.truncate(key.len() - suffix.len());
+                    break;
+                }
+            }
+
+            key
+        }
+    }
+}
+
+/// 日类型判定工具
+pub mod day_kind {
+    use std::collections::{HashMap, HashSet};
+
+    /// 按分类构成判定的日类型
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub enum DayKind {
+        /// 工作日：大部分时间在工作分类
+        Work,
+        /// 休息日：很少时间在工作分类
+        Rest,
+        /// 介于两者之间
+        Mixed,
+    }
+
+    /// 日类型判定阈值（工作分类时长占全部分类时长的比例）
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    pub struct DayKindThresholds {
+        /// 占比不低于该值判定为工作日
+        pub work_share: f64,
+        /// 占比不高于该值判定为休息日
+        pub rest_share: f64,
+    }
+
+    impl Default for DayKindThresholds {
+        fn default() -> Self {
+            Self {
+                work_share: 0.6,
+                rest_share: 0.3,
+            }
+        }
+    }
+
+    /// 按默认阈值判定日类型
+    pub fn classify_day(
+        category_seconds: &HashMap<String, i64>,
+        work_categories: &HashSet<String>,
+    ) -> DayKind {
+        classify_day_with(
+            category_seconds,
+            work_categories,
+            DayKindThresholds::default(),
+        )
+    }
+
+    /// 按指定阈值判定日类型
+    ///
+    /// 负数时长按 0 处理；没有任何使用时长的日期视为休息日。
+    pub fn classify_day_with(
+        category_seconds: &HashMap<String, i64>,
+        work_categories: &HashSet<String>,
+        thresholds: DayKindThresholds,
+    ) -> DayKind {
+        let (work, total) =
+            category_seconds
+                .iter()
+                .fold((0i64, 0i64), |(work, total), (category, &seconds)| {
+                    let seconds = seconds.max(0);
+                    if work_categories.contains(category) {
+                        (work + seconds, total + seconds)
+                    } else {
+                        (work, total + seconds)
+                    }
+                });
+        if total == 0 {
+            return DayKind::Rest;
+        }
+
+        let share = work as f64 / total as f64;
+        if share >= thresholds.work_share {
+            DayKind::Work
+        } else if share <= thresholds.rest_share {
+            DayKind::Rest
+        } else {
+            DayKind::Mixed
+        }
+    }
+}
+
+/// 应用到分类的流向数据（桑基图的数据层）
+pub mod flow {
+    use crate::models::{AppUsage, Category};
+    use std::collections::{HashMap, HashSet};
+
+    /// 一条应用到分类的流向
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub struct Flow {
+        pub app: String,
+        pub category: String,
+        pub seconds: i64,
+    }
+
+    /// 由应用使用统计和应用所属分类（应用名称 -> 分类名称列表）构建流向
+    ///
+    /// 属于多个分类的应用按分类数平均拆分时长，除不尽的秒数依次分给靠前的分类，
+    /// 保证各流向之和等于应用的总时长、不会重复计算。不在映射中（或分类列表为空）的应用
+    /// 流向"未分类"。时长不为正的应用被忽略。
+    pub fn build_app_category_flows(
+        usage: &[AppUsage],
+        map: &HashMap<String, Vec<String>>,
+    ) -> Vec<Flow> {
+        let uncategorized = vec![Category::UNCATEGORIZED_NAME.to_string()];
+        let mut flows = Vec::new();
+
+        for app in usage.iter().filter(|app| app.total_seconds > 0) {
+            let mut categories: Vec<&String> = map
+                .get(&app.app_name)
+                .filter(|categories| !categories.is_empty())
+                .unwrap_or(&uncategorized)
+                .iter()
+                .collect();
+            // 同一分类重复出现时只计一次
+            let mut seen = HashSet::new();
+            categories.retain(|category| seen.insert(*category));
+
+            let count = categories.len() as i64;
+            let share = app.total_seconds / count;
+            let remainder = app.total_seconds % count;
+            for (i, category) in categories.into_iter().enumerate() {
+                let extra = i64::from((i as i64) < remainder);
+                flows.push(Flow {
+                    app: app.app_name.clone(),
+                    category: category.clone(),
+                    seconds: share + extra,
+                });
+            }
+        }
+
+        flows
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use duration::{format_duration, DurationStyle};
+    use normalize::NameNormalizer;
+    use std::collections::{HashMap, HashSet};
     use time_range::{days_in_month, month_range, year_range};
 
+    fn app_usage(app_name: &str, total_seconds: i64) -> crate::models::AppUsage {
+        crate::models::AppUsage {
+            app_name: app_name.to_string(),
+            total_seconds,
+            window_events: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_flows_single_category_app() {
+        let map = HashMap::from([("code".to_string(), vec!["开发".to_string()])]);
+        let flows =
+            flow::build_app_category_flows(&[app_usage("code", 3600), app_usage("mpv", 600)], &map);
+        assert_eq!(
+            flows,
+            vec![
+                flow::Flow {
+                    app: "code".to_string(),
+                    category: "开发".to_string(),
+                    seconds: 3600,
+                },
+                flow::Flow {
+                    app: "mpv".to_string(),
+                    category: "未分类".to_string(),
+                    seconds: 600,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_flows_split_multi_category_app() {
+        let map = HashMap::from([(
+            "firefox".to_string(),
+            vec!["工作".to_string(), "娱乐".to_string()],
+        )]);
+        let flows = flow::build_app_category_flows(&[app_usage("firefox", 1801)], &map);
+        let seconds: Vec<(&str, i64)> = flows
+            .iter()
+            .map(|f| (f.category.as_str(), f.seconds))
+            .collect();
+        assert_eq!(seconds, vec![("工作", 901), ("娱乐", 900)]);
+        assert_eq!(flows.iter().map(|f| f.seconds).sum::<i64>(), 1801);
+    }
+
     #[test]
     fn test_format_duration_short() {
         assert_eq!(format_duration(0, DurationStyle::Short), "0s");
@@ -256,4 +827,301 @@ mod tests {
         assert_eq!(days_in_month(2024, 1), 31);
         assert_eq!(days_in_month(2024, 4), 30);
     }
+
+    fn usage(app_name: &str, seconds: &[i64]) -> AppUsage {
+        use crate::models::WindowEvent;
+
+        AppUsage {
+            app_name: app_name.to_string(),
+            total_seconds: seconds.iter().sum(),
+            window_events: seconds
+                .iter()
+                .map(|&duration_secs| WindowEvent {
+                    id: None,
+                    timestamp: Utc::now(),
+                    app_name: app_name.to_string(),
+                    window_title: String::new(),
+                    workspace: String::new(),
+                    duration_secs,
+                    is_afk: false,
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_top_n_with_other() {
+        use filter::{top_n_with_other, OTHER_APP_NAME};
+
+        let apps = vec![
+            usage("a", &[100]),
+            usage("b", &[500]),
+            usage("c", &[300]),
+            usage("d", &[400]),
+            usage("e", &[200]),
+        ];
+
+        let result = top_n_with_other(apps.clone(), 2);
+        assert_eq!(result.len(), 3);
+        assert_eq!(result[0].app_name, "b");
+        assert_eq!(result[1].app_name, "d");
+        assert_eq!(result[2].app_name, OTHER_APP_NAME);
+        assert_eq!(result[2].total_seconds, 300 + 200 + 100);
+        assert!(result[2].window_events.is_empty());
+
+        // N 不小于应用数时不生成"其他"
+        let result = top_n_with_other(apps, 5);
+        assert_eq!(result.len(), 5);
+        assert!(result.iter().all(|u| u.app_name != OTHER_APP_NAME));
+    }
+
+    #[test]
+    fn test_compare_app_usage() {
+        use compare::compare_app_usage;
+
+        let current = vec![usage("code", &[3000, 600]), usage("firefox", &[1200])];
+        let previous = vec![usage("code", &[1800]), usage("steam", &[2400])];
+
+        let result = compare_app_usage(&current, &previous);
+        assert_eq!(result.len(), 3);
+
+        // 两个时间段都有
+        assert_eq!(result[0].app_name, "code");
+        assert_eq!(result[0].current_seconds, 3600);
+        assert_eq!(result[0].previous_seconds, 1800);
+        assert_eq!(result[0].delta_seconds(), 1800);
+
+        // 只在当前时间段
+        assert_eq!(result[1].app_name, "firefox");
+        assert_eq!(result[1].previous_seconds, 0);
+        assert_eq!(result[1].delta_seconds(), 1200);
+
+        // 只在对比时间段
+        assert_eq!(result[2].app_name, "steam");
+        assert_eq!(result[2].current_seconds, 0);
+        assert_eq!(result[2].delta_seconds(), -2400);
+    }
+
+    #[test]
+    fn test_comparison_counterparts() {
+        use crate::models::TimeRange as Quick;
+        use crate::time::range::TimeRangeCalculator;
+        use compare::ComparisonPeriod;
+
+        let local = |month, day, hour| {
+            TimeRangeCalculator::local_to_utc(
+                NaiveDate::from_ymd_opt(2024, month, day)
+                    .unwrap()
+                    .and_hms_opt(hour, 0, 0)
+                    .unwrap(),
+            )
+        };
+        let now = local(5, 15, 10);
+        let previous = |range: Quick| {
+            let (start, end) = range.bounds_at(now);
+            ComparisonPeriod::Previous.counterpart(&range, start, end)
+        };
+
+        // 今天截至 10:00 对比昨天截至 10:00，而不是昨天 14:00 之后
+        assert_eq!(Quick::Today.bounds_at(now), (local(5, 15, 0), now));
+        assert_eq!(previous(Quick::Today), (local(5, 14, 0), local(5, 14, 10)));
+        assert_eq!(
+            previous(Quick::Yesterday),
+            (local(5, 13, 0), local(5, 14, 0))
+        );
+        // 最近 7 天（含今天）对比再往前的 7 天
+        assert_eq!(Quick::Last7Days.bounds_at(now), (local(5, 9, 0), now));
+        assert_eq!(
+            previous(Quick::Last7Days),
+            (local(5, 2, 0), local(5, 8, 10))
+        );
+        assert_eq!(
+            previous(Quick::Last30Days),
+            (local(3, 17, 0), local(4, 15, 10))
+        );
+        // 自定义的整月对比上一个自然月
+        let april = Quick::Custom(local(4, 1, 0), local(5, 1, 0));
+        assert_eq!(previous(april), (local(3, 1, 0), local(4, 1, 0)));
+
+        let (start, end) = Quick::Today.bounds_at(now);
+        assert_eq!(
+            ComparisonPeriod::LastWeek.counterpart(&Quick::Today, start, end),
+            (local(5, 8, 0), local(5, 8, 10))
+        );
+        let last_year = ComparisonPeriod::LastYear.counterpart(&Quick::Today, start, end);
+        assert_eq!(
+            last_year.0.with_timezone(&chrono::Local).date_naive(),
+            NaiveDate::from_ymd_opt(2023, 5, 15).unwrap()
+        );
+        assert_eq!(last_year.1 - last_year.0, end - start);
+    }
+
+    #[test]
+    fn test_resolve_display_name_modes() {
+        use display::{resolve_display_name, DisplayNameMode};
+        use std::collections::HashMap;
+
+        let aliases: HashMap<String, String> = [("code".to_string(), "VS Code".to_string())].into();
+        let desktop = |app: &str| (app == "code").then(|| "Visual Studio Code".to_string());
+
+        assert_eq!(
+            resolve_display_name("code", &aliases, desktop, DisplayNameMode::Raw),
+            "code"
+        );
+        assert_eq!(
+            resolve_display_name("code", &aliases, desktop, DisplayNameMode::Alias),
+            "VS Code"
+        );
+        assert_eq!(
+            resolve_display_name("code", &aliases, desktop, DisplayNameMode::Desktop),
+            "Visual Studio Code"
+        );
+    }
+
+    #[test]
+    fn test_resolve_display_name_fallbacks() {
+        use display::{resolve_display_name, DisplayNameMode};
+        use std::collections::HashMap;
+
+        let aliases: HashMap<String, String> = [
+            ("firefox".to_string(), "浏览器".to_string()),
+            ("kitty".to_string(), String::new()),
+        ]
+        .into();
+        let desktop = |app: &str| (app == "steam").then(|| "Steam".to_string());
+
+        // 别名缺失时回退到 .desktop 名称，再回退到原始名称
+        assert_eq!(
+            resolve_display_name("steam", &aliases, desktop, DisplayNameMode::Alias),
+            "Steam"
+        );
+        assert_eq!(
+            resolve_display_name("kitty", &aliases, desktop, DisplayNameMode::Alias),
+            "kitty"
+        );
+
+        // .desktop 名称缺失时回退到别名，再回退到原始名称
+        assert_eq!(
+            resolve_display_name("firefox", &aliases, desktop, DisplayNameMode::Desktop),
+            "浏览器"
+        );
+        assert_eq!(
+            resolve_display_name("htop", &aliases, desktop, DisplayNameMode::Desktop),
+            "htop"
+        );
+    }
+
+    #[test]
+    fn test_display_name_mode_parse() {
+        use display::DisplayNameMode;
+
+        for mode in DisplayNameMode::all() {
+            assert_eq!(DisplayNameMode::parse(mode.as_str()), Some(*mode));
+        }
+        assert_eq!(DisplayNameMode::parse("unknown"), None);
+    }
+
+    #[test]
+    fn test_format_duration_locales() {
+        use duration::{format_duration_localized, DurationLocale};
+
+        let en = |style| format_duration_localized(7385, style, DurationLocale::En);
+        let zh = |style| format_duration_localized(7385, style, DurationLocale::Zh);
+
+        assert_eq!(en(DurationStyle::Short), "2h 3m");
+        assert_eq!(zh(DurationStyle::Short), "2小时3分");
+        assert_eq!(en(DurationStyle::Full), "2h 3m 5s");
+        assert_eq!(zh(DurationStyle::Full), "2小时3分5秒");
+        assert_eq!(en(DurationStyle::Minimal), "2h");
+        assert_eq!(zh(DurationStyle::Minimal), "2小时");
+        assert_eq!(
+            format_duration_localized(65, DurationStyle::Short, DurationLocale::Zh),
+            "1分5秒"
+        );
+
+        // 默认语言与原有行为一致
+        assert_eq!(
+            format_duration(7385, DurationStyle::Short),
+            en(DurationStyle::Short)
+        );
+    }
+
+    #[test]
+    fn test_normalizer_strips_suffix_and_case() {
+        let normalizer = NameNormalizer::default();
+        assert_eq!(normalizer.canonical("Firefox"), "firefox");
+        assert_eq!(normalizer.canonical("firefox"), "firefox");
+        assert_eq!(normalizer.canonical("firefox.desktop"), "firefox");
+        assert_eq!(normalizer.canonical("Code.EXE"), "code");
+        // 名称本身等于后缀时保留
+        assert_eq!(normalizer.canonical(".desktop"), ".desktop");
+    }
+
+    #[test]
+    fn test_normalizer_explicit_alias() {
+        let normalizer = NameNormalizer::default()
+            .alias("org.mozilla.firefox", "firefox")
+            .alias("Firefox-ESR.desktop", "firefox");
+
+        assert_eq!(normalizer.canonical("org.mozilla.firefox"), "firefox");
+        assert_eq!(normalizer.canonical("firefox-esr"), "firefox");
+        assert_eq!(normalizer.canonical("Firefox"), "firefox");
+        assert_eq!(normalizer.canonical("kitty"), "kitty");
+    }
+
+    #[test]
+    fn test_normalizer_case_sensitive() {
+        let normalizer = NameNormalizer::empty().strip_suffix(".desktop");
+        assert_eq!(normalizer.canonical("Firefox.desktop"), "Firefox");
+        assert_eq!(normalizer.canonical("Firefox.DESKTOP"), "Firefox.DESKTOP");
+    }
+
+    fn category_mix(pairs: &[(&str, i64)]) -> HashMap<String, i64> {
+        pairs
+            .iter()
+            .map(|(category, seconds)| (category.to_string(), *seconds))
+            .collect()
+    }
+
+    fn work_categories() -> HashSet<String> {
+        ["编程", "办公"].iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_classify_day_mostly_work() {
+        use day_kind::{classify_day, DayKind};
+
+        let day = category_mix(&[("编程", 5 * 3600), ("办公", 3600), ("娱乐", 1800)]);
+        assert_eq!(classify_day(&day, &work_categories()), DayKind::Work);
+    }
+
+    #[test]
+    fn test_classify_day_mostly_rest() {
+        use day_kind::{classify_day, DayKind};
+
+        let day = category_mix(&[("编程", 600), ("娱乐", 3 * 3600), ("社交", 3600)]);
+        assert_eq!(classify_day(&day, &work_categories()), DayKind::Rest);
+        assert_eq!(
+            classify_day(&HashMap::new(), &work_categories()),
+            DayKind::Rest
+        );
+    }
+
+    #[test]
+    fn test_classify_day_balanced() {
+        use day_kind::{classify_day, classify_day_with, DayKind, DayKindThresholds};
+
+        let day = category_mix(&[("编程", 2 * 3600), ("娱乐", 2 * 3600)]);
+        assert_eq!(classify_day(&day, &work_categories()), DayKind::Mixed);
+
+        // 阈值可配置
+        let lenient = DayKindThresholds {
+            work_share: 0.5,
+            rest_share: 0.2,
+        };
+        assert_eq!(
+            classify_day_with(&day, &work_categories(), lenient),
+            DayKind::Work
+        );
+    }
 }
@@ -1,25 +1,64 @@
 //! TaiL GUI - egui 应用
 
 use chrono::{DateTime, Datelike, Duration as ChronoDuration, Local, Utc};
+use std::collections::HashMap;
 use std::sync::Arc;
-use tail_core::db::Config as DbConfig;
-use tail_core::models::{TimeNavigationState, TimeRange};
+use tail_core::compare::ComparisonPeriod;
+use tail_core::db::repositories::{
+    AFK_THRESHOLD_SECS_KEY, DEFAULT_AFK_THRESHOLD_SECS, format_alias_mapping, parse_alias_mapping,
+};
+use tail_core::db::{Config as DbConfig, DbHealth};
+use tail_core::display::{DISPLAY_NAME_MODE_KEY, DisplayNameMode};
+use tail_core::duration::{DURATION_LOCALE_KEY, DurationLocale};
+use tail_core::models::{SortOrder, TimeNavigationState, TimeRange, WindowEvent};
+use tail_core::services::category_service::default_categories;
+use tail_core::time::analysis::TimeSeriesAnalyzer;
+use tail_core::time::range::TimeRangeCalculator;
+use tail_core::time::{WEEK_NUMBERING_KEY, WeekNumbering};
 use tail_core::traits::{
-    AliasRepository, AppUsageQuery, CategoryRepository, CategoryUsageQuery, DailyGoalRepository,
+    AliasRepository, AppUsageQuery, CategoryRepository, DailyGoalRepository, SettingsRepository,
+    WindowEventRepository,
 };
 use tail_core::{AppUsage, DailyGoal, Repository};
 use tracing::{debug, info};
 
 use crate::components::{
-    AliasDialog, DefaultStatsView, NavigationMode, SidebarNav, TopTabNav, View,
+    AliasDialog, AppHeader, CategoryColorMap, DefaultStatsView, NavigationMode, SidebarNav,
+    TopTabNav, View, parse_hex_color, tracker_heartbeat_warning,
 };
 use crate::icons::IconCache;
 use crate::theme::{TaiLTheme, ThemeType};
+use crate::utils::duration;
 use crate::views::{
-    AddGoalDialog, CategoriesView, CategoryAction, DashboardView, DetailsView, SettingsAction,
-    SettingsView, StatisticsView,
+    AddGoalDialog, CalendarView, CategoriesView, CategoryAction, DEFAULT_DETAILS_MAX_RECORDS,
+    DashboardView, DetailsView, DuplicateCleanupDialog, OnboardingAction, OnboardingView,
+    SettingsAction, SettingsView, StatisticsView, TimelineView, should_show_onboarding,
 };
 
+/// 时间线中划分会话的最大空隙（秒）
+const TIMELINE_SESSION_GAP_SECS: i64 = 300;
+
+/// 时间线中视为记录空白（守护进程可能未运行）的最小间隔（秒）
+const TIMELINE_TRACKING_GAP_SECS: i64 = 3600;
+
+/// 统计页面是否使用堆叠柱形图的设置键
+const STATS_STACKED_VIEW_KEY: &str = "stats_use_stacked_view";
+
+/// 用户是否已关闭首次使用引导的设置键
+const ONBOARDING_DISMISSED_KEY: &str = "onboarding_dismissed";
+
+/// 详细记录最多显示条数的设置键（0 表示不限制）
+const DETAILS_MAX_RECORDS_KEY: &str = "details_max_records";
+
+/// 详细记录页面最多加载的窗口事件数量，防止把全部历史读入内存
+const DETAILS_MAX_LOADED_EVENTS: usize = 100_000;
+
+/// 启动时预加载最近多少天内用过的应用图标
+const ICON_PREWARM_DAYS: i64 = 30;
+
+/// 追踪服务心跳超过该时长（秒）未更新时显示警告
+const TRACKER_HEARTBEAT_STALE_SECS: i64 = 60;
+
 /// TaiL GUI 应用
 pub struct TaiLApp {
     /// 当前视图
@@ -37,17 +76,31 @@ pub struct TaiLApp {
     /// 数据库仓库
     repo: Arc<Repository>,
 
+    /// 当前数据库文件路径
+    db_path: String,
+
     /// Tokio runtime（用于处理异步数据库调用）
     runtime: tokio::runtime::Runtime,
 
     /// 仪表板数据缓存（固定为今天）
     dashboard_usage_cache: Vec<AppUsage>,
+    /// 仪表板数据的墙钟活跃时长（秒，重叠事件只计一次）
+    dashboard_wall_clock_seconds: i64,
 
     /// 统计页面数据缓存
     stats_usage_cache: Vec<AppUsage>,
 
+    /// 统计页面对照时间段的数据缓存（对比模式）
+    stats_previous_usage_cache: Vec<AppUsage>,
+
+    /// 统计页面是否启用对比模式
+    stats_compare_mode: bool,
+
+    /// 统计页面对比模式下的对照时间段
+    stats_compare_period: ComparisonPeriod,
+
     /// 详细记录数据缓存（所有历史数据）
-    details_usage_cache: Vec<AppUsage>,
+    details_events_cache: Vec<WindowEvent>,
 
     /// 每日目标缓存
     daily_goals_cache: Vec<DailyGoal>,
@@ -79,15 +132,33 @@ pub struct TaiLApp {
     /// 别名对话框
     alias_dialog: AliasDialog,
 
+    /// 清理重复数据对话框
+    duplicate_cleanup_dialog: DuplicateCleanupDialog,
+
     /// 分类视图（持久化状态）
     categories_view: CategoriesView,
 
     /// 详细视图（持久化状态）
     details_view: DetailsView,
 
+    /// 时间线视图（持久化选中的日期和会话）
+    timeline_view: TimelineView,
+
+    /// 时间线数据上次刷新时间
+    timeline_last_refresh: Option<DateTime<Utc>>,
+
+    /// 活动日历视图
+    calendar_view: CalendarView,
+
+    /// 活动日历数据上次刷新时间
+    calendar_last_refresh: Option<DateTime<Utc>>,
+
     /// 是否已应用主题
     theme_applied: bool,
 
+    /// 是否已提交最近使用应用的图标预加载
+    icons_prewarmed: bool,
+
     /// 窗口是否可见（用于检测工作区切换）
     was_visible: bool,
 
@@ -96,6 +167,34 @@ pub struct TaiLApp {
 
     /// 默认统计视图
     default_stats_view: DefaultStatsView,
+
+    /// AFK 判定阈值（秒）
+    afk_threshold_secs: i64,
+
+    /// 详细记录最多显示的条数（0 表示不限制）
+    details_max_records: usize,
+
+    /// 图表颜色配置（包含用户为应用指定的颜色）
+    chart_color_map: CategoryColorMap,
+
+    /// 应用名称显示模式
+    display_name_mode: DisplayNameMode,
+    /// 时长单位语言
+    duration_locale: DurationLocale,
+
+    /// 数据库健康状态
+    db_health: DbHealth,
+
+    /// 上次健康检查时间
+    health_last_check: Option<DateTime<Utc>>,
+
+    /// 追踪服务心跳警告
+    tracker_warning: Option<String>,
+
+    /// 数据库中是否已有窗口事件（没有时统计页面显示引导页）
+    has_events: bool,
+    /// 用户是否已关闭首次使用引导
+    onboarding_dismissed: bool,
 }
 
 impl TaiLApp {
@@ -103,11 +202,14 @@ impl TaiLApp {
     pub fn new(_cc: &eframe::CreationContext<'_>) -> Self {
         // 注意：字体配置已在 main.rs 中通过 setup_fonts() 完成
         // 不要在这里重复配置字体，否则会覆盖已设置的字体
+        Self::with_config(&DbConfig::default())
+    }
 
-        let config = DbConfig::default();
+    /// 使用指定的数据库配置创建应用实例
+    fn with_config(config: &DbConfig) -> Self {
         tracing::info!("初始化数据库，路径: {}", config.path);
 
-        let repo = Repository::new(&config).expect("Failed to initialize database");
+        let repo = Repository::new(config).expect("Failed to initialize database");
 
         // 创建 tokio runtime 用于异步数据库调用
         let runtime = tokio::runtime::Runtime::new().expect("Failed to create tokio runtime");
@@ -146,16 +248,21 @@ impl TaiLApp {
             }
         };
 
-        Self {
+        let mut app = Self {
             current_view: View::Dashboard,
             stats_time_range: TimeRange::Today,
             navigation_state,
             stats_use_stacked_view: false,
             repo: Arc::new(repo),
+            db_path: config.path.clone(),
             runtime,
             dashboard_usage_cache: Vec::new(),
+            dashboard_wall_clock_seconds: 0,
             stats_usage_cache: Vec::new(),
-            details_usage_cache: Vec::new(),
+            stats_previous_usage_cache: Vec::new(),
+            stats_compare_mode: false,
+            stats_compare_period: ComparisonPeriod::default(),
+            details_events_cache: Vec::new(),
             daily_goals_cache: Vec::new(),
             dashboard_last_refresh: None,
             stats_last_refresh: None,
@@ -166,12 +273,427 @@ impl TaiLApp {
             icon_cache: IconCache::new(),
             add_goal_dialog: AddGoalDialog::new(),
             alias_dialog: AliasDialog::default(),
+            duplicate_cleanup_dialog: DuplicateCleanupDialog::new(),
             categories_view: CategoriesView::new(theme.clone()),
             details_view: DetailsView::new(),
+            timeline_view: TimelineView::new(),
+            timeline_last_refresh: None,
+            calendar_view: CalendarView::new(),
+            calendar_last_refresh: None,
             theme_applied: false,
+            icons_prewarmed: false,
             was_visible: true,
             navigation_mode: NavigationMode::default(), // 默认为侧边栏模式
             default_stats_view,
+            afk_threshold_secs: DEFAULT_AFK_THRESHOLD_SECS,
+            details_max_records: DEFAULT_DETAILS_MAX_RECORDS,
+            chart_color_map: CategoryColorMap::default(),
+            display_name_mode: DisplayNameMode::default(),
+            duration_locale: DurationLocale::default(),
+            db_health: DbHealth::healthy(),
+            health_last_check: None,
+            tracker_warning: None,
+            has_events: false,
+            onboarding_dismissed: false,
+        };
+        app.load_settings();
+        app.seed_default_categories();
+        app.reload_display_names();
+        app
+    }
+
+    /// 在后台预加载最近使用过的应用图标
+    fn prewarm_icons(&mut self, ctx: &egui::Context) {
+        self.icons_prewarmed = true;
+        let since = Utc::now() - ChronoDuration::days(ICON_PREWARM_DAYS);
+        match self
+            .runtime
+            .block_on(self.repo.get_all_app_names_since(since))
+        {
+            Ok(app_names) => self.icon_cache.prewarm(ctx, &app_names),
+            Err(e) => tracing::warn!(error = %e, "获取最近使用的应用失败，跳过图标预加载"),
+        }
+    }
+
+    /// 首次使用数据库时创建默认分类
+    fn seed_default_categories(&self) {
+        if let Err(e) = self
+            .runtime
+            .block_on(self.repo.category_service().seed_default_categories())
+        {
+            tracing::warn!(error = %e, "创建默认分类失败");
+        }
+    }
+
+    /// 从当前数据库加载持久化的设置
+    fn load_settings(&mut self) {
+        self.afk_threshold_secs = self
+            .runtime
+            .block_on(async {
+                self.repo
+                    .get_setting_i64(AFK_THRESHOLD_SECS_KEY, DEFAULT_AFK_THRESHOLD_SECS)
+                    .await
+            })
+            .unwrap_or_else(|e| {
+                tracing::warn!(error = %e, "读取 AFK 判定阈值失败，使用默认值");
+                DEFAULT_AFK_THRESHOLD_SECS
+            });
+
+        self.details_max_records = self
+            .runtime
+            .block_on(async {
+                self.repo
+                    .get_setting_i64(DETAILS_MAX_RECORDS_KEY, DEFAULT_DETAILS_MAX_RECORDS as i64)
+                    .await
+            })
+            .map(|value| value.max(0) as usize)
+            .unwrap_or_else(|e| {
+                tracing::warn!(error = %e, "读取详细记录显示条数失败，使用默认值");
+                DEFAULT_DETAILS_MAX_RECORDS
+            });
+        self.details_view.set_max_records(self.details_max_records);
+
+        let app_colors = self
+            .runtime
+            .block_on(async { self.repo.get_app_colors().await })
+            .unwrap_or_else(|e| {
+                tracing::warn!(error = %e, "读取应用颜色失败，使用默认调色板");
+                HashMap::new()
+            });
+        self.chart_color_map = CategoryColorMap::default().with_overrides(
+            app_colors
+                .into_iter()
+                .filter_map(|(app_name, hex)| parse_hex_color(&hex).map(|color| (app_name, color)))
+                .collect(),
+        );
+        self.timeline_view
+            .set_color_map(self.chart_color_map.clone());
+
+        self.display_name_mode = self
+            .runtime
+            .block_on(async {
+                self.repo
+                    .get_setting_string(DISPLAY_NAME_MODE_KEY, DisplayNameMode::default().as_str())
+                    .await
+            })
+            .ok()
+            .and_then(|value| DisplayNameMode::parse(&value))
+            .unwrap_or_default();
+
+        self.duration_locale = self
+            .runtime
+            .block_on(async {
+                self.repo
+                    .get_setting_string(DURATION_LOCALE_KEY, DurationLocale::default().as_str())
+                    .await
+            })
+            .ok()
+            .and_then(|value| DurationLocale::parse(&value))
+            .unwrap_or_default();
+        duration::set_locale(self.duration_locale);
+
+        self.stats_use_stacked_view = self
+            .runtime
+            .block_on(async {
+                self.repo
+                    .get_setting_bool(STATS_STACKED_VIEW_KEY, false)
+                    .await
+            })
+            .unwrap_or_else(|e| {
+                tracing::warn!(error = %e, "读取统计图表样式失败，使用简单柱形图");
+                false
+            });
+
+        self.onboarding_dismissed = self
+            .runtime
+            .block_on(async {
+                self.repo
+                    .get_setting_bool(ONBOARDING_DISMISSED_KEY, false)
+                    .await
+            })
+            .unwrap_or_else(|e| {
+                tracing::warn!(error = %e, "读取引导页设置失败");
+                false
+            });
+
+        let week_numbering = self
+            .runtime
+            .block_on(async {
+                self.repo
+                    .get_setting_string(WEEK_NUMBERING_KEY, WeekNumbering::default().as_str())
+                    .await
+            })
+            .ok()
+            .and_then(|value| value.parse().ok())
+            .unwrap_or_default();
+        if week_numbering != self.navigation_state.week_numbering {
+            // 初始导航状态按默认方式计算了周数，需要重新应用
+            self.navigation_state.week_numbering = week_numbering;
+            self.apply_default_stats_view();
+        }
+    }
+
+    /// 清空所有数据缓存，并让每个页面在下一帧重新加载
+    fn invalidate_caches(&mut self) {
+        self.dashboard_usage_cache.clear();
+        self.dashboard_wall_clock_seconds = 0;
+        self.stats_usage_cache.clear();
+        self.stats_previous_usage_cache.clear();
+        self.details_events_cache.clear();
+        self.daily_goals_cache.clear();
+        self.calendar_view.set_activity(Vec::new());
+        self.timeline_view.set_sessions(Vec::new());
+        self.timeline_view.set_gaps(Vec::new());
+
+        self.dashboard_last_refresh = None;
+        self.stats_last_refresh = None;
+        self.details_last_refresh = None;
+        self.categories_last_refresh = None;
+        self.timeline_last_refresh = None;
+        self.calendar_last_refresh = None;
+        self.health_last_check = None;
+        self.db_health = DbHealth::healthy();
+        self.tracker_warning = None;
+        self.has_events = false;
+    }
+
+    /// 切换到另一个数据库文件，无需重启应用
+    ///
+    /// 新数据库打开失败时保留当前数据库。
+    fn switch_database(&mut self, path: String) {
+        let config = DbConfig { path };
+        let repo = match Repository::new(&config) {
+            Ok(repo) => repo,
+            Err(e) => {
+                tracing::error!(error = %e, path = %config.path, "切换数据库失败");
+                return;
+            }
+        };
+
+        info!(path = %config.path, "已切换数据库");
+        self.repo = Arc::new(repo);
+        self.db_path = config.path;
+        self.invalidate_caches();
+        self.icons_prewarmed = false;
+        self.load_settings();
+        self.seed_default_categories();
+        self.reload_display_names();
+    }
+
+    /// 统计全部数据中的重复事件并打开清理确认对话框
+    fn preview_duplicate_cleanup(&mut self) {
+        match self.runtime.block_on(
+            self.repo
+                .find_duplicate_events(DateTime::UNIX_EPOCH, Utc::now()),
+        ) {
+            Ok(duplicates) => self.duplicate_cleanup_dialog.open(duplicates.len()),
+            Err(e) => tracing::error!(error = %e, "查找重复数据失败"),
+        }
+    }
+
+    /// 删除全部数据中的重复事件
+    fn remove_duplicate_events(&mut self) {
+        match self.runtime.block_on(
+            self.repo
+                .remove_duplicate_events(DateTime::UNIX_EPOCH, Utc::now()),
+        ) {
+            Ok(removed) => {
+                info!(removed, "已清理重复数据");
+                self.invalidate_caches();
+            }
+            Err(e) => tracing::error!(error = %e, "清理重复数据失败"),
+        }
+    }
+
+    /// 在 tokio 运行时上下文中执行 `f`，用于弹出文件对话框
+    ///
+    /// rfd 的 xdg-portal 后端通过 zbus 在当前 tokio 运行时上派生任务，
+    /// UI 线程默认不在运行时上下文中，直接弹出对话框会 panic。
+    fn with_runtime<T>(&self, f: impl FnOnce() -> T) -> T {
+        let _guard = self.runtime.enter();
+        f()
+    }
+
+    /// 选择 ActivityWatch 分类规则文件并导入
+    fn import_aw_categories(&mut self) {
+        let Some(path) = self.with_runtime(|| {
+            rfd::FileDialog::new()
+                .set_title("选择 ActivityWatch 分类规则")
+                .add_filter("JSON", &["json"])
+                .pick_file()
+        }) else {
+            return;
+        };
+
+        let json = match std::fs::read_to_string(&path) {
+            Ok(json) => json,
+            Err(e) => {
+                tracing::error!(error = %e, path = %path.display(), "读取分类规则文件失败");
+                return;
+            }
+        };
+
+        match self
+            .runtime
+            .block_on(self.repo.import_aw_category_rules(&json))
+        {
+            Ok(created) => {
+                info!(created, "已导入 ActivityWatch 分类规则");
+                self.categories_last_refresh = None;
+            }
+            Err(e) => tracing::error!(error = %e, "导入 ActivityWatch 分类规则失败"),
+        }
+    }
+
+    /// 选择 `原始名称=别名` 格式的文件并批量导入别名
+    fn import_aliases(&mut self) {
+        let Some(path) = self.with_runtime(|| {
+            rfd::FileDialog::new()
+                .set_title("导入应用别名")
+                .add_filter("文本文件", &["txt", "conf"])
+                .pick_file()
+        }) else {
+            return;
+        };
+
+        let text = match std::fs::read_to_string(&path) {
+            Ok(text) => text,
+            Err(e) => {
+                tracing::error!(error = %e, path = %path.display(), "读取别名文件失败");
+                return;
+            }
+        };
+
+        let result = parse_alias_mapping(&text)
+            .and_then(|pairs| self.runtime.block_on(self.repo.import_aliases(&pairs)));
+        match result {
+            Ok(imported) => {
+                info!(imported, "已导入应用别名");
+                self.reload_display_names();
+            }
+            Err(e) => tracing::error!(error = %e, "导入应用别名失败"),
+        }
+    }
+
+    /// 把所有别名导出为 `原始名称=别名` 格式的文件
+    fn export_aliases(&mut self) {
+        let Some(path) = self.with_runtime(|| {
+            rfd::FileDialog::new()
+                .set_title("导出应用别名")
+                .add_filter("文本文件", &["txt"])
+                .set_file_name("tail-aliases.txt")
+                .save_file()
+        }) else {
+            return;
+        };
+
+        let pairs = match self.runtime.block_on(self.repo.export_aliases()) {
+            Ok(pairs) => pairs,
+            Err(e) => {
+                tracing::error!(error = %e, "读取应用别名失败");
+                return;
+            }
+        };
+        match std::fs::write(&path, format_alias_mapping(&pairs)) {
+            Ok(()) => info!(exported = pairs.len(), path = %path.display(), "已导出应用别名"),
+            Err(e) => tracing::error!(error = %e, path = %path.display(), "写入别名文件失败"),
+        }
+    }
+
+    /// 弹出文件对话框选择数据库文件（可选择已有文件或输入新文件名）
+    fn pick_database(&mut self) {
+        let current = std::path::Path::new(&self.db_path);
+        let mut dialog = rfd::FileDialog::new()
+            .set_title("选择数据库文件")
+            .add_filter("SQLite 数据库", &["db", "sqlite", "sqlite3"])
+            .set_file_name("tail.db");
+        if let Some(dir) = current.parent() {
+            dialog = dialog.set_directory(dir);
+        }
+
+        if let Some(path) = self.with_runtime(|| dialog.save_file()) {
+            self.switch_database(path.to_string_lossy().into_owned());
+        }
+    }
+
+    /// 选择备份文件并在线备份当前数据库
+    fn backup_database(&mut self) {
+        let current = std::path::Path::new(&self.db_path);
+        let file_name = format!("tail-backup-{}.db", Local::now().format("%Y%m%d-%H%M%S"));
+        let mut dialog = rfd::FileDialog::new()
+            .set_title("备份数据库")
+            .add_filter("SQLite 数据库", &["db", "sqlite", "sqlite3"])
+            .set_file_name(file_name);
+        if let Some(dir) = current.parent() {
+            dialog = dialog.set_directory(dir);
+        }
+
+        let Some(path) = self.with_runtime(|| dialog.save_file()) else {
+            return;
+        };
+        if path == current {
+            tracing::warn!("备份目标与当前数据库相同，已忽略");
+            return;
+        }
+
+        match self.runtime.block_on(self.repo.backup_to(&path)) {
+            Ok(()) => tracing::info!(path = %path.display(), "数据库备份完成"),
+            Err(e) => tracing::error!(error = %e, "备份数据库失败"),
+        }
+    }
+
+    /// 重新加载别名并更新应用显示名称
+    fn reload_display_names(&mut self) {
+        let aliases: HashMap<String, String> = self
+            .runtime
+            .block_on(async { AliasRepository::get_all(&self.repo.aliases()).await })
+            .unwrap_or_else(|e| {
+                tracing::warn!(error = %e, "加载应用别名失败");
+                Vec::new()
+            })
+            .into_iter()
+            .collect();
+        self.icon_cache
+            .set_display_names(self.display_name_mode, aliases);
+    }
+
+    /// 定期检查数据库是否可用
+    fn check_db_health(&mut self) {
+        let now = Utc::now();
+        // 每30秒检查一次
+        if let Some(last) = self.health_last_check
+            && now.signed_duration_since(last).num_seconds() < 30
+        {
+            return;
+        }
+        self.health_last_check = Some(now);
+
+        let health = self.runtime.block_on(self.repo.health_check());
+        if health != self.db_health {
+            match &health.detail {
+                Some(detail) => tracing::warn!(detail = %detail, "数据库不可用"),
+                None => info!("数据库已恢复可用"),
+            }
+        }
+        self.db_health = health;
+
+        self.tracker_warning = match self.runtime.block_on(self.repo.get_last_heartbeat()) {
+            Ok(last) => tracker_heartbeat_warning(last, now, TRACKER_HEARTBEAT_STALE_SECS),
+            Err(e) => {
+                tracing::warn!(error = %e, "读取追踪服务心跳失败");
+                None
+            }
+        };
+
+        // 一旦有了数据就不会再变为空，之后不必再检查
+        if !self.has_events {
+            self.has_events = self
+                .runtime
+                .block_on(self.repo.has_any_events())
+                .unwrap_or_else(|e| {
+                    tracing::warn!(error = %e, "检查是否有数据失败");
+                    true
+                });
         }
     }
 
@@ -202,6 +724,8 @@ impl TaiLApp {
         }) {
             Ok(usage) => {
                 tracing::debug!("仪表板获取 {} 条应用使用记录", usage.len());
+                self.dashboard_wall_clock_seconds =
+                    TimeSeriesAnalyzer::total_active_seconds(&AppUsage::flatten_events(&usage));
                 self.dashboard_usage_cache = usage;
             }
             Err(e) => {
@@ -266,6 +790,25 @@ impl TaiLApp {
             }
         }
 
+        if self.stats_compare_mode {
+            let (previous_start, previous_end) =
+                self.stats_compare_period
+                    .counterpart(&self.stats_time_range, start, end);
+            match self.runtime.block_on(async {
+                AppUsageQuery::get_app_usage(
+                    &self.repo.usage_service(),
+                    previous_start,
+                    previous_end,
+                )
+                .await
+            }) {
+                Ok(usage) => self.stats_previous_usage_cache = usage,
+                Err(e) => {
+                    debug!(error = %e, "获取对照时间段统计数据失败");
+                }
+            }
+        }
+
         self.stats_last_refresh = Some(now);
     }
 
@@ -290,13 +833,17 @@ impl TaiLApp {
             "刷新详细记录数据"
         );
 
-        // 使用 tokio runtime 处理异步调用
+        // 使用 tokio runtime 处理异步调用，直接按时间降序查询最近的事件
         match self.runtime.block_on(async {
-            AppUsageQuery::get_app_usage(&self.repo.usage_service(), start, now).await
+            self.repo
+                .get_window_events_capped(start, now, SortOrder::Desc, DETAILS_MAX_LOADED_EVENTS)
+                .await
         }) {
-            Ok(usage) => {
-                debug!(count = usage.len(), "详细记录数据获取成功");
-                self.details_usage_cache = usage;
+            Ok((events, truncated)) => {
+                debug!(count = events.len(), truncated, "详细记录数据获取成功");
+                self.details_view
+                    .set_loaded_limit(truncated.then_some(DETAILS_MAX_LOADED_EVENTS));
+                self.details_events_cache = events;
             }
             Err(e) => {
                 debug!(error = %e, "获取详细记录数据失败");
@@ -306,68 +853,74 @@ impl TaiLApp {
         self.details_last_refresh = Some(now);
     }
 
-    /// 获取统计页面时间范围的开始和结束时间
-    fn get_stats_time_range_bounds(&self) -> (DateTime<Utc>, DateTime<Utc>) {
+    /// 刷新时间线数据（当前选中日期的会话）
+    fn refresh_timeline_data(&mut self) {
         let now = Utc::now();
-        let local_now = Local::now();
+        // 每10秒刷新一次
+        if let Some(last) = self.timeline_last_refresh
+            && now.signed_duration_since(last).num_seconds() < 10
+        {
+            return;
+        }
+
+        let range = TimeRangeCalculator::day(self.timeline_view.date());
+        match self.runtime.block_on(async {
+            WindowEventRepository::get_by_time_range(&*self.repo, range.start, range.end).await
+        }) {
+            Ok(events) => {
+                let sessions = TimeSeriesAnalyzer::sessionize(&events, TIMELINE_SESSION_GAP_SECS);
+                debug!(count = sessions.len(), "时间线会话数据获取成功");
+                self.timeline_view.set_sessions(sessions);
+            }
+            Err(e) => {
+                debug!(error = %e, "获取时间线数据失败");
+            }
+        }
 
-        match self.stats_time_range {
-            TimeRange::Today => {
-                // 使用本地时间计算"今天"的开始
-                let today_start = local_now
-                    .date_naive()
-                    .and_hms_opt(0, 0, 0)
-                    .unwrap()
-                    .and_local_timezone(Local)
-                    .unwrap()
-                    .with_timezone(&Utc);
-                (today_start, now)
-            }
-            TimeRange::Yesterday => {
-                // 使用本地时间计算"昨天"
-                let local_yesterday = local_now - ChronoDuration::days(1);
-                let yesterday_start = local_yesterday
-                    .date_naive()
-                    .and_hms_opt(0, 0, 0)
-                    .unwrap()
-                    .and_local_timezone(Local)
-                    .unwrap()
-                    .with_timezone(&Utc);
-                let yesterday_end = local_yesterday
-                    .date_naive()
-                    .and_hms_opt(23, 59, 59)
-                    .unwrap()
-                    .and_local_timezone(Local)
-                    .unwrap()
-                    .with_timezone(&Utc);
-                (yesterday_start, yesterday_end)
-            }
-            TimeRange::Last7Days => {
-                // 使用本地时间计算7天前的开始
-                let local_week_ago = local_now - ChronoDuration::days(7);
-                let week_ago_start = local_week_ago
-                    .date_naive()
-                    .and_hms_opt(0, 0, 0)
-                    .unwrap()
-                    .and_local_timezone(Local)
-                    .unwrap()
-                    .with_timezone(&Utc);
-                (week_ago_start, now)
-            }
-            TimeRange::Last30Days => {
-                // 使用本地时间计算30天前的开始
-                let local_month_ago = local_now - ChronoDuration::days(30);
-                let month_ago_start = local_month_ago
-                    .date_naive()
-                    .and_hms_opt(0, 0, 0)
-                    .unwrap()
-                    .and_local_timezone(Local)
-                    .unwrap()
-                    .with_timezone(&Utc);
-                (month_ago_start, now)
-            }
-            TimeRange::Custom(start, end) => (start, end),
+        match self.runtime.block_on(async {
+            self.repo
+                .find_tracking_gaps(range.start, range.end, TIMELINE_TRACKING_GAP_SECS)
+                .await
+        }) {
+            Ok(gaps) => self.timeline_view.set_gaps(gaps),
+            Err(e) => {
+                debug!(error = %e, "获取记录空白失败");
+            }
+        }
+
+        self.timeline_last_refresh = Some(now);
+    }
+
+    /// 刷新活动日历数据（最近一年的每日活跃时长）
+    fn refresh_calendar_data(&mut self) {
+        let now = Utc::now();
+        // 每60秒刷新一次
+        if let Some(last) = self.calendar_last_refresh
+            && now.signed_duration_since(last).num_seconds() < 60
+        {
+            return;
+        }
+
+        let range = TimeRangeCalculator::last_n_days(365);
+        match self
+            .runtime
+            .block_on(self.repo.get_daily_activity(range.start, range.end))
+        {
+            Ok(activity) => {
+                debug!(days = activity.len(), "活动日历数据获取成功");
+                self.calendar_view.set_activity(activity);
+            }
+            Err(e) => {
+                debug!(error = %e, "获取活动日历数据失败");
+            }
         }
+
+        self.calendar_last_refresh = Some(now);
+    }
+
+    /// 获取统计页面时间范围的开始和结束时间
+    fn get_stats_time_range_bounds(&self) -> (DateTime<Utc>, DateTime<Utc>) {
+        self.stats_time_range.bounds_at(Utc::now())
     }
 
     /// 切换主题
@@ -454,6 +1007,160 @@ impl TaiLApp {
                 AliasRepository::set(&self.repo.aliases(), &app_name, &alias).await
             });
         }
+        self.reload_display_names();
+        self.categories_last_refresh = None;
+    }
+
+    /// 设置应用名称显示模式
+    fn set_display_name_mode(&mut self, mode: DisplayNameMode) {
+        match self.runtime.block_on(async {
+            self.repo
+                .set_setting_string(DISPLAY_NAME_MODE_KEY, mode.as_str())
+                .await
+        }) {
+            Ok(()) => {
+                self.display_name_mode = mode;
+                self.reload_display_names();
+                self.categories_last_refresh = None;
+            }
+            Err(e) => tracing::error!(error = %e, "保存应用名称显示模式失败"),
+        }
+    }
+
+    /// 设置时长单位语言
+    fn set_duration_locale(&mut self, locale: DurationLocale) {
+        match self.runtime.block_on(async {
+            self.repo
+                .set_setting_string(DURATION_LOCALE_KEY, locale.as_str())
+                .await
+        }) {
+            Ok(()) => {
+                self.duration_locale = locale;
+                duration::set_locale(locale);
+            }
+            Err(e) => tracing::error!(error = %e, "保存时长单位语言失败"),
+        }
+    }
+
+    /// 设置周编号方式
+    ///
+    /// 已选中的周数在新方式下含义不同，保存后重新应用默认统计视图。
+    fn set_week_numbering(&mut self, numbering: WeekNumbering) {
+        match self.runtime.block_on(async {
+            self.repo
+                .set_setting_string(WEEK_NUMBERING_KEY, numbering.as_str())
+                .await
+        }) {
+            Ok(()) => {
+                self.navigation_state.week_numbering = numbering;
+                self.apply_default_stats_view();
+            }
+            Err(e) => tracing::error!(error = %e, "保存周编号方式失败"),
+        }
+    }
+
+    /// 切换统计页面的图表样式并保存
+    fn set_stats_stacked_view(&mut self, use_stacked: bool) {
+        self.stats_use_stacked_view = use_stacked;
+        if let Err(e) = self.runtime.block_on(async {
+            self.repo
+                .set_setting_bool(STATS_STACKED_VIEW_KEY, use_stacked)
+                .await
+        }) {
+            tracing::error!(error = %e, "保存统计图表样式失败");
+        }
+    }
+
+    /// 当前页面是否应显示首次使用引导（分类和设置页面始终可用）
+    fn shows_onboarding(&self) -> bool {
+        should_show_onboarding(self.has_events, self.onboarding_dismissed)
+            && !matches!(self.current_view, View::Categories | View::Settings)
+    }
+
+    /// 处理引导页的操作
+    fn handle_onboarding_action(&mut self, action: OnboardingAction) {
+        match action {
+            OnboardingAction::CreateDefaultCategories => {
+                match self
+                    .runtime
+                    .block_on(self.repo.ensure_categories(&default_categories()))
+                {
+                    Ok(ids) => info!(count = ids.len(), "已创建默认分类"),
+                    Err(e) => tracing::error!(error = %e, "创建默认分类失败"),
+                }
+                self.categories_last_refresh = None;
+                self.current_view = View::Categories;
+            }
+            OnboardingAction::Dismiss => {
+                self.onboarding_dismissed = true;
+                if let Err(e) = self.runtime.block_on(async {
+                    self.repo
+                        .set_setting_bool(ONBOARDING_DISMISSED_KEY, true)
+                        .await
+                }) {
+                    tracing::error!(error = %e, "保存引导页设置失败");
+                }
+            }
+        }
+    }
+
+    /// 打开添加目标对话框
+    ///
+    /// 候选应用为最近 30 天内使用过的应用，并附带各应用今天已使用的时长。
+    fn open_add_goal_dialog(&mut self) {
+        let since = Utc::now() - ChronoDuration::days(30);
+        let apps = self
+            .runtime
+            .block_on(async { self.repo.get_all_app_names_since(since).await })
+            .unwrap_or_else(|e| {
+                tracing::warn!(error = %e, "获取应用列表失败");
+                Vec::new()
+            });
+        let today_usage = self
+            .runtime
+            .block_on(async {
+                let today = TimeRangeCalculator::today();
+                AppUsageQuery::get_app_usage(&self.repo.usage_service(), today.start, today.end)
+                    .await
+            })
+            .map(|usage| {
+                usage
+                    .into_iter()
+                    .map(|u| (u.app_name, u.total_seconds))
+                    .collect()
+            })
+            .unwrap_or_else(|e| {
+                tracing::warn!(error = %e, "获取今天的使用时长失败");
+                HashMap::new()
+            });
+        self.add_goal_dialog.open(apps, today_usage);
+    }
+
+    /// 设置 AFK 判定阈值
+    fn set_afk_threshold(&mut self, secs: i64) {
+        match self.runtime.block_on(async {
+            self.repo
+                .set_setting_i64(AFK_THRESHOLD_SECS_KEY, secs)
+                .await
+        }) {
+            Ok(()) => self.afk_threshold_secs = secs,
+            Err(e) => tracing::error!(error = %e, "保存 AFK 判定阈值失败"),
+        }
+    }
+
+    /// 设置详细记录最多显示的条数
+    fn set_details_max_records(&mut self, max_records: usize) {
+        match self.runtime.block_on(async {
+            self.repo
+                .set_setting_i64(DETAILS_MAX_RECORDS_KEY, max_records as i64)
+                .await
+        }) {
+            Ok(()) => {
+                self.details_max_records = max_records;
+                self.details_view.set_max_records(max_records);
+            }
+            Err(e) => tracing::error!(error = %e, "保存详细记录显示条数失败"),
+        }
     }
 
     /// 打开别名管理对话框
@@ -468,27 +1175,19 @@ impl TaiLApp {
 
     /// 加载分类页面数据
     fn load_categories_data(&mut self, start: DateTime<Utc>, end: DateTime<Utc>) {
-        // 加载分类使用统计
-        let category_usage: Vec<tail_core::CategoryUsage> = self
-            .runtime
-            .block_on(async {
-                CategoryUsageQuery::get_category_usage(&self.repo.usage_service(), start, end).await
-            })
-            .unwrap_or_default();
-
-        // 加载所有分类
-        let categories = self
-            .runtime
-            .block_on(async { CategoryRepository::get_all(&self.repo.category_service()).await })
-            .unwrap_or_default();
-
-        // 加载所有应用名称
-        let all_apps = self
-            .runtime
-            .block_on(async {
-                CategoryRepository::get_all_app_names(&self.repo.category_service()).await
-            })
-            .unwrap_or_default();
+        // 加载分类、分类使用统计及应用归类情况
+        let data = match self.runtime.block_on(async {
+            self.repo
+                .category_service()
+                .get_category_management_data(start, end)
+                .await
+        }) {
+            Ok(data) => data,
+            Err(e) => {
+                tracing::warn!(error = %e, "加载分类数据失败");
+                return;
+            }
+        };
 
         // 加载应用使用数据（用于堆叠柱形图）
         let app_usage = self
@@ -498,9 +1197,46 @@ impl TaiLApp {
             })
             .unwrap_or_default();
 
+        // 计算应用显示名称
+        let display_names = data
+            .all_app_names
+            .iter()
+            .chain(app_usage.iter().map(|u| &u.app_name))
+            .map(|app_name| (app_name.clone(), self.icon_cache.display_name(app_name)))
+            .collect();
+        self.categories_view.set_display_names(display_names);
+
         // 将数据加载到视图
-        self.categories_view
-            .load_data(category_usage, categories, all_apps, app_usage);
+        self.categories_view.load_data(data, app_usage);
+    }
+
+    /// 只刷新单个分类的使用统计和未分类应用列表
+    fn refresh_single_category(&mut self, category_id: i64) {
+        let (start, end) = self.get_stats_time_range_bounds();
+        let result = self.runtime.block_on(async {
+            let usage = self
+                .repo
+                .get_single_category_usage(category_id, start, end)
+                .await?;
+            let unclassified = self.repo.categories().get_unclassified_app_names().await?;
+            Ok::<_, tail_core::DbError>((usage, unclassified))
+        });
+
+        match result {
+            Ok((Some(usage), unclassified)) => {
+                if self.categories_view.splice_category_usage(usage) {
+                    self.categories_view.set_unclassified_apps(unclassified);
+                } else {
+                    self.categories_last_refresh = None;
+                }
+            }
+            // 分类已不存在，重新加载整个页面
+            Ok((None, _)) => self.categories_last_refresh = None,
+            Err(e) => {
+                tracing::warn!(error = %e, category_id, "刷新分类统计失败");
+                self.categories_last_refresh = None;
+            }
+        }
     }
 
     /// 处理分类视图操作
@@ -540,6 +1276,7 @@ impl TaiLApp {
                     )
                     .await
                 });
+                self.refresh_single_category(category_id);
             }
             CategoryAction::LoadAppCategories(app_name) => {
                 if let Ok(categories) = self.runtime.block_on(async {
@@ -562,6 +1299,11 @@ impl eframe::App for TaiLApp {
             self.theme_applied = true;
         }
 
+        // 预加载最近使用过的应用图标
+        if !self.icons_prewarmed {
+            self.prewarm_icons(ctx);
+        }
+
         // 检测窗口焦点状态变化
         let has_focus = ctx.input(|i| i.focused);
         let just_got_focus = has_focus && !self.was_visible;
@@ -582,12 +1324,18 @@ impl eframe::App for TaiLApp {
         // 注意：当窗口没有焦点时，不请求重绘
         // 当用户切换回来时，系统会自动触发重绘
 
+        self.check_db_health();
+
         // 根据当前视图刷新对应数据
         match self.current_view {
+            // 显示引导页时各统计页面必然为空，不必查询
+            _ if self.shows_onboarding() => {}
             View::Dashboard => self.refresh_dashboard_data(),
             View::Statistics => self.refresh_stats_data(),
             View::Categories => self.refresh_dashboard_data(), // 分类页面也刷新仪表板数据
             View::Details => self.refresh_details_data(),      // 详细页面刷新详细数据
+            View::Timeline => self.refresh_timeline_data(),    // 时间线页面刷新当天会话
+            View::Calendar => self.refresh_calendar_data(),    // 日历页面刷新最近一年的活动
             View::Settings => self.refresh_dashboard_data(),   // 设置页面也刷新仪表板数据
         }
 
@@ -601,6 +1349,11 @@ impl eframe::App for TaiLApp {
             self.set_app_alias(app_name, alias);
         }
 
+        // 处理清理重复数据对话框
+        if self.duplicate_cleanup_dialog.show(ctx, &self.theme) {
+            self.remove_duplicate_events();
+        }
+
         // 根据导航模式显示导航栏
         let new_view = match self.navigation_mode {
             NavigationMode::Sidebar => {
@@ -627,13 +1380,26 @@ impl eframe::App for TaiLApp {
                     .inner_margin(egui::Margin::same(self.theme.spacing)),
             )
             .show(ctx, |ui| {
+                let breadcrumb = self.navigation_state.get_breadcrumb();
+                AppHeader::new(&self.db_health, &self.theme)
+                    .breadcrumb(&breadcrumb)
+                    .tracker_warning(self.tracker_warning.as_deref())
+                    .show(ui);
+
                 match self.current_view {
+                    _ if self.shows_onboarding() => {
+                        if let Some(action) = OnboardingView::new(&self.theme).show(ui) {
+                            self.handle_onboarding_action(action);
+                        }
+                    }
                     View::Dashboard => {
                         let mut view = DashboardView::new(
                             &self.dashboard_usage_cache,
                             &self.theme,
                             &mut self.icon_cache,
-                        );
+                        )
+                        .with_wall_clock(self.dashboard_wall_clock_seconds)
+                        .with_color_map(self.chart_color_map.clone());
                         view.show(ui);
                     }
                     View::Statistics => {
@@ -643,13 +1409,30 @@ impl eframe::App for TaiLApp {
                             &self.theme,
                             &mut self.icon_cache,
                             self.stats_use_stacked_view,
-                        );
+                        )
+                        .with_comparison(
+                            self.stats_compare_mode,
+                            self.stats_compare_period,
+                            &self.stats_previous_usage_cache,
+                        )
+                        .with_color_map(self.chart_color_map.clone());
                         let (new_range, use_stacked) = view.show(ui);
+                        let compare_mode = view.compare_mode();
+                        let compare_period = view.compare_period();
                         if let Some(range) = new_range {
                             self.stats_time_range = range;
                             self.stats_last_refresh = None; // 强制刷新
                         }
-                        self.stats_use_stacked_view = use_stacked;
+                        if compare_mode != self.stats_compare_mode
+                            || compare_period != self.stats_compare_period
+                        {
+                            self.stats_compare_mode = compare_mode;
+                            self.stats_compare_period = compare_period;
+                            self.stats_last_refresh = None; // 强制刷新
+                        }
+                        if use_stacked != self.stats_use_stacked_view {
+                            self.set_stats_stacked_view(use_stacked);
+                        }
                     }
                     View::Categories => {
                         // 检查是否需要刷新数据
@@ -674,20 +1457,38 @@ impl eframe::App for TaiLApp {
                     }
                     View::Details => {
                         // 更新数据并显示持久化的详细视图
-                        self.details_view.update_data(&self.details_usage_cache);
+                        self.details_view.update_data(&self.details_events_cache);
                         self.details_view
                             .show(ui, &self.theme, &mut self.icon_cache);
                     }
+                    View::Timeline => {
+                        if self
+                            .timeline_view
+                            .show(ui, &self.theme, &mut self.icon_cache)
+                            .is_some()
+                        {
+                            self.timeline_last_refresh = None; // 日期变化，强制刷新
+                        }
+                    }
+                    View::Calendar => {
+                        self.calendar_view.show(ui, &self.theme);
+                    }
                     View::Settings => {
                         let view = SettingsView::new(
                             &self.daily_goals_cache,
                             self.theme_type,
                             self.default_stats_view,
+                            self.afk_threshold_secs,
+                            self.details_max_records,
+                            self.navigation_state.week_numbering,
+                            self.display_name_mode,
+                            self.duration_locale,
+                            &self.db_path,
                             &self.theme,
                         );
                         match view.show(ui) {
                             SettingsAction::AddGoal => {
-                                self.add_goal_dialog.open();
+                                self.open_add_goal_dialog();
                             }
                             SettingsAction::DeleteGoal(app_name) => {
                                 self.delete_daily_goal(&app_name);
@@ -700,9 +1501,42 @@ impl eframe::App for TaiLApp {
                                 // 应用新的默认视图
                                 self.apply_default_stats_view();
                             }
+                            SettingsAction::ChangeAfkThreshold(secs) => {
+                                self.set_afk_threshold(secs);
+                            }
+                            SettingsAction::ChangeDetailsMaxRecords(max_records) => {
+                                self.set_details_max_records(max_records);
+                            }
+                            SettingsAction::ChangeWeekNumbering(numbering) => {
+                                self.set_week_numbering(numbering);
+                            }
+                            SettingsAction::ChangeDisplayNameMode(mode) => {
+                                self.set_display_name_mode(mode);
+                            }
+                            SettingsAction::ChangeDurationLocale(locale) => {
+                                self.set_duration_locale(locale);
+                            }
                             SettingsAction::ManageAliases => {
                                 self.open_alias_management();
                             }
+                            SettingsAction::ImportAliases => {
+                                self.import_aliases();
+                            }
+                            SettingsAction::ExportAliases => {
+                                self.export_aliases();
+                            }
+                            SettingsAction::ChangeDatabase => {
+                                self.pick_database();
+                            }
+                            SettingsAction::BackupDatabase => {
+                                self.backup_database();
+                            }
+                            SettingsAction::CleanDuplicates => {
+                                self.preview_duplicate_cleanup();
+                            }
+                            SettingsAction::ImportAwCategories => {
+                                self.import_aw_categories();
+                            }
                             SettingsAction::None => {}
                         }
                     }
@@ -710,3 +1544,49 @@ impl eframe::App for TaiLApp {
             });
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_db_path(name: &str) -> String {
+        std::env::temp_dir()
+            .join(format!("tail-gui-{}-{}.db", name, std::process::id()))
+            .to_string_lossy()
+            .into_owned()
+    }
+
+    #[test]
+    fn test_switch_database_invalidates_caches() {
+        let first = temp_db_path("first");
+        let second = temp_db_path("second");
+        let mut app = TaiLApp::with_config(&DbConfig {
+            path: first.clone(),
+        });
+
+        let now = Utc::now();
+        app.dashboard_last_refresh = Some(now);
+        app.stats_last_refresh = Some(now);
+        app.details_last_refresh = Some(now);
+        app.categories_last_refresh = Some(now);
+        app.timeline_last_refresh = Some(now);
+        app.calendar_last_refresh = Some(now);
+
+        // 无法打开的路径不影响当前状态
+        app.switch_database("/nonexistent/tail/tail.db".to_string());
+        assert_eq!(app.db_path, first);
+        assert_eq!(app.dashboard_last_refresh, Some(now));
+
+        app.switch_database(second.clone());
+        assert_eq!(app.db_path, second);
+        assert_eq!(app.dashboard_last_refresh, None);
+        assert_eq!(app.stats_last_refresh, None);
+        assert_eq!(app.details_last_refresh, None);
+        assert_eq!(app.categories_last_refresh, None);
+        assert_eq!(app.timeline_last_refresh, None);
+        assert_eq!(app.calendar_last_refresh, None);
+
+        std::fs::remove_file(&first).ok();
+        std::fs::remove_file(&second).ok();
+    }
+}
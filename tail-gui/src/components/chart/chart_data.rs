@@ -2,9 +2,10 @@
 //!
 //! 提供统一的图表数据接口，支持不同时间粒度和分组模式
 
-use chrono::{DateTime, Datelike, Local, Timelike, Utc};
+use chrono::{DateTime, Local, Timelike, Utc};
 use std::collections::HashMap;
 use tail_core::AppUsage;
+use tail_core::time::{TimeGranularity, TimeSlots};
 
 /// 时间粒度
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -34,6 +35,18 @@ impl ChartTimeGranularity {
     }
 }
 
+impl From<TimeGranularity> for ChartTimeGranularity {
+    fn from(granularity: TimeGranularity) -> Self {
+        match granularity {
+            TimeGranularity::Year => Self::Year,
+            TimeGranularity::Month => Self::Month,
+            TimeGranularity::Week => Self::Week,
+            TimeGranularity::Day => Self::Day,
+            TimeGranularity::Hour => Self::Hour,
+        }
+    }
+}
+
 /// 分组模式
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ChartGroupMode {
@@ -71,6 +84,18 @@ impl ChartTimeSlot {
         *self.group_durations.entry(group_name).or_insert(0) += seconds;
     }
 
+    /// 各分组在该时间槽中所占的比例（总和为 1），没有数据时返回空表
+    pub fn normalized_breakdown(&self) -> HashMap<String, f32> {
+        if self.total_seconds <= 0 {
+            return HashMap::new();
+        }
+
+        self.group_durations
+            .iter()
+            .map(|(group, &seconds)| (group.clone(), seconds as f32 / self.total_seconds as f32))
+            .collect()
+    }
+
     /// 获取该时间槽内时长最高的分组
     pub fn top_groups(&self, limit: usize) -> Vec<(String, i64)> {
         let mut groups: Vec<_> = self
@@ -78,7 +103,8 @@ impl ChartTimeSlot {
             .iter()
             .map(|(k, v)| (k.clone(), *v))
             .collect();
-        groups.sort_by(|a, b| b.1.cmp(&a.1));
+        // 时长相同时按名称排序，避免 HashMap 迭代顺序导致结果不稳定
+        groups.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
         groups.truncate(limit);
         groups
     }
@@ -107,6 +133,22 @@ impl ChartData {
         }
     }
 
+    /// 从核心层的时间槽聚合结果转换
+    ///
+    /// 保留各时间槽的标签、索引和分组时长，粒度取自聚合结果，
+    /// 视图可以直接渲染 `tail_core` 的聚合结果而不必再经过 [`ChartDataBuilder`] 分桶。
+    pub fn from_time_slots(slots: &TimeSlots, group_mode: ChartGroupMode) -> Self {
+        let mut data = Self::new(slots.granularity().into(), group_mode);
+        for slot in slots.slots() {
+            let mut chart_slot = ChartTimeSlot::new(slot.label().to_string(), slot.index());
+            for (group_name, &seconds) in slot.group_durations() {
+                chart_slot.add_group(group_name.clone(), seconds);
+            }
+            data.add_slot(chart_slot);
+        }
+        data
+    }
+
     /// 添加时间槽
     pub fn add_slot(&mut self, slot: ChartTimeSlot) {
         self.total_seconds += slot.total_seconds;
@@ -328,7 +370,7 @@ impl<'a> ChartDataBuilder<'a> {
                 }
 
                 let local_time = event.timestamp.with_timezone(&Local);
-                let weekday = local_time.weekday().num_days_from_monday() as usize;
+                let weekday = TimeGranularity::Week.bucket_index(local_time);
                 let seconds = event.duration_secs;
 
                 match self.group_mode {
@@ -376,17 +418,10 @@ impl<'a> ChartDataBuilder<'a> {
                 }
 
                 let local_time = event.timestamp.with_timezone(&Local);
-                let day = local_time.day();
-
-                // 边界检查：确保日期有效 (1-31)
-                if day == 0 {
-                    continue;
-                }
-
-                let week = ((day - 1) / 7) + 1;
+                let week = TimeGranularity::Month.bucket_index(local_time) as u32 + 1;
 
                 // 边界检查：周数应该在 1-6 范围内
-                if week == 0 || week > 6 {
+                if week > 6 {
                     continue;
                 }
 
@@ -448,13 +483,7 @@ impl<'a> ChartDataBuilder<'a> {
                 }
 
                 let local_time = event.timestamp.with_timezone(&Local);
-                let month = local_time.month() as usize;
-
-                // 边界检查：确保月份索引在有效范围内 (1-12 -> 0-11)
-                if month == 0 || month > 12 {
-                    continue;
-                }
-                let month_idx = month - 1;
+                let month_idx = TimeGranularity::Year.bucket_index(local_time);
 
                 let seconds = event.duration_secs;
                 match self.group_mode {
@@ -504,7 +533,7 @@ impl<'a> ChartDataBuilder<'a> {
                 }
 
                 let local_time = event.timestamp.with_timezone(&Local);
-                let minute = local_time.minute() as usize;
+                let minute = TimeGranularity::Hour.bucket_index(local_time);
 
                 let seconds = event.duration_secs;
                 match self.group_mode {
@@ -533,10 +562,24 @@ impl<'a> ChartDataBuilder<'a> {
     }
 }
 
+/// 解析 `#RRGGBB`（`#` 可省略）格式的颜色字符串
+pub fn parse_hex_color(hex: &str) -> Option<egui::Color32> {
+    let hex = hex.trim_start_matches('#');
+    if hex.len() != 6 {
+        return None;
+    }
+    let r = u8::from_str_radix(&hex[0..2], 16).ok()?;
+    let g = u8::from_str_radix(&hex[2..4], 16).ok()?;
+    let b = u8::from_str_radix(&hex[4..6], 16).ok()?;
+    Some(egui::Color32::from_rgb(r, g, b))
+}
+
 /// 分类颜色配置
 #[derive(Debug, Clone)]
 pub struct CategoryColorMap {
     colors: HashMap<String, egui::Color32>,
+    /// 用户为单个应用指定的颜色，优先于其他所有颜色
+    overrides: HashMap<String, egui::Color32>,
     default_colors: Vec<egui::Color32>,
     other_color: egui::Color32,
 }
@@ -564,6 +607,7 @@ impl Default for CategoryColorMap {
 
         Self {
             colors,
+            overrides: HashMap::new(),
             default_colors,
             other_color: egui::Color32::from_gray(150),
         }
@@ -579,8 +623,17 @@ impl CategoryColorMap {
         self.colors.insert(category, color);
     }
 
+    /// 设置用户为应用指定的颜色，分配颜色时优先于预设颜色和调色板
+    pub fn with_overrides(mut self, overrides: HashMap<String, egui::Color32>) -> Self {
+        self.overrides = overrides;
+        self
+    }
+
     pub fn get(&self, category: &str) -> Option<egui::Color32> {
-        self.colors.get(category).copied()
+        self.overrides
+            .get(category)
+            .or_else(|| self.colors.get(category))
+            .copied()
     }
 
     /// 获取"其他"分类的颜色
@@ -592,7 +645,7 @@ impl CategoryColorMap {
     pub fn assign_colors(&self, groups: &[String]) -> HashMap<String, egui::Color32> {
         let mut result = HashMap::new();
         for (idx, group) in groups.iter().enumerate() {
-            let color = self.colors.get(group).copied().unwrap_or_else(|| {
+            let color = self.get(group).unwrap_or_else(|| {
                 self.default_colors
                     .get(idx % self.default_colors.len())
                     .copied()
@@ -603,3 +656,106 @@ impl CategoryColorMap {
         result
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tail_core::models::TimeNavigationLevel;
+
+    #[test]
+    fn test_granularity_for_navigation_level() {
+        let cases = [
+            (TimeNavigationLevel::Year, ChartTimeGranularity::Year),
+            (TimeNavigationLevel::Month, ChartTimeGranularity::Year),
+            (TimeNavigationLevel::Week, ChartTimeGranularity::Month),
+            (TimeNavigationLevel::Day, ChartTimeGranularity::Week),
+            (TimeNavigationLevel::Hour, ChartTimeGranularity::Day),
+        ];
+        for (level, expected) in cases {
+            let granularity = ChartTimeGranularity::from(TimeGranularity::for_range(level));
+            assert_eq!(granularity, expected, "level {:?}", level);
+        }
+    }
+
+    #[test]
+    fn test_from_time_slots() {
+        use tail_core::time::TimeSlot;
+
+        let mut slots = TimeSlots::new(TimeGranularity::Week);
+        let mut monday = TimeSlot::new("周一".to_string(), 0);
+        monday.add_duration("code", 3600);
+        monday.add_duration("firefox", 600);
+        slots.add_slot(monday);
+        slots.add_slot(TimeSlot::new("周二".to_string(), 1));
+        let mut wednesday = TimeSlot::new("周三".to_string(), 2);
+        wednesday.add_duration("code", 1200);
+        slots.add_slot(wednesday);
+
+        let data = ChartData::from_time_slots(&slots, ChartGroupMode::ByApp);
+        assert_eq!(data.granularity, ChartTimeGranularity::Week);
+        assert_eq!(data.group_mode, ChartGroupMode::ByApp);
+        assert_eq!(data.total_seconds, 5400);
+        assert_eq!(data.max_seconds(), 4200);
+        assert_eq!(data.time_slots.len(), 3);
+
+        for (chart_slot, slot) in data.time_slots.iter().zip(slots.slots()) {
+            assert_eq!(chart_slot.label, slot.label());
+            assert_eq!(chart_slot.index, slot.index());
+            assert_eq!(chart_slot.total_seconds, slot.duration().as_seconds());
+            assert_eq!(&chart_slot.group_durations, slot.group_durations());
+        }
+        assert_eq!(data.time_slots[0].group_durations["firefox"], 600);
+        assert!(data.time_slots[1].group_durations.is_empty());
+    }
+
+    #[test]
+    fn test_normalized_breakdown_sums_to_one() {
+        let mut slot = ChartTimeSlot::new("09:00".to_string(), 9);
+        slot.add_group("firefox".to_string(), 1200);
+        slot.add_group("code".to_string(), 600);
+        slot.add_group("kitty".to_string(), 300);
+        slot.add_group("firefox".to_string(), 300);
+
+        let breakdown = slot.normalized_breakdown();
+        assert_eq!(breakdown.len(), 3);
+        assert!((breakdown["firefox"] - 0.625).abs() < 1e-6);
+        let sum: f32 = breakdown.values().sum();
+        assert!((sum - 1.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_app_color_override_ignores_position() {
+        let overrides = HashMap::from([("code".to_string(), egui::Color32::GREEN)]);
+        let map = CategoryColorMap::default().with_overrides(overrides);
+
+        for groups in [
+            vec!["code".to_string(), "firefox".to_string()],
+            vec![
+                "firefox".to_string(),
+                "kitty".to_string(),
+                "code".to_string(),
+            ],
+        ] {
+            let colors = map.assign_colors(&groups);
+            assert_eq!(colors["code"], egui::Color32::GREEN);
+        }
+        // 未指定颜色的应用仍按位置使用调色板
+        let colors = map.assign_colors(&["firefox".to_string()]);
+        assert_eq!(colors["firefox"], egui::Color32::from_rgb(74, 144, 226));
+    }
+
+    #[test]
+    fn test_parse_hex_color() {
+        assert_eq!(
+            parse_hex_color("#34A853"),
+            Some(egui::Color32::from_rgb(52, 168, 83))
+        );
+        assert_eq!(parse_hex_color("green"), None);
+    }
+
+    #[test]
+    fn test_normalized_breakdown_empty_slot() {
+        let slot = ChartTimeSlot::new("09:00".to_string(), 9);
+        assert!(slot.normalized_breakdown().is_empty());
+    }
+}
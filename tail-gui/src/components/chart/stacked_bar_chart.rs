@@ -8,6 +8,13 @@ use std::collections::HashMap;
 use super::chart_data::{CategoryColorMap, ChartData, ChartTimeGranularity};
 use crate::theme::TaiLTheme;
 
+/// 柱子最小宽度（像素）
+const MIN_BAR_WIDTH: f32 = 2.0;
+/// 柱子最大宽度（像素）
+const MAX_BAR_WIDTH: f32 = 48.0;
+/// 柱间距与柱宽的比例
+const BAR_GAP_RATIO: f32 = 0.4;
+
 /// 堆叠柱形图配置
 pub struct StackedBarChartConfig {
     /// 分组颜色映射
@@ -22,6 +29,8 @@ pub struct StackedBarChartConfig {
     pub show_grid_lines: bool,
     /// 是否显示悬停高亮
     pub show_hover_highlight: bool,
+    /// 是否按百分比显示（每个柱子都是满高，分段为各分组的占比）
+    pub normalize: bool,
 }
 
 impl Default for StackedBarChartConfig {
@@ -33,6 +42,7 @@ impl Default for StackedBarChartConfig {
             show_y_axis: true,
             show_grid_lines: true,
             show_hover_highlight: true,
+            normalize: false,
         }
     }
 }
@@ -77,27 +87,26 @@ impl<'a> StackedBarChart<'a> {
         let y_axis_width = if self.config.show_y_axis { 45.0 } else { 0.0 };
         let y_tick_count = 5;
 
-        // Y 轴时间格式化函数 - 使用统一的时间格式化模块
-        let format_y_tick = |seconds: i64| -> String {
-            tail_core::time::format::TimeFormatter::format_y_axis(seconds)
+        // Y 轴格式化函数 - 百分比模式显示占比，否则使用统一的时间格式化模块
+        let normalize = self.config.normalize;
+        let format_y_tick = move |value: i64| -> String {
+            if normalize {
+                format!("{}%", value)
+            } else {
+                tail_core::time::format::TimeFormatter::format_y_axis(value)
+            }
         };
 
-        // 计算 Y 轴刻度值
+        // 计算 Y 轴刻度值（百分比模式下刻度为 0-100）
+        let y_axis_max = if normalize { 100 } else { max_seconds };
         let y_ticks: Vec<i64> = (0..y_tick_count)
-            .map(|i| max_seconds * i / (y_tick_count - 1))
+            .map(|i| y_axis_max * i / (y_tick_count - 1))
             .collect();
 
         // 获取所有分组并分配颜色
         let all_groups = self.data.all_groups();
         let group_colors = self.config.color_map.assign_colors(&all_groups);
 
-        // 根据时间粒度确定柱子宽度
-        let (bar_width, bar_gap) = self.calculate_bar_sizes();
-
-        let total_chart_width = y_axis_width
-            + bar_width * self.data.time_slots.len() as f32
-            + bar_gap * (self.data.time_slots.len() as f32 - 1.0);
-
         ui.vertical(|ui| {
             // 图例区域
             if self.config.show_legend && !all_groups.is_empty() {
@@ -106,6 +115,14 @@ impl<'a> StackedBarChart<'a> {
 
             // 柱状图区域
             let available_width = ui.available_width();
+            let slot_count = self.data.time_slots.len();
+
+            // 根据可用宽度和时间槽数量确定柱子宽度
+            let (bar_width, bar_gap) =
+                Self::calculate_bar_sizes(available_width - y_axis_width, slot_count);
+            let total_chart_width =
+                y_axis_width + bar_width * slot_count as f32 + bar_gap * (slot_count as f32 - 1.0);
+
             let chart_height = self.config.max_bar_height;
 
             // Y轴位置信息，用于后续绘制X轴
@@ -178,15 +195,29 @@ impl<'a> StackedBarChart<'a> {
         hovered_slot
     }
 
-    /// 根据时间粒度计算柱子尺寸
-    fn calculate_bar_sizes(&self) -> (f32, f32) {
-        match self.data.granularity {
-            ChartTimeGranularity::Year => (24.0, 12.0),
-            ChartTimeGranularity::Month => (30.0, 10.0),
-            ChartTimeGranularity::Week => (40.0, 15.0),
-            ChartTimeGranularity::Day => (18.0, 6.0),
-            ChartTimeGranularity::Hour => (4.0, 2.0),
+    /// 根据可用宽度和时间槽数量计算柱子尺寸，返回 `(柱宽, 柱间距)`
+    ///
+    /// 柱宽限制在 [`MIN_BAR_WIDTH`, `MAX_BAR_WIDTH`] 之间，间距按柱宽比例计算；
+    /// 宽度不足时优先压缩间距，只有在最小柱宽也放不下时才会超出可用宽度。
+    fn calculate_bar_sizes(available_width: f32, slot_count: usize) -> (f32, f32) {
+        if slot_count == 0 {
+            return (MIN_BAR_WIDTH, 0.0);
         }
+
+        let slots = slot_count as f32;
+        let available_width = available_width.max(0.0);
+        // n 个柱子 + (n - 1) 个间距刚好占满可用宽度
+        let bar_width = (available_width / (slots + BAR_GAP_RATIO * (slots - 1.0)))
+            .clamp(MIN_BAR_WIDTH, MAX_BAR_WIDTH);
+
+        let bar_gap = if slot_count > 1 {
+            let max_gap = ((available_width - bar_width * slots) / (slots - 1.0)).max(0.0);
+            (bar_width * BAR_GAP_RATIO).min(max_gap)
+        } else {
+            0.0
+        };
+
+        (bar_width, bar_gap)
     }
 
     /// 显示图例
@@ -300,10 +331,14 @@ impl<'a> StackedBarChart<'a> {
         bar_gap: f32,
         group_colors: &HashMap<String, Color32>,
     ) -> BarDrawResult {
-        // 柱子高度 = (该小时总时长 / 最大时长) * 图表高度
+        // 柱子高度 = (该小时总时长 / 最大时长) * 图表高度，百分比模式下为满高
         // 如果没有数据，使用最小高度2像素
         let bar_height = if slot.total_seconds > 0 {
-            (slot.total_seconds as f32 / max_seconds as f32) * chart_height
+            if self.config.normalize {
+                chart_height
+            } else {
+                (slot.total_seconds as f32 / max_seconds as f32) * chart_height
+            }
         } else {
             2.0 // 空柱子，显示有这个位置
         };
@@ -324,21 +359,21 @@ impl<'a> StackedBarChart<'a> {
             if slot.total_seconds > 0 {
                 let mut current_y = bottom_y;
 
-                // 按时长从大到小排序分组（大的在底部）
-                let mut groups: Vec<_> = slot.group_durations.iter().collect();
-                groups.sort_by(|a, b| b.1.cmp(a.1));
+                // 按占比从大到小排序分组（大的在底部）
+                let mut groups: Vec<_> = slot.normalized_breakdown().into_iter().collect();
+                groups.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
 
-                for (group, &seconds) in groups {
-                    if seconds <= 0 {
+                for (group, fraction) in groups {
+                    if fraction <= 0.0 {
                         continue;
                     }
 
-                    // 该应用段的高度 = (该应用时长 / 总时长) * 柱子高度
-                    let segment_height = (seconds as f32 / slot.total_seconds as f32) * bar_height;
+                    // 该应用段的高度 = 该应用占比 * 柱子高度
+                    let segment_height = fraction * bar_height;
                     let segment_top_y = current_y - segment_height;
 
                     let color = group_colors
-                        .get(&**group)
+                        .get(&group)
                         .copied()
                         .unwrap_or(self.config.color_map.other_color());
 
@@ -534,3 +569,43 @@ impl<'a> StackedBarTooltip<'a> {
             });
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn total_width(bar_width: f32, bar_gap: f32, slot_count: usize) -> f32 {
+        bar_width * slot_count as f32 + bar_gap * (slot_count as f32 - 1.0)
+    }
+
+    #[test]
+    fn test_bar_sizes_fit_available_width() {
+        for (available_width, slot_count) in [(800.0, 60), (300.0, 60), (1600.0, 24), (500.0, 7)] {
+            let (bar_width, bar_gap) =
+                StackedBarChart::calculate_bar_sizes(available_width, slot_count);
+            assert!((MIN_BAR_WIDTH..=MAX_BAR_WIDTH).contains(&bar_width));
+            assert!(bar_gap >= 0.0);
+            assert!(total_width(bar_width, bar_gap, slot_count) <= available_width + 0.01);
+        }
+
+        // 宽窗口下柱子随宽度变宽，但不超过上限
+        let (narrow, _) = StackedBarChart::calculate_bar_sizes(400.0, 24);
+        let (wide, _) = StackedBarChart::calculate_bar_sizes(900.0, 24);
+        assert!(wide > narrow);
+        assert_eq!(
+            StackedBarChart::calculate_bar_sizes(5000.0, 3).0,
+            MAX_BAR_WIDTH
+        );
+    }
+
+    #[test]
+    fn test_bar_sizes_respect_min_width() {
+        let (bar_width, bar_gap) = StackedBarChart::calculate_bar_sizes(100.0, 60);
+        assert_eq!(bar_width, MIN_BAR_WIDTH);
+        assert_eq!(bar_gap, 0.0);
+
+        let (bar_width, bar_gap) = StackedBarChart::calculate_bar_sizes(150.0, 60);
+        assert_eq!(bar_width, MIN_BAR_WIDTH);
+        assert!(total_width(bar_width, bar_gap, 60) <= 150.0 + 0.01);
+    }
+}
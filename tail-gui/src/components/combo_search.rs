@@ -0,0 +1,137 @@
+//! TaiL GUI - 可搜索的选择列表组件
+
+use egui::{RichText, ScrollArea, TextEdit, Ui};
+
+use crate::theme::TaiLTheme;
+
+/// 判断条目是否匹配过滤词
+///
+/// 不区分大小写的子串匹配；过滤词为空（或只有空白）时匹配所有条目。
+pub fn matches_filter(item: &str, query: &str) -> bool {
+    let query = query.trim();
+    query.is_empty() || item.to_lowercase().contains(&query.to_lowercase())
+}
+
+/// 按过滤词筛选条目，保持原有顺序
+pub fn filter_items<'a>(items: &'a [String], query: &str) -> Vec<&'a String> {
+    items
+        .iter()
+        .filter(|item| matches_filter(item, query))
+        .collect()
+}
+
+/// 可搜索的选择列表
+///
+/// 上方是过滤输入框，下方是过滤后的可滚动列表。过滤词由调用方持有，以便跨帧保留。
+pub struct ComboSearch<'a> {
+    /// 列表滚动区域的 id
+    id_source: &'a str,
+    /// 过滤词
+    query: &'a mut String,
+    /// 候选条目
+    items: &'a [String],
+    /// 当前选中的条目
+    selected: Option<&'a str>,
+    /// 输入框提示文字
+    hint: &'a str,
+    /// 列表最大高度
+    max_height: f32,
+    theme: &'a TaiLTheme,
+}
+
+impl<'a> ComboSearch<'a> {
+    pub fn new(
+        id_source: &'a str,
+        query: &'a mut String,
+        items: &'a [String],
+        theme: &'a TaiLTheme,
+    ) -> Self {
+        Self {
+            id_source,
+            query,
+            items,
+            selected: None,
+            hint: "搜索...",
+            max_height: 200.0,
+            theme,
+        }
+    }
+
+    pub fn selected(mut self, selected: Option<&'a str>) -> Self {
+        self.selected = selected;
+        self
+    }
+
+    pub fn hint_text(mut self, hint: &'a str) -> Self {
+        self.hint = hint;
+        self
+    }
+
+    pub fn max_height(mut self, max_height: f32) -> Self {
+        self.max_height = max_height;
+        self
+    }
+
+    /// 渲染组件，返回本帧点击选中的条目
+    pub fn show(self, ui: &mut Ui) -> Option<String> {
+        ui.add(
+            TextEdit::singleline(self.query)
+                .hint_text(self.hint)
+                .desired_width(f32::INFINITY),
+        );
+
+        let matches = filter_items(self.items, self.query);
+        let mut picked = None;
+
+        ScrollArea::vertical()
+            .id_source(self.id_source)
+            .max_height(self.max_height)
+            .show(ui, |ui| {
+                if matches.is_empty() {
+                    ui.label(
+                        RichText::new("无匹配项")
+                            .size(self.theme.small_size)
+                            .color(self.theme.secondary_text_color),
+                    );
+                }
+                for item in matches {
+                    if ui
+                        .selectable_label(self.selected == Some(item.as_str()), item)
+                        .clicked()
+                    {
+                        picked = Some(item.clone());
+                    }
+                }
+            });
+
+        picked
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn items() -> Vec<String> {
+        ["firefox", "Code", "kitty", "FireFox-dev"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect()
+    }
+
+    #[test]
+    fn test_empty_query_returns_all() {
+        let items = items();
+        assert_eq!(filter_items(&items, ""), items.iter().collect::<Vec<_>>());
+        assert_eq!(filter_items(&items, "   ").len(), items.len());
+    }
+
+    #[test]
+    fn test_filter_case_insensitive_substring() {
+        let items = items();
+        assert_eq!(filter_items(&items, "FIRE"), vec!["firefox", "FireFox-dev"]);
+        assert_eq!(filter_items(&items, "od"), vec!["Code"]);
+        assert_eq!(filter_items(&items, " kit "), vec!["kitty"]);
+        assert!(filter_items(&items, "steam").is_empty());
+    }
+}
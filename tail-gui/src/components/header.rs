@@ -2,6 +2,9 @@
 
 use egui::{Color32, Pos2, Response, Rounding, Sense, Ui, Vec2, Widget};
 
+use chrono::{DateTime, Local, Utc};
+use tail_core::db::DbHealth;
+
 use crate::theme::TaiLTheme;
 
 /// 统计卡片组件
@@ -350,3 +353,139 @@ impl<'a> Widget for EmptyState<'a> {
         response
     }
 }
+
+/// 格式化头部时钟显示（`HH:MM:SS`）
+pub fn format_header_clock(now: DateTime<Local>) -> String {
+    now.format("%H:%M:%S").to_string()
+}
+
+/// 根据追踪服务最近一次心跳生成警告文本
+///
+/// 心跳超过 `stale_secs` 秒未更新或从未记录时返回警告，否则返回 `None`。
+pub fn tracker_heartbeat_warning(
+    last: Option<DateTime<Utc>>,
+    now: DateTime<Utc>,
+    stale_secs: i64,
+) -> Option<String> {
+    match last {
+        None => Some("未检测到追踪服务".to_string()),
+        Some(last) => {
+            let elapsed = now.signed_duration_since(last).num_seconds();
+            (elapsed > stale_secs)
+                .then(|| format!("追踪服务可能未运行（{} 分钟无心跳）", (elapsed / 60).max(1)))
+        }
+    }
+}
+
+/// 应用头部状态栏组件
+///
+/// 显示在主内容区顶部：左侧为当前时间范围的面包屑，右侧为本地时钟和数据库健康状态指示器。
+pub struct AppHeader<'a> {
+    /// 数据库健康状态
+    health: &'a DbHealth,
+    /// 当前时间范围的面包屑（可选）
+    breadcrumb: Option<&'a str>,
+    /// 追踪服务警告（可选）
+    tracker_warning: Option<&'a str>,
+    /// 主题
+    theme: &'a TaiLTheme,
+}
+
+impl<'a> AppHeader<'a> {
+    pub fn new(health: &'a DbHealth, theme: &'a TaiLTheme) -> Self {
+        Self {
+            health,
+            breadcrumb: None,
+            tracker_warning: None,
+            theme,
+        }
+    }
+
+    pub fn breadcrumb(mut self, breadcrumb: &'a str) -> Self {
+        self.breadcrumb = Some(breadcrumb);
+        self
+    }
+
+    pub fn tracker_warning(mut self, warning: Option<&'a str>) -> Self {
+        self.tracker_warning = warning;
+        self
+    }
+
+    /// 显示状态栏
+    pub fn show(self, ui: &mut Ui) {
+        ui.horizontal(|ui| {
+            if let Some(breadcrumb) = self.breadcrumb {
+                ui.label(
+                    egui::RichText::new(format!("📍 {}", breadcrumb))
+                        .size(self.theme.small_size)
+                        .color(self.theme.secondary_text_color),
+                );
+            }
+
+            ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                let (color, text) = if self.health.ok {
+                    (self.theme.success_color, "数据库正常")
+                } else {
+                    (self.theme.danger_color, "数据库不可用")
+                };
+
+                let label = ui.label(
+                    egui::RichText::new(text)
+                        .size(self.theme.small_size)
+                        .color(self.theme.secondary_text_color),
+                );
+                if let Some(detail) = &self.health.detail {
+                    label.on_hover_text(detail);
+                }
+
+                let (rect, _) = ui.allocate_exact_size(Vec2::splat(10.0), Sense::hover());
+                ui.painter().circle_filled(rect.center(), 4.0, color);
+
+                if let Some(warning) = self.tracker_warning {
+                    ui.add_space(12.0);
+                    ui.label(
+                        egui::RichText::new(format!("⚠ {}", warning))
+                            .size(self.theme.small_size)
+                            .color(self.theme.warning_color),
+                    );
+                }
+
+                ui.add_space(12.0);
+                ui.label(
+                    egui::RichText::new(format_header_clock(Local::now()))
+                        .size(self.theme.small_size)
+                        .color(self.theme.text_color)
+                        .monospace(),
+                );
+            });
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_format_header_clock() {
+        let now = Local.with_ymd_and_hms(2024, 3, 5, 9, 7, 3).unwrap();
+        assert_eq!(format_header_clock(now), "09:07:03");
+    }
+
+    #[test]
+    fn test_tracker_heartbeat_warning() {
+        let now = Utc.with_ymd_and_hms(2024, 3, 5, 9, 0, 0).unwrap();
+
+        assert!(tracker_heartbeat_warning(None, now, 60).is_some());
+        assert_eq!(
+            tracker_heartbeat_warning(Some(now - chrono::Duration::seconds(30)), now, 60),
+            None
+        );
+        assert_eq!(
+            tracker_heartbeat_warning(Some(now - chrono::Duration::minutes(10)), now, 60)
+                .as_deref(),
+            Some("追踪服务可能未运行（10 分钟无心跳）")
+        );
+    }
+}
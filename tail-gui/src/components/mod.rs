@@ -3,6 +3,7 @@
 pub mod alias_dialog;
 pub mod app_card;
 pub mod chart;
+pub mod combo_search;
 pub mod header;
 pub mod hierarchical_chart;
 pub mod navigation;
@@ -16,6 +17,7 @@ pub mod top_tab;
 pub use alias_dialog::*;
 pub use app_card::*;
 pub use chart::*;
+pub use combo_search::*;
 pub use header::*;
 pub use hierarchical_chart::*;
 pub use navigation::*;
@@ -16,6 +16,8 @@ pub enum View {
     Dashboard,
     Statistics,
     Details,
+    Timeline,
+    Calendar,
     Categories,
     Settings,
 }
@@ -25,6 +27,8 @@ impl View {
         View::Dashboard,
         View::Statistics,
         View::Details,
+        View::Timeline,
+        View::Calendar,
         View::Categories,
         View::Settings,
     ];
@@ -34,6 +38,8 @@ impl View {
             View::Dashboard => "仪表板",
             View::Statistics => "统计",
             View::Details => "详细",
+            View::Timeline => "时间线",
+            View::Calendar => "日历",
             View::Categories => "分类",
             View::Settings => "设置",
         }
@@ -44,6 +50,8 @@ impl View {
             View::Dashboard => "📊",
             View::Statistics => "📈",
             View::Details => "📋",
+            View::Timeline => "🕒",
+            View::Calendar => "📅",
             View::Categories => "📂",
             View::Settings => "⚙",
         }
@@ -55,6 +63,8 @@ impl View {
             View::Dashboard => "📊",
             View::Statistics => "≣",
             View::Details => "≡",
+            View::Timeline => "🕒",
+            View::Calendar => "📅",
             View::Categories => "⌘",
             View::Settings => "⚙",
         }
@@ -4,6 +4,80 @@ use egui::{Color32, Pos2, Rect, Response, Rounding, Sense, Ui, Vec2, Widget};
 
 use crate::theme::TaiLTheme;
 
+/// 简单的水平进度条
+///
+/// 按比例绘制填充的圆角矩形，背景色取自主题
+pub struct ProgressBar {
+    /// 进度值 (0.0 - 1.0)
+    fraction: f32,
+    /// 填充颜色
+    color: Color32,
+    /// 高度
+    height: f32,
+    /// 宽度（默认占满可用宽度）
+    width: Option<f32>,
+    /// 圆角（默认为高度的一半）
+    rounding: Option<f32>,
+}
+
+impl ProgressBar {
+    pub fn new(fraction: f32, color: Color32) -> Self {
+        Self {
+            fraction: fraction.clamp(0.0, 1.0),
+            color,
+            height: 8.0,
+            width: None,
+            rounding: None,
+        }
+    }
+
+    pub fn height(mut self, height: f32) -> Self {
+        self.height = height;
+        self
+    }
+
+    pub fn width(mut self, width: f32) -> Self {
+        self.width = Some(width);
+        self
+    }
+
+    pub fn rounding(mut self, rounding: f32) -> Self {
+        self.rounding = Some(rounding);
+        self
+    }
+
+    /// 获取（已限制在 0.0 - 1.0 内的）进度值
+    pub fn fraction(&self) -> f32 {
+        self.fraction
+    }
+
+    /// 绘制进度条
+    pub fn show(self, ui: &mut Ui, theme: &TaiLTheme) -> Response {
+        let width = self.width.unwrap_or_else(|| ui.available_width());
+        let (rect, response) =
+            ui.allocate_exact_size(Vec2::new(width, self.height), Sense::hover());
+
+        if ui.is_rect_visible(rect) {
+            let painter = ui.painter();
+            let rounding = Rounding::same(self.rounding.unwrap_or(self.height / 2.0));
+
+            // 背景
+            painter.rect_filled(rect, rounding, theme.progress_background);
+
+            // 填充
+            if self.fraction > 0.0 {
+                let fill_rect = Rect::from_min_size(
+                    rect.min,
+                    Vec2::new(rect.width() * self.fraction, self.height),
+                );
+                painter.rect_filled(fill_rect, rounding, self.color);
+            }
+        }
+
+        response
+    }
+}
+
 /// 增强的进度条组件
 pub struct EnhancedProgressBar<'a> {
     /// 进度值 (0.0 - 1.0)
@@ -417,3 +491,15 @@ impl<'a> Widget for GoalProgressCard<'a> {
         response
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_progress_bar_fraction_clamp() {
+        assert_eq!(ProgressBar::new(1.5, Color32::RED).fraction(), 1.0);
+        assert_eq!(ProgressBar::new(-0.3, Color32::RED).fraction(), 0.0);
+        assert_eq!(ProgressBar::new(0.25, Color32::RED).fraction(), 0.25);
+    }
+}
@@ -62,6 +62,8 @@ impl<'a> SidebarNav<'a> {
                     View::Dashboard,
                     View::Statistics,
                     View::Details,
+                    View::Timeline,
+                    View::Calendar,
                     View::Categories,
                 ];
 
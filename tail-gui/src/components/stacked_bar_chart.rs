@@ -266,7 +266,7 @@ impl<'a> StackedBarChart<'a> {
 
                         // 按时长从大到小排序应用（确保大的在底部）
                         let mut apps: Vec<_> = slot.app_durations.iter().collect();
-                        apps.sort_by(|a, b| b.1.cmp(a.1));
+                        apps.sort_by(|a, b| b.1.cmp(a.1).then_with(|| a.0.cmp(b.0)));
 
                         for (app, &seconds) in apps {
                             if seconds <= 0 {
@@ -4,13 +4,18 @@
 //! 支持从系统图标目录和 .desktop 文件中查找图标。
 
 use egui::{ColorImage, Context, TextureHandle, TextureOptions};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::path::PathBuf;
 use std::sync::Arc;
+use std::sync::mpsc::{Receiver, Sender, channel};
+use tail_core::display::{DisplayNameMode, resolve_display_name};
 
 /// 图标大小（像素）
 const ICON_SIZE: u32 = 48;
 
+/// 后台预加载的结果：(小写应用名, 图标路径, 解码后的图片)
+type PrewarmResult = (String, Option<PathBuf>, Option<ColorImage>);
+
 /// 图标缓存
 pub struct IconCache {
     /// 缓存的纹理句柄
@@ -19,6 +24,18 @@ pub struct IconCache {
     icon_paths: HashMap<String, Option<PathBuf>>,
     /// 默认图标文本（当找不到图标时使用）
     default_labels: HashMap<String, &'static str>,
+    /// .desktop 文件中 Name 的缓存
+    desktop_names: HashMap<String, Option<String>>,
+    /// 应用名称显示模式
+    display_name_mode: DisplayNameMode,
+    /// 应用别名（应用名 -> 别名）
+    aliases: HashMap<String, String>,
+    /// 已交给后台预加载、尚未取回结果的应用（小写名称）
+    pending: HashSet<String>,
+    /// 后台预加载结果的发送端（交给后台线程）
+    prewarm_tx: Sender<PrewarmResult>,
+    /// 后台预加载结果的接收端
+    prewarm_rx: Receiver<PrewarmResult>,
 }
 
 impl Default for IconCache {
@@ -84,11 +101,103 @@ impl IconCache {
         default_labels.insert("zed".to_string(), "✨");
         default_labels.insert("dev.zed.zed".to_string(), "✨");
 
+        let (prewarm_tx, prewarm_rx) = channel();
+
         Self {
             textures: HashMap::new(),
             icon_paths: HashMap::new(),
             default_labels,
+            desktop_names: HashMap::new(),
+            display_name_mode: DisplayNameMode::default(),
+            aliases: HashMap::new(),
+            pending: HashSet::new(),
+            prewarm_tx,
+            prewarm_rx,
+        }
+    }
+
+    /// 在后台线程中为一批应用查找并解码图标，避免滚动列表时逐个同步加载
+    ///
+    /// 已缓存或正在预加载的应用会被跳过。结果在之后调用 [`Self::get_texture`] 时取回并创建纹理。
+    pub fn prewarm(&mut self, ctx: &Context, app_names: &[String]) {
+        let queued = self.queue_prewarm(app_names);
+        if queued.is_empty() {
+            return;
+        }
+
+        tracing::debug!("后台预加载 {} 个应用图标", queued.len());
+        let tx = self.prewarm_tx.clone();
+        let ctx = ctx.clone();
+        std::thread::spawn(move || {
+            for app_name in queued {
+                let icon_path = Self::find_icon(&app_name);
+                let image = icon_path.as_ref().and_then(Self::load_image);
+                if tx.send((app_name, icon_path, image)).is_err() {
+                    return;
+                }
+            }
+            ctx.request_repaint();
+        });
+    }
+
+    /// 把尚未缓存、也不在预加载中的应用标记为待加载，返回新加入的应用（小写，去重）
+    fn queue_prewarm(&mut self, app_names: &[String]) -> Vec<String> {
+        let mut queued = Vec::new();
+        for app_name in app_names {
+            let name_lower = app_name.to_lowercase();
+            if name_lower.is_empty() || self.textures.contains_key(&name_lower) {
+                continue;
+            }
+            if self.pending.insert(name_lower.clone()) {
+                queued.push(name_lower);
+            }
         }
+        queued
+    }
+
+    /// 取回后台预加载完成的图标并创建纹理
+    fn collect_prewarmed(&mut self, ctx: &Context) {
+        while let Ok((app_name, icon_path, image)) = self.prewarm_rx.try_recv() {
+            self.pending.remove(&app_name);
+            let texture = image.map(|image| {
+                Arc::new(ctx.load_texture(
+                    format!("icon_{}", app_name),
+                    image,
+                    TextureOptions::LINEAR,
+                ))
+            });
+            self.icon_paths.insert(app_name.clone(), icon_path);
+            self.textures.insert(app_name, texture);
+        }
+    }
+
+    /// 设置应用名称的显示模式和别名
+    pub fn set_display_names(&mut self, mode: DisplayNameMode, aliases: HashMap<String, String>) {
+        self.display_name_mode = mode;
+        self.aliases = aliases;
+    }
+
+    /// 按当前显示模式获取应用的显示名称
+    pub fn display_name(&mut self, app_name: &str) -> String {
+        let desktop_names = &mut self.desktop_names;
+        resolve_display_name(
+            app_name,
+            &self.aliases,
+            |name| Self::cached_desktop_name(desktop_names, name),
+            self.display_name_mode,
+        )
+    }
+
+    /// 从缓存获取 .desktop 文件中的应用名称，未缓存时查找并写入缓存
+    fn cached_desktop_name(
+        desktop_names: &mut HashMap<String, Option<String>>,
+        app_name: &str,
+    ) -> Option<String> {
+        let name_lower = app_name.to_lowercase();
+        desktop_names
+            .entry(name_lower)
+            .or_insert_with_key(|name| Self::find_name_from_desktop(name))
+            .clone()
     }
 
     /// 获取应用的 emoji 标签（当没有图标时使用）
@@ -113,6 +222,7 @@ impl IconCache {
 
     /// 获取应用图标的纹理句柄
     pub fn get_texture(&mut self, ctx: &Context, app_name: &str) -> Option<Arc<TextureHandle>> {
+        self.collect_prewarmed(ctx);
         let name_lower = app_name.to_lowercase();
 
         // 检查纹理缓存
@@ -120,6 +230,11 @@ impl IconCache {
             return cached.clone();
         }
 
+        // 正在后台预加载，先使用 emoji 后备
+        if self.pending.contains(&name_lower) {
+            return None;
+        }
+
         // 尝试加载图标
         let texture = self.load_icon_texture(ctx, &name_lower);
         self.textures.insert(name_lower, texture.clone());
@@ -134,7 +249,7 @@ impl IconCache {
         tracing::debug!("加载图标: {} -> {:?}", app_name, icon_path);
 
         // 加载图片
-        let image = self.load_image(&icon_path)?;
+        let image = Self::load_image(&icon_path)?;
 
         // 创建纹理
         let texture = ctx.load_texture(format!("icon_{}", app_name), image, TextureOptions::LINEAR);
@@ -143,7 +258,7 @@ impl IconCache {
     }
 
     /// 加载图片文件
-    fn load_image(&self, path: &PathBuf) -> Option<ColorImage> {
+    fn load_image(path: &PathBuf) -> Option<ColorImage> {
         let extension = path.extension()?.to_str()?.to_lowercase();
 
         match extension.as_str() {
@@ -156,13 +271,13 @@ impl IconCache {
                 let pixels = rgba.into_raw();
                 Some(ColorImage::from_rgba_unmultiplied(size, &pixels))
             }
-            "svg" => self.load_svg(path),
+            "svg" => Self::load_svg(path),
             _ => None,
         }
     }
 
     /// 加载 SVG 文件并渲染为位图
-    fn load_svg(&self, path: &PathBuf) -> Option<ColorImage> {
+    fn load_svg(path: &PathBuf) -> Option<ColorImage> {
         let svg_data = std::fs::read(path).ok()?;
 
         // 解析 SVG
@@ -205,7 +320,7 @@ impl IconCache {
         }
 
         // 尝试查找图标
-        let icon_path = self.find_icon(app_name);
+        let icon_path = Self::find_icon(app_name);
         self.icon_paths
             .insert(app_name.to_string(), icon_path.clone());
         icon_path
@@ -310,7 +425,7 @@ impl IconCache {
     }
 
     /// 在系统中查找图标
-    fn find_icon(&self, app_name: &str) -> Option<PathBuf> {
+    fn find_icon(app_name: &str) -> Option<PathBuf> {
         let name_lower = app_name.to_lowercase();
 
         let icon_dirs = Self::get_icon_dirs();
@@ -334,7 +449,7 @@ impl IconCache {
         }
 
         // 尝试从 .desktop 文件获取图标
-        if let Some(icon) = self.find_icon_from_desktop(&name_lower) {
+        if let Some(icon) = Self::find_icon_from_desktop(&name_lower) {
             return Some(icon);
         }
 
@@ -365,7 +480,7 @@ impl IconCache {
     }
 
     /// 从 .desktop 文件获取图标
-    fn find_icon_from_desktop(&self, app_name: &str) -> Option<PathBuf> {
+    fn find_icon_from_desktop(app_name: &str) -> Option<PathBuf> {
         let desktop_dirs = Self::get_desktop_dirs();
 
         for dir in &desktop_dirs {
@@ -387,7 +502,7 @@ impl IconCache {
 
                         // 检查文件名是否匹配
                         if (file_name.contains(app_name) || app_name.contains(&file_name))
-                            && let Some(icon) = self.parse_desktop_file(&path)
+                            && let Some(icon) = Self::parse_desktop_file(&path)
                         {
                             return Some(icon);
                         }
@@ -399,8 +514,58 @@ impl IconCache {
         None
     }
 
+    /// 从 .desktop 文件获取应用名称
+    fn find_name_from_desktop(app_name: &str) -> Option<String> {
+        for dir in &Self::get_desktop_dirs() {
+            let Ok(entries) = std::fs::read_dir(dir) else {
+                continue;
+            };
+
+            for entry in entries.flatten() {
+                let path = entry.path();
+                if path.extension().is_none_or(|e| e != "desktop") {
+                    continue;
+                }
+
+                let file_name = path
+                    .file_stem()
+                    .and_then(|s| s.to_str())
+                    .unwrap_or("")
+                    .to_lowercase();
+
+                if file_name == app_name
+                    && let Some(name) = Self::parse_desktop_name(&path)
+                {
+                    return Some(name);
+                }
+            }
+        }
+
+        None
+    }
+
+    /// 解析 .desktop 文件中 [Desktop Entry] 段的 Name
+    fn parse_desktop_name(path: &PathBuf) -> Option<String> {
+        let content = std::fs::read_to_string(path).ok()?;
+        let mut in_entry = false;
+
+        for line in content.lines() {
+            let line = line.trim();
+            if line.starts_with('[') {
+                in_entry = line == "[Desktop Entry]";
+                continue;
+            }
+
+            if in_entry && let Some(name) = line.strip_prefix("Name=") {
+                return Some(name.trim().to_string());
+            }
+        }
+
+        None
+    }
+
     /// 解析 .desktop 文件获取图标
-    fn parse_desktop_file(&self, path: &PathBuf) -> Option<PathBuf> {
+    fn parse_desktop_file(path: &PathBuf) -> Option<PathBuf> {
         let content = std::fs::read_to_string(path).ok()?;
 
         let mut icon_name = None;
@@ -454,6 +619,8 @@ impl IconCache {
     pub fn clear(&mut self) {
         self.textures.clear();
         self.icon_paths.clear();
+        self.desktop_names.clear();
+        self.pending.clear();
     }
 }
 
@@ -503,6 +670,27 @@ mod tests {
         assert_eq!(cache.get_emoji("unknown_app"), "📦");
     }
 
+    #[test]
+    fn test_queue_prewarm_each_app_once() {
+        let mut cache = IconCache::new();
+        let apps: Vec<String> = ["code", "Firefox", "firefox", "", "kitty"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+
+        assert_eq!(cache.queue_prewarm(&apps), vec!["code", "firefox", "kitty"]);
+        // 已在预加载中的应用不会再次加入
+        assert!(cache.queue_prewarm(&apps).is_empty());
+
+        // 已缓存的应用直接跳过
+        cache.textures.insert("mpv".to_string(), None);
+        assert_eq!(
+            cache.queue_prewarm(&["mpv".to_string(), "Steam".to_string()]),
+            vec!["steam"]
+        );
+        assert_eq!(cache.pending.len(), 4);
+    }
+
     #[test]
     fn test_partial_match() {
         let cache = IconCache::new();
@@ -4,21 +4,42 @@
 
 /// 时长格式化工具
 pub mod duration {
-    use tail_core::utils::duration::DurationStyle;
+    use std::sync::atomic::{AtomicU8, Ordering};
+    use tail_core::utils::duration::{DurationLocale, DurationStyle, format_duration_localized};
+
+    /// 全局时长单位语言（0 = 英文，1 = 中文）
+    static LOCALE: AtomicU8 = AtomicU8::new(0);
+
+    /// 设置全局时长单位语言
+    pub fn set_locale(locale: DurationLocale) {
+        let value = match locale {
+            DurationLocale::En => 0,
+            DurationLocale::Zh => 1,
+        };
+        LOCALE.store(value, Ordering::Relaxed);
+    }
+
+    /// 当前的时长单位语言
+    pub fn locale() -> DurationLocale {
+        match LOCALE.load(Ordering::Relaxed) {
+            1 => DurationLocale::Zh,
+            _ => DurationLocale::En,
+        }
+    }
 
     /// 格式化秒数为简短时长 (1h 30m)
     pub fn format_duration(seconds: i64) -> String {
-        tail_core::utils::duration::format_duration(seconds, DurationStyle::Short)
+        format_duration_localized(seconds, DurationStyle::Short, locale())
     }
 
     /// 格式化秒数为完整时长 (1h 30m 15s)
     pub fn format_duration_full(seconds: i64) -> String {
-        tail_core::utils::duration::format_duration(seconds, DurationStyle::Full)
+        format_duration_localized(seconds, DurationStyle::Full, locale())
     }
 
     /// 格式化秒数为极简时长 (1h)
     pub fn format_duration_short(seconds: i64) -> String {
-        tail_core::utils::duration::format_duration(seconds, DurationStyle::Minimal)
+        format_duration_localized(seconds, DurationStyle::Minimal, locale())
     }
 
     /// 格式化秒数为中文时长 (1小时30分钟)
@@ -28,7 +49,7 @@ pub mod duration {
 
     /// 从分钟格式化（用于目标进度等场景）
     pub fn format_minutes(minutes: i32) -> String {
-        tail_core::utils::duration::format_minutes(minutes, DurationStyle::Short)
+        format_duration(minutes as i64 * 60)
     }
 
     /// 从分钟格式化为中文（用于目标进度等场景）
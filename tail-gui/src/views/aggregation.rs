@@ -4,6 +4,7 @@ use chrono::{Datelike, Duration, Local, NaiveDate, Timelike};
 use std::collections::HashMap;
 use tail_core::AppUsage;
 use tail_core::models::{PeriodUsage, TimeNavigationLevel, TimeNavigationState};
+use tail_core::time::WeekNumbering;
 
 /// 数据聚合器
 pub struct DataAggregator<'a> {
@@ -25,16 +26,19 @@ impl<'a> DataAggregator<'a> {
             TimeNavigationLevel::Month => self.aggregate_by_year(state.selected_year),
             TimeNavigationLevel::Week => {
                 let month = state.selected_month.unwrap_or(1);
-                self.aggregate_by_month(state.selected_year, month)
+                self.aggregate_by_month(state.selected_year, month, state.week_numbering)
             }
             TimeNavigationLevel::Day => {
                 let month = state.selected_month.unwrap_or(1);
-                if state.selected_week.is_none() {
+                match state.selected_week {
+                    Some(week) => self.aggregate_by_week(
+                        state.selected_year,
+                        month,
+                        week,
+                        state.week_numbering,
+                    ),
                     // selected_week 为 None 表示"本周"快捷选项
-                    self.aggregate_this_week()
-                } else {
-                    let week = state.selected_week.unwrap();
-                    self.aggregate_by_week(state.selected_year, month, week)
+                    None => self.aggregate_this_week(),
                 }
             }
             TimeNavigationLevel::Hour => {
@@ -70,46 +74,57 @@ impl<'a> DataAggregator<'a> {
     }
 
     /// 按月聚合：返回当月各周的使用数据
-    fn aggregate_by_month(&self, year: i32, month: u32) -> Vec<PeriodUsage> {
-        let mut weekly_usage: HashMap<u32, i64> = HashMap::new();
+    ///
+    /// 周的编号和边界由 `numbering` 决定，ISO 周跨月时包含相邻月份的日期。
+    fn aggregate_by_month(
+        &self,
+        year: i32,
+        month: u32,
+        numbering: WeekNumbering,
+    ) -> Vec<PeriodUsage> {
+        let weeks = numbering.weeks_in_month(year, month);
+        let mut weekly_usage = vec![0i64; weeks.len()];
 
         for usage in self.app_usage {
             for event in &usage.window_events {
-                // 使用本地时间进行比较，只计算非 AFK 时间
-                let local_time = event.timestamp.with_timezone(&Local);
-                if local_time.year() == year && local_time.month() == month && !event.is_afk {
-                    let day = local_time.day();
-                    let week = Self::get_week_of_month(year, month, day);
-                    *weekly_usage.entry(week).or_insert(0) += event.duration_secs;
+                // 只计算非 AFK 时间，按本地日期归入所在的周
+                if event.is_afk {
+                    continue;
+                }
+                let date = event.timestamp.with_timezone(&Local).date_naive();
+                if let Some(idx) = weeks
+                    .iter()
+                    .position(|(_, start, end)| *start <= date && date <= *end)
+                {
+                    weekly_usage[idx] += event.duration_secs;
                 }
             }
         }
 
-        // 计算该月有几周
-        let days_in_month = Self::days_in_month(year, month);
-        let max_week = Self::get_week_of_month(year, month, days_in_month);
-
-        (1..=max_week)
-            .map(|week| PeriodUsage {
-                label: format!("第{}周", week),
-                index: week as i32,
-                total_seconds: weekly_usage.get(&week).copied().unwrap_or(0),
+        weeks
+            .iter()
+            .zip(weekly_usage)
+            .map(|((week, _, _), total_seconds)| PeriodUsage {
+                label: numbering.week_label(*week),
+                index: *week as i32,
+                total_seconds,
             })
             .collect()
     }
 
     /// 按周聚合：返回当周7天的使用数据
-    fn aggregate_by_week(&self, year: i32, month: u32, week: u32) -> Vec<PeriodUsage> {
+    fn aggregate_by_week(
+        &self,
+        year: i32,
+        month: u32,
+        week: u32,
+        numbering: WeekNumbering,
+    ) -> Vec<PeriodUsage> {
         // 计算该周的起止日期
         let first_day = NaiveDate::from_ymd_opt(year, month, 1).unwrap();
-        let first_weekday = first_day.weekday().num_days_from_monday();
-
-        let week_start_day = ((week - 1) * 7) as i64 - first_weekday as i64 + 1;
-        let week_start = if week_start_day < 1 {
-            first_day
-        } else {
-            first_day + Duration::days(week_start_day - 1)
-        };
+        let week_start = numbering
+            .week_dates(year, month, week)
+            .map_or(first_day, |(start, _)| start);
 
         let mut daily_usage: HashMap<NaiveDate, i64> = HashMap::new();
 
@@ -233,22 +248,4 @@ impl<'a> DataAggregator<'a> {
             })
             .collect()
     }
-
-    /// 计算某天是该月的第几周
-    fn get_week_of_month(year: i32, month: u32, day: u32) -> u32 {
-        let first_day = NaiveDate::from_ymd_opt(year, month, 1).unwrap();
-        let first_weekday = first_day.weekday().num_days_from_monday();
-        ((day + first_weekday - 1) / 7) + 1
-    }
-
-    /// 获取某月的天数
-    fn days_in_month(year: i32, month: u32) -> u32 {
-        if month == 12 {
-            NaiveDate::from_ymd_opt(year + 1, 1, 1).unwrap()
-        } else {
-            NaiveDate::from_ymd_opt(year, month + 1, 1).unwrap()
-        }
-        .signed_duration_since(NaiveDate::from_ymd_opt(year, month, 1).unwrap())
-        .num_days() as u32
-    }
 }
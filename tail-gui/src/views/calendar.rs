@@ -0,0 +1,244 @@
+//! TaiL GUI - 活动日历视图
+//!
+//! 类似贡献日历的热力图：每列为一周，每行为星期几，颜色深浅表示当天活跃时长
+
+use chrono::{Datelike, NaiveDate};
+use egui::{Color32, Pos2, Rect, Rounding, ScrollArea, Sense, Ui, Vec2};
+
+use crate::components::{EmptyState, PageHeader};
+use crate::theme::TaiLTheme;
+use crate::utils::duration;
+
+/// 单元格边长
+const CELL_SIZE: f32 = 14.0;
+
+/// 单元格间距
+const CELL_GAP: f32 = 3.0;
+
+/// 左侧星期标签的宽度
+const LABEL_WIDTH: f32 = 28.0;
+
+/// 计算强度分级的阈值（非零时长的四分位数）
+///
+/// 返回 `[Q1, Q2, Q3]`，只统计大于 0 的时长；没有非零时长时返回全 0。
+pub fn intensity_thresholds(totals: &[i64]) -> [i64; 3] {
+    let mut active: Vec<i64> = totals.iter().copied().filter(|&t| t > 0).collect();
+    if active.is_empty() {
+        return [0; 3];
+    }
+    active.sort_unstable();
+
+    let last = active.len() - 1;
+    [active[last / 4], active[last / 2], active[last * 3 / 4]]
+}
+
+/// 根据阈值把时长映射为强度等级 0-4
+///
+/// 0 表示没有活动，1-4 依次对应四分位区间。
+pub fn intensity_level(total: i64, thresholds: &[i64; 3]) -> u8 {
+    if total <= 0 {
+        0
+    } else if total <= thresholds[0] {
+        1
+    } else if total <= thresholds[1] {
+        2
+    } else if total <= thresholds[2] {
+        3
+    } else {
+        4
+    }
+}
+
+/// 计算日期在日历网格中的位置 `(列, 行)`
+///
+/// 第 0 列是 `first_day` 所在的周（周一开始），行 0-6 对应周一到周日。
+/// 早于 `first_day` 的日期返回 `None`。
+pub fn grid_position(first_day: NaiveDate, date: NaiveDate) -> Option<(usize, usize)> {
+    if date < first_day {
+        return None;
+    }
+
+    let first_monday =
+        first_day - chrono::Duration::days(first_day.weekday().num_days_from_monday() as i64);
+    let column = (date - first_monday).num_days() / 7;
+    let row = date.weekday().num_days_from_monday();
+    Some((column as usize, row as usize))
+}
+
+/// 活动日历视图
+#[derive(Default)]
+pub struct CalendarView {
+    /// 每天的活跃时长（按日期升序）
+    activity: Vec<(NaiveDate, i64)>,
+}
+
+impl CalendarView {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 更新每日活跃时长
+    pub fn set_activity(&mut self, activity: Vec<(NaiveDate, i64)>) {
+        self.activity = activity;
+    }
+
+    /// 渲染活动日历
+    pub fn show(&self, ui: &mut Ui, theme: &TaiLTheme) {
+        ui.add(PageHeader::new("活动日历", "📅", theme).subtitle("最近一年每天的活跃程度"));
+        ui.add_space(theme.spacing);
+
+        let Some(&(first_day, _)) = self.activity.first() else {
+            ui.add(EmptyState::new(
+                "📅",
+                "暂无数据",
+                "还没有记录到任何活动",
+                theme,
+            ));
+            return;
+        };
+
+        let totals: Vec<i64> = self.activity.iter().map(|&(_, t)| t).collect();
+        let thresholds = intensity_thresholds(&totals);
+        let active_days = totals.iter().filter(|&&t| t > 0).count();
+
+        ui.label(
+            egui::RichText::new(format!(
+                "{} 天有活动 · 共 {}",
+                active_days,
+                duration::format_duration(totals.iter().sum())
+            ))
+            .size(theme.small_size)
+            .color(theme.secondary_text_color),
+        );
+        ui.add_space(theme.spacing / 2.0);
+
+        ScrollArea::horizontal().show(ui, |ui| {
+            self.show_grid(ui, theme, first_day, &thresholds);
+        });
+
+        ui.add_space(theme.spacing / 2.0);
+        Self::show_legend(ui, theme);
+    }
+
+    /// 绘制日历网格
+    fn show_grid(
+        &self,
+        ui: &mut Ui,
+        theme: &TaiLTheme,
+        first_day: NaiveDate,
+        thresholds: &[i64; 3],
+    ) {
+        let columns = self
+            .activity
+            .last()
+            .and_then(|&(last_day, _)| grid_position(first_day, last_day))
+            .map_or(1, |(column, _)| column + 1);
+        let step = CELL_SIZE + CELL_GAP;
+        let desired_size = Vec2::new(LABEL_WIDTH + columns as f32 * step, 7.0 * step);
+        let (rect, _) = ui.allocate_exact_size(desired_size, Sense::hover());
+        let painter = ui.painter_at(rect);
+
+        for (row, label) in [(0, "一"), (2, "三"), (4, "五")] {
+            painter.text(
+                Pos2::new(rect.min.x, rect.min.y + row as f32 * step + CELL_SIZE / 2.0),
+                egui::Align2::LEFT_CENTER,
+                label,
+                egui::FontId::proportional(theme.small_size),
+                theme.secondary_text_color,
+            );
+        }
+
+        for &(date, total) in &self.activity {
+            let Some((column, row)) = grid_position(first_day, date) else {
+                continue;
+            };
+            let min = Pos2::new(
+                rect.min.x + LABEL_WIDTH + column as f32 * step,
+                rect.min.y + row as f32 * step,
+            );
+            let cell = Rect::from_min_size(min, Vec2::splat(CELL_SIZE));
+            let level = intensity_level(total, thresholds);
+            painter.rect_filled(cell, Rounding::same(2.0), Self::level_color(theme, level));
+
+            ui.interact(cell, ui.id().with(("calendar_cell", date)), Sense::hover())
+                .on_hover_text(format!(
+                    "{} · {}",
+                    date.format("%Y-%m-%d"),
+                    duration::format_duration(total)
+                ));
+        }
+    }
+
+    /// 显示颜色图例
+    fn show_legend(ui: &mut Ui, theme: &TaiLTheme) {
+        ui.horizontal(|ui| {
+            ui.label(
+                egui::RichText::new("少")
+                    .size(theme.small_size)
+                    .color(theme.secondary_text_color),
+            );
+            for level in 0..=4 {
+                let (rect, _) = ui.allocate_exact_size(Vec2::splat(CELL_SIZE), Sense::hover());
+                ui.painter().rect_filled(
+                    rect,
+                    Rounding::same(2.0),
+                    Self::level_color(theme, level),
+                );
+            }
+            ui.label(
+                egui::RichText::new("多")
+                    .size(theme.small_size)
+                    .color(theme.secondary_text_color),
+            );
+        });
+    }
+
+    /// 强度等级对应的颜色
+    fn level_color(theme: &TaiLTheme, level: u8) -> Color32 {
+        match level {
+            0 => theme.progress_background,
+            level => theme.primary_color.gamma_multiply(0.25 * level as f32),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_intensity_levels() {
+        let totals = [0, 600, 1200, 1800, 2400, 3000, 3600, 4200, 4800, 0];
+        let thresholds = intensity_thresholds(&totals);
+        assert_eq!(thresholds, [1200, 2400, 3600]);
+
+        let levels: Vec<u8> = totals
+            .iter()
+            .map(|&t| intensity_level(t, &thresholds))
+            .collect();
+        assert_eq!(levels, vec![0, 1, 1, 2, 2, 3, 3, 4, 4, 0]);
+    }
+
+    #[test]
+    fn test_intensity_no_activity() {
+        let thresholds = intensity_thresholds(&[0, 0, 0]);
+        assert_eq!(thresholds, [0, 0, 0]);
+        assert_eq!(intensity_level(0, &thresholds), 0);
+    }
+
+    #[test]
+    fn test_grid_position() {
+        // 2024-01-03 是周三，所在周的周一为 2024-01-01
+        let first_day = NaiveDate::from_ymd_opt(2024, 1, 3).unwrap();
+
+        assert_eq!(grid_position(first_day, first_day), Some((0, 2)));
+        let sunday = NaiveDate::from_ymd_opt(2024, 1, 7).unwrap();
+        assert_eq!(grid_position(first_day, sunday), Some((0, 6)));
+        let next_monday = NaiveDate::from_ymd_opt(2024, 1, 8).unwrap();
+        assert_eq!(grid_position(first_day, next_monday), Some((1, 0)));
+        let later = NaiveDate::from_ymd_opt(2024, 2, 1).unwrap();
+        assert_eq!(grid_position(first_day, later), Some((4, 3)));
+        let before = NaiveDate::from_ymd_opt(2024, 1, 2).unwrap();
+        assert_eq!(grid_position(first_day, before), None);
+    }
+}
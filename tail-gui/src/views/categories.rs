@@ -1,14 +1,18 @@
 //! TaiL GUI - 分类视图
 
 use egui::{Color32, Rounding, ScrollArea, Stroke, Ui, Vec2};
-use std::collections::HashSet;
-use tail_core::{AppUsage, AppUsageInCategory, CATEGORY_ICONS, Category, CategoryUsage};
+use std::collections::HashMap;
+use tail_core::{
+    AppUsage, AppUsageInCategory, CATEGORY_ICONS, Category, CategoryManagementData, CategoryUsage,
+};
 
 use crate::components::chart::{
     ChartDataBuilder, ChartGroupMode, ChartTimeGranularity, StackedBarChart, StackedBarChartConfig,
-    StackedBarTooltip,
+    StackedBarTooltip, parse_hex_color,
+};
+use crate::components::{
+    ComboSearch, EmptyState, PageHeader, ProgressBar, SectionDivider, StatCard,
 };
-use crate::components::{EmptyState, PageHeader, SectionDivider, StatCard};
 use crate::icons::ui_icons::categories as icons;
 use crate::theme::TaiLTheme;
 use crate::utils::duration;
@@ -74,6 +78,10 @@ pub struct CategoriesView {
     selected_category_ids: Vec<i64>,
     /// 所有应用名称列表
     all_apps: Vec<String>,
+    /// 归类对话框中应用列表的过滤词
+    assign_app_query: String,
+    /// 未分类的应用名称列表
+    unclassified_apps: Vec<String>,
     /// 图标选择器是否展开
     show_icon_picker: bool,
     /// 是否需要刷新数据
@@ -82,6 +90,14 @@ pub struct CategoriesView {
     hovered_slot: Option<usize>,
     /// 待处理的操作
     pending_action: Option<CategoryAction>,
+    /// 正在内联重命名的分类 ID
+    editing_id: Option<i64>,
+    /// 内联重命名的输入缓冲
+    rename_buffer: String,
+    /// 内联重命名输入框是否需要获取焦点
+    rename_needs_focus: bool,
+    /// 应用显示名称（应用名 -> 显示名称）
+    display_names: HashMap<String, String>,
 }
 
 impl CategoriesView {
@@ -101,13 +117,61 @@ impl CategoriesView {
             selected_app_name: None,
             selected_category_ids: Vec::new(),
             all_apps: Vec::new(),
+            assign_app_query: String::new(),
+            unclassified_apps: Vec::new(),
             show_icon_picker: false,
             needs_refresh: false,
             hovered_slot: None,
             pending_action: None,
+            editing_id: None,
+            rename_buffer: String::new(),
+            rename_needs_focus: false,
+            display_names: HashMap::new(),
         }
     }
 
+    /// 开始内联重命名分类
+    fn begin_inline_rename(&mut self, id: i64, name: &str) {
+        self.editing_id = Some(id);
+        self.rename_buffer = name.to_string();
+        self.rename_needs_focus = true;
+    }
+
+    /// 提交内联重命名
+    ///
+    /// 名称为空或未改变时不产生操作，仅退出编辑状态。
+    fn commit_inline_rename(&mut self) {
+        let Some(id) = self.editing_id.take() else {
+            return;
+        };
+        let new_name = self.rename_buffer.trim().to_string();
+        self.rename_buffer.clear();
+        self.rename_needs_focus = false;
+
+        if new_name.is_empty() {
+            return;
+        }
+
+        if let Some(category) = self.categories.iter().find(|c| c.id == Some(id))
+            && category.name != new_name
+        {
+            self.pending_action = Some(CategoryAction::UpdateCategory(Category {
+                id: Some(id),
+                name: new_name,
+                icon: category.icon.clone(),
+                color: category.color.clone(),
+            }));
+            self.needs_refresh = true;
+        }
+    }
+
+    /// 取消内联重命名
+    fn cancel_inline_rename(&mut self) {
+        self.editing_id = None;
+        self.rename_buffer.clear();
+        self.rename_needs_focus = false;
+    }
+
     /// 检查是否需要刷新数据
     pub fn needs_refresh(&self) -> bool {
         self.needs_refresh
@@ -119,19 +183,49 @@ impl CategoriesView {
     }
 
     /// 加载分类数据（接收预加载的数据）
-    pub fn load_data(
-        &mut self,
-        category_usage: Vec<CategoryUsage>,
-        categories: Vec<Category>,
-        all_apps: Vec<String>,
-        app_usage: Vec<AppUsage>,
-    ) {
-        self.category_usage = category_usage;
-        self.categories = categories;
-        self.all_apps = all_apps;
+    pub fn load_data(&mut self, data: CategoryManagementData, app_usage: Vec<AppUsage>) {
+        self.category_usage = data.category_usage;
+        self.categories = data.categories;
+        self.all_apps = data.all_app_names;
+        self.unclassified_apps = data.unclassified_apps;
         self.app_usage = app_usage;
     }
 
+    /// 用新的统计替换列表中同一分类的条目，其余分类保持原有顺序
+    ///
+    /// 返回是否找到了对应的分类。
+    pub fn splice_category_usage(&mut self, usage: CategoryUsage) -> bool {
+        match self
+            .category_usage
+            .iter_mut()
+            .find(|c| c.category.id.is_some() && c.category.id == usage.category.id)
+        {
+            Some(existing) => {
+                *existing = usage;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// 更新未分类应用列表
+    pub fn set_unclassified_apps(&mut self, apps: Vec<String>) {
+        self.unclassified_apps = apps;
+    }
+
+    /// 设置应用显示名称
+    pub fn set_display_names(&mut self, display_names: HashMap<String, String>) {
+        self.display_names = display_names;
+    }
+
+    /// 获取应用的显示名称，未设置时使用原始名称
+    fn display_name<'b>(&'b self, app_name: &'b str) -> &'b str {
+        self.display_names
+            .get(app_name)
+            .map(String::as_str)
+            .unwrap_or(app_name)
+    }
+
     /// 设置加载的应用分类（响应 LoadAppCategories 操作）
     pub fn set_app_categories(&mut self, category_ids: Vec<i64>) {
         self.selected_category_ids = category_ids;
@@ -325,16 +419,12 @@ impl CategoriesView {
             .category_usage
             .iter()
             .map(|usage| {
-                let percentage = if total_seconds > 0 {
-                    (usage.total_seconds as f32 / total_seconds as f32) * 100.0
-                } else {
-                    0.0
-                };
+                let percentage = usage.percentage_of(total_seconds);
                 let color = usage
                     .category
                     .color
                     .as_ref()
-                    .and_then(|c| Self::parse_color(c))
+                    .and_then(|c| parse_hex_color(c))
                     .unwrap_or(self.theme.primary_color);
                 let color_str = usage.category.color.clone();
                 (
@@ -351,21 +441,7 @@ impl CategoriesView {
             })
             .collect();
 
-        // 收集所有已分类的应用名称
-        let mut classified_apps = HashSet::new();
-        for usage in &self.category_usage {
-            for app in &usage.apps {
-                classified_apps.insert(app.app_name.as_str());
-            }
-        }
-
-        // 找出未分类的应用
-        let unclassified_apps: Vec<_> = self
-            .all_apps
-            .iter()
-            .filter(|app| !classified_apps.contains(app.as_str()))
-            .cloned()
-            .collect();
+        let unclassified_apps = self.unclassified_apps.clone();
 
         ScrollArea::vertical()
             .id_source("category_list_scroll")
@@ -439,9 +515,11 @@ impl CategoriesView {
                                         for app_name in &unclassified_apps {
                                             ui.horizontal(|ui| {
                                                 ui.label(
-                                                    egui::RichText::new(app_name)
-                                                        .size(self.theme.body_size)
-                                                        .color(self.theme.text_color),
+                                                    egui::RichText::new(
+                                                        self.display_name(app_name),
+                                                    )
+                                                    .size(self.theme.body_size)
+                                                    .color(self.theme.text_color),
                                                 );
                                                 ui.with_layout(
                                                     egui::Layout::right_to_left(
@@ -497,11 +575,37 @@ impl CategoriesView {
                     ui.horizontal(|ui| {
                         // 图标和名称
                         ui.label(egui::RichText::new(cat_icon).size(self.theme.heading_size));
-                        ui.label(
-                            egui::RichText::new(cat_name)
-                                .size(self.theme.heading_size)
-                                .color(self.theme.text_color),
-                        );
+                        if cat_id.is_some() && self.editing_id == cat_id {
+                            // 内联重命名：回车或失去焦点提交，Esc 取消
+                            let response = ui.add(
+                                egui::TextEdit::singleline(&mut self.rename_buffer)
+                                    .font(egui::FontId::proportional(self.theme.heading_size))
+                                    .desired_width(160.0),
+                            );
+                            if self.rename_needs_focus {
+                                response.request_focus();
+                                self.rename_needs_focus = false;
+                            }
+                            if ui.input(|i| i.key_pressed(egui::Key::Escape)) {
+                                self.cancel_inline_rename();
+                            } else if response.lost_focus() {
+                                self.commit_inline_rename();
+                            }
+                        } else {
+                            let response = ui.add(
+                                egui::Label::new(
+                                    egui::RichText::new(cat_name)
+                                        .size(self.theme.heading_size)
+                                        .color(self.theme.text_color),
+                                )
+                                .sense(egui::Sense::click()),
+                            );
+                            if let Some(id) = cat_id
+                                && response.on_hover_text("双击重命名").double_clicked()
+                            {
+                                self.begin_inline_rename(id, cat_name);
+                            }
+                        }
 
                         ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
                             // 操作按钮
@@ -563,7 +667,7 @@ impl CategoriesView {
                         for app in apps {
                             ui.horizontal(|ui| {
                                 ui.label(
-                                    egui::RichText::new(&app.app_name)
+                                    egui::RichText::new(self.display_name(&app.app_name))
                                         .size(self.theme.body_size)
                                         .color(self.theme.text_color),
                                 );
@@ -581,12 +685,12 @@ impl CategoriesView {
                                         .on_hover_text("从此分类中移除")
                                         .clicked()
                                 {
+                                    // 由应用层只刷新该分类，不重新加载整个页面
                                     self.pending_action =
                                         Some(CategoryAction::RemoveAppFromCategory(
                                             app.app_name.clone(),
                                             id,
                                         ));
-                                    self.needs_refresh = true;
                                 }
                             });
                         }
@@ -597,44 +701,10 @@ impl CategoriesView {
 
     /// 显示柱形图
     fn show_bar_chart(&self, ui: &mut Ui, percentage: f32, color: Color32) {
-        let height = 20.0;
-        let (rect, _response) = ui.allocate_exact_size(
-            Vec2::new(ui.available_width(), height),
-            egui::Sense::hover(),
-        );
-
-        if ui.is_rect_visible(rect) {
-            let painter = ui.painter();
-
-            // 背景
-            painter.rect_filled(
-                rect,
-                Rounding::same(self.theme.card_rounding / 2.0),
-                self.theme.progress_background,
-            );
-
-            // 进度条
-            let bar_width = rect.width() * (percentage / 100.0);
-            let bar_rect = egui::Rect::from_min_size(rect.min, Vec2::new(bar_width, height));
-
-            painter.rect_filled(
-                bar_rect,
-                Rounding::same(self.theme.card_rounding / 2.0),
-                color,
-            );
-        }
-    }
-
-    /// 解析颜色字符串为 Color32
-    fn parse_color(hex: &str) -> Option<Color32> {
-        let hex = hex.trim_start_matches('#');
-        if hex.len() != 6 {
-            return None;
-        }
-        let r = u8::from_str_radix(&hex[0..2], 16).ok()?;
-        let g = u8::from_str_radix(&hex[2..4], 16).ok()?;
-        let b = u8::from_str_radix(&hex[4..6], 16).ok()?;
-        Some(Color32::from_rgb(r, g, b))
+        ProgressBar::new(percentage / 100.0, color)
+            .height(20.0)
+            .rounding(self.theme.card_rounding / 2.0)
+            .show(ui, &self.theme);
     }
 
     /// 显示对话框
@@ -701,7 +771,7 @@ impl CategoriesView {
                             let is_selected = self
                                 .new_category_color
                                 .as_ref()
-                                .and_then(|c| Self::parse_color(c))
+                                .and_then(|c| parse_hex_color(c))
                                 .map(|c| c == *color)
                                 .unwrap_or(false);
 
@@ -801,7 +871,7 @@ impl CategoriesView {
                             let is_selected = self
                                 .new_category_color
                                 .as_ref()
-                                .and_then(|c| Self::parse_color(c))
+                                .and_then(|c| parse_hex_color(c))
                                 .map(|c| c == *color)
                                 .unwrap_or(false);
 
@@ -868,28 +938,22 @@ impl CategoriesView {
                 ui.vertical(|ui| {
                     ui.label("选择应用:");
 
-                    // 克隆 all_apps 以避免借用冲突
-                    let all_apps = self.all_apps.clone();
-
-                    ScrollArea::vertical()
-                        .id_source("assign_apps_list")
-                        .max_height(300.0)
-                        .show(ui, |ui| {
-                            for app_name in &all_apps {
-                                if ui
-                                    .selectable_label(
-                                        self.selected_app_name.as_ref() == Some(app_name),
-                                        app_name,
-                                    )
-                                    .clicked()
-                                {
-                                    // 选择新应用时，触发加载该应用当前的分类
-                                    self.selected_app_name = Some(app_name.clone());
-                                    self.pending_action =
-                                        Some(CategoryAction::LoadAppCategories(app_name.clone()));
-                                }
-                            }
-                        });
+                    let picked = ComboSearch::new(
+                        "assign_apps_list",
+                        &mut self.assign_app_query,
+                        &self.all_apps,
+                        &self.theme,
+                    )
+                    .selected(self.selected_app_name.as_deref())
+                    .hint_text("搜索应用")
+                    .max_height(300.0)
+                    .show(ui);
+                    if let Some(app_name) = picked {
+                        // 选择新应用时，触发加载该应用当前的分类
+                        self.pending_action =
+                            Some(CategoryAction::LoadAppCategories(app_name.clone()));
+                        self.selected_app_name = Some(app_name);
+                    }
 
                     ui.add_space(self.theme.spacing);
 
@@ -944,6 +1008,7 @@ impl CategoriesView {
                             ));
                             self.needs_refresh = true;
                             self.show_assign_dialog = false;
+                            self.assign_app_query.clear();
                             self.selected_app_name = None;
                             self.selected_category_ids.clear();
                         }
@@ -953,6 +1018,7 @@ impl CategoriesView {
 
                     if ui.button("取消").clicked() {
                         self.show_assign_dialog = false;
+                        self.assign_app_query.clear();
                         self.selected_app_name = None;
                         self.selected_category_ids.clear();
                     }
@@ -960,3 +1026,127 @@ impl CategoriesView {
             });
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn view_with_category() -> CategoriesView {
+        let mut view = CategoriesView::new(TaiLTheme::default());
+        view.load_data(
+            CategoryManagementData {
+                categories: vec![Category {
+                    id: Some(1),
+                    name: "工作".to_string(),
+                    icon: "💼".to_string(),
+                    color: Some("#4A90E2".to_string()),
+                }],
+                category_usage: Vec::new(),
+                all_app_names: Vec::new(),
+                unclassified_apps: Vec::new(),
+                classified_app_count: 0,
+            },
+            Vec::new(),
+        );
+        view
+    }
+
+    #[test]
+    fn test_begin_inline_rename() {
+        let mut view = view_with_category();
+        view.begin_inline_rename(1, "工作");
+
+        assert_eq!(view.editing_id, Some(1));
+        assert_eq!(view.rename_buffer, "工作");
+        assert!(view.take_action().is_none());
+    }
+
+    #[test]
+    fn test_commit_inline_rename_emits_update() {
+        let mut view = view_with_category();
+        view.begin_inline_rename(1, "工作");
+        view.rename_buffer = "  学习 ".to_string();
+        view.commit_inline_rename();
+
+        assert_eq!(view.editing_id, None);
+        match view.take_action() {
+            Some(CategoryAction::UpdateCategory(category)) => {
+                assert_eq!(category.id, Some(1));
+                assert_eq!(category.name, "学习");
+                // 图标和颜色保持不变
+                assert_eq!(category.icon, "💼");
+                assert_eq!(category.color.as_deref(), Some("#4A90E2"));
+            }
+            _ => panic!("expected UpdateCategory"),
+        }
+        assert!(view.needs_refresh());
+    }
+
+    #[test]
+    fn test_commit_inline_rename_unchanged_or_empty() {
+        let mut view = view_with_category();
+
+        view.begin_inline_rename(1, "工作");
+        view.commit_inline_rename();
+        assert!(view.take_action().is_none());
+
+        view.begin_inline_rename(1, "工作");
+        view.rename_buffer = "   ".to_string();
+        view.commit_inline_rename();
+        assert!(view.take_action().is_none());
+        assert_eq!(view.editing_id, None);
+    }
+
+    #[test]
+    fn test_cancel_inline_rename() {
+        let mut view = view_with_category();
+        view.begin_inline_rename(1, "工作");
+        view.rename_buffer = "学习".to_string();
+        view.cancel_inline_rename();
+
+        assert_eq!(view.editing_id, None);
+        assert!(view.rename_buffer.is_empty());
+        assert!(view.take_action().is_none());
+        assert!(!view.needs_refresh());
+    }
+
+    #[test]
+    fn test_splice_category_usage() {
+        let usage = |id: i64, name: &str, total_seconds: i64| CategoryUsage {
+            category: Category {
+                id: Some(id),
+                name: name.to_string(),
+                icon: "🗀".to_string(),
+                color: None,
+            },
+            total_seconds,
+            app_count: 0,
+            apps: Vec::new(),
+        };
+
+        let mut view = CategoriesView::new(TaiLTheme::default());
+        view.load_data(
+            CategoryManagementData {
+                categories: Vec::new(),
+                category_usage: vec![
+                    usage(1, "工作", 300),
+                    usage(2, "娱乐", 200),
+                    usage(3, "学习", 100),
+                ],
+                all_app_names: Vec::new(),
+                unclassified_apps: Vec::new(),
+                classified_app_count: 0,
+            },
+            Vec::new(),
+        );
+
+        assert!(view.splice_category_usage(usage(2, "娱乐", 900)));
+        let ids: Vec<_> = view.category_usage.iter().map(|c| c.category.id).collect();
+        assert_eq!(ids, vec![Some(1), Some(2), Some(3)]);
+        assert_eq!(view.category_usage[1].total_seconds, 900);
+        assert_eq!(view.category_usage[0].total_seconds, 300);
+
+        assert!(!view.splice_category_usage(usage(4, "其他", 50)));
+        assert_eq!(view.category_usage.len(), 3);
+    }
+}
@@ -4,8 +4,8 @@ use egui::{ScrollArea, Ui};
 use tail_core::AppUsage;
 
 use crate::components::chart::{
-    ChartDataBuilder, ChartGroupMode, ChartTimeGranularity, StackedBarChart, StackedBarChartConfig,
-    StackedBarTooltip,
+    CategoryColorMap, ChartDataBuilder, ChartGroupMode, ChartTimeGranularity, StackedBarChart,
+    StackedBarChartConfig, StackedBarTooltip,
 };
 use crate::components::{
     AppCard, EmptyState, EnhancedProgressBar, PageHeader, SectionDivider, StatCard,
@@ -24,6 +24,10 @@ pub struct DashboardView<'a> {
     icon_cache: &'a mut IconCache,
     /// 悬停的时间槽索引
     hovered_slot: Option<usize>,
+    /// 墙钟活跃时长（秒），重叠的事件只计一次
+    wall_clock_seconds: Option<i64>,
+    /// 图表颜色配置
+    color_map: CategoryColorMap,
 }
 
 impl<'a> DashboardView<'a> {
@@ -37,9 +41,23 @@ impl<'a> DashboardView<'a> {
             theme,
             icon_cache,
             hovered_slot: None,
+            wall_clock_seconds: None,
+            color_map: CategoryColorMap::default(),
         }
     }
 
+    /// 设置墙钟活跃时长，用于和累加的总使用时间对比
+    pub fn with_wall_clock(mut self, seconds: i64) -> Self {
+        self.wall_clock_seconds = Some(seconds);
+        self
+    }
+
+    /// 设置图表颜色配置（包含用户为应用指定的颜色）
+    pub fn with_color_map(mut self, color_map: CategoryColorMap) -> Self {
+        self.color_map = color_map;
+        self
+    }
+
     /// 渲染仪表板
     pub fn show(&mut self, ui: &mut Ui) {
         // 页面标题
@@ -72,7 +90,7 @@ impl<'a> DashboardView<'a> {
     }
 
     /// 显示 KPI 卡片（增强版）
-    fn show_kpi_cards(&self, ui: &mut Ui) {
+    fn show_kpi_cards(&mut self, ui: &mut Ui) {
         // 过滤掉空名称的应用
         let valid_apps: Vec<_> = self
             .app_usage
@@ -121,6 +139,25 @@ impl<'a> DashboardView<'a> {
                 ),
             );
 
+            // 实际经过的时间（重叠事件只计一次），与累加的总使用时间对比
+            if let Some(wall_clock_seconds) = self.wall_clock_seconds {
+                let overlap_seconds = total_seconds - wall_clock_seconds;
+                ui.add(
+                    StatCard::new(
+                        "实际在线",
+                        &duration::format_duration(wall_clock_seconds),
+                        "⏱",
+                        self.theme,
+                    )
+                    .accent_color(self.theme.primary_color)
+                    .with_subtitle_option(
+                        (overlap_seconds > 0)
+                            .then(|| format!("重叠 {}", duration::format_duration(overlap_seconds)))
+                            .as_deref(),
+                    ),
+                );
+            }
+
             // 活跃应用数量
             ui.add(
                 StatCard::new("活跃应用", &format!("{} 个", app_count), "📱", self.theme)
@@ -151,17 +188,18 @@ impl<'a> DashboardView<'a> {
                     (u, non_afk_seconds)
                 })
                 .collect();
-            valid_apps_with_non_afk.sort_by(|a, b| b.1.cmp(&a.1));
+            valid_apps_with_non_afk.sort_by_key(|a| std::cmp::Reverse(a.1));
 
             if let Some((top_app, top_app_seconds)) = valid_apps_with_non_afk.first() {
                 let icon = self.icon_cache.get_emoji(&top_app.app_name);
+                let top_app_name = self.icon_cache.display_name(&top_app.app_name);
                 let percentage = if total_seconds > 0 {
                     (*top_app_seconds as f32 / total_seconds as f32) * 100.0
                 } else {
                     0.0
                 };
                 ui.add(
-                    StatCard::new("最常用", &top_app.app_name, icon, self.theme)
+                    StatCard::new("最常用", &top_app_name, icon, self.theme)
                         .subtitle(&format!(
                             "{} · {}%",
                             duration::format_duration(*top_app_seconds),
@@ -239,6 +277,7 @@ impl<'a> DashboardView<'a> {
         }
 
         let config = StackedBarChartConfig {
+            color_map: self.color_map.clone(),
             max_bar_height: 180.0,
             show_hover_highlight: false,
             ..Default::default()
@@ -315,7 +354,7 @@ impl<'a> DashboardView<'a> {
             .collect();
 
         // 按使用时长降序排序，确保排名正确
-        app_data.sort_by(|a, b| b.1.cmp(&a.1));
+        app_data.sort_by_key(|a| std::cmp::Reverse(a.1));
 
         // 使用 ScrollArea 占满宽度
         ScrollArea::vertical()
@@ -326,9 +365,10 @@ impl<'a> DashboardView<'a> {
                 for (rank, (app_name, total_secs, percentage, window_title)) in
                     app_data.into_iter().enumerate()
                 {
+                    let display_name = self.icon_cache.display_name(&app_name);
                     let mut card = AppCard::new(
                         &app_name,
-                        &app_name, // TODO: 使用别名
+                        &display_name,
                         total_secs,
                         percentage,
                         rank + 1, // 排名从1开始
@@ -4,7 +4,7 @@
 
 use chrono::{DateTime, Datelike, Local, NaiveDate, Utc};
 use egui::{ScrollArea, TextEdit, Ui, Vec2};
-use tail_core::AppUsage;
+use tail_core::WindowEvent;
 use tail_core::time::range::TimeRangeCalculator;
 
 use crate::components::{EmptyState, PageHeader, SectionDivider};
@@ -12,6 +12,9 @@ use crate::icons::{AppIcon, IconCache};
 use crate::theme::TaiLTheme;
 use crate::utils::duration;
 
+/// 详细记录默认最多显示的条数
+pub const DEFAULT_DETAILS_MAX_RECORDS: usize = 500;
+
 /// 详细视图
 pub struct DetailsView {
     /// 搜索关键词
@@ -28,6 +31,10 @@ pub struct DetailsView {
     show_custom_range: bool,
     /// 数据缓存（扁平化的窗口事件）
     flat_data: Vec<WindowEventRecord>,
+    /// 最多显示的记录条数（0 表示不限制）
+    max_records: usize,
+    /// 加载事件时触发的数量上限（`None` 表示已加载全部事件）
+    loaded_limit: Option<usize>,
 }
 
 /// 时间过滤器
@@ -69,26 +76,33 @@ impl DetailsView {
             custom_end_date: Some(today),
             show_custom_range: false,
             flat_data: Vec::new(),
+            max_records: DEFAULT_DETAILS_MAX_RECORDS,
+            loaded_limit: None,
         }
     }
 
-    /// 更新扁平化数据
-    pub fn update_data(&mut self, app_usage: &[AppUsage]) {
-        self.flat_data.clear();
-        for usage in app_usage {
-            for event in &usage.window_events {
-                self.flat_data.push(WindowEventRecord {
-                    app_name: usage.app_name.clone(),
-                    window_title: event.window_title.clone(),
-                    start_time: event.timestamp,
-                    duration_secs: event.duration_secs,
-                    is_afk: event.is_afk,
-                });
-            }
-        }
-        // 按开始时间降序排序
-        self.flat_data
-            .sort_by(|a, b| b.start_time.cmp(&a.start_time));
+    /// 设置最多显示的记录条数（0 表示不限制）
+    pub fn set_max_records(&mut self, max_records: usize) {
+        self.max_records = max_records;
+    }
+
+    /// 设置加载事件时触发的数量上限，`Some(n)` 表示只加载了最近的 n 个事件
+    pub fn set_loaded_limit(&mut self, loaded_limit: Option<usize>) {
+        self.loaded_limit = loaded_limit;
+    }
+
+    /// 更新扁平化数据（`events` 需已按开始时间降序排列）
+    pub fn update_data(&mut self, events: &[WindowEvent]) {
+        self.flat_data = events
+            .iter()
+            .map(|event| WindowEventRecord {
+                app_name: event.app_name.clone(),
+                window_title: event.window_title.clone(),
+                start_time: event.timestamp,
+                duration_secs: event.duration_secs,
+                is_afk: event.is_afk,
+            })
+            .collect();
     }
 
     /// 渲染详细视图
@@ -105,6 +119,15 @@ impl DetailsView {
         ui.add(SectionDivider::new(theme).with_title("记录列表"));
         ui.add_space(theme.spacing / 2.0);
 
+        if let Some(limit) = self.loaded_limit {
+            ui.label(
+                egui::RichText::new(format!("⚠ 记录过多，仅加载了最近的 {} 个事件", limit))
+                    .size(theme.small_size)
+                    .color(theme.warning_color),
+            );
+            ui.add_space(theme.spacing / 2.0);
+        }
+
         // 数据列表
         self.show_data_list(ui, theme, icon_cache);
     }
@@ -602,12 +625,8 @@ impl DetailsView {
     /// 显示数据列表
     fn show_data_list(&mut self, ui: &mut Ui, theme: &TaiLTheme, icon_cache: &mut IconCache) {
         // 收集过滤后的数据（克隆以避免借用问题）
-        let filtered_data: Vec<WindowEventRecord> = self
-            .filter_data()
-            .iter()
-            .take(500)
-            .map(|r| (*r).clone())
-            .collect();
+        let filtered_data: Vec<WindowEventRecord> =
+            self.filter_data().iter().map(|r| (*r).clone()).collect();
 
         if filtered_data.is_empty() {
             ui.add(EmptyState::new(
@@ -679,7 +698,7 @@ impl DetailsView {
 
             // 应用名
             ui.label(
-                egui::RichText::new(&record.app_name)
+                egui::RichText::new(icon_cache.display_name(&record.app_name))
                     .size(theme.body_size)
                     .color(theme.text_color),
             );
@@ -801,13 +820,8 @@ impl DetailsView {
                                 .and_local_timezone(Local)
                                 .unwrap()
                                 .with_timezone(&Utc);
-                            let end_utc = end_date
-                                .and_hms_opt(23, 59, 59)
-                                .unwrap()
-                                .and_local_timezone(Local)
-                                .unwrap()
-                                .with_timezone(&Utc);
-                            record.start_time >= start_utc && record.start_time <= end_utc
+                            let end_utc = TimeRangeCalculator::day(end_date).end;
+                            record.start_time >= start_utc && record.start_time < end_utc
                         } else {
                             true
                         }
@@ -816,8 +830,47 @@ impl DetailsView {
             })
             .collect();
 
-        // 最多显示 1000 条
-        result.truncate(1000);
+        if self.max_records > 0 {
+            result.truncate(self.max_records);
+        }
         result
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn view_with_records(count: usize) -> DetailsView {
+        let now = Utc::now();
+        let events: Vec<WindowEvent> = (0..count)
+            .map(|i| WindowEvent {
+                id: None,
+                timestamp: now - chrono::Duration::minutes(i as i64),
+                app_name: format!("app{}", i),
+                window_title: String::new(),
+                workspace: String::new(),
+                duration_secs: 60,
+                is_afk: false,
+            })
+            .collect();
+        let mut view = DetailsView::new();
+        view.update_data(&events);
+        view
+    }
+
+    #[test]
+    fn test_filter_data_honors_max_records() {
+        let mut view = view_with_records(10);
+        assert_eq!(view.filter_data().len(), 10);
+
+        view.set_max_records(3);
+        let filtered = view.filter_data();
+        assert_eq!(filtered.len(), 3);
+        assert_eq!(filtered[0].app_name, "app0");
+
+        // 0 表示不限制
+        view.set_max_records(0);
+        assert_eq!(view.filter_data().len(), 10);
+    }
+}
@@ -1,15 +1,21 @@
 //! TaiL GUI - 视图模块
 
 pub mod aggregation;
+pub mod calendar;
 pub mod categories;
 pub mod dashboard;
 pub mod details;
+pub mod onboarding;
 pub mod settings;
 pub mod statistics;
+pub mod timeline;
 
 pub use aggregation::*;
+pub use calendar::*;
 pub use categories::*;
 pub use dashboard::*;
 pub use details::*;
+pub use onboarding::*;
 pub use settings::*;
 pub use statistics::*;
+pub use timeline::*;
@@ -0,0 +1,96 @@
+//! TaiL GUI - 首次使用引导
+//!
+//! 数据库中还没有任何窗口事件时，用引导页代替空白的统计页面，说明需要先运行追踪服务。
+
+use egui::{Ui, Vec2};
+
+use crate::components::PageHeader;
+use crate::theme::TaiLTheme;
+
+/// 是否显示引导页：没有任何数据且用户没有关闭引导时显示
+pub fn should_show_onboarding(has_events: bool, dismissed: bool) -> bool {
+    !has_events && !dismissed
+}
+
+/// 引导页的操作
+pub enum OnboardingAction {
+    /// 创建默认分类
+    CreateDefaultCategories,
+    /// 关闭引导页
+    Dismiss,
+}
+
+/// 首次使用引导视图
+pub struct OnboardingView<'a> {
+    theme: &'a TaiLTheme,
+}
+
+impl<'a> OnboardingView<'a> {
+    pub fn new(theme: &'a TaiLTheme) -> Self {
+        Self { theme }
+    }
+
+    /// 渲染引导页，返回用户选择的操作
+    pub fn show(&self, ui: &mut Ui) -> Option<OnboardingAction> {
+        let mut action = None;
+
+        ui.add(PageHeader::new("欢迎使用 TaiL", "👋", self.theme).subtitle("还没有任何使用记录"));
+        ui.add_space(self.theme.spacing);
+
+        let steps = [
+            "1. 启动追踪服务（tail-service），它会在后台记录窗口切换和空闲状态",
+            "2. 正常使用电脑，几秒钟后数据就会出现在各个统计页面",
+            "3. 可以先创建默认分类，之后在分类页面把应用归入分类",
+        ];
+        for step in steps {
+            ui.label(
+                egui::RichText::new(step)
+                    .size(self.theme.body_size)
+                    .color(self.theme.text_color),
+            );
+            ui.add_space(4.0);
+        }
+
+        ui.add_space(self.theme.spacing);
+        ui.horizontal(|ui| {
+            if ui
+                .add(
+                    egui::Button::new("创建默认分类")
+                        .fill(self.theme.primary_color)
+                        .min_size(Vec2::new(120.0, 32.0)),
+                )
+                .clicked()
+            {
+                action = Some(OnboardingAction::CreateDefaultCategories);
+            }
+
+            ui.add_space(8.0);
+
+            if ui
+                .add(
+                    egui::Button::new("不再显示")
+                        .fill(self.theme.card_hover_background)
+                        .min_size(Vec2::new(80.0, 32.0)),
+                )
+                .clicked()
+            {
+                action = Some(OnboardingAction::Dismiss);
+            }
+        });
+
+        action
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_should_show_onboarding() {
+        assert!(should_show_onboarding(false, false));
+        assert!(!should_show_onboarding(false, true));
+        assert!(!should_show_onboarding(true, false));
+        assert!(!should_show_onboarding(true, true));
+    }
+}
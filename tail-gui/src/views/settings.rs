@@ -1,11 +1,15 @@
 //! TaiL GUI - 设置视图
 
 use egui::{Color32, Rounding, ScrollArea, Ui, Vec2};
+use std::collections::HashMap;
 use tail_core::DailyGoal;
-use tail_core::db::Config as DbConfig;
+use tail_core::display::DisplayNameMode;
+use tail_core::duration::DurationLocale;
+use tail_core::time::WeekNumbering;
 
-use crate::components::{DefaultStatsView, PageHeader, SectionDivider};
+use crate::components::{ComboSearch, DefaultStatsView, PageHeader, SectionDivider};
 use crate::theme::{TaiLTheme, ThemeType};
+use crate::utils::duration;
 
 /// 设置视图
 pub struct SettingsView<'a> {
@@ -15,6 +19,18 @@ pub struct SettingsView<'a> {
     current_theme_type: ThemeType,
     /// 当前默认统计视图
     current_default_view: DefaultStatsView,
+    /// 当前 AFK 判定阈值（秒）
+    afk_threshold_secs: i64,
+    /// 详细记录最多显示的条数（0 表示不限制）
+    details_max_records: usize,
+    /// 当前周编号方式
+    week_numbering: WeekNumbering,
+    /// 当前应用名称显示模式
+    display_name_mode: DisplayNameMode,
+    /// 当前时长单位语言
+    duration_locale: DurationLocale,
+    /// 当前数据库文件路径
+    db_path: &'a str,
     /// 主题
     theme: &'a TaiLTheme,
 }
@@ -29,23 +45,83 @@ pub enum SettingsAction {
     ChangeTheme(ThemeType),
     /// 更改默认统计视图
     ChangeDefaultView(DefaultStatsView),
+    /// 更改 AFK 判定阈值（秒）
+    ChangeAfkThreshold(i64),
+    /// 更改详细记录最多显示的条数（0 表示不限制）
+    ChangeDetailsMaxRecords(usize),
+    /// 更改周编号方式
+    ChangeWeekNumbering(WeekNumbering),
+    /// 更改应用名称显示模式
+    ChangeDisplayNameMode(DisplayNameMode),
+    /// 更改时长单位语言
+    ChangeDurationLocale(DurationLocale),
     /// 管理别名
     ManageAliases,
+    /// 从 `原始名称=别名` 文件导入别名
+    ImportAliases,
+    /// 导出别名到 `原始名称=别名` 文件
+    ExportAliases,
+    /// 切换数据库文件
+    ChangeDatabase,
+    /// 备份数据库
+    BackupDatabase,
+    /// 清理重复数据
+    CleanDuplicates,
+    /// 导入 ActivityWatch 分类规则
+    ImportAwCategories,
     /// 无操作
     None,
 }
 
+/// 时长单位语言的界面名称
+fn duration_locale_label(locale: DurationLocale) -> &'static str {
+    match locale {
+        DurationLocale::En => "英文 (1h 5m)",
+        DurationLocale::Zh => "中文 (1小时5分)",
+    }
+}
+
+/// 周编号方式的界面名称
+fn week_numbering_label(numbering: WeekNumbering) -> &'static str {
+    match numbering {
+        WeekNumbering::MonthRelative => "月内周序 (第1周)",
+        WeekNumbering::Iso => "ISO 周数 (W11)",
+    }
+}
+
+/// 应用名称显示模式的界面名称
+fn display_name_mode_label(mode: DisplayNameMode) -> &'static str {
+    match mode {
+        DisplayNameMode::Raw => "进程名",
+        DisplayNameMode::Alias => "别名",
+        DisplayNameMode::Desktop => "桌面名称",
+    }
+}
+
 impl<'a> SettingsView<'a> {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         daily_goals: &'a [DailyGoal],
         current_theme_type: ThemeType,
         current_default_view: DefaultStatsView,
+        afk_threshold_secs: i64,
+        details_max_records: usize,
+        week_numbering: WeekNumbering,
+        display_name_mode: DisplayNameMode,
+        duration_locale: DurationLocale,
+        db_path: &'a str,
         theme: &'a TaiLTheme,
     ) -> Self {
         Self {
             daily_goals,
             current_theme_type,
             current_default_view,
+            afk_threshold_secs,
+            details_max_records,
+            week_numbering,
+            display_name_mode,
+            duration_locale,
+            db_path,
             theme,
         }
     }
@@ -70,6 +146,12 @@ impl<'a> SettingsView<'a> {
                     action = SettingsAction::ChangeTheme(new_theme);
                 }
 
+                ui.add_space(self.theme.spacing / 2.0);
+
+                if let Some(new_locale) = self.show_duration_locale_settings(ui) {
+                    action = SettingsAction::ChangeDurationLocale(new_locale);
+                }
+
                 ui.add_space(self.theme.spacing);
 
                 // 统计设置
@@ -80,6 +162,24 @@ impl<'a> SettingsView<'a> {
                     action = SettingsAction::ChangeDefaultView(new_view);
                 }
 
+                ui.add_space(self.theme.spacing / 2.0);
+
+                if let Some(new_numbering) = self.show_week_numbering_settings(ui) {
+                    action = SettingsAction::ChangeWeekNumbering(new_numbering);
+                }
+
+                ui.add_space(self.theme.spacing / 2.0);
+
+                if let Some(new_threshold) = self.show_afk_settings(ui) {
+                    action = SettingsAction::ChangeAfkThreshold(new_threshold);
+                }
+
+                ui.add_space(self.theme.spacing / 2.0);
+
+                if let Some(new_max) = self.show_details_settings(ui) {
+                    action = SettingsAction::ChangeDetailsMaxRecords(new_max);
+                }
+
                 ui.add_space(self.theme.spacing);
 
                 // 每日目标设置
@@ -96,10 +196,28 @@ impl<'a> SettingsView<'a> {
                 ui.add(SectionDivider::new(self.theme).with_title("应用别名"));
                 ui.add_space(self.theme.spacing / 2.0);
 
-                if ui.button("📝 管理应用别名").clicked() {
-                    action = SettingsAction::ManageAliases;
+                if let Some(new_mode) = self.show_display_name_settings(ui) {
+                    action = SettingsAction::ChangeDisplayNameMode(new_mode);
                 }
 
+                ui.add_space(self.theme.spacing / 2.0);
+
+                ui.horizontal(|ui| {
+                    if ui.button("📝 管理应用别名").clicked() {
+                        action = SettingsAction::ManageAliases;
+                    }
+                    if ui
+                        .button("导入别名")
+                        .on_hover_text("从每行一条 原始名称=别名 的文本文件批量导入")
+                        .clicked()
+                    {
+                        action = SettingsAction::ImportAliases;
+                    }
+                    if ui.button("导出别名").clicked() {
+                        action = SettingsAction::ExportAliases;
+                    }
+                });
+
                 ui.add_space(4.0);
                 ui.label(
                     egui::RichText::new("为应用设置友好的名称，让统计数据更易读")
@@ -112,7 +230,9 @@ impl<'a> SettingsView<'a> {
                 // 数据设置
                 ui.add(SectionDivider::new(self.theme).with_title("数据"));
                 ui.add_space(self.theme.spacing / 2.0);
-                self.show_data_settings(ui);
+                if let Some(data_action) = self.show_data_settings(ui) {
+                    action = data_action;
+                }
 
                 ui.add_space(self.theme.spacing);
 
@@ -241,6 +361,292 @@ impl<'a> SettingsView<'a> {
         new_view
     }
 
+    /// 显示 AFK 设置
+    fn show_afk_settings(&self, ui: &mut Ui) -> Option<i64> {
+        let mut new_threshold = None;
+
+        let card_width = ui.available_width();
+
+        ui.allocate_ui_with_layout(
+            Vec2::new(card_width, 80.0),
+            egui::Layout::left_to_right(egui::Align::Center),
+            |ui| {
+                // 绘制卡片背景
+                let painter = ui.painter();
+                let rect = ui.available_rect_before_wrap();
+                painter.rect_filled(
+                    rect,
+                    Rounding::same(self.theme.card_rounding),
+                    self.theme.card_background,
+                );
+
+                ui.add_space(self.theme.card_padding);
+
+                ui.vertical(|ui| {
+                    ui.add_space(8.0);
+                    ui.label(
+                        egui::RichText::new("AFK 判定阈值")
+                            .size(self.theme.body_size)
+                            .color(self.theme.text_color),
+                    );
+
+                    ui.add_space(8.0);
+
+                    ui.horizontal(|ui| {
+                        let mut secs = self.afk_threshold_secs;
+                        let response = ui.add(
+                            egui::DragValue::new(&mut secs)
+                                .range(30..=3600)
+                                .speed(10)
+                                .suffix(" 秒"),
+                        );
+                        if response.changed() && secs != self.afk_threshold_secs {
+                            new_threshold = Some(secs);
+                        }
+
+                        ui.label(
+                            egui::RichText::new("无操作超过该时间后视为离开")
+                                .size(self.theme.small_size)
+                                .color(self.theme.secondary_text_color),
+                        );
+                    });
+                });
+            },
+        );
+
+        new_threshold
+    }
+
+    /// 显示详细记录设置
+    fn show_details_settings(&self, ui: &mut Ui) -> Option<usize> {
+        let mut new_max = None;
+
+        let card_width = ui.available_width();
+
+        ui.allocate_ui_with_layout(
+            Vec2::new(card_width, 80.0),
+            egui::Layout::left_to_right(egui::Align::Center),
+            |ui| {
+                // 绘制卡片背景
+                let painter = ui.painter();
+                let rect = ui.available_rect_before_wrap();
+                painter.rect_filled(
+                    rect,
+                    Rounding::same(self.theme.card_rounding),
+                    self.theme.card_background,
+                );
+
+                ui.add_space(self.theme.card_padding);
+
+                ui.vertical(|ui| {
+                    ui.add_space(8.0);
+                    ui.label(
+                        egui::RichText::new("详细记录显示条数")
+                            .size(self.theme.body_size)
+                            .color(self.theme.text_color),
+                    );
+
+                    ui.add_space(8.0);
+
+                    ui.horizontal(|ui| {
+                        let mut max = self.details_max_records;
+                        let response = ui.add(
+                            egui::DragValue::new(&mut max)
+                                .range(0..=100_000)
+                                .speed(50)
+                                .suffix(" 条"),
+                        );
+                        if response.changed() && max != self.details_max_records {
+                            new_max = Some(max);
+                        }
+
+                        ui.label(
+                            egui::RichText::new("详细记录页面最多显示的记录数，0 表示不限制")
+                                .size(self.theme.small_size)
+                                .color(self.theme.secondary_text_color),
+                        );
+                    });
+                });
+            },
+        );
+
+        new_max
+    }
+
+    /// 显示周编号方式设置
+    fn show_week_numbering_settings(&self, ui: &mut Ui) -> Option<WeekNumbering> {
+        let mut new_numbering = None;
+
+        let card_width = ui.available_width();
+
+        ui.allocate_ui_with_layout(
+            Vec2::new(card_width, 80.0),
+            egui::Layout::left_to_right(egui::Align::Center),
+            |ui| {
+                // 绘制卡片背景
+                let painter = ui.painter();
+                let rect = ui.available_rect_before_wrap();
+                painter.rect_filled(
+                    rect,
+                    Rounding::same(self.theme.card_rounding),
+                    self.theme.card_background,
+                );
+
+                ui.add_space(self.theme.card_padding);
+
+                ui.vertical(|ui| {
+                    ui.add_space(8.0);
+                    ui.label(
+                        egui::RichText::new("周编号")
+                            .size(self.theme.body_size)
+                            .color(self.theme.text_color),
+                    );
+
+                    ui.add_space(8.0);
+
+                    ui.horizontal(|ui| {
+                        for numbering in WeekNumbering::all() {
+                            let is_selected = *numbering == self.week_numbering;
+
+                            let button = egui::Button::new(
+                                egui::RichText::new(week_numbering_label(*numbering))
+                                    .size(self.theme.small_size),
+                            )
+                            .fill(if is_selected {
+                                self.theme.primary_color
+                            } else {
+                                self.theme.card_hover_background
+                            })
+                            .rounding(Rounding::same(6.0));
+
+                            if ui.add(button).clicked() && !is_selected {
+                                new_numbering = Some(*numbering);
+                            }
+                        }
+                    });
+                });
+            },
+        );
+
+        new_numbering
+    }
+
+    /// 显示时长单位语言设置
+    fn show_duration_locale_settings(&self, ui: &mut Ui) -> Option<DurationLocale> {
+        let mut new_locale = None;
+
+        let card_width = ui.available_width();
+
+        ui.allocate_ui_with_layout(
+            Vec2::new(card_width, 80.0),
+            egui::Layout::left_to_right(egui::Align::Center),
+            |ui| {
+                // 绘制卡片背景
+                let painter = ui.painter();
+                let rect = ui.available_rect_before_wrap();
+                painter.rect_filled(
+                    rect,
+                    Rounding::same(self.theme.card_rounding),
+                    self.theme.card_background,
+                );
+
+                ui.add_space(self.theme.card_padding);
+
+                ui.vertical(|ui| {
+                    ui.add_space(8.0);
+                    ui.label(
+                        egui::RichText::new("时长单位")
+                            .size(self.theme.body_size)
+                            .color(self.theme.text_color),
+                    );
+
+                    ui.add_space(8.0);
+
+                    ui.horizontal(|ui| {
+                        for locale in DurationLocale::all() {
+                            let is_selected = *locale == self.duration_locale;
+
+                            let button = egui::Button::new(
+                                egui::RichText::new(duration_locale_label(*locale))
+                                    .size(self.theme.small_size),
+                            )
+                            .fill(if is_selected {
+                                self.theme.primary_color
+                            } else {
+                                self.theme.card_hover_background
+                            })
+                            .rounding(Rounding::same(6.0));
+
+                            if ui.add(button).clicked() && !is_selected {
+                                new_locale = Some(*locale);
+                            }
+                        }
+                    });
+                });
+            },
+        );
+
+        new_locale
+    }
+
+    /// 显示应用名称显示模式设置
+    fn show_display_name_settings(&self, ui: &mut Ui) -> Option<DisplayNameMode> {
+        let mut new_mode = None;
+
+        let card_width = ui.available_width();
+
+        ui.allocate_ui_with_layout(
+            Vec2::new(card_width, 80.0),
+            egui::Layout::left_to_right(egui::Align::Center),
+            |ui| {
+                // 绘制卡片背景
+                let painter = ui.painter();
+                let rect = ui.available_rect_before_wrap();
+                painter.rect_filled(
+                    rect,
+                    Rounding::same(self.theme.card_rounding),
+                    self.theme.card_background,
+                );
+
+                ui.add_space(self.theme.card_padding);
+
+                ui.vertical(|ui| {
+                    ui.add_space(8.0);
+                    ui.label(
+                        egui::RichText::new("应用名称显示")
+                            .size(self.theme.body_size)
+                            .color(self.theme.text_color),
+                    );
+
+                    ui.add_space(8.0);
+
+                    ui.horizontal(|ui| {
+                        for mode in DisplayNameMode::all() {
+                            let is_selected = *mode == self.display_name_mode;
+
+                            let button = egui::Button::new(
+                                egui::RichText::new(display_name_mode_label(*mode))
+                                    .size(self.theme.small_size),
+                            )
+                            .fill(if is_selected {
+                                self.theme.primary_color
+                            } else {
+                                self.theme.card_hover_background
+                            })
+                            .rounding(Rounding::same(6.0));
+
+                            if ui.add(button).clicked() && !is_selected {
+                                new_mode = Some(*mode);
+                            }
+                        }
+                    });
+                });
+            },
+        );
+
+        new_mode
+    }
+
     /// 显示目标设置
     fn show_goal_settings(&self, ui: &mut Ui) -> Option<SettingsAction> {
         let mut action = None;
@@ -344,8 +750,8 @@ impl<'a> SettingsView<'a> {
     }
 
     /// 显示数据设置
-    fn show_data_settings(&self, ui: &mut Ui) {
-        let config = DbConfig::default();
+    fn show_data_settings(&self, ui: &mut Ui) -> Option<SettingsAction> {
+        let mut action = None;
 
         // 数据库位置卡片
         ui.allocate_ui_with_layout(
@@ -378,7 +784,7 @@ impl<'a> SettingsView<'a> {
                     });
                     ui.add_space(4.0);
                     ui.label(
-                        egui::RichText::new(&config.path)
+                        egui::RichText::new(self.db_path)
                             .size(self.theme.small_size)
                             .color(self.theme.secondary_text_color),
                     );
@@ -390,6 +796,58 @@ impl<'a> SettingsView<'a> {
 
         // 数据操作按钮
         ui.horizontal(|ui| {
+            if ui
+                .add(
+                    egui::Button::new(
+                        egui::RichText::new("切换数据库").size(self.theme.small_size),
+                    )
+                    .rounding(Rounding::same(6.0)),
+                )
+                .on_hover_text("选择其他数据库文件（如工作/家庭分开记录）")
+                .clicked()
+            {
+                action = Some(SettingsAction::ChangeDatabase);
+            }
+
+            if ui
+                .add(
+                    egui::Button::new(
+                        egui::RichText::new("备份数据库").size(self.theme.small_size),
+                    )
+                    .rounding(Rounding::same(6.0)),
+                )
+                .on_hover_text("将当前数据库复制到指定文件，记录期间也可安全备份")
+                .clicked()
+            {
+                action = Some(SettingsAction::BackupDatabase);
+            }
+
+            if ui
+                .add(
+                    egui::Button::new(
+                        egui::RichText::new("清理重复数据").size(self.theme.small_size),
+                    )
+                    .rounding(Rounding::same(6.0)),
+                )
+                .on_hover_text("删除追踪器重复记录的相同事件")
+                .clicked()
+            {
+                action = Some(SettingsAction::CleanDuplicates);
+            }
+
+            if ui
+                .add(
+                    egui::Button::new(
+                        egui::RichText::new("导入 ActivityWatch 分类").size(self.theme.small_size),
+                    )
+                    .rounding(Rounding::same(6.0)),
+                )
+                .on_hover_text("从 ActivityWatch 导出的分类规则 JSON 创建分类并归类已有应用")
+                .clicked()
+            {
+                action = Some(SettingsAction::ImportAwCategories);
+            }
+
             if ui
                 .add(
                     egui::Button::new(egui::RichText::new("导出数据").size(self.theme.small_size))
@@ -415,6 +873,8 @@ impl<'a> SettingsView<'a> {
                 // TODO: 实现数据清除（需要确认对话框）
             }
         });
+
+        action
     }
 
     /// 显示关于信息
@@ -488,10 +948,43 @@ impl<'a> SettingsView<'a> {
     }
 }
 
+/// 每日目标输入的校验结果
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GoalCheck {
+    /// 上限无效（必须大于 0 分钟）
+    Invalid,
+    /// 有效，但低于今天已使用的时长，保存后会立即超出
+    BelowCurrent,
+    /// 有效
+    Ok,
+}
+
+impl GoalCheck {
+    /// 根据上限分钟数和今天已使用的秒数校验目标
+    pub fn evaluate(max_minutes: i32, used_seconds: i64) -> Self {
+        if max_minutes <= 0 {
+            Self::Invalid
+        } else if (max_minutes as i64) * 60 < used_seconds {
+            Self::BelowCurrent
+        } else {
+            Self::Ok
+        }
+    }
+
+    /// 是否允许保存
+    pub fn can_save(self) -> bool {
+        self != Self::Invalid
+    }
+}
+
 /// 添加目标对话框
 pub struct AddGoalDialog {
     /// 应用名称
     pub app_name: String,
+    /// 可选的应用列表
+    pub apps: Vec<String>,
+    /// 各应用今天已使用的秒数
+    pub today_usage: HashMap<String, i64>,
     /// 最大分钟数
     pub max_minutes: i32,
     /// 是否显示
@@ -502,6 +995,8 @@ impl Default for AddGoalDialog {
     fn default() -> Self {
         Self {
             app_name: String::new(),
+            apps: Vec::new(),
+            today_usage: HashMap::new(),
             max_minutes: 60,
             visible: false,
         }
@@ -513,8 +1008,11 @@ impl AddGoalDialog {
         Self::default()
     }
 
-    pub fn open(&mut self) {
+    /// 打开对话框，`apps` 为可供选择的应用，`today_usage` 为各应用今天已使用的秒数
+    pub fn open(&mut self, apps: Vec<String>, today_usage: HashMap<String, i64>) {
         self.visible = true;
+        self.apps = apps;
+        self.today_usage = today_usage;
         self.app_name.clear();
         self.max_minutes = 60;
     }
@@ -546,11 +1044,31 @@ impl AddGoalDialog {
                         .size(theme.small_size)
                         .color(theme.secondary_text_color),
                 );
-                ui.add(
-                    egui::TextEdit::singleline(&mut self.app_name)
+                // 输入框既是过滤词也是应用名称，可以直接输入列表中没有的应用
+                let picked =
+                    ComboSearch::new("add_goal_apps_list", &mut self.app_name, &self.apps, theme)
                         .hint_text("例如: firefox, code")
-                        .desired_width(f32::INFINITY),
-                );
+                        .max_height(150.0)
+                        .show(ui);
+                if let Some(app_name) = picked {
+                    self.app_name = app_name;
+                }
+
+                let used_seconds = self
+                    .today_usage
+                    .get(self.app_name.trim())
+                    .copied()
+                    .unwrap_or(0);
+                if !self.app_name.trim().is_empty() {
+                    ui.label(
+                        egui::RichText::new(format!(
+                            "今天已使用 {}",
+                            duration::format_duration(used_seconds)
+                        ))
+                        .size(theme.small_size)
+                        .color(theme.secondary_text_color),
+                    );
+                }
 
                 ui.add_space(12.0);
 
@@ -575,6 +1093,25 @@ impl AddGoalDialog {
                         .color(theme.secondary_text_color),
                 );
 
+                let check = GoalCheck::evaluate(self.max_minutes, used_seconds);
+                match check {
+                    GoalCheck::Invalid => {
+                        ui.label(
+                            egui::RichText::new("⚠ 每日目标必须大于 0 分钟")
+                                .size(theme.small_size)
+                                .color(theme.danger_color),
+                        );
+                    }
+                    GoalCheck::BelowCurrent => {
+                        ui.label(
+                            egui::RichText::new("⚠ 上限低于今天已使用的时长，保存后将立即超出")
+                                .size(theme.small_size)
+                                .color(theme.warning_color),
+                        );
+                    }
+                    GoalCheck::Ok => {}
+                }
+
                 ui.add_space(16.0);
 
                 ui.horizontal(|ui| {
@@ -591,7 +1128,7 @@ impl AddGoalDialog {
 
                     ui.add_space(8.0);
 
-                    let can_add = !self.app_name.trim().is_empty();
+                    let can_add = !self.app_name.trim().is_empty() && check.can_save();
                     if ui
                         .add_enabled(
                             can_add,
@@ -619,3 +1156,127 @@ impl AddGoalDialog {
         result
     }
 }
+
+/// 清理重复数据确认对话框
+#[derive(Default)]
+pub struct DuplicateCleanupDialog {
+    /// 发现的重复事件数量
+    duplicate_count: usize,
+    /// 是否显示
+    visible: bool,
+}
+
+impl DuplicateCleanupDialog {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 打开对话框并显示重复事件数量
+    pub fn open(&mut self, duplicate_count: usize) {
+        self.duplicate_count = duplicate_count;
+        self.visible = true;
+    }
+
+    pub fn close(&mut self) {
+        self.visible = false;
+    }
+
+    /// 显示对话框，返回是否确认删除
+    pub fn show(&mut self, ctx: &egui::Context, theme: &TaiLTheme) -> bool {
+        if !self.visible {
+            return false;
+        }
+
+        let mut confirmed = false;
+        let mut should_close = false;
+
+        egui::Window::new("清理重复数据")
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+            .show(ctx, |ui| {
+                ui.set_min_width(300.0);
+
+                ui.add_space(8.0);
+
+                let message = if self.duplicate_count == 0 {
+                    "没有发现重复数据".to_string()
+                } else {
+                    format!(
+                        "发现 {} 条重复记录，删除后每组只保留第一条。此操作无法撤销。",
+                        self.duplicate_count
+                    )
+                };
+                ui.label(
+                    egui::RichText::new(message)
+                        .size(theme.body_size)
+                        .color(theme.text_color),
+                );
+
+                ui.add_space(16.0);
+
+                ui.horizontal(|ui| {
+                    let cancel_label = if self.duplicate_count == 0 {
+                        "关闭"
+                    } else {
+                        "取消"
+                    };
+                    if ui
+                        .add(
+                            egui::Button::new(cancel_label)
+                                .fill(theme.card_hover_background)
+                                .min_size(Vec2::new(80.0, 32.0)),
+                        )
+                        .clicked()
+                    {
+                        should_close = true;
+                    }
+
+                    if self.duplicate_count > 0 {
+                        ui.add_space(8.0);
+
+                        if ui
+                            .add(
+                                egui::Button::new("删除")
+                                    .fill(theme.danger_color)
+                                    .min_size(Vec2::new(80.0, 32.0)),
+                            )
+                            .clicked()
+                        {
+                            confirmed = true;
+                            should_close = true;
+                        }
+                    }
+                });
+            });
+
+        if should_close {
+            self.close();
+        }
+
+        confirmed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_goal_check_rejects_zero() {
+        assert_eq!(GoalCheck::evaluate(0, 0), GoalCheck::Invalid);
+        assert_eq!(GoalCheck::evaluate(-5, 0), GoalCheck::Invalid);
+        assert!(!GoalCheck::evaluate(0, 0).can_save());
+    }
+
+    #[test]
+    fn test_goal_check_below_current_usage() {
+        // 今天已使用 45 分钟
+        let used = 45 * 60;
+        assert_eq!(GoalCheck::evaluate(30, used), GoalCheck::BelowCurrent);
+        assert!(GoalCheck::evaluate(30, used).can_save());
+        assert_eq!(GoalCheck::evaluate(45, used), GoalCheck::Ok);
+        assert_eq!(GoalCheck::evaluate(60, used), GoalCheck::Ok);
+        assert_eq!(GoalCheck::evaluate(1, 0), GoalCheck::Ok);
+    }
+}
@@ -1,18 +1,20 @@
 //! TaiL GUI - 统计视图
 
 use chrono::{Datelike, Local, Utc};
-use egui::{Color32, Rect, Rounding, Ui, Vec2};
+use egui::{Color32, Ui};
 use egui_extras::{Column, TableBuilder};
 use tail_core::AppUsage;
 use tail_core::TimeNavigationState;
+use tail_core::compare::{self, ComparisonPeriod};
 use tail_core::models::TimeRange;
+use tail_core::time::TimeGranularity;
 
 use crate::components::chart::{
-    ChartDataBuilder, ChartGroupMode, ChartTimeGranularity, StackedBarChart, StackedBarChartConfig,
-    StackedBarTooltip,
+    CategoryColorMap, ChartDataBuilder, ChartGroupMode, ChartTimeGranularity, StackedBarChart,
+    StackedBarChartConfig, StackedBarTooltip,
 };
 use crate::components::{
-    EmptyState, HierarchicalBarChart, PageHeader, QuickTimeRange, SectionDivider,
+    EmptyState, HierarchicalBarChart, PageHeader, ProgressBar, QuickTimeRange, SectionDivider,
     TimeNavigationController,
 };
 use crate::icons::IconCache;
@@ -34,6 +36,14 @@ pub struct StatisticsView<'a> {
     use_stacked_view: bool,
     /// 悬停的时间槽索引
     hovered_slot: Option<usize>,
+    /// 是否启用对比模式
+    compare_mode: bool,
+    /// 对比模式下的对照时间段
+    compare_period: ComparisonPeriod,
+    /// 对照时间段的应用使用数据（对比模式）
+    previous_usage: &'a [AppUsage],
+    /// 图表颜色配置
+    color_map: CategoryColorMap,
 }
 
 impl<'a> StatisticsView<'a> {
@@ -51,9 +61,42 @@ impl<'a> StatisticsView<'a> {
             icon_cache,
             use_stacked_view,
             hovered_slot: None,
+            compare_mode: false,
+            compare_period: ComparisonPeriod::default(),
+            previous_usage: &[],
+            color_map: CategoryColorMap::default(),
         }
     }
 
+    /// 设置对比模式，`previous_usage` 为 `compare_period` 对应时间段的数据
+    pub fn with_comparison(
+        mut self,
+        compare_mode: bool,
+        compare_period: ComparisonPeriod,
+        previous_usage: &'a [AppUsage],
+    ) -> Self {
+        self.compare_mode = compare_mode;
+        self.compare_period = compare_period;
+        self.previous_usage = previous_usage;
+        self
+    }
+
+    /// 设置图表颜色配置（包含用户为应用指定的颜色）
+    pub fn with_color_map(mut self, color_map: CategoryColorMap) -> Self {
+        self.color_map = color_map;
+        self
+    }
+
+    /// 当前是否处于对比模式（在 `show` 之后读取用户的切换）
+    pub fn compare_mode(&self) -> bool {
+        self.compare_mode
+    }
+
+    /// 当前选择的对照时间段（在 `show` 之后读取用户的选择）
+    pub fn compare_period(&self) -> ComparisonPeriod {
+        self.compare_period
+    }
+
     /// 渲染统计视图，返回 (新选择的时间范围, 是否使用堆叠视图)
     pub fn show(&mut self, ui: &mut Ui) -> (Option<TimeRange>, bool) {
         let mut new_time_range = None;
@@ -157,6 +200,17 @@ impl<'a> StatisticsView<'a> {
                 eprintln!("[DEBUG] 切换到堆叠柱形图");
                 self.use_stacked_view = true;
             }
+            ui.separator();
+            ui.checkbox(&mut self.compare_mode, "🔀 对比");
+            ui.add_enabled_ui(self.compare_mode, |ui| {
+                egui::ComboBox::from_id_source("stats_compare_period")
+                    .selected_text(self.compare_period.label())
+                    .show_ui(ui, |ui| {
+                        for period in ComparisonPeriod::ALL {
+                            ui.selectable_value(&mut self.compare_period, period, period.label());
+                        }
+                    });
+            });
         });
 
         ui.add_space(self.theme.spacing / 2.0);
@@ -223,13 +277,114 @@ impl<'a> StatisticsView<'a> {
         ui.add_space(self.theme.spacing);
 
         // 应用详情表格
-        ui.add(SectionDivider::new(self.theme).with_title("应用详情"));
-        ui.add_space(self.theme.spacing / 2.0);
-        self.show_app_table(ui);
+        if self.compare_mode {
+            let title = format!("应用对比（本期 / {}）", self.compare_period.label());
+            ui.add(SectionDivider::new(self.theme).with_title(&title));
+            ui.add_space(self.theme.spacing / 2.0);
+            self.show_comparison_table(ui);
+        } else {
+            ui.add(SectionDivider::new(self.theme).with_title("应用详情"));
+            ui.add_space(self.theme.spacing / 2.0);
+            self.show_app_table(ui);
+        }
 
         (new_time_range, self.use_stacked_view)
     }
 
+    /// 显示两个周期的应用对比表格
+    fn show_comparison_table(&mut self, ui: &mut Ui) {
+        let rows = compare::compare_app_usage(self.app_usage, self.previous_usage);
+
+        if rows.is_empty() {
+            ui.add(EmptyState::new(
+                "📭",
+                "两个时间段内均暂无数据",
+                "尝试选择其他时间范围",
+                self.theme,
+            ));
+            return;
+        }
+
+        let available_height = ui.available_height().max(200.0);
+        let header_label = |ui: &mut Ui, text: &str| {
+            ui.label(
+                egui::RichText::new(text)
+                    .size(self.theme.small_size)
+                    .color(self.theme.secondary_text_color),
+            );
+        };
+
+        TableBuilder::new(ui)
+            .striped(true)
+            .cell_layout(egui::Layout::left_to_right(egui::Align::Center))
+            .column(Column::remainder().at_least(150.0)) // 应用名称
+            .column(Column::exact(100.0)) // 本期
+            .column(Column::exact(100.0)) // 上期
+            .column(Column::exact(100.0)) // 变化
+            .min_scrolled_height(0.0)
+            .max_scroll_height(available_height)
+            .header(32.0, |mut header| {
+                header.col(|ui| header_label(ui, "应用"));
+                header.col(|ui| header_label(ui, "本期"));
+                header.col(|ui| header_label(ui, "上期"));
+                header.col(|ui| header_label(ui, "变化"));
+            })
+            .body(|mut body| {
+                for row_data in &rows {
+                    body.row(36.0, |mut row| {
+                        row.col(|ui| {
+                            ui.label(
+                                egui::RichText::new(
+                                    self.icon_cache.display_name(&row_data.app_name),
+                                )
+                                .size(self.theme.body_size)
+                                .color(self.theme.text_color),
+                            );
+                        });
+                        row.col(|ui| {
+                            ui.label(
+                                egui::RichText::new(duration::format_duration(
+                                    row_data.current_seconds,
+                                ))
+                                .size(self.theme.body_size)
+                                .color(self.theme.text_color),
+                            );
+                        });
+                        row.col(|ui| {
+                            ui.label(
+                                egui::RichText::new(duration::format_duration(
+                                    row_data.previous_seconds,
+                                ))
+                                .size(self.theme.body_size)
+                                .color(self.theme.secondary_text_color),
+                            );
+                        });
+                        row.col(|ui| {
+                            let delta = row_data.delta_seconds();
+                            let (text, color) = match delta.cmp(&0) {
+                                std::cmp::Ordering::Greater => (
+                                    format!("+{}", duration::format_duration(delta)),
+                                    self.theme.warning_color,
+                                ),
+                                std::cmp::Ordering::Less => (
+                                    format!("-{}", duration::format_duration(-delta)),
+                                    self.theme.success_color,
+                                ),
+                                std::cmp::Ordering::Equal => {
+                                    ("—".to_string(), self.theme.secondary_text_color)
+                                }
+                            };
+                            ui.label(
+                                egui::RichText::new(text)
+                                    .size(self.theme.small_size)
+                                    .color(color),
+                            );
+                        });
+                    });
+                }
+            });
+    }
+
     /// 显示应用详情表格
     fn show_app_table(&mut self, ui: &mut Ui) {
         use crate::icons::AppIcon;
@@ -283,7 +438,7 @@ impl<'a> StatisticsView<'a> {
             .collect();
 
         // 按使用时长降序排序
-        app_data.sort_by(|a, b| b.1.cmp(&a.1));
+        app_data.sort_by_key(|a| std::cmp::Reverse(a.1));
 
         TableBuilder::new(ui)
             .striped(true)
@@ -358,7 +513,7 @@ impl<'a> StatisticsView<'a> {
                         // 应用名称
                         row.col(|ui| {
                             ui.label(
-                                egui::RichText::new(&app_name)
+                                egui::RichText::new(self.icon_cache.display_name(&app_name))
                                     .size(self.theme.body_size)
                                     .color(self.theme.text_color),
                             );
@@ -384,35 +539,10 @@ impl<'a> StatisticsView<'a> {
 
                         // 进度条
                         row.col(|ui| {
-                            let bar_width = 80.0;
-                            let bar_height = 6.0;
-                            let (rect, _) = ui.allocate_exact_size(
-                                Vec2::new(bar_width, bar_height),
-                                egui::Sense::hover(),
-                            );
-
-                            if ui.is_rect_visible(rect) {
-                                let painter = ui.painter();
-
-                                // 背景
-                                painter.rect_filled(
-                                    rect,
-                                    Rounding::same(3.0),
-                                    self.theme.progress_background,
-                                );
-
-                                // 填充
-                                let fill_width = rect.width() * (percentage / 100.0).min(1.0);
-                                let fill_rect = Rect::from_min_size(
-                                    rect.min,
-                                    Vec2::new(fill_width, bar_height),
-                                );
-                                painter.rect_filled(
-                                    fill_rect,
-                                    Rounding::same(3.0),
-                                    self.theme.primary_color,
-                                );
-                            }
+                            ProgressBar::new(percentage / 100.0, self.theme.primary_color)
+                                .width(80.0)
+                                .height(6.0)
+                                .show(ui, self.theme);
                         });
                     });
                 }
@@ -421,37 +551,10 @@ impl<'a> StatisticsView<'a> {
 
     /// 显示堆叠柱状图（按应用堆叠）
     fn show_stacked_chart(&mut self, ui: &mut Ui) {
-        // 根据当前导航状态确定时间粒度
-        // 快捷选项的 level 设置：
-        // - Today: level = Hour (显示24小时)
-        // - ThisWeek: level = Day, selected_week = None (显示7天)
-        // - ThisMonth: level = Week (显示该月的周)
-        // - ThisYear: level = Month (显示12个月)
-
-        let granularity = match self.navigation_state.level {
-            tail_core::models::TimeNavigationLevel::Month => {
-                // Month level 表示显示12个月（本年快捷选项）
-                ChartTimeGranularity::Year
-            }
-            tail_core::models::TimeNavigationLevel::Week => {
-                // Week level 表示显示该月的周（本月快捷选项）
-                ChartTimeGranularity::Month
-            }
-            tail_core::models::TimeNavigationLevel::Day => {
-                // Day level:
-                // - 如果 selected_week 是 None，表示显示7天（本周快捷选项）
-                // - 如果 selected_week 有值，表示显示该周7天
-                ChartTimeGranularity::Week
-            }
-            tail_core::models::TimeNavigationLevel::Hour => {
-                // Hour level 表示显示24小时（今天快捷选项）
-                ChartTimeGranularity::Day
-            }
-            tail_core::models::TimeNavigationLevel::Year => {
-                // Year level 不应该出现在快捷选项中
-                ChartTimeGranularity::Year
-            }
-        };
+        // 根据当前导航层级确定时间粒度（例如周视图显示 7 天，而不是 24 小时）
+        let granularity = ChartTimeGranularity::from(TimeGranularity::for_range(
+            self.navigation_state.current_level(),
+        ));
 
         eprintln!(
             "[DEBUG] show_stacked_chart - level={:?}, granularity={:?}, app_usage.len()={}",
@@ -488,6 +591,7 @@ impl<'a> StatisticsView<'a> {
         }
 
         let config = StackedBarChartConfig {
+            color_map: self.color_map.clone(),
             max_bar_height: 200.0,
             ..Default::default()
         };
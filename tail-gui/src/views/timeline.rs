@@ -0,0 +1,440 @@
+//! TaiL GUI - 时间线视图
+//!
+//! 在纵向 24 小时坐标轴上按会话显示一天的活动
+
+use chrono::{DateTime, Local, NaiveDate};
+use egui::{Color32, Pos2, Rect, Rounding, ScrollArea, Sense, Ui, Vec2};
+use std::collections::HashMap;
+use tail_core::time::analysis::Session;
+use tail_core::time::range::TimeRange;
+
+use crate::components::{CategoryColorMap, EmptyState, PageHeader, SectionDivider};
+use crate::icons::IconCache;
+use crate::theme::TaiLTheme;
+use crate::utils::duration;
+
+/// 每小时的像素高度
+const HOUR_HEIGHT: f32 = 48.0;
+
+/// 左侧时间刻度的宽度
+const AXIS_WIDTH: f32 = 48.0;
+
+/// 会话块的最小高度（保证短会话可见、可点击）
+const MIN_BLOCK_HEIGHT: f32 = 2.0;
+
+/// 计算会话块在时间线上的矩形
+///
+/// `origin` 为当天 0 点在屏幕上的位置，会话超出当天的部分被截断。
+pub fn session_block_rect(
+    origin: Pos2,
+    width: f32,
+    hour_height: f32,
+    day: NaiveDate,
+    start: DateTime<Local>,
+    end: DateTime<Local>,
+) -> Rect {
+    let day_start = day.and_hms_opt(0, 0, 0).unwrap();
+    let to_y = |t: DateTime<Local>| {
+        let hours = (t.naive_local() - day_start).num_seconds() as f32 / 3600.0;
+        origin.y + hours.clamp(0.0, 24.0) * hour_height
+    };
+
+    let top = to_y(start);
+    let bottom = to_y(end).max(top + MIN_BLOCK_HEIGHT);
+    Rect::from_min_max(
+        Pos2::new(origin.x, top),
+        Pos2::new(origin.x + width, bottom),
+    )
+}
+
+/// 提示条中最多逐条列出的记录空白数量
+const MAX_LISTED_GAPS: usize = 3;
+
+/// 记录空白区域斜线的间距
+const GAP_HATCH_SPACING: f32 = 8.0;
+
+/// 把记录空白列表格式化为提示文字，没有空白时返回 `None`
+///
+/// 时间按本地时区显示，超过 [`MAX_LISTED_GAPS`] 段时只列出前几段并注明总数。
+pub fn tracking_gap_summary(gaps: &[TimeRange]) -> Option<String> {
+    if gaps.is_empty() {
+        return None;
+    }
+
+    let ranges: Vec<String> = gaps
+        .iter()
+        .take(MAX_LISTED_GAPS)
+        .map(|gap| {
+            format!(
+                "{}–{}",
+                gap.start.with_timezone(&Local).format("%H:%M"),
+                gap.end.with_timezone(&Local).format("%H:%M")
+            )
+        })
+        .collect();
+    let listed = if gaps.len() > MAX_LISTED_GAPS {
+        format!("{} 等 {} 段时间", ranges.join("、"), gaps.len())
+    } else {
+        format!("{} ", ranges.join("、"))
+    };
+
+    Some(format!("{}没有记录到数据，TaiL 当时在运行吗？", listed))
+}
+
+/// 时间线视图
+pub struct TimelineView {
+    /// 当前显示的日期
+    date: NaiveDate,
+    /// 当天的活动会话
+    sessions: Vec<Session>,
+    /// 当天的记录空白（守护进程可能未运行）
+    gaps: Vec<TimeRange>,
+    /// 选中的会话下标
+    selected: Option<usize>,
+    /// 颜色配置
+    color_map: CategoryColorMap,
+}
+
+impl Default for TimelineView {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TimelineView {
+    pub fn new() -> Self {
+        Self {
+            date: Local::now().date_naive(),
+            sessions: Vec::new(),
+            gaps: Vec::new(),
+            selected: None,
+            color_map: CategoryColorMap::default(),
+        }
+    }
+
+    /// 当前显示的日期
+    pub fn date(&self) -> NaiveDate {
+        self.date
+    }
+
+    /// 更新会话数据
+    pub fn set_sessions(&mut self, sessions: Vec<Session>) {
+        if self.selected.is_some_and(|i| i >= sessions.len()) {
+            self.selected = None;
+        }
+        self.sessions = sessions;
+    }
+
+    /// 设置颜色配置（包含用户为应用指定的颜色）
+    pub fn set_color_map(&mut self, color_map: CategoryColorMap) {
+        self.color_map = color_map;
+    }
+
+    /// 更新记录空白
+    pub fn set_gaps(&mut self, gaps: Vec<TimeRange>) {
+        self.gaps = gaps;
+    }
+
+    /// 渲染时间线视图，切换日期时返回新的日期
+    pub fn show(
+        &mut self,
+        ui: &mut Ui,
+        theme: &TaiLTheme,
+        icon_cache: &mut IconCache,
+    ) -> Option<NaiveDate> {
+        ui.add(PageHeader::new("时间线", "🕒", theme).subtitle("按连续活动会话查看一天"));
+        ui.add_space(theme.spacing);
+
+        let new_date = self.show_date_selector(ui, theme);
+        if let Some(date) = new_date {
+            self.date = date;
+            self.selected = None;
+        }
+
+        ui.add_space(theme.spacing);
+
+        if self.sessions.is_empty() {
+            ui.add(EmptyState::new(
+                "🕒",
+                "暂无活动",
+                "这一天没有记录到活动会话",
+                theme,
+            ));
+            return new_date;
+        }
+
+        if let Some(summary) = tracking_gap_summary(&self.gaps) {
+            ui.label(
+                egui::RichText::new(format!("⚠ {}", summary))
+                    .size(theme.small_size)
+                    .color(theme.warning_color),
+            );
+            ui.add_space(theme.spacing / 2.0);
+        }
+
+        if let Some(session) = self.selected.and_then(|i| self.sessions.get(i)) {
+            Self::show_session_details(ui, session, theme, icon_cache);
+            ui.add_space(theme.spacing);
+        }
+
+        ScrollArea::vertical()
+            .auto_shrink([false, false])
+            .show(ui, |ui| self.show_timeline(ui, theme, icon_cache));
+
+        new_date
+    }
+
+    /// 显示日期切换
+    fn show_date_selector(&self, ui: &mut Ui, theme: &TaiLTheme) -> Option<NaiveDate> {
+        let mut new_date = None;
+        let today = Local::now().date_naive();
+
+        ui.horizontal(|ui| {
+            if ui.button("◀ 前一天").clicked() {
+                new_date = Some(self.date - chrono::Duration::days(1));
+            }
+
+            ui.label(
+                egui::RichText::new(self.date.format("%Y-%m-%d").to_string())
+                    .size(theme.body_size)
+                    .color(theme.text_color)
+                    .strong(),
+            );
+
+            if ui
+                .add_enabled(self.date < today, egui::Button::new("后一天 ▶"))
+                .clicked()
+            {
+                new_date = Some(self.date + chrono::Duration::days(1));
+            }
+
+            if self.date != today && ui.button("今天").clicked() {
+                new_date = Some(today);
+            }
+        });
+
+        new_date
+    }
+
+    /// 绘制时间轴和会话块
+    fn show_timeline(&mut self, ui: &mut Ui, theme: &TaiLTheme, icon_cache: &mut IconCache) {
+        let desired_size = Vec2::new(ui.available_width(), HOUR_HEIGHT * 24.0);
+        let (rect, _) = ui.allocate_exact_size(desired_size, Sense::hover());
+        let painter = ui.painter_at(rect);
+
+        // 小时刻度
+        for hour in 0..=24 {
+            let y = rect.min.y + hour as f32 * HOUR_HEIGHT;
+            painter.line_segment(
+                [
+                    Pos2::new(rect.min.x + AXIS_WIDTH, y),
+                    Pos2::new(rect.max.x, y),
+                ],
+                egui::Stroke::new(1.0, theme.divider_color),
+            );
+            if hour < 24 {
+                painter.text(
+                    Pos2::new(rect.min.x, y + 2.0),
+                    egui::Align2::LEFT_TOP,
+                    format!("{:02}:00", hour),
+                    egui::FontId::proportional(theme.small_size),
+                    theme.secondary_text_color,
+                );
+            }
+        }
+
+        let colors = self.assign_colors();
+        let origin = Pos2::new(rect.min.x + AXIS_WIDTH + 4.0, rect.min.y);
+        let width = rect.width() - AXIS_WIDTH - 8.0;
+
+        // 记录空白用斜线区域标出
+        for gap in &self.gaps {
+            let block = session_block_rect(
+                origin,
+                width,
+                HOUR_HEIGHT,
+                self.date,
+                gap.start.with_timezone(&Local),
+                gap.end.with_timezone(&Local),
+            );
+            let hatch_painter = painter.with_clip_rect(block);
+            let stroke = egui::Stroke::new(1.0, theme.warning_color.gamma_multiply(0.4));
+            let mut x = block.min.x - block.height();
+            while x < block.max.x {
+                hatch_painter.line_segment(
+                    [
+                        Pos2::new(x, block.max.y),
+                        Pos2::new(x + block.height(), block.min.y),
+                    ],
+                    stroke,
+                );
+                x += GAP_HATCH_SPACING;
+            }
+        }
+
+        for (i, session) in self.sessions.iter().enumerate() {
+            let block = session_block_rect(
+                origin,
+                width,
+                HOUR_HEIGHT,
+                self.date,
+                session.start.with_timezone(&Local),
+                session.end.with_timezone(&Local),
+            );
+            let color = session
+                .dominant_app()
+                .and_then(|app| colors.get(app).copied())
+                .unwrap_or(self.color_map.other_color());
+
+            let response =
+                ui.interact(block, ui.id().with(("timeline_session", i)), Sense::click());
+            let fill = if self.selected == Some(i) || response.hovered() {
+                color
+            } else {
+                color.gamma_multiply(0.75)
+            };
+            painter.rect_filled(block, Rounding::same(4.0), fill);
+
+            if self.selected == Some(i) {
+                painter.rect_stroke(
+                    block,
+                    Rounding::same(4.0),
+                    egui::Stroke::new(2.0, theme.text_color),
+                );
+            }
+
+            if block.height() >= theme.small_size + 4.0
+                && let Some(app) = session.dominant_app()
+            {
+                painter.text(
+                    block.left_top() + Vec2::new(6.0, 2.0),
+                    egui::Align2::LEFT_TOP,
+                    icon_cache.display_name(app),
+                    egui::FontId::proportional(theme.small_size),
+                    Color32::WHITE,
+                );
+            }
+
+            if response.clicked() {
+                self.selected = Some(i);
+            }
+        }
+    }
+
+    /// 为会话的主要应用分配颜色（按名称排序保证颜色稳定）
+    fn assign_colors(&self) -> HashMap<String, Color32> {
+        let mut apps: Vec<String> = self
+            .sessions
+            .iter()
+            .filter_map(|s| s.dominant_app().map(str::to_string))
+            .collect();
+        apps.sort();
+        apps.dedup();
+        self.color_map.assign_colors(&apps)
+    }
+
+    /// 显示选中会话的详情
+    fn show_session_details(
+        ui: &mut Ui,
+        session: &Session,
+        theme: &TaiLTheme,
+        icon_cache: &mut IconCache,
+    ) {
+        let title = format!(
+            "{} - {} · {}",
+            session.start.with_timezone(&Local).format("%H:%M"),
+            session.end.with_timezone(&Local).format("%H:%M"),
+            duration::format_duration(session.total_seconds)
+        );
+        ui.add(SectionDivider::new(theme).with_title(&title));
+
+        let mut apps: Vec<(&String, &i64)> = session.app_breakdown.iter().collect();
+        apps.sort_by(|a, b| b.1.cmp(a.1).then_with(|| a.0.cmp(b.0)));
+
+        for (app_name, seconds) in apps {
+            ui.horizontal(|ui| {
+                ui.label(
+                    egui::RichText::new(icon_cache.display_name(app_name))
+                        .size(theme.body_size)
+                        .color(theme.text_color),
+                );
+                ui.label(
+                    egui::RichText::new(duration::format_duration(*seconds))
+                        .size(theme.small_size)
+                        .color(theme.secondary_text_color),
+                );
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_session_block_rect() {
+        let day = NaiveDate::from_ymd_opt(2024, 3, 1).unwrap();
+        let start = Local.with_ymd_and_hms(2024, 3, 1, 9, 0, 0).unwrap();
+        let end = Local.with_ymd_and_hms(2024, 3, 1, 10, 30, 0).unwrap();
+
+        let rect = session_block_rect(Pos2::new(50.0, 100.0), 200.0, 40.0, day, start, end);
+        assert_eq!(rect.min, Pos2::new(50.0, 460.0));
+        assert_eq!(rect.max, Pos2::new(250.0, 520.0));
+    }
+
+    #[test]
+    fn test_session_block_rect_clamps_to_day() {
+        let day = NaiveDate::from_ymd_opt(2024, 3, 1).unwrap();
+        let start = Local.with_ymd_and_hms(2024, 3, 1, 23, 0, 0).unwrap();
+        let end = Local.with_ymd_and_hms(2024, 3, 2, 1, 0, 0).unwrap();
+
+        let rect = session_block_rect(Pos2::ZERO, 100.0, 40.0, day, start, end);
+        assert_eq!(rect.min.y, 920.0);
+        assert_eq!(rect.max.y, 960.0);
+    }
+
+    fn gap(start: (u32, u32), end: (u32, u32)) -> TimeRange {
+        let at = |(hour, minute): (u32, u32)| {
+            Local
+                .with_ymd_and_hms(2024, 3, 1, hour, minute, 0)
+                .unwrap()
+                .with_timezone(&chrono::Utc)
+        };
+        TimeRange::new(at(start), at(end))
+    }
+
+    #[test]
+    fn test_tracking_gap_summary_none_without_gaps() {
+        assert_eq!(tracking_gap_summary(&[]), None);
+    }
+
+    #[test]
+    fn test_tracking_gap_summary_single_gap() {
+        assert_eq!(
+            tracking_gap_summary(&[gap((13, 0), (15, 0))]).unwrap(),
+            "13:00–15:00 没有记录到数据，TaiL 当时在运行吗？"
+        );
+    }
+
+    #[test]
+    fn test_tracking_gap_summary_multiple_gaps() {
+        let gaps = [gap((9, 0), (10, 30)), gap((13, 0), (15, 0))];
+        assert_eq!(
+            tracking_gap_summary(&gaps).unwrap(),
+            "09:00–10:30、13:00–15:00 没有记录到数据，TaiL 当时在运行吗？"
+        );
+
+        let gaps = [
+            gap((1, 0), (2, 0)),
+            gap((4, 0), (5, 0)),
+            gap((9, 0), (10, 30)),
+            gap((13, 0), (15, 0)),
+        ];
+        assert_eq!(
+            tracking_gap_summary(&gaps).unwrap(),
+            "01:00–02:00、04:00–05:00、09:00–10:30 等 4 段时间没有记录到数据，TaiL 当时在运行吗？"
+        );
+    }
+}
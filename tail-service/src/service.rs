@@ -4,8 +4,9 @@ use anyhow::Result;
 use chrono::{DateTime, Utc};
 use std::time::Instant;
 use tail_afk::{AfkDetector, AfkState};
-use tail_core::traits::WindowEventRepository;
-use tail_core::{db::Config as DbConfig, Repository, WindowEvent};
+use tail_core::db::repositories::{AFK_THRESHOLD_SECS_KEY, DEFAULT_AFK_THRESHOLD_SECS};
+use tail_core::traits::{SettingsRepository, WindowEventRepository};
+use tail_core::{db::Config as DbConfig, DbError, Repository, WindowEvent};
 use tail_hyprland::{HyprlandEvent, HyprlandIpc};
 use tokio::sync::mpsc;
 use tokio::time::{interval, Duration};
@@ -70,6 +71,31 @@ impl TailService {
         })
     }
 
+    /// 从数据库设置中加载 AFK 判定阈值（未设置时使用默认值）
+    ///
+    /// 启动时调用一次，之后在定期更新中重新读取，设置页面修改阈值后无需重启追踪服务；
+    /// 阈值没有变化时不做任何事，已记录的活动时间不受影响。
+    pub async fn load_afk_threshold(&mut self) {
+        match self
+            .repo
+            .get_setting_i64(AFK_THRESHOLD_SECS_KEY, DEFAULT_AFK_THRESHOLD_SECS)
+            .await
+        {
+            Ok(secs) if secs > 0 => {
+                if self.afk_detector.timeout_secs() != secs as u64 {
+                    info!(afk_timeout_secs = secs, "已加载 AFK 判定阈值");
+                    self.afk_detector.set_timeout(secs as u64);
+                }
+            }
+            Ok(secs) => {
+                warn!(afk_timeout_secs = secs, "AFK 判定阈值无效，保持当前值");
+            }
+            Err(e) => {
+                warn!(error = %e, "读取 AFK 判定阈值失败，保持当前值");
+            }
+        }
+    }
+
     /// 运行服务
     pub async fn run(mut self) -> Result<()> {
         info!("TaiL Service 正在启动...");
@@ -141,6 +167,10 @@ impl TailService {
                 if let Err(e) = self.update_current_window_duration().await {
                     error!(error = %e, "更新当前窗口时长失败");
                 }
+                if let Err(e) = self.repo.touch_heartbeat().await {
+                    warn!(error = %e, "记录心跳失败");
+                }
+                self.load_afk_threshold().await;
                 continue;
             }
 
@@ -216,43 +246,24 @@ impl TailService {
         let afk_state = self.afk_detector.check_state();
         let is_afk = matches!(afk_state, AfkState::Afk { .. });
 
-        // 如果有当前窗口，计算其使用时长并保存
-        if let Some(prev_window) = self.current_window.take() {
+        // 如果有当前窗口，计算其使用时长，与新事件在同一事务中保存
+        let finalize = self.current_window.take().and_then(|prev_window| {
             let duration_secs = now_instant
                 .duration_since(prev_window.start_instant)
                 .as_secs() as i64;
 
             // 只有时长大于0才记录
             if duration_secs > 0 {
-                if let Some(event_id) = prev_window.event_id {
-                    // 更新已存在的事件
-                    match WindowEventRepository::update_duration(
-                        &self.repo,
-                        event_id,
-                        duration_secs,
-                    )
-                    .await
-                    {
-                        Ok(_) => {
-                            info!(
-                                app_name = %prev_window.app_name,
-                                duration_secs = duration_secs,
-                                event_id = event_id,
-                                "窗口事件已更新"
-                            );
-                        }
-                        Err(e) => {
-                            error!(
-                                error = %e,
-                                event_id = event_id,
-                                app_name = %prev_window.app_name,
-                                "更新窗口事件时长失败"
-                            );
-                        }
-                    }
-                }
+                debug!(
+                    app_name = %prev_window.app_name,
+                    duration_secs = duration_secs,
+                    "结束上一个窗口事件"
+                );
+                prev_window.event_id.map(|id| (id, duration_secs))
+            } else {
+                None
             }
-        }
+        });
 
         // 创建新的窗口事件（初始时长为0）
         let event = WindowEvent {
@@ -265,14 +276,27 @@ impl TailService {
             is_afk,
         };
 
-        // 插入新事件到数据库
-        match WindowEventRepository::insert(&self.repo, &event).await {
+        // 更新上一个事件并插入新事件；上一个事件已被删除（例如清理时长为 0 的事件）时
+        // 整个事务会回滚，此时放弃结束上一个事件，只插入新事件，避免丢失新的焦点
+        let result = match self.repo.record_focus_change(finalize, &event).await {
+            Err(DbError::NotFound(_)) if finalize.is_some() => {
+                warn!(
+                    app_name = %app_name,
+                    finalized = ?finalize,
+                    "上一个窗口事件已不存在，只记录新事件"
+                );
+                self.repo.record_focus_change(None, &event).await
+            }
+            result => result,
+        };
+        match result {
             Ok(event_id) => {
                 info!(
                     app_name = %app_name,
                     event_id = event_id,
                     is_afk = is_afk,
-                    "新窗口事件已插入"
+                    finalized = ?finalize,
+                    "窗口切换已记录"
                 );
 
                 // 更新当前窗口
@@ -289,7 +313,8 @@ impl TailService {
                 error!(
                     error = %e,
                     app_name = %app_name,
-                    "插入窗口事件失败"
+                    finalized = ?finalize,
+                    "记录窗口切换失败"
                 );
             }
         }
@@ -383,7 +408,8 @@ pub fn service_main() {
 async fn run_service() -> anyhow::Result<()> {
     info!("正在启动 TaiL Service...");
 
-    let service = TailService::new()?;
+    let mut service = TailService::new()?;
+    service.load_afk_threshold().await;
 
     // 直接运行服务，Ctrl+C 会自动终止进程
     service.run().await?;